@@ -11,8 +11,9 @@ use sn_interface::types::log_markers::LogMarker;
 use clap::{AppSettings::ColoredHelp, Parser, Subcommand};
 use eyre::{bail, Error, Result};
 use grep::{matcher::Matcher, regex::RegexMatcher, searcher::sinks::UTF8, searcher::Searcher};
+use serde::Deserialize;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -49,10 +50,18 @@ enum SubCmds {
     },
     /// Generate a report of cmds dispatched, and which were started but not completed
     IncompleteCmds,
+    /// Generate a report of the relocation decisions made by elders on churn, and their reasons
+    Relocations,
 }
 
 fn main() -> Result<()> {
     let args = CmdArgs::parse();
+    if let Some(SubCmds::Relocations) = args.cmd {
+        let decisions = scan_relocation_decisions(&args.logs_path)?;
+        print_relocations_report(&decisions);
+        return Ok(());
+    }
+
     let report = inspect_log_files(&args)?;
 
     println!();
@@ -99,6 +108,7 @@ fn main() -> Result<()> {
                     }
                 }
             }
+            SubCmds::Relocations => {}
         }
     }
 
@@ -334,6 +344,7 @@ fn inspect_log_files(args: &CmdArgs) -> Result<BTreeMap<String, Vec<String>>> {
             SubCmds::IncompleteCmds => {
                 check_completed_cmds(&info, spawned, started, succeeded, failed);
             }
+            SubCmds::Relocations => {}
         }
     }
 
@@ -525,3 +536,129 @@ fn get_root_cmd_id(cmd_id: &str) -> CmdId {
     root_cmd_id.truncate(cmd_id.find('.').unwrap_or(cmd_id.len()));
     root_cmd_id
 }
+
+// A relocation decision, as logged by elders along with the `RelocationDecision` marker.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct RelocationDecision {
+    policy: String,
+    churn: String,
+    prefix: String,
+    section_size: usize,
+    relocated: Vec<Relocated>,
+    reasons: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Relocated {
+    name: String,
+    age: u8,
+    dst: String,
+    reason: String,
+}
+
+// Find all the relocation decisions logged, along with the nodes which logged each of them.
+// All the elders of a section make the same decision on a churn, so these are deduplicated.
+fn scan_relocation_decisions(
+    path: &Path,
+) -> Result<BTreeMap<RelocationDecision, BTreeSet<NodeId>>> {
+    println!("Inspecting testnet logs folder: {}", path.display());
+
+    let marker = LogMarker::RelocationDecision.to_string();
+    let matcher = RegexMatcher::new_line_matcher(&format!(r"{marker} \{{"))?;
+    let mut decisions = BTreeMap::<RelocationDecision, BTreeSet<NodeId>>::new();
+
+    for result in WalkDir::new(path) {
+        let dent = match result {
+            Ok(dent) => dent,
+            Err(err) => {
+                bail!(err)
+            }
+        };
+
+        if !dent.file_type().is_file() {
+            continue;
+        }
+
+        let node_name = dent
+            .path()
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Searcher::new().search_path(
+            &matcher,
+            dent.path(),
+            UTF8(|lnum, line| {
+                let json = line
+                    .find(&marker)
+                    .map(|index| line[index + marker.len()..].trim());
+                match json.map(serde_json::from_str::<RelocationDecision>) {
+                    Some(Ok(decision)) => {
+                        let _ = decisions
+                            .entry(decision)
+                            .or_default()
+                            .insert(node_name.clone());
+                    }
+                    _ => println!(
+                        "Skipping unparseable relocation decision at {}:{lnum}",
+                        dent.path().display()
+                    ),
+                }
+
+                Ok(true)
+            }),
+        )?;
+    }
+
+    Ok(decisions)
+}
+
+fn print_relocations_report(decisions: &BTreeMap<RelocationDecision, BTreeSet<NodeId>>) {
+    println!();
+    if decisions.is_empty() {
+        println!("** No relocation decisions were found in the logs scanned **");
+        return;
+    }
+
+    let mut per_policy = BTreeMap::<&str, (usize, usize)>::new();
+    let mut reasons = BTreeMap::<&str, usize>::new();
+    for decision in decisions.keys() {
+        let (decisions_count, relocations_count) = per_policy.entry(&decision.policy).or_default();
+        *decisions_count += 1;
+        *relocations_count += decision.relocated.len();
+        for reason in &decision.reasons {
+            *reasons.entry(reason).or_default() += 1;
+        }
+    }
+
+    println!("*** REPORT: Relocation decisions ***");
+    for (policy, (decisions_count, relocations_count)) in &per_policy {
+        println!(
+            "Policy {policy}: {decisions_count} decisions, {relocations_count} nodes relocated"
+        );
+    }
+    println!();
+
+    println!("Nodes relocated:");
+    for (decision, nodes) in decisions {
+        for relocated in &decision.relocated {
+            println!(
+                "{} (age {}) from prefix '{}' to {} on {}, decided by {} node(s): {}",
+                relocated.name,
+                relocated.age,
+                decision.prefix,
+                relocated.dst,
+                decision.churn,
+                nodes.len(),
+                relocated.reason
+            );
+        }
+    }
+    println!();
+
+    println!("Reasons for not relocating (more) nodes:");
+    for (reason, count) in reasons {
+        println!("{count} times: {reason}");
+    }
+}
@@ -31,7 +31,7 @@ async fn main() -> Result<()> {
 
     println!("Uploading '{}' to Safe ...", file_path.display());
     let (xorurl, _, _) = safe
-        .files_container_create_from(&file_path, dst, recursive, follow_links)
+        .files_container_create_from(&file_path, dst, recursive, follow_links, None)
         .await?;
 
     // The 'files_container_create_from' API returns (among other information) the
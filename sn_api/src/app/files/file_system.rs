@@ -6,7 +6,11 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{metadata::get_metadata, FilesMapChange, ProcessedFiles};
+use super::{
+    files_filter::{FilesFilter, PathFilter},
+    metadata::get_metadata,
+    FilesMapChange, ProcessedFiles,
+};
 use crate::{Error, Result, Safe, XorUrl};
 use bytes::Bytes;
 use log::info;
@@ -39,7 +43,15 @@ pub(crate) async fn upload_file_to_net(safe: &Safe, path: &Path) -> Result<XorUr
 
     // If the upload verification failed, the file could still have been uploaded successfully,
     // thus let's report the error but providing the xorurl for the user to be aware of.
-    if let Err(Error::ClientError(ClientError::NotEnoughChunksRetrieved { .. })) = result {
+    // The address of private content cannot be calculated again as it changes with every encryption.
+    if !safe.private_mode
+        && matches!(
+            result,
+            Err(Error::ClientError(
+                ClientError::NotEnoughChunksRetrieved { .. }
+            ))
+        )
+    {
         // Let's obtain the xorurl with using dry-run mode.
         // Use a dry runner only for this next operation
         let dry_runner = Safe::dry_runner(Some(safe.xorurl_base));
@@ -58,38 +70,71 @@ pub(crate) fn normalise_path_separator(from: &str) -> String {
 
 // Walk the local filesystem starting from `location`, creating a list of files paths,
 // and if not requested as a `dry_run` upload the files to the network filling up
-// the list of files with their corresponding XOR-URLs
+// the list of files with their corresponding XOR-URLs. The paths filtered out, either
+// by the filter provided or by the .safeignore files found, are reported as skipped.
 pub(crate) async fn file_system_dir_walk(
     safe: &Safe,
     location: &Path,
     recursive: bool,
     follow_links: bool,
+    filter: Option<&FilesFilter>,
 ) -> Result<ProcessedFiles> {
     info!("Reading files from {}", location.display());
 
     let (metadata, _) = get_metadata(location, follow_links)?;
     if metadata.is_dir() || !recursive {
+        let filter_root = if metadata.is_dir() {
+            location
+        } else {
+            location.parent().unwrap_or(location)
+        };
+        let mut path_filter = PathFilter::new(filter_root, filter)?;
+
         // TODO: option to enable following symlinks?
         // We now compare both FilesMaps to upload the missing files
         let max_depth = if recursive { MAX_RECURSIVE_DEPTH } else { 1 };
         let mut processed_files = ProcessedFiles::default();
-        let children_to_process = WalkDir::new(location)
+        let mut children_to_process = WalkDir::new(location)
             .follow_links(follow_links)
             .into_iter()
-            .filter_entry(|e| valid_depth(e, max_depth))
-            .filter_map(|v| v.ok());
+            .filter_entry(|e| valid_depth(e, max_depth));
 
-        for (idx, child) in children_to_process.enumerate() {
+        while let Some(child) = children_to_process.next() {
+            let child = match child {
+                Ok(child) => child,
+                Err(_) => continue,
+            };
             let current_file_path = child.path();
             let current_path_str = current_file_path.to_str().unwrap_or("").to_string();
             info!("Processing {}...", current_path_str);
             let normalised_path = PathBuf::from(normalise_path_separator(&current_path_str));
 
+            // Sub-dirs are not included unless recursing, thus not filtered either
+            let is_dir = child.file_type().is_dir();
+            if child.depth() > 0 && (recursive || !is_dir) {
+                if let Some(reason) = path_filter.skip_reason(current_file_path, is_dir) {
+                    info!(
+                        "Skipping \"{}\" since it's {reason}",
+                        normalised_path.display()
+                    );
+                    processed_files.insert(normalised_path, FilesMapChange::Skipped(reason));
+                    if is_dir {
+                        children_to_process.skip_current_dir();
+                    }
+                    continue;
+                }
+            }
+            if is_dir && (recursive || child.depth() == 0) {
+                path_filter.enter_dir(current_file_path)?;
+            }
+
             let result = get_metadata(current_file_path, follow_links);
             match result {
                 Ok((metadata, _)) => {
                     if metadata.file_type().is_dir() {
-                        if idx == 0 && normalised_path.display().to_string().ends_with('/') {
+                        if child.depth() == 0
+                            && normalised_path.display().to_string().ends_with('/')
+                        {
                             // If the first directory ends with '/' then it is
                             // the root, and we are only interested in the children,
                             // so we skip it.
@@ -168,10 +213,11 @@ fn valid_depth(entry: &DirEntry, max_depth: usize) -> bool {
 
 // Read the local filesystem at `location`, creating a list of one single file's path,
 // and if not as a `dry_run` upload the file to the network and putting
-// the obtained XOR-URL in the single file list returned
+// the obtained XOR-URL in the single file list returned, unless it's filtered out
 pub(crate) async fn file_system_single_file(
     safe: &Safe,
     location: &Path,
+    filter: Option<&FilesFilter>,
 ) -> Result<ProcessedFiles> {
     info!("Reading file {}", location.display());
     let (metadata, _) = get_metadata(location, true)?; // follows symlinks.
@@ -185,6 +231,17 @@ pub(crate) async fn file_system_single_file(
             location.display()
         )))
     } else {
+        let filter_root = location.parent().unwrap_or(location);
+        let mut path_filter = PathFilter::new(filter_root, filter)?;
+        if let Some(reason) = path_filter.skip_reason(location, false) {
+            info!(
+                "Skipping \"{}\" since it's {reason}",
+                normalised_path.display()
+            );
+            processed_files.insert(normalised_path, FilesMapChange::Skipped(reason));
+            return Ok(processed_files);
+        }
+
         match upload_file_to_net(safe, location).await {
             Ok(xorurl) => {
                 processed_files.insert(normalised_path, FilesMapChange::Added(xorurl));
@@ -0,0 +1,249 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{metadata::FileMeta, FileInfo, FilesMap};
+use crate::{app::consts::*, Error, Result, SafeUrl};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use xor_name::XorName;
+
+// Metadata which is set when an item is stored rather than taken from the file itself,
+// thus not compared. The link of the files is compared by the address of their content.
+const NOT_COMPARED: [&str; 3] = [PREDICATE_LINK, PREDICATE_CREATED, PREDICATE_MODIFIED];
+
+// Timestamps of folders change with their content, which is compared on its own
+const NOT_COMPARED_FOR_DIRS: [&str; 2] = [PREDICATE_ORIGINAL_CREATED, PREDICATE_ORIGINAL_MODIFIED];
+
+/// Differences found between two `FilesMap`s, keyed by the path of the items which differ.
+pub type FilesDiff = BTreeMap<String, FileDiff>;
+
+/// Difference found for an item when comparing two `FilesMap`s.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileDiff {
+    /// The item is only found in the new `FilesMap`.
+    Added(FileInfo),
+    /// The item is only found in the old `FilesMap`.
+    Removed(FileInfo),
+    /// The content of the item differs, or it's no longer the same type of item,
+    /// e.g. a file replaced by a folder.
+    Modified { old: FileInfo, new: FileInfo },
+    /// The content of the item is the same, but the listed metadata differs.
+    MetadataChanged {
+        old: FileInfo,
+        new: FileInfo,
+        changed: Vec<String>,
+    },
+}
+
+/// Summary of the differences found between two `FilesMap`s.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilesDiffStat {
+    pub added: u64,
+    pub removed: u64,
+    pub modified: u64,
+    pub metadata_changed: u64,
+    /// Size of the content added, including the new content of modified files.
+    pub bytes_added: u64,
+    /// Size of the content removed, including the old content of modified files.
+    pub bytes_removed: u64,
+}
+
+impl FilesDiffStat {
+    pub fn new(diff: &FilesDiff) -> Self {
+        let mut stat = Self::default();
+        for file_diff in diff.values() {
+            match file_diff {
+                FileDiff::Added(new) => {
+                    stat.added += 1;
+                    stat.bytes_added += item_size(new);
+                }
+                FileDiff::Removed(old) => {
+                    stat.removed += 1;
+                    stat.bytes_removed += item_size(old);
+                }
+                FileDiff::Modified { old, new } => {
+                    stat.modified += 1;
+                    stat.bytes_added += item_size(new);
+                    stat.bytes_removed += item_size(old);
+                }
+                FileDiff::MetadataChanged { .. } => stat.metadata_changed += 1,
+            }
+        }
+        stat
+    }
+}
+
+/// Compares two `FilesMap`s, e.g. of two versions of a FilesContainer, matching their items
+/// by path, and comparing the content of the files by the address it's stored at.
+pub fn files_map_diff(old: &FilesMap, new: &FilesMap) -> Result<FilesDiff> {
+    let mut diff = FilesDiff::new();
+    for (path, old_item) in old.iter() {
+        match new.get(path) {
+            None => {
+                diff.insert(path.clone(), FileDiff::Removed(old_item.clone()));
+            }
+            Some(new_item) => {
+                if let Some(file_diff) = file_item_diff(path, old_item, new_item)? {
+                    diff.insert(path.clone(), file_diff);
+                }
+            }
+        }
+    }
+
+    for (path, new_item) in new.iter() {
+        if !old.contains_key(path) {
+            diff.insert(path.clone(), FileDiff::Added(new_item.clone()));
+        }
+    }
+
+    Ok(diff)
+}
+
+// What an item holds, to tell if it changed
+#[derive(PartialEq)]
+enum ItemContent<'a> {
+    Dir,
+    Symlink(Option<&'a str>),
+    // Address of the content, if the file is not empty
+    File(Option<XorName>),
+}
+
+fn item_content<'a>(path: &str, file_item: &'a FileInfo) -> Result<ItemContent<'a>> {
+    let file_type = file_item.get(PREDICATE_TYPE).ok_or_else(|| {
+        Error::ContentError(format!(
+            "corrupt FileInfo: missing a \"type\" property at: {path}"
+        ))
+    })?;
+
+    let content = if FileMeta::filetype_is_dir(file_type) {
+        ItemContent::Dir
+    } else if FileMeta::filetype_is_symlink(file_type) {
+        ItemContent::Symlink(file_item.get("symlink_target").map(String::as_str))
+    } else {
+        match file_item.get(PREDICATE_LINK) {
+            Some(link) if !link.is_empty() => {
+                ItemContent::File(Some(SafeUrl::from_url(link)?.xorname()))
+            }
+            _ => ItemContent::File(None),
+        }
+    };
+    Ok(content)
+}
+
+fn file_item_diff(path: &str, old: &FileInfo, new: &FileInfo) -> Result<Option<FileDiff>> {
+    let old_content = item_content(path, old)?;
+    if old_content != item_content(path, new)? {
+        return Ok(Some(FileDiff::Modified {
+            old: old.clone(),
+            new: new.clone(),
+        }));
+    }
+
+    let is_dir = old_content == ItemContent::Dir;
+    let changed: Vec<String> = old
+        .keys()
+        .chain(new.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|key| {
+            !NOT_COMPARED.contains(&key.as_str())
+                && !(is_dir && NOT_COMPARED_FOR_DIRS.contains(&key.as_str()))
+                && old.get(*key) != new.get(*key)
+        })
+        .cloned()
+        .collect();
+
+    if changed.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(FileDiff::MetadataChanged {
+            old: old.clone(),
+            new: new.clone(),
+            changed,
+        }))
+    }
+}
+
+fn item_size(file_item: &FileInfo) -> u64 {
+    file_item
+        .get(PREDICATE_SIZE)
+        .and_then(|size| size.parse().ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::files::file_item_for_link, ContentType};
+    use anyhow::Result;
+
+    fn file_item(content: &[u8], size: u64) -> Result<FileInfo> {
+        let link = SafeUrl::from_bytes(XorName::from_content(content), ContentType::Raw)?
+            .to_xorurl_string();
+        Ok(file_item_for_link(&link, "text/plain", size))
+    }
+
+    #[test]
+    fn files_maps_are_compared_by_path_and_content() -> Result<()> {
+        let mut old = FilesMap::new();
+        old.insert("/same.txt".to_string(), file_item(b"same", 4)?);
+        old.insert("/removed.txt".to_string(), file_item(b"removed", 7)?);
+        old.insert("/modified.txt".to_string(), file_item(b"old", 3)?);
+        old.insert("/mode.txt".to_string(), file_item(b"mode", 4)?);
+
+        let mut new = FilesMap::new();
+        // the stored timestamps differ, but they are not compared
+        new.insert("/same.txt".to_string(), file_item(b"same", 4)?);
+        new.insert("/added.txt".to_string(), file_item(b"added", 5)?);
+        new.insert("/modified.txt".to_string(), file_item(b"new content", 11)?);
+        let mut mode_item = file_item(b"mode", 4)?;
+        mode_item.insert(PREDICATE_MODE_BITS.to_string(), "33188".to_string());
+        new.insert("/mode.txt".to_string(), mode_item);
+
+        let diff = files_map_diff(&old, &new)?;
+        assert_eq!(diff.len(), 4);
+        assert!(!diff.contains_key("/same.txt"));
+        assert!(matches!(diff["/added.txt"], FileDiff::Added(_)));
+        assert!(matches!(diff["/removed.txt"], FileDiff::Removed(_)));
+        assert!(matches!(diff["/modified.txt"], FileDiff::Modified { .. }));
+        assert!(
+            matches!(&diff["/mode.txt"], FileDiff::MetadataChanged { changed, .. } if changed == &[PREDICATE_MODE_BITS])
+        );
+
+        let stat = FilesDiffStat::new(&diff);
+        assert_eq!(
+            stat,
+            FilesDiffStat {
+                added: 1,
+                removed: 1,
+                modified: 1,
+                metadata_changed: 1,
+                bytes_added: 16,
+                bytes_removed: 10,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn replacing_a_file_with_a_folder_is_a_modification() -> Result<()> {
+        let mut old = FilesMap::new();
+        old.insert("/item".to_string(), file_item(b"file", 4)?);
+        let mut new = FilesMap::new();
+        new.insert(
+            "/item".to_string(),
+            FileMeta::from_type_and_size(MIMETYPE_FILESYSTEM_DIR, "0").to_file_item(),
+        );
+
+        let diff = files_map_diff(&old, &new)?;
+        assert!(matches!(diff["/item"], FileDiff::Modified { .. }));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{Error, Result};
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    Match,
+};
+use log::debug;
+use std::path::{Path, PathBuf};
+
+/// Name of the files listing, with gitignore syntax, the paths not to upload from the
+/// folder they are found in, and from its subfolders.
+pub const SAFE_IGNORE_FILE: &str = ".safeignore";
+
+/// Globs selecting the local files to upload, with the same syntax as `.safeignore` files,
+/// and relative to the location the files are uploaded from.
+#[derive(Clone, Debug, Default)]
+pub struct FilesFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl FilesFilter {
+    /// Only the files matching any of the `include` globs are uploaded, if any is provided,
+    /// and none of those matching any of the `exclude` globs are.
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        Self { include, exclude }
+    }
+}
+
+// Decides which of the local files found under a root folder are to be uploaded, applying
+// the globs of a FilesFilter and the .safeignore files found within the folder.
+pub(crate) struct PathFilter {
+    root: PathBuf,
+    include: Option<Gitignore>,
+    exclude: Gitignore,
+    // Matchers of the .safeignore files of the folders being walked, outermost first
+    ignore_files: Vec<(PathBuf, Gitignore)>,
+}
+
+impl PathFilter {
+    pub(crate) fn new(root: &Path, filter: Option<&FilesFilter>) -> Result<Self> {
+        let (include, exclude) = match filter {
+            Some(filter) => (
+                if filter.include.is_empty() {
+                    None
+                } else {
+                    Some(globs_matcher(root, &filter.include)?)
+                },
+                globs_matcher(root, &filter.exclude)?,
+            ),
+            None => (None, Gitignore::empty()),
+        };
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            include,
+            exclude,
+            ignore_files: Vec::new(),
+        })
+    }
+
+    // Applies the .safeignore file of the folder, if any, to the paths within it.
+    // The folders need to be entered in the order they are walked, depth first.
+    pub(crate) fn enter_dir(&mut self, dir: &Path) -> Result<()> {
+        let ignore_file = dir.join(SAFE_IGNORE_FILE);
+        if !ignore_file.is_file() {
+            return Ok(());
+        }
+
+        let mut builder = GitignoreBuilder::new(dir);
+        if let Some(err) = builder.add(&ignore_file) {
+            return Err(Error::InvalidInput(format!(
+                "Failed to read '{}': {err}",
+                ignore_file.display()
+            )));
+        }
+        let matcher = builder.build().map_err(|err| {
+            Error::InvalidInput(format!("Invalid '{}': {err}", ignore_file.display()))
+        })?;
+        debug!(
+            "Applying {} ignore rules from '{}'",
+            matcher.num_ignores() + matcher.num_whitelists(),
+            ignore_file.display()
+        );
+
+        self.ignore_files.push((dir.to_path_buf(), matcher));
+        Ok(())
+    }
+
+    // Returns the reason for skipping the path, if it's not to be uploaded.
+    pub(crate) fn skip_reason(&mut self, path: &Path, is_dir: bool) -> Option<String> {
+        // the .safeignore files of the folders already walked no longer apply
+        while let Some((dir, _)) = self.ignore_files.last() {
+            if path.starts_with(dir) {
+                break;
+            }
+            let _ = self.ignore_files.pop();
+        }
+
+        if let Match::Ignore(glob) = self.exclude.matched(path, is_dir) {
+            return Some(format!("excluded by the '{}' pattern", glob.original()));
+        }
+
+        // rules of the innermost .safeignore files take precedence
+        for (dir, matcher) in self.ignore_files.iter().rev() {
+            match matcher.matched(path, is_dir) {
+                Match::Ignore(glob) => {
+                    return Some(format!(
+                        "ignored by the '{}' rule of '{}'",
+                        glob.original(),
+                        dir.join(SAFE_IGNORE_FILE).display()
+                    ))
+                }
+                Match::Whitelist(_) => break,
+                Match::None => {}
+            }
+        }
+
+        match &self.include {
+            Some(include)
+                if !is_dir
+                    && path.starts_with(&self.root)
+                    && !include
+                        .matched_path_or_any_parents(path, is_dir)
+                        .is_ignore() =>
+            {
+                Some("not matching any of the included patterns".to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn globs_matcher(root: &Path, globs: &[String]) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(root);
+    for glob in globs {
+        let _ = builder
+            .add_line(None, glob)
+            .map_err(|err| Error::InvalidInput(format!("Invalid pattern '{glob}': {err}")))?;
+    }
+    builder
+        .build()
+        .map_err(|err| Error::InvalidInput(format!("Invalid patterns {globs:?}: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app::files::{
+            file_system::{file_system_dir_walk, file_system_single_file},
+            FilesMapChange, ProcessedFiles,
+        },
+        Safe,
+    };
+    use anyhow::{anyhow, Result};
+    use assert_fs::{prelude::*, TempDir};
+
+    fn skipped_reason<'a>(processed_files: &'a ProcessedFiles, path: &Path) -> Option<&'a str> {
+        match processed_files.get(path) {
+            Some(FilesMapChange::Skipped(reason)) => Some(reason),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn safeignore_files_are_applied_to_their_folder_and_subfolders() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        tmp_dir
+            .child(SAFE_IGNORE_FILE)
+            .write_str("target/\n*.key\n")?;
+        tmp_dir.child("index.md").write_str("content")?;
+        tmp_dir.child("secret.key").write_str("content")?;
+        tmp_dir.child("target/debug/bin").write_str("content")?;
+        tmp_dir.child("docs/intro.md").write_str("content")?;
+        tmp_dir.child("docs/public.key").write_str("content")?;
+        tmp_dir.child("docs/draft.md").write_str("content")?;
+        tmp_dir
+            .child(format!("docs/{SAFE_IGNORE_FILE}"))
+            .write_str("!public.key\ndraft.md\n")?;
+        tmp_dir.child("other/draft.md").write_str("content")?;
+
+        let safe = Safe::dry_runner(None);
+        let processed_files =
+            file_system_dir_walk(&safe, tmp_dir.path(), true, false, None).await?;
+
+        let added = |path: &str| {
+            processed_files
+                .get(&tmp_dir.path().join(path))
+                .map_or(false, FilesMapChange::is_success)
+        };
+        assert!(added("index.md"));
+        assert!(added("docs/intro.md"));
+        // re-included by the innermost .safeignore
+        assert!(added("docs/public.key"));
+        // the rules of the innermost .safeignore only apply to its folder
+        assert!(added("other/draft.md"));
+
+        let reason = skipped_reason(&processed_files, &tmp_dir.path().join("target"))
+            .ok_or_else(|| anyhow!("target/ should have been skipped"))?;
+        assert!(reason.contains("'target/'"));
+        // the content of skipped folders is not walked
+        assert!(!processed_files.contains_key(&tmp_dir.path().join("target/debug")));
+        assert!(skipped_reason(&processed_files, &tmp_dir.path().join("secret.key")).is_some());
+        assert!(skipped_reason(&processed_files, &tmp_dir.path().join("docs/draft.md")).is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn files_are_included_and_excluded_by_globs() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        tmp_dir.child("index.md").write_str("content")?;
+        tmp_dir.child("logo.png").write_str("content")?;
+        tmp_dir.child("docs/intro.md").write_str("content")?;
+        tmp_dir.child("docs/old/intro.md").write_str("content")?;
+
+        let filter = FilesFilter::new(vec!["*.md".to_string()], vec!["old/".to_string()]);
+        let safe = Safe::dry_runner(None);
+        let processed_files =
+            file_system_dir_walk(&safe, tmp_dir.path(), true, false, Some(&filter)).await?;
+
+        assert!(processed_files[&tmp_dir.path().join("index.md")].is_success());
+        assert!(processed_files[&tmp_dir.path().join("docs/intro.md")].is_success());
+        assert_eq!(
+            skipped_reason(&processed_files, &tmp_dir.path().join("logo.png")),
+            Some("not matching any of the included patterns")
+        );
+        assert_eq!(
+            skipped_reason(&processed_files, &tmp_dir.path().join("docs/old")),
+            Some("excluded by the 'old/' pattern")
+        );
+
+        // single files are filtered as well
+        let file_path = tmp_dir.path().join("logo.png");
+        let processed_files = file_system_single_file(&safe, &file_path, Some(&filter)).await?;
+        assert!(skipped_reason(&processed_files, &file_path).is_some());
+
+        let filter = FilesFilter::new(vec![], vec!["[".to_string()]);
+        assert!(PathFilter::new(tmp_dir.path(), Some(&filter)).is_err());
+
+        Ok(())
+    }
+}
@@ -30,41 +30,50 @@ pub enum FilesMapChange {
     Updated(XorUrl),
     Removed(XorUrl),
     Failed(String),
+    // The local file was filtered out, with the reason for it
+    Skipped(String),
 }
 
 impl FilesMapChange {
     pub fn is_success(&self) -> bool {
         match self {
             Self::Added(_) | Self::Updated(_) | Self::Removed(_) => true,
-            Self::Failed(_) => false,
+            Self::Failed(_) | Self::Skipped(_) => false,
         }
     }
 
     pub fn link(&self) -> Option<&XorUrl> {
         match self {
             Self::Added(link) | Self::Updated(link) | Self::Removed(link) => Some(link),
-            Self::Failed(_) => None,
+            Self::Failed(_) | Self::Skipped(_) => None,
         }
     }
 
     pub fn is_added(&self) -> bool {
         match self {
             Self::Added(_) => true,
-            Self::Updated(_) | Self::Removed(_) | Self::Failed(_) => false,
+            Self::Updated(_) | Self::Removed(_) | Self::Failed(_) | Self::Skipped(_) => false,
         }
     }
 
     pub fn is_updated(&self) -> bool {
         match self {
             Self::Updated(_) => true,
-            Self::Added(_) | Self::Removed(_) | Self::Failed(_) => false,
+            Self::Added(_) | Self::Removed(_) | Self::Failed(_) | Self::Skipped(_) => false,
         }
     }
 
     pub fn is_removed(&self) -> bool {
         match self {
             Self::Removed(_) => true,
-            Self::Added(_) | Self::Updated(_) | Self::Failed(_) => false,
+            Self::Added(_) | Self::Updated(_) | Self::Failed(_) | Self::Skipped(_) => false,
+        }
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        match self {
+            Self::Skipped(_) => true,
+            Self::Added(_) | Self::Updated(_) | Self::Removed(_) | Self::Failed(_) => false,
         }
     }
 }
@@ -85,6 +94,20 @@ impl GetAttr for FileInfo {
     }
 }
 
+/// Generates the FileInfo of a file, of the given type and size, stored at the given XOR-URL,
+/// e.g. as returned by `Safe::store_bytes`, to be inserted into a `FilesMap`.
+pub fn file_item_for_link(file_link: &str, file_type: &str, file_size: u64) -> FileInfo {
+    let mut file_item =
+        FileMeta::from_type_and_size(file_type, &file_size.to_string()).to_file_item();
+    file_item.insert(PREDICATE_LINK.to_string(), file_link.to_string());
+    file_item
+}
+
+/// Generates the FileInfo of an empty directory, to be inserted into a `FilesMap`.
+pub fn dir_file_item() -> FileInfo {
+    FileMeta::from_type_and_size(MIMETYPE_FILESYSTEM_DIR, "0").to_file_item()
+}
+
 // Helper function to add or update a FileInfo in a FilesMap
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn add_or_update_file_item(
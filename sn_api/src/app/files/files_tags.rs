@@ -0,0 +1,379 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    app::{multimap::Multimap, nrs::VersionHash, register::EntryHash},
+    ContentType, Error, Result, Safe, SafeUrl,
+};
+use log::{debug, warn};
+use sn_client::Error as ClientError;
+use sn_interface::{messaging::data::Error as ErrorMsg, types::register::User};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str,
+};
+use xor_name::XorName;
+
+// Type tag to use for the Multimap storing the version tags of a FilesContainer,
+// which is stored at an xorname derived from the FilesContainer's and its owner's key
+pub(crate) const FILES_CONTAINER_TAGS_TYPE_TAG: u64 = 1_101;
+// Number of addresses tried in turn for the version tags of a FilesContainer, in case
+// others created Registers at the first ones
+const FILES_CONTAINER_TAGS_SLOTS: u8 = 8;
+
+impl Safe {
+    /// # Tag a version of a `FilesContainer` with a name.
+    ///
+    /// The version in the URL is tagged, or the latest version if the URL has none.
+    /// The tag can then be used instead of the version hash in URLs, e.g. `safe://<xorurl>?v=v1.2.0`.
+    /// Moving an existing tag to another version is only allowed if `force` is set.
+    /// It returns the version hash the tag points to.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata/", None, true, false, None).await.unwrap();
+    ///     let version = safe.files_container_tag_add(&xorurl, "v1.0.0", false).await.unwrap();
+    ///     let (tagged_version, _files_map) = safe.files_container_get(&format!("{xorurl}?v=v1.0.0")).await.unwrap().unwrap();
+    ///     assert_eq!(tagged_version, version);
+    /// # });
+    /// ```
+    pub async fn files_container_tag_add(
+        &self,
+        url: &str,
+        tag: &str,
+        force: bool,
+    ) -> Result<VersionHash> {
+        if !SafeUrl::is_version_tag_valid(tag) {
+            return Err(Error::InvalidInput(format!(
+                "Invalid version tag '{tag}'. It should have up to 64 alphanumeric, '.', '-' or '_' characters, starting with an alphanumeric one"
+            )));
+        }
+
+        let safe_url = self.parse_and_resolve_url(url).await?;
+        ensure_files_container(&safe_url)?;
+
+        let (version, _) = self
+            .fetch_files_container(&safe_url)
+            .await?
+            .ok_or_else(|| {
+                Error::EmptyContent(format!(
+                    "FilesContainer found at \"{safe_url}\" has no versions to tag"
+                ))
+            })?;
+
+        let (tags_url, tags) = self.fetch_files_container_tags(&safe_url).await?;
+        let tags = match tags {
+            Some(tags) => tags,
+            None => {
+                debug!("Creating Multimap for the version tags of {}", safe_url);
+                let _ = self
+                    .multimap_create(Some(tags_url.xorname()), FILES_CONTAINER_TAGS_TYPE_TAG)
+                    .await?;
+                Multimap::new()
+            }
+        };
+        let tags_url = tags_url.encode(self.xorurl_base);
+
+        let mut replace = BTreeSet::new();
+        for (hash, tagged_version) in tag_entries(&tags, tag)? {
+            if tagged_version == version {
+                debug!("Version {} is already tagged as '{}'", version, tag);
+                return Ok(version);
+            }
+            if !force {
+                return Err(Error::InvalidInput(format!(
+                    "Tag '{tag}' already points to version {tagged_version}. Use the 'force' flag to move it to version {version}"
+                )));
+            }
+            replace.insert(hash);
+        }
+
+        let entry = (tag.as_bytes().to_vec(), version.to_string().into_bytes());
+        let _ = self.multimap_insert(&tags_url, entry, replace).await?;
+
+        Ok(version)
+    }
+
+    /// # List the version tags of a `FilesContainer`, with the version hash each points to.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata/", None, true, false, None).await.unwrap();
+    ///     let version = safe.files_container_tag_add(&xorurl, "latest-stable", false).await.unwrap();
+    ///     let tags = safe.files_container_tags(&xorurl).await.unwrap();
+    ///     assert_eq!(tags.get("latest-stable"), Some(&version));
+    /// # });
+    /// ```
+    pub async fn files_container_tags(&self, url: &str) -> Result<BTreeMap<String, VersionHash>> {
+        let safe_url = self.parse_and_resolve_url(url).await?;
+        ensure_files_container(&safe_url)?;
+
+        let mut tags = BTreeMap::new();
+        if let (_, Some(entries)) = self.fetch_files_container_tags(&safe_url).await? {
+            for (_, (key, value)) in entries.iter() {
+                let tag = str::from_utf8(key)?;
+                tags.insert(tag.to_string(), decode_tagged_version(tag, value)?);
+            }
+        }
+
+        Ok(tags)
+    }
+
+    /// # Remove a version tag from a `FilesContainer`.
+    ///
+    /// The tagged version is not affected, and the tag can be added again afterwards.
+    /// It returns the version hash the tag pointed to.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata/", None, true, false, None).await.unwrap();
+    ///     let version = safe.files_container_tag_add(&xorurl, "v1.0.0", false).await.unwrap();
+    ///     let removed_version = safe.files_container_tag_remove(&xorurl, "v1.0.0").await.unwrap();
+    ///     assert_eq!(removed_version, version);
+    /// # });
+    /// ```
+    pub async fn files_container_tag_remove(&self, url: &str, tag: &str) -> Result<VersionHash> {
+        let safe_url = self.parse_and_resolve_url(url).await?;
+        ensure_files_container(&safe_url)?;
+
+        let (tags_url, tags) = self.fetch_files_container_tags(&safe_url).await?;
+        let entries = tag_entries(&tags.unwrap_or_default(), tag)?;
+        let version = match entries.last() {
+            Some((_, version)) => *version,
+            None => {
+                return Err(Error::ContentNotFound(format!(
+                    "No tag '{tag}' found for FilesContainer at \"{safe_url}\""
+                )))
+            }
+        };
+
+        let _ = self
+            .multimap_remove(
+                &tags_url.encode(self.xorurl_base),
+                entries.into_iter().map(|(hash, _)| hash).collect(),
+            )
+            .await?;
+
+        Ok(version)
+    }
+
+    // Crate's helper to get the version hash a tag of a FilesContainer points to
+    pub(crate) async fn resolve_files_container_tag(
+        &self,
+        safe_url: &SafeUrl,
+        tag: &str,
+    ) -> Result<VersionHash> {
+        debug!("Resolving version tag '{}' of {}", tag, safe_url);
+        let (_, tags) = self.fetch_files_container_tags(safe_url).await?;
+
+        match tag_entries(&tags.unwrap_or_default(), tag)?.last() {
+            Some((_, version)) => Ok(*version),
+            None => Err(Error::VersionNotFound(format!(
+                "Version tag '{tag}' is invalid for FilesContainer found at \"{safe_url}\""
+            ))),
+        }
+    }
+
+    // Fetch the Multimap with the version tags of a FilesContainer, if it has any, along with
+    // the URL it's, or is to be, stored at. Registers found at the derived addresses which
+    // weren't created by the owner of the FilesContainer are skipped.
+    async fn fetch_files_container_tags(
+        &self,
+        safe_url: &SafeUrl,
+    ) -> Result<(SafeUrl, Option<Multimap>)> {
+        let client = self.get_safe_client()?;
+        let owner = client
+            .get_register_owner(self.get_register_address(safe_url)?)
+            .await?;
+
+        for slot in 0..FILES_CONTAINER_TAGS_SLOTS {
+            let tags_url = files_container_tags_url(safe_url, &owner, slot)?;
+            match client
+                .get_register_owner(self.get_register_address(&tags_url)?)
+                .await
+            {
+                Ok(tags_owner) if tags_owner == owner => {
+                    return match self.fetch_multimap(&tags_url).await {
+                        Ok(tags) => Ok((tags_url, Some(tags))),
+                        Err(Error::EmptyContent(_)) => Ok((tags_url, Some(Multimap::new()))),
+                        Err(err) => Err(err),
+                    };
+                }
+                Ok(_) => warn!(
+                    "Skipping the version tags found at {tags_url} for FilesContainer at \"{safe_url}\", as they're not owned by its owner"
+                ),
+                Err(ClientError::ErrorMsg {
+                    source: ErrorMsg::DataNotFound(_),
+                    ..
+                }) => return Ok((tags_url, None)),
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Err(Error::ContentError(format!(
+            "The addresses for the version tags of FilesContainer at \"{safe_url}\" are all taken by Registers not owned by its owner"
+        )))
+    }
+}
+
+fn ensure_files_container(safe_url: &SafeUrl) -> Result<()> {
+    if safe_url.content_type() != ContentType::FilesContainer {
+        return Err(Error::InvalidInput(format!(
+            "Version tags are only supported on FilesContainers, but the URL targets {}: {safe_url}",
+            safe_url.content_type()
+        )));
+    }
+    Ok(())
+}
+
+// The URL of the Multimap storing the version tags of a FilesContainer, at the given slot
+fn files_container_tags_url(safe_url: &SafeUrl, owner: &User, slot: u8) -> Result<SafeUrl> {
+    let mut content = safe_url.xorname().0.to_vec();
+    if let User::Key(owner) = owner {
+        content.extend(owner.to_bytes());
+    }
+    content.push(slot);
+    let url = SafeUrl::from_register(
+        XorName::from_content(&content),
+        FILES_CONTAINER_TAGS_TYPE_TAG,
+        ContentType::Multimap,
+    )?;
+    Ok(url)
+}
+
+// The Multimap entries for a tag, with the version hash they point to.
+// There can be several if the tag was concurrently added to different versions.
+fn tag_entries(tags: &Multimap, tag: &str) -> Result<Vec<(EntryHash, VersionHash)>> {
+    tags.iter()
+        .filter(|(_, (key, _))| key == tag.as_bytes())
+        .map(|(hash, (_, value))| Ok((*hash, decode_tagged_version(tag, value)?)))
+        .collect()
+}
+
+fn decode_tagged_version(tag: &str, value: &[u8]) -> Result<VersionHash> {
+    str::from_utf8(value)?.parse().map_err(|err| {
+        Error::ContentError(format!(
+            "Invalid version hash stored for tag '{tag}': {err:?}"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::test_helpers::new_safe_instance;
+    use anyhow::{anyhow, Result};
+    use assert_matches::assert_matches;
+
+    #[tokio::test]
+    async fn test_files_container_tags() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, _, _) = safe
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
+            .await?;
+        assert!(safe.files_container_tags(&xorurl).await?.is_empty());
+
+        let version0 = safe
+            .files_container_tag_add(&xorurl, "v1.0.0", false)
+            .await?;
+        let (version1, _, _) = safe
+            .files_container_copy(
+                &format!("{xorurl}/sub2.md"),
+                &format!("{xorurl}/copy.md"),
+                false,
+                false,
+            )
+            .await?;
+
+        // the tag resolves to the version it was added to
+        let (version, files_map) = safe
+            .files_container_get(&format!("{xorurl}?v=v1.0.0"))
+            .await?
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+        assert_eq!(version, version0);
+        assert!(!files_map.contains_key("/copy.md"));
+
+        // tags are only moved to other versions if forced
+        let result = safe.files_container_tag_add(&xorurl, "v1.0.0", false).await;
+        assert_matches!(result, Err(Error::InvalidInput(_)));
+        assert_eq!(
+            safe.files_container_tag_add(&xorurl, "v1.0.0", true)
+                .await?,
+            version1
+        );
+        let tagged_version = safe
+            .files_container_tag_add(&format!("{xorurl}?v={version0}"), "v0.9.0", false)
+            .await?;
+        assert_eq!(tagged_version, version0);
+
+        let tags = safe.files_container_tags(&xorurl).await?;
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags["v1.0.0"], version1);
+        assert_eq!(tags["v0.9.0"], version0);
+
+        assert_eq!(
+            safe.files_container_tag_remove(&xorurl, "v0.9.0").await?,
+            version0
+        );
+        let result = safe
+            .files_container_get(&format!("{xorurl}?v=v0.9.0"))
+            .await;
+        assert_matches!(result, Err(Error::VersionNotFound(_)));
+
+        assert_matches!(
+            safe.files_container_tag_add(&xorurl, "not a tag", false)
+                .await,
+            Err(Error::InvalidInput(_))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_files_container_tags_skip_registers_not_created_by_the_owner() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, _, _) = safe
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
+            .await?;
+
+        // someone else creates a Register at the first address of the tags
+        let owner = User::Key(safe.get_safe_client()?.public_key());
+        let squatted_url = files_container_tags_url(&SafeUrl::from_url(&xorurl)?, &owner, 0)?;
+        let other_safe =
+            Safe::connected(Some(crate::Keypair::new_ed25519()), None, None, None).await?;
+        let _ = other_safe
+            .multimap_create(Some(squatted_url.xorname()), FILES_CONTAINER_TAGS_TYPE_TAG)
+            .await?;
+
+        let version = safe
+            .files_container_tag_add(&xorurl, "v1.0.0", false)
+            .await?;
+        let (tagged_version, _) = safe
+            .files_container_get(&format!("{xorurl}?v=v1.0.0"))
+            .await?
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+        assert_eq!(tagged_version, version);
+
+        Ok(())
+    }
+}
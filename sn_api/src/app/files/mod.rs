@@ -7,7 +7,10 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 mod file_system;
+mod files_diff;
+mod files_filter;
 mod files_map;
+mod files_tags;
 mod metadata;
 mod realpath;
 
@@ -24,7 +27,7 @@ use file_system::{
 use files_map::add_or_update_file_item;
 use log::{debug, info, warn};
 use relative_path::RelativePath;
-use sn_client::Client;
+use sn_client::{Client, Error as ClientError};
 use std::{
     collections::{BTreeMap, HashSet},
     iter::FromIterator,
@@ -37,7 +40,11 @@ pub(crate) use files_map::{file_map_for_path, get_file_link_and_metadata};
 pub(crate) use metadata::FileMeta;
 pub(crate) use realpath::RealPath;
 
-pub use files_map::{FileInfo, FilesMap, FilesMapChange, GetAttr};
+pub use files_diff::{files_map_diff, FileDiff, FilesDiff, FilesDiffStat};
+pub use files_filter::{FilesFilter, SAFE_IGNORE_FILE};
+pub use files_map::{
+    dir_file_item, file_item_for_link, FileInfo, FilesMap, FilesMapChange, GetAttr,
+};
 
 // List of files uploaded with details if they were added, updated or removed from FilesContainer
 pub type ProcessedFiles = BTreeMap<PathBuf, FilesMapChange>;
@@ -71,6 +78,9 @@ impl Safe {
 
     /// # Create a `FilesContainer` containing files uploaded from a local folder.
     ///
+    /// The files matching the rules of the `.safeignore` files found in the folder, or
+    /// filtered out by the `filter` provided, are not uploaded, being reported as skipped.
+    ///
     /// ## Example
     ///
     /// ```no_run
@@ -78,7 +88,7 @@ impl Safe {
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     ///     let mut safe = Safe::connected(None, None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, None).await.unwrap();
     ///     assert!(xorurl.contains("safe://"))
     /// # });
     /// ```
@@ -88,10 +98,11 @@ impl Safe {
         dst: Option<&Path>,
         recursive: bool,
         follow_links: bool,
+        filter: Option<&FilesFilter>,
     ) -> Result<(XorUrl, ProcessedFiles, FilesMap)> {
         // Let's upload the files (if not dry_run) and generate the list of local files paths
         let mut processed_files =
-            file_system_dir_walk(self, location.as_ref(), recursive, follow_links).await?;
+            file_system_dir_walk(self, location.as_ref(), recursive, follow_links, filter).await?;
 
         // The FilesContainer is stored on a Register
         // and the link to the serialised FilesMap as the entry's value
@@ -141,7 +152,7 @@ impl Safe {
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, None).await.unwrap();
     ///     let (version, files_map) = safe.files_container_get(&xorurl).await.unwrap().unwrap();
     ///     println!("FilesContainer fetched is at version: {}", version);
     ///     println!("FilesMap of fetched version is: {:?}", files_map);
@@ -216,6 +227,9 @@ impl Safe {
 
     /// # Sync up local folder with the content on a `FilesContainer`.
     ///
+    /// The local files are filtered as with `files_container_create_from`. Files skipped
+    /// locally are thus removed from the container if the `delete` flag is set.
+    ///
     /// ## Example
     ///
     /// ```no_run
@@ -223,8 +237,8 @@ impl Safe {
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, false).await.unwrap();
-    ///     let (optional_version_map, new_processed_files) = safe.files_container_sync("./testdata", &xorurl, true, true, false, false).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, false, None).await.unwrap();
+    ///     let (optional_version_map, new_processed_files) = safe.files_container_sync("./testdata", &xorurl, true, true, false, false, None).await.unwrap();
     ///     if let Some((version, new_files_map)) = optional_version_map {
     ///         println!("FilesContainer is now at version: {}", version);
     ///         println!("The local files that were synced up are: {:?}", new_processed_files);
@@ -241,6 +255,7 @@ impl Safe {
         follow_links: bool,
         delete: bool,
         update_nrs: bool,
+        filter: Option<&FilesFilter>,
     ) -> Result<(Option<(VersionHash, FilesMap)>, ProcessedFiles)> {
         if delete && !recursive {
             return Err(Error::InvalidInput(
@@ -272,8 +287,14 @@ impl Safe {
         // Let's generate the list of local files paths, without uploading any new file yet.
         // Use a dry runner only for this next operation
         let dry_runner = Safe::dry_runner(Some(self.xorurl_base));
-        let processed_files =
-            file_system_dir_walk(&dry_runner, location.as_ref(), recursive, follow_links).await?;
+        let processed_files = file_system_dir_walk(
+            &dry_runner,
+            location.as_ref(),
+            recursive,
+            follow_links,
+            filter,
+        )
+        .await?;
 
         let dst_path = Path::new(safe_url.path());
 
@@ -311,9 +332,9 @@ impl Safe {
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, None).await.unwrap();
     ///     let new_file_name = format!("{}/new_name_test.md", xorurl);
-    ///     let (optional_version_map, new_processed_files) = safe.files_container_add("./testdata/test.md", &new_file_name, false, false, true).await.unwrap();
+    ///     let (optional_version_map, new_processed_files) = safe.files_container_add("./testdata/test.md", &new_file_name, false, false, true, None).await.unwrap();
     ///     if let Some((version, new_files_map)) = optional_version_map {
     ///         println!("FilesContainer is now at version: {}", version);
     ///         println!("The local files that were synced up are: {:?}", new_processed_files);
@@ -328,6 +349,7 @@ impl Safe {
         force: bool,
         update_nrs: bool,
         follow_links: bool,
+        filter: Option<&FilesFilter>,
     ) -> Result<(Option<(VersionHash, FilesMap)>, ProcessedFiles)> {
         debug!("Adding file to FilesContainer at {}", url);
         let (safe_url, current_version, current_files_map) =
@@ -346,7 +368,7 @@ impl Safe {
             // Let's generate the list of local files paths, without uploading any new file yet.
             // Use dry runner only for this next operation
             let dry_runner = Safe::dry_runner(Some(self.xorurl_base));
-            let processed_files = file_system_single_file(&dry_runner, source_path).await?;
+            let processed_files = file_system_single_file(&dry_runner, source_path, filter).await?;
 
             files_map_sync(
                 self,
@@ -384,7 +406,7 @@ impl Safe {
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
-    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true, None).await.unwrap();
     ///     let new_file_name = format!("{}/new_name_test.md", xorurl);
     ///     let (optional_version_map, new_processed_files) = safe.files_container_add_from_raw(Bytes::from("0123456789"), &new_file_name, false, false).await.unwrap();
     ///     if let Some((version, new_files_map)) = optional_version_map {
@@ -431,7 +453,7 @@ impl Safe {
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
-    ///     let (xorurl, processed_files, files_map) = safe.files_container_create_from("./testdata/", None, true, true).await.unwrap();
+    ///     let (xorurl, processed_files, files_map) = safe.files_container_create_from("./testdata/", None, true, true, None).await.unwrap();
     ///     let remote_file_path = format!("{}/test.md", xorurl);
     ///     let (version, new_processed_files, new_files_map) = safe.files_container_remove_path(&remote_file_path, false, false).await.unwrap();
     ///     println!("FilesContainer is now at version: {}", version);
@@ -494,6 +516,402 @@ impl Safe {
         Ok((version, processed_files, new_files_map))
     }
 
+    /// # Store a `FilesMap` as a new version of an existing `FilesContainer`.
+    ///
+    /// Any number of changes can be made to a `FilesMap` obtained with `files_container_get`,
+    /// e.g. inserting links to content stored with `store_bytes`, to then store them all as
+    /// a single new version of the container, rather than one version per change.
+    /// The new version replaces the current one provided, which is expected to be the version
+    /// the `FilesMap` was obtained from, if the container wasn't empty.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::{files::file_item_for_link, Safe};
+    /// # use bytes::Bytes;
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, false, None).await.unwrap();
+    ///     let (version, mut files_map) = safe.files_container_get(&xorurl).await.unwrap().unwrap();
+    ///     for name in ["/one.txt", "/two.txt"] {
+    ///         let link = safe.store_bytes(Bytes::from(name), None).await.unwrap();
+    ///         files_map.insert(name.to_string(), file_item_for_link(&link, "Raw", name.len() as u64));
+    ///     }
+    ///     let new_version = safe.files_container_store_map(&xorurl, Some(version), &files_map, false).await.unwrap();
+    ///     println!("FilesContainer is now at version: {}", new_version);
+    /// # });
+    /// ```
+    pub async fn files_container_store_map(
+        &self,
+        url: &str,
+        current_version: Option<VersionHash>,
+        files_map: &FilesMap,
+        update_nrs: bool,
+    ) -> Result<VersionHash> {
+        // If NRS name shall be updated then the URL has to be an NRS-URL
+        if update_nrs && SafeUrl::from_url(url)?.content_type() != ContentType::NrsMapContainer {
+            return Err(Error::InvalidInput(
+                "'update-nrs' is not allowed since the URL provided is not an NRS URL".to_string(),
+            ));
+        }
+
+        let mut safe_url = self.parse_and_resolve_url(url).await?;
+        if safe_url.content_type() != ContentType::FilesContainer {
+            return Err(Error::InvalidInput(format!(
+                "The URL provided doesn't target a FilesContainer: {url}"
+            )));
+        }
+
+        // If the FilesContainer URL was resolved from an NRS name we need to remove
+        // the version from it so we append the new version to the latest one
+        safe_url.set_content_version(None);
+
+        self.append_version_to_files_container(
+            current_version.into_iter().collect(),
+            files_map,
+            url,
+            safe_url,
+            update_nrs,
+        )
+        .await
+    }
+
+    /// # Copy a file, or a folder with all its content, within or across `FilesContainer`s.
+    ///
+    /// The copies link to the same content as the originals, so nothing is uploaded again.
+    /// If the destination path is an existing folder, or ends with '/', the source is copied
+    /// into it keeping its name. The files copied can be taken from a specific version of the
+    /// source container, while the destination container gets a single new version with all
+    /// the copies. Existing files are only replaced at the destination if `force` is set.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata/", None, true, false, None).await.unwrap();
+    ///     let (version, processed_files, files_map) = safe.files_container_copy(&format!("{xorurl}/subfolder"), &format!("{xorurl}/backup/"), false, false).await.unwrap();
+    ///     println!("FilesContainer is now at version: {}", version);
+    ///     println!("The files that were copied: {:?}", processed_files);
+    ///     println!("The FilesMap of the updated FilesContainer now is: {:?}", files_map);
+    /// # });
+    /// ```
+    pub async fn files_container_copy(
+        &self,
+        src_url: &str,
+        dst_url: &str,
+        force: bool,
+        update_nrs: bool,
+    ) -> Result<(VersionHash, ProcessedFiles, FilesMap)> {
+        self.copy_files_container_path(src_url, dst_url, force, update_nrs, false)
+            .await
+    }
+
+    /// # Move a file, or a folder with all its content, within or across `FilesContainer`s.
+    ///
+    /// As with `files_container_copy`, but removing the source from its container. Within a
+    /// single container, the move is stored as a single new version of it. Across containers,
+    /// each of them gets a new version, the destination one first, and the `update_nrs` flag
+    /// only applies to the destination URL. Such a move is not atomic: if the source container
+    /// fails to be updated, a version with the destination's previous content is appended to it,
+    /// so the items are not left in both containers, and the error is returned.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata/", None, true, false, None).await.unwrap();
+    ///     let (version, processed_files, files_map) = safe.files_container_move(&format!("{xorurl}/test.md"), &format!("{xorurl}/renamed.md"), false, false).await.unwrap();
+    ///     println!("FilesContainer is now at version: {}", version);
+    ///     println!("The files that were moved: {:?}", processed_files);
+    ///     println!("The FilesMap of the updated FilesContainer now is: {:?}", files_map);
+    /// # });
+    /// ```
+    pub async fn files_container_move(
+        &self,
+        src_url: &str,
+        dst_url: &str,
+        force: bool,
+        update_nrs: bool,
+    ) -> Result<(VersionHash, ProcessedFiles, FilesMap)> {
+        self.copy_files_container_path(src_url, dst_url, force, update_nrs, true)
+            .await
+    }
+
+    /// # Compare two versions of a `FilesContainer`.
+    ///
+    /// Reports the files and folders added, removed, modified, or with only their metadata
+    /// changed, from the `from_version` to the `to_version`, or to the latest version if `None`.
+    /// The content of the files is compared by its address, thus no file is downloaded.
+    /// If the URL includes a path, only the items found within it are compared.
+    /// It returns the version compared to, along with the differences found.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata/", None, true, false, None).await.unwrap();
+    ///     let (version0, _files_map) = safe.files_container_get(&xorurl).await.unwrap().unwrap();
+    ///     let _ = safe.files_container_sync("./testdata/subfolder/", &xorurl, true, false, false, false, None).await.unwrap();
+    ///     let (version1, diff) = safe.files_container_diff(&xorurl, version0, None).await.unwrap();
+    ///     println!("Changes from version {} to version {}: {:?}", version0, version1, diff);
+    /// # });
+    /// ```
+    pub async fn files_container_diff(
+        &self,
+        url: &str,
+        from_version: VersionHash,
+        to_version: Option<VersionHash>,
+    ) -> Result<(VersionHash, FilesDiff)> {
+        let mut safe_url = self.parse_and_resolve_url(url).await?;
+        if safe_url.content_type() != ContentType::FilesContainer {
+            return Err(Error::InvalidInput(format!(
+                "The URL doesn't target a FilesContainer: {safe_url}"
+            )));
+        }
+        let path = normalise_files_map_path(&safe_url.path_decoded()?);
+
+        safe_url.set_content_version(Some(from_version));
+        let old_files_map = match self.fetch_files_container(&safe_url).await? {
+            Some((_, files_map)) => files_map,
+            None => FilesMap::default(),
+        };
+
+        safe_url.set_content_version(to_version);
+        let (version, new_files_map) =
+            self.fetch_files_container(&safe_url)
+                .await?
+                .ok_or_else(|| {
+                    Error::EmptyContent(format!("The FilesContainer has no versions: {safe_url}"))
+                })?;
+
+        let diff = files_map_diff(
+            &files_map_items_at_path(&old_files_map, &path),
+            &files_map_items_at_path(&new_files_map, &path),
+        )?;
+        Ok((version, diff))
+    }
+
+    /// # Compare a `FilesContainer` with a local file or folder.
+    ///
+    /// The local files are mapped to paths of the container as `files_container_sync` does,
+    /// and compared with the items found at the URL's path, from the version in the URL, or
+    /// the latest one. The files found locally are reported as added, and those only found on
+    /// the container as removed. The content of the local files is compared by the address it
+    /// would be stored at, thus no file is uploaded or downloaded. It returns the version of the
+    /// container compared, if it's not empty, along with the differences found.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata/", None, true, false, None).await.unwrap();
+    ///     let (version, diff) = safe.files_container_diff_local(&xorurl, "./testdata/", true, false, None).await.unwrap();
+    ///     println!("Local changes not in version {:?}: {:?}", version, diff);
+    /// # });
+    /// ```
+    pub async fn files_container_diff_local<P: AsRef<Path>>(
+        &self,
+        url: &str,
+        location: P,
+        recursive: bool,
+        follow_links: bool,
+        filter: Option<&FilesFilter>,
+    ) -> Result<(Option<VersionHash>, FilesDiff)> {
+        let safe_url = self.parse_and_resolve_url(url).await?;
+        if safe_url.content_type() != ContentType::FilesContainer {
+            return Err(Error::InvalidInput(format!(
+                "The URL doesn't target a FilesContainer: {safe_url}"
+            )));
+        }
+
+        let (version, files_map) = match self.fetch_files_container(&safe_url).await? {
+            Some((version, files_map)) => (Some(version), files_map),
+            None => (None, FilesMap::default()),
+        };
+
+        // Let's generate the FilesMap of the local files, calculating the addresses of their
+        // content without uploading them. Use a dry runner only for this next operation
+        let dry_runner = Safe::dry_runner(Some(self.xorurl_base));
+        let mut processed_files = file_system_dir_walk(
+            &dry_runner,
+            location.as_ref(),
+            recursive,
+            follow_links,
+            filter,
+        )
+        .await?;
+        let local_files_map = files_map_create(
+            &dry_runner,
+            &mut processed_files,
+            location.as_ref(),
+            Some(Path::new(safe_url.path())),
+            follow_links,
+        )
+        .await?;
+
+        let path = normalise_files_map_path(&safe_url.path_decoded()?);
+        let diff = files_map_diff(
+            &files_map_items_at_path(&files_map, &path),
+            &local_files_map,
+        )?;
+        Ok((version, diff))
+    }
+
+    // Private helper to copy, or move, the items at a path of a container to a path of the
+    // same or another container, returning the new version of the destination container.
+    async fn copy_files_container_path(
+        &self,
+        src_url: &str,
+        dst_url: &str,
+        force: bool,
+        update_nrs: bool,
+        remove_src: bool,
+    ) -> Result<(VersionHash, ProcessedFiles, FilesMap)> {
+        let src_path = SafeUrl::from_url(src_url)?.path_decoded()?;
+        if src_path.trim_matches('/').is_empty() {
+            return Err(Error::InvalidInput(
+                "The source URL should include the path of the file or folder to copy".to_string(),
+            ));
+        }
+        let src_path = normalise_files_map_path(&src_path);
+
+        let dst_safe_url = SafeUrl::from_url(dst_url)?;
+        // If NRS name shall be updated then the URL has to be an NRS-URL
+        if update_nrs && dst_safe_url.content_type() != ContentType::NrsMapContainer {
+            return Err(Error::InvalidInput(
+                "'update-nrs' is not allowed since the URL provided is not an NRS URL".to_string(),
+            ));
+        }
+        let dst_path = dst_safe_url.path_decoded()?;
+        let dst_into_folder = dst_path.is_empty() || dst_path.ends_with('/');
+        let dst_path = normalise_files_map_path(&dst_path);
+
+        let mut src_safe_url = self.parse_and_resolve_url(src_url).await?;
+        let mut dst_safe_url = self.parse_and_resolve_url(dst_url).await?;
+        for safe_url in [&src_safe_url, &dst_safe_url] {
+            if safe_url.content_type() != ContentType::FilesContainer {
+                return Err(Error::InvalidInput(format!(
+                    "The URL doesn't target a FilesContainer: {safe_url}"
+                )));
+            }
+        }
+        if remove_src {
+            // Files can only be moved from the latest version of the source container
+            src_safe_url.set_content_version(None);
+        }
+        // The copies are always added to the latest version of the destination container
+        dst_safe_url.set_content_version(None);
+        let same_container = src_safe_url.address() == dst_safe_url.address();
+
+        let (dst_version, mut dst_files_map) =
+            match self.fetch_files_container(&dst_safe_url).await? {
+                Some((version, files_map)) => (Some(version), files_map),
+                None => (None, FilesMap::default()),
+            };
+        let (src_version, src_files_map) =
+            if same_container && src_safe_url.content_version().is_none() {
+                (dst_version, dst_files_map.clone())
+            } else {
+                match self.fetch_files_container(&src_safe_url).await? {
+                    Some((version, files_map)) => (Some(version), files_map),
+                    None => (None, FilesMap::default()),
+                }
+            };
+
+        let src_items = files_map_items_at_path(&src_files_map, &src_path);
+        if src_items.is_empty() {
+            return Err(Error::ContentError(format!(
+                "No content found matching the \"{src_path}\" path on the source FilesContainer"
+            )));
+        }
+
+        let target_path =
+            files_map_copy_target(&dst_files_map, &src_path, &dst_path, dst_into_folder);
+        if same_container
+            && (target_path == src_path || target_path.starts_with(&format!("{src_path}/")))
+        {
+            return Err(Error::InvalidInput(format!(
+                "\"{src_path}\" cannot be copied or moved to \"{target_path}\", within itself"
+            )));
+        }
+
+        let mut processed_files = ProcessedFiles::new();
+        if remove_src {
+            for (path, file_item) in src_items.iter() {
+                let link = file_item
+                    .get(PREDICATE_LINK)
+                    .map(String::to_string)
+                    .unwrap_or_default();
+                processed_files.insert(PathBuf::from(path), FilesMapChange::Removed(link));
+                if same_container {
+                    dst_files_map.remove(path);
+                }
+            }
+        }
+
+        // keep the destination's content to roll it back to, if the source update then fails
+        let prev_dst_files_map = (remove_src && !same_container).then(|| dst_files_map.clone());
+        let (copied_files, dst_files_map) =
+            files_map_insert_items(dst_files_map, &src_items, &src_path, &target_path, force)?;
+        processed_files.extend(copied_files);
+
+        let new_version = self
+            .append_version_to_files_container(
+                dst_version.into_iter().collect(),
+                &dst_files_map,
+                dst_url,
+                dst_safe_url.clone(),
+                update_nrs,
+            )
+            .await?;
+
+        if let Some(prev_dst_files_map) = prev_dst_files_map {
+            let mut src_files_map = src_files_map;
+            src_files_map.retain(|path, _| !src_items.contains_key(path));
+            if let Err(err) = self
+                .append_version_to_files_container(
+                    src_version.into_iter().collect(),
+                    &src_files_map,
+                    src_url,
+                    src_safe_url,
+                    false,
+                )
+                .await
+            {
+                warn!("Failed to remove the moved items from the source container, rolling back the destination container: {err}");
+                self.append_version_to_files_container(
+                    HashSet::from([new_version]),
+                    &prev_dst_files_map,
+                    dst_url,
+                    dst_safe_url,
+                    update_nrs,
+                )
+                .await
+                .map_err(|rollback_err| {
+                    Error::ContentError(format!(
+                        "Failed to remove the moved items from the source container ({err}), and to roll back the destination container: {rollback_err}"
+                    ))
+                })?;
+                return Err(err);
+            }
+        }
+
+        Ok((new_version, processed_files, dst_files_map))
+    }
+
     // Private helper to append new FilesMap entry to container, and/or return
     // information regarding the update and new version if so
     #[allow(clippy::too_many_arguments)]
@@ -613,6 +1031,10 @@ impl Safe {
             },
         )?;
 
+        if self.private_mode {
+            return self.store_private_bytes(bytes, content_type).await;
+        }
+
         let address = if self.dry_run_mode {
             debug!(
                 "Calculating network address for {} bytes of data",
@@ -629,6 +1051,32 @@ impl Safe {
         Ok(xorurl)
     }
 
+    // Private helper to store bytes as private content, i.e. with their data map
+    // encrypted to the group key if set, or to the owner's key otherwise
+    async fn store_private_bytes(&self, bytes: Bytes, content_type: ContentType) -> Result<XorUrl> {
+        let address = if self.dry_run_mode {
+            debug!(
+                "Calculating network address for {} bytes of private data",
+                bytes.len()
+            );
+            // The address of private content changes with every encryption,
+            // thus a random key gives as good an address as any when not connected
+            let public_key = self
+                .private_content_key()
+                .unwrap_or_else(|_| bls::SecretKey::random().public_key());
+            let (address, _) = Client::chunk_private_bytes(bytes, &public_key)?;
+            address
+        } else {
+            debug!("Storing {} bytes of private data", bytes.len());
+            let public_key = self.private_content_key()?;
+            let client = self.get_safe_client()?;
+            client.upload_private_and_verify(bytes, &public_key).await?
+        };
+        let xorurl = SafeUrl::from_private_bytes(address, content_type)?.encode(self.xorurl_base);
+
+        Ok(xorurl)
+    }
+
     /// # Get a file
     /// Get file from the network.
     ///
@@ -655,6 +1103,7 @@ impl Safe {
     pub(crate) async fn fetch_data(&self, safe_url: &SafeUrl, range: Range) -> Result<Bytes> {
         match safe_url.data_type() {
             DataType::File => self.get_bytes(safe_url.xorname(), range).await,
+            DataType::PrivateFile => self.get_private_bytes(safe_url.xorname(), range).await,
             other => Err(Error::ContentError(format!("{other}"))),
         }
     }
@@ -682,6 +1131,48 @@ impl Safe {
         Ok(data)
     }
 
+    // Private helper to fetch private content, decrypting it with the first of our keys it
+    // was encrypted to
+    async fn get_private_bytes(&self, address: XorName, range: Range) -> Result<Bytes> {
+        debug!("Attempting to fetch private data from {address:?}");
+        let client = self.get_safe_client()?;
+        let (start, len) = match range {
+            Some((start, end)) => {
+                let start = start.map(|start_index| start_index as usize).unwrap_or(0);
+                let len = end
+                    .map(|end_index| end_index as usize - start)
+                    .unwrap_or(usize::MAX);
+                (start, len)
+            }
+            None => (0, usize::MAX),
+        };
+
+        for secret_key in self.private_content_secret_keys() {
+            match client
+                .read_private_from(address, &secret_key, start, len)
+                .await
+            {
+                Ok(data) => {
+                    debug!(
+                        "{} bytes of private data successfully retrieved from: {address:?}",
+                        data.len(),
+                    );
+                    return Ok(data);
+                }
+                Err(ClientError::PrivateDataDecryption(_)) => continue,
+                Err(err) => {
+                    return Err(Error::NetDataError(format!(
+                        "Failed to GET private file: {err:?}"
+                    )))
+                }
+            }
+        }
+
+        Err(Error::AccessDenied(format!(
+            "None of the keys available can decrypt the private content at {address:?}"
+        )))
+    }
+
     /// Fetch a file with the provided `SafeUrl`, without performing any type of URL resolution,
     /// from each of the data replicas on the network that match each of the indexes provided.
     pub(crate) async fn fetch_data_replicas(
@@ -690,7 +1181,7 @@ impl Safe {
         replicas_indexes: &[usize],
     ) -> Result<Vec<QueriedDataReplicas>> {
         match safe_url.data_type() {
-            DataType::File => {
+            DataType::File | DataType::PrivateFile => {
                 let addr = safe_url.xorname();
                 debug!("Attempting to fetch data from {addr:?}, with replicas indexes: {replicas_indexes:?}");
                 let client = self.get_safe_client()?;
@@ -752,7 +1243,10 @@ async fn validate_files_add_params(
     // Let's act according to if it's a local file path or a safe:// location
     if source_file.starts_with("safe://") {
         let source_safe_url = SafeUrl::from_url(source_file)?;
-        if source_safe_url.data_type() != DataType::File {
+        if !matches!(
+            source_safe_url.data_type(),
+            DataType::File | DataType::PrivateFile
+        ) {
             return Err(Error::InvalidInput(format!(
                 "The source URL should target a file ('{}'), but the URL provided targets a '{}'",
                 DataType::File,
@@ -833,9 +1327,20 @@ async fn files_map_sync(
 ) -> Result<(ProcessedFiles, FilesMap, u64)> {
     let (location_base_path, dst_base_path) = get_base_paths(location, dst_path);
     let mut updated_files_map = FilesMap::new();
-    let mut processed_files = ProcessedFiles::new();
     let mut success_count = 0;
 
+    // The local files skipped are reported along with the changes made
+    let mut processed_files: ProcessedFiles = new_content
+        .iter()
+        .filter_map(|(local_file_name, change)| match change {
+            FilesMapChange::Skipped(reason) => Some((
+                local_file_name.clone(),
+                FilesMapChange::Skipped(reason.clone()),
+            )),
+            _ => None,
+        })
+        .collect();
+
     for (local_file_name, _) in new_content.iter().filter(|(_, change)| change.is_success()) {
         let file_path = Path::new(&local_file_name);
 
@@ -1107,6 +1612,81 @@ async fn files_map_add_link(
     Ok((processed_files, files_map, success_count))
 }
 
+// Normalise a path of a FilesMap to start with '/', and without trailing '/'
+fn normalise_files_map_path(path: &str) -> String {
+    format!("/{}", path.trim_matches('/'))
+}
+
+// Get the items at a path of a FilesMap, i.e. the file, or the folder and all its content
+fn files_map_items_at_path(files_map: &FilesMap, path: &str) -> FilesMap {
+    let folder_path = format!("{}/", path.trim_end_matches('/'));
+    files_map
+        .iter()
+        .filter(|(item_path, _)| *item_path == path || item_path.starts_with(&folder_path))
+        .map(|(item_path, file_item)| (item_path.clone(), file_item.clone()))
+        .collect()
+}
+
+// Calculate the path the item at the source path is copied to. If the destination path is a
+// folder, the item is copied into it keeping its name, otherwise it's copied as the destination.
+fn files_map_copy_target(
+    dst_files_map: &FilesMap,
+    src_path: &str,
+    dst_path: &str,
+    dst_into_folder: bool,
+) -> String {
+    let dst_is_folder = dst_into_folder
+        || dst_path == "/"
+        || match dst_files_map.get(dst_path) {
+            Some(file_item) => FileMeta::filetype_is_dir(&file_item[PREDICATE_TYPE]),
+            None => !files_map_items_at_path(dst_files_map, dst_path).is_empty(),
+        };
+
+    if dst_is_folder {
+        let name = src_path.rsplit('/').next().unwrap_or_default();
+        format!("{}/{name}", dst_path.trim_end_matches('/'))
+    } else {
+        dst_path.to_string()
+    }
+}
+
+// Insert the items found at the source path into the FilesMap, at the target path, reusing
+// their links. Folders can be merged, but files are only replaced if 'force' is set.
+fn files_map_insert_items(
+    mut files_map: FilesMap,
+    items: &FilesMap,
+    src_path: &str,
+    target_path: &str,
+    force: bool,
+) -> Result<(ProcessedFiles, FilesMap)> {
+    let mut processed_files = ProcessedFiles::new();
+    for (path, file_item) in items.iter() {
+        let new_path = format!("{target_path}{}", &path[src_path.len()..]);
+        let link = file_item
+            .get(PREDICATE_LINK)
+            .map(String::to_string)
+            .unwrap_or_default();
+
+        let change = match files_map.get(&new_path) {
+            None => FilesMapChange::Added(link),
+            Some(existing)
+                if FileMeta::filetype_is_dir(&existing[PREDICATE_TYPE])
+                    && FileMeta::filetype_is_dir(&file_item[PREDICATE_TYPE]) =>
+            {
+                // the folder already exists, its content is merged
+                continue;
+            }
+            Some(_) if force => FilesMapChange::Updated(link),
+            Some(_) => return Err(Error::FileNameConflict(new_path)),
+        };
+
+        files_map.insert(new_path.clone(), file_item.clone());
+        processed_files.insert(PathBuf::from(new_path), change);
+    }
+
+    Ok((processed_files, files_map))
+}
+
 // Remove a path from the FilesMap provided
 fn files_map_remove_path(
     dst_path: &Path,
@@ -1182,7 +1762,7 @@ async fn files_map_create(
     let names = content.keys().cloned().collect::<Vec<_>>();
     for file_name in names {
         let link = match &content[&file_name] {
-            FilesMapChange::Failed(_) => continue,
+            FilesMapChange::Failed(_) | FilesMapChange::Skipped(_) => continue,
             FilesMapChange::Added(link)
             | FilesMapChange::Updated(link)
             | FilesMapChange::Removed(link) => link.clone(),
@@ -1225,7 +1805,7 @@ async fn files_map_create(
 mod tests {
     use super::*;
     use crate::{
-        app::test_helpers::{new_safe_instance, random_nrs_name},
+        app::test_helpers::{new_read_only_safe_instance, new_safe_instance, random_nrs_name},
         register::EntryHash,
     };
     use anyhow::{anyhow, bail, Result};
@@ -1248,7 +1828,7 @@ mod tests {
         safe: &Safe,
     ) -> Result<(String, ProcessedFiles, FilesMap)> {
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from(TEST_DATA_FOLDER, None, true, true)
+            .files_container_create_from(TEST_DATA_FOLDER, None, true, true, None)
             .await?;
 
         assert!(xorurl.starts_with("safe://"));
@@ -1310,7 +1890,7 @@ mod tests {
 
         // let's add a file
         let (content, new_processed_files) = safe
-            .files_container_add("./testdata/test.md", &xorurl, false, false, false)
+            .files_container_add("./testdata/test.md", &xorurl, false, false, false, None)
             .await?;
         let (_, new_files_map) =
             content.ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
@@ -1352,7 +1932,7 @@ mod tests {
         let safe = new_safe_instance().await?;
         let filename = Path::new("./testdata/test.md");
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from(&filename.display().to_string(), None, false, false)
+            .files_container_create_from(&filename.display().to_string(), None, false, false, None)
             .await?;
 
         assert!(xorurl.starts_with("safe://"));
@@ -1372,7 +1952,7 @@ mod tests {
         let mut safe = new_safe_instance().await?;
         safe.dry_run_mode = true;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from(TEST_DATA_FOLDER, None, true, false)
+            .files_container_create_from(TEST_DATA_FOLDER, None, true, false, None)
             .await?;
 
         assert!(xorurl.starts_with("safe://"));
@@ -1418,7 +1998,7 @@ mod tests {
     async fn test_files_container_create_from_folder_without_trailing_slash() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from(TEST_DATA_FOLDER_NO_SLASH, None, true, true)
+            .files_container_create_from(TEST_DATA_FOLDER_NO_SLASH, None, true, true, None)
             .await?;
 
         assert!(xorurl.starts_with("safe://"));
@@ -1501,6 +2081,7 @@ mod tests {
                 Some(Path::new("/myroot")),
                 true,
                 true,
+                None,
             )
             .await?;
 
@@ -1548,6 +2129,7 @@ mod tests {
                 Some(Path::new("/myroot/")),
                 true,
                 true,
+                None,
             )
             .await?;
 
@@ -1597,7 +2179,15 @@ mod tests {
             .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
 
         let (content, new_processed_files) = safe
-            .files_container_sync("./testdata/subfolder/", &xorurl, true, true, false, false)
+            .files_container_sync(
+                "./testdata/subfolder/",
+                &xorurl,
+                true,
+                true,
+                false,
+                false,
+                None,
+            )
             .await?;
         let (version, new_files_map) =
             content.ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
@@ -1662,7 +2252,15 @@ mod tests {
         // set dry_run flag on
         safe.dry_run_mode = true;
         let (content, new_processed_files) = safe
-            .files_container_sync("./testdata/subfolder/", &xorurl, true, true, false, false)
+            .files_container_sync(
+                "./testdata/subfolder/",
+                &xorurl,
+                true,
+                true,
+                false,
+                false,
+                None,
+            )
             .await?;
         let (_, new_files_map) =
             content.ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
@@ -1724,7 +2322,7 @@ mod tests {
     async fn test_files_container_sync_same_size() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from("./testdata/test.md", None, false, false)
+            .files_container_create_from("./testdata/test.md", None, false, false, None)
             .await?;
 
         assert_eq!(processed_files.len(), 1);
@@ -1740,6 +2338,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await?;
         let (_, new_files_map) =
@@ -1789,6 +2388,7 @@ mod tests {
                 false,
                 // FIXME: shall we just set this to false
                 true, // this flag requests the update-nrs
+                None,
             )
             .await
         {
@@ -1826,6 +2426,7 @@ mod tests {
                 false,
                 true, // this sets the delete flag
                 false,
+                None,
             )
             .await?;
         let (version1, new_files_map) =
@@ -1889,6 +2490,7 @@ mod tests {
                 false, // do not follow links
                 true,  // this sets the delete flag
                 false,
+                None,
             )
             .await
         {
@@ -1948,6 +2550,7 @@ mod tests {
                 false,
                 false,
                 true, // this flag requests the update-nrs
+                None,
             )
             .await
         {
@@ -1993,6 +2596,7 @@ mod tests {
                 false,
                 false,
                 true, // this flag requests the update-nrs
+                None,
             )
             .await?;
         let (version1, _) =
@@ -2022,6 +2626,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await?;
         let (_, new_files_map) =
@@ -2090,6 +2695,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await?;
         let (_, new_files_map) =
@@ -2184,6 +2790,7 @@ mod tests {
                 false,
                 true, // this sets the delete flag,
                 false,
+                None,
             )
             .await?;
         let (version1, _) =
@@ -2221,6 +2828,7 @@ mod tests {
                 false,
                 true, // this sets the delete flag
                 false,
+                None,
             )
             .await?;
         let (version1, new_files_map) =
@@ -2316,7 +2924,7 @@ mod tests {
     async fn test_files_container_sync_with_nrs_url() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, _, _) = safe
-            .files_container_create_from("./testdata/test.md", None, false, true)
+            .files_container_create_from("./testdata/test.md", None, false, true, None)
             .await?;
         let _ = safe.fetch(&xorurl, None).await;
         let (version0, _) = safe
@@ -2333,7 +2941,15 @@ mod tests {
         let _ = safe.fetch(&nrs_xorurl.to_string(), None).await?;
 
         let _ = safe
-            .files_container_sync("./testdata/subfolder/", &xorurl, false, false, false, false)
+            .files_container_sync(
+                "./testdata/subfolder/",
+                &xorurl,
+                false,
+                false,
+                false,
+                false,
+                None,
+            )
             .await?;
 
         let (version2_content, _) = safe
@@ -2344,6 +2960,7 @@ mod tests {
                 false,
                 false,
                 true, // this flag requests the update-nrs
+                None,
             )
             .await?;
         let (version2, _) =
@@ -2373,7 +2990,7 @@ mod tests {
     async fn test_files_container_add() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from("./testdata/subfolder/", None, false, true)
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
             .await?;
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2393,6 +3010,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await?;
         let (version1, new_files_map) =
@@ -2429,7 +3047,7 @@ mod tests {
     async fn test_files_container_add_dry_run() -> Result<()> {
         let mut safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from("./testdata/subfolder/", None, false, true)
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
             .await?;
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2446,6 +3064,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await?;
         let (_, new_files_map) =
@@ -2463,6 +3082,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await?;
         let (_, new_files_map2) =
@@ -2490,14 +3110,21 @@ mod tests {
     async fn test_files_container_add_dir() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from("./testdata/subfolder/", None, false, true)
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
             .await?;
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT); // root "/" + 2 files
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         let _ = safe.fetch(&xorurl, None).await;
 
         match safe
-            .files_container_add(TEST_DATA_FOLDER_NO_SLASH, &xorurl, false, false, false)
+            .files_container_add(
+                TEST_DATA_FOLDER_NO_SLASH,
+                &xorurl,
+                false,
+                false,
+                false,
+                None,
+            )
             .await
         {
             Ok(_) => Err(anyhow!(
@@ -2521,7 +3148,7 @@ mod tests {
     async fn test_files_container_add_existing_name() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from("./testdata/subfolder/", None, false, true)
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
             .await?;
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2544,6 +3171,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await?;
         let (version1, new_files_map) =
@@ -2567,6 +3195,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await?;
         let (version2, new_files_map) =
@@ -2589,6 +3218,7 @@ mod tests {
                 true, //force it
                 false,
                 false,
+                None,
             )
             .await?;
         let (version3, new_files_map) =
@@ -2610,14 +3240,22 @@ mod tests {
     async fn test_files_container_fail_add_or_sync_invalid_path() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from("./testdata/test.md", None, false, true)
+            .files_container_create_from("./testdata/test.md", None, false, true, None)
             .await?;
         assert_eq!(processed_files.len(), 1);
         assert_eq!(files_map.len(), 1);
         let _ = safe.fetch(&xorurl, None).await;
 
         match safe
-            .files_container_sync("/non-existing-path", &xorurl, false, false, false, false)
+            .files_container_sync(
+                "/non-existing-path",
+                &xorurl,
+                false,
+                false,
+                false,
+                false,
+                None,
+            )
             .await
         {
             Ok(_) => {
@@ -2642,6 +3280,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await
         {
@@ -2664,7 +3303,7 @@ mod tests {
     async fn test_files_container_add_a_url() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from("./testdata/subfolder/", None, false, true)
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
             .await?;
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2688,6 +3327,7 @@ mod tests {
                 false,
                 false,
                 false,
+                None,
             )
             .await?;
         let (version1, new_files_map) =
@@ -2731,6 +3371,7 @@ mod tests {
                 true, // force to overwrite it with new link
                 false,
                 false,
+                None,
             )
             .await?;
         let (mut version2, mut new_files_map) =
@@ -2745,6 +3386,7 @@ mod tests {
                     true, // force to overwrite it with new link
                     false,
                     false,
+                    None,
                 )
                 .await?;
 
@@ -2776,7 +3418,7 @@ mod tests {
     async fn test_files_container_add_from_raw() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (xorurl, processed_files, files_map) = safe
-            .files_container_create_from("./testdata/subfolder/", None, false, true)
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
             .await?;
         assert_eq!(processed_files.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
         assert_eq!(files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT);
@@ -2833,6 +3475,308 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_files_container_store_map() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, _, _) = safe
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
+            .await?;
+        let (version0, mut files_map) = safe
+            .files_container_get(&xorurl)
+            .await?
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+
+        // several changes are stored as a single version
+        let data = Bytes::from("0123456789");
+        let file_xorurl = safe.store_bytes(data.clone(), None).await?;
+        files_map.insert(
+            "/new_file.md".to_string(),
+            file_item_for_link(&file_xorurl, "Raw", data.len() as u64),
+        );
+        files_map.insert("/new_folder".to_string(), dir_file_item());
+
+        let version1 = safe
+            .files_container_store_map(&xorurl, Some(version0), &files_map, false)
+            .await?;
+        assert_ne!(version1, version0);
+
+        let (version, stored_files_map) = safe
+            .files_container_get(&xorurl)
+            .await?
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+        assert_eq!(version, version1);
+        assert_eq!(stored_files_map, files_map);
+        assert_eq!(stored_files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT + 2);
+        assert_eq!(
+            stored_files_map["/new_file.md"][PREDICATE_LINK],
+            file_xorurl
+        );
+        assert_eq!(
+            stored_files_map["/new_folder"][PREDICATE_TYPE],
+            MIMETYPE_FILESYSTEM_DIR
+        );
+
+        let retrieved = safe
+            .files_get(&format!("{xorurl}/new_file.md"), None)
+            .await?;
+        assert_eq!(retrieved, data);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_files_container_copy() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, _, files_map) = new_files_container_from_testdata(&safe).await?;
+
+        // a folder is copied with all its content into an existing folder
+        let (version, processed_files, new_files_map) = safe
+            .files_container_copy(
+                &format!("{xorurl}/subfolder"),
+                &format!("{xorurl}/.subhidden"),
+                false,
+                false,
+            )
+            .await?;
+        assert_eq!(processed_files.len(), SUBFOLDER_NO_SLASH_PUT_FILEITEM_COUNT);
+        assert_eq!(
+            new_files_map.len(),
+            TESTDATA_PUT_FILESMAP_COUNT + SUBFOLDER_NO_SLASH_PUT_FILEITEM_COUNT
+        );
+        assert_eq!(
+            new_files_map["/.subhidden/subfolder/sub2.md"][PREDICATE_LINK],
+            files_map["/subfolder/sub2.md"][PREDICATE_LINK]
+        );
+        assert_matches!(
+            &processed_files[Path::new("/.subhidden/subfolder/sub2.md")],
+            FilesMapChange::Added(l) if *l == files_map["/subfolder/sub2.md"][PREDICATE_LINK]
+        );
+        // the originals are kept
+        assert!(new_files_map.contains_key("/subfolder/sub2.md"));
+
+        let (current_version, _) = safe
+            .files_container_get(&xorurl)
+            .await?
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+        assert_eq!(current_version, version);
+
+        // files are not replaced unless forced
+        let result = safe
+            .files_container_copy(
+                &format!("{xorurl}/test.md"),
+                &format!("{xorurl}/another.md"),
+                false,
+                false,
+            )
+            .await;
+        assert_matches!(result, Err(Error::FileNameConflict(path)) if path == "/another.md");
+
+        let (_, processed_files, new_files_map) = safe
+            .files_container_copy(
+                &format!("{xorurl}/test.md"),
+                &format!("{xorurl}/another.md"),
+                true,
+                false,
+            )
+            .await?;
+        assert_matches!(
+            &processed_files[Path::new("/another.md")],
+            FilesMapChange::Updated(l) if *l == files_map["/test.md"][PREDICATE_LINK]
+        );
+        assert_eq!(
+            new_files_map["/another.md"][PREDICATE_LINK],
+            files_map["/test.md"][PREDICATE_LINK]
+        );
+
+        // a folder cannot be copied within itself
+        let result = safe
+            .files_container_copy(
+                &format!("{xorurl}/subfolder"),
+                &format!("{xorurl}/subfolder/"),
+                false,
+                false,
+            )
+            .await;
+        assert_matches!(result, Err(Error::InvalidInput(_)));
+
+        // files are copied across containers
+        let (other_xorurl, _, _) = safe
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
+            .await?;
+        let (_, _, new_files_map) = safe
+            .files_container_copy(
+                &format!("{xorurl}/test.md"),
+                &format!("{other_xorurl}/"),
+                false,
+                false,
+            )
+            .await?;
+        assert_eq!(new_files_map.len(), SUBFOLDER_PUT_FILEITEM_COUNT + 1);
+        assert_eq!(
+            new_files_map["/test.md"][PREDICATE_LINK],
+            files_map["/test.md"][PREDICATE_LINK]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_files_container_move() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, _, files_map) = new_files_container_from_testdata(&safe).await?;
+
+        // a file is renamed within the container
+        let (_, processed_files, new_files_map) = safe
+            .files_container_move(
+                &format!("{xorurl}/test.md"),
+                &format!("{xorurl}/renamed.md"),
+                false,
+                false,
+            )
+            .await?;
+        let link = files_map["/test.md"][PREDICATE_LINK].clone();
+        assert_eq!(processed_files.len(), 2);
+        assert_matches!(
+            &processed_files[Path::new("/test.md")],
+            FilesMapChange::Removed(l) if *l == link
+        );
+        assert_matches!(
+            &processed_files[Path::new("/renamed.md")],
+            FilesMapChange::Added(l) if *l == link
+        );
+        assert_eq!(new_files_map.len(), TESTDATA_PUT_FILESMAP_COUNT);
+        assert!(!new_files_map.contains_key("/test.md"));
+        assert_eq!(new_files_map["/renamed.md"][PREDICATE_LINK], link);
+
+        // a folder is moved to another container
+        let (other_xorurl, _, _) = safe
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
+            .await?;
+        let (_, _, other_files_map) = safe
+            .files_container_move(
+                &format!("{xorurl}/subfolder"),
+                &format!("{other_xorurl}/moved"),
+                false,
+                false,
+            )
+            .await?;
+        assert_eq!(
+            other_files_map["/moved/subexists.md"][PREDICATE_LINK],
+            files_map["/subfolder/subexists.md"][PREDICATE_LINK]
+        );
+
+        let (_, src_files_map) = safe
+            .files_container_get(&xorurl)
+            .await?
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+        assert!(src_files_map
+            .keys()
+            .all(|path| !path.starts_with("/subfolder")));
+
+        // moving a path which doesn't exist fails
+        let result = safe
+            .files_container_move(
+                &format!("{xorurl}/test.md"),
+                &format!("{xorurl}/other.md"),
+                false,
+                false,
+            )
+            .await;
+        assert_matches!(result, Err(Error::ContentError(_)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_files_container_diff() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, _, files_map) = new_files_container_from_testdata(&safe).await?;
+        let (version0, _) = safe
+            .files_container_get(&xorurl)
+            .await?
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+
+        // the container is the same as the local folder it was created from
+        let (version, diff) = safe
+            .files_container_diff_local(&xorurl, TEST_DATA_FOLDER, true, false, None)
+            .await?;
+        assert_eq!(version, Some(version0));
+        assert!(diff.is_empty(), "unexpected differences: {diff:?}");
+
+        let (version1, _, _) = safe
+            .files_container_move(
+                &format!("{xorurl}/test.md"),
+                &format!("{xorurl}/renamed.md"),
+                false,
+                false,
+            )
+            .await?;
+
+        let (version, diff) = safe.files_container_diff(&xorurl, version0, None).await?;
+        assert_eq!(version, version1);
+        assert_eq!(diff.len(), 2);
+        assert_matches!(&diff["/test.md"], FileDiff::Removed(item) if item == &files_map["/test.md"]);
+        assert_matches!(&diff["/renamed.md"], FileDiff::Added(item) if item[PREDICATE_LINK] == files_map["/test.md"][PREDICATE_LINK]);
+
+        // only the items within the URL path are compared
+        let (_, diff) = safe
+            .files_container_diff(&format!("{xorurl}/subfolder"), version0, Some(version1))
+            .await?;
+        assert!(diff.is_empty());
+
+        // and the local files are reported as added
+        let (_, diff) = safe
+            .files_container_diff_local(&xorurl, TEST_DATA_FOLDER, true, false, None)
+            .await?;
+        assert_eq!(diff.len(), 2);
+        assert_matches!(&diff["/test.md"], FileDiff::Added(_));
+        assert_matches!(&diff["/renamed.md"], FileDiff::Removed(_));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_files_container_create_from_private() -> Result<()> {
+        let group_key = bls::SecretKey::random();
+        let mut safe = new_safe_instance().await?;
+        safe.private_mode = true;
+        safe.set_group_key(Some(group_key.clone()));
+
+        let (xorurl, processed_files, files_map) = new_files_container_from_testdata(&safe).await?;
+        let file_link = &files_map["/test.md"][PREDICATE_LINK];
+        assert_eq!(
+            processed_files[Path::new("./testdata/test.md")].link(),
+            Some(file_link)
+        );
+        assert!(SafeUrl::from_url(file_link)?.is_private());
+
+        // another user holding the group key reads the content transparently
+        let mut group_safe = new_read_only_safe_instance().await?;
+        group_safe.set_group_key(Some(group_key));
+        let (_, fetched_files_map) = group_safe
+            .files_container_get(&xorurl)
+            .await?
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+        assert_eq!(fetched_files_map, files_map);
+        let data = group_safe
+            .files_get(&format!("{xorurl}/test.md"), None)
+            .await?;
+        assert_eq!(data, Bytes::from(std::fs::read("./testdata/test.md")?));
+
+        // but without it, neither the FilesMap nor the files can be read
+        let other_safe = new_read_only_safe_instance().await?;
+        assert_matches!(
+            other_safe.files_container_get(&xorurl).await,
+            Err(Error::AccessDenied(_))
+        );
+        assert_matches!(
+            other_safe.files_get(file_link, None).await,
+            Err(Error::AccessDenied(_))
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_files_container_remove_path() -> Result<()> {
         let safe = new_safe_instance().await?;
@@ -19,8 +19,11 @@ pub mod wallet;
 
 pub use crate::safeurl::*;
 pub use consts::DEFAULT_XORURL_BASE;
-pub use sn_client::DEFAULT_NETWORK_CONTACTS_FILE_NAME;
-pub use sn_interface::network_knowledge::SectionTree;
+pub use sn_client::{
+    NetworkHealthReport, SectionHealthReport, SectionMembersReport,
+    DEFAULT_NETWORK_CONTACTS_FILE_NAME,
+};
+pub use sn_interface::network_knowledge::{NetworkContacts, SectionTree};
 pub use xor_name::{XorName, XOR_NAME_LEN};
 
 // --------------------------------------------------------------------
@@ -38,8 +41,11 @@ use sn_client::Client;
 use sn_dbc::Owner;
 use sn_interface::types::Keypair;
 
+use bls::{PublicKey as BlsPublicKey, SecretKey as BlsSecretKey};
+
 use std::time::Duration;
 use tracing::debug;
+use xor_name::Prefix;
 
 const APP_NOT_CONNECTED: &str = "Application is not connected to the network";
 
@@ -48,6 +54,10 @@ pub struct Safe {
     client: Option<Client>,
     pub xorurl_base: XorUrlBase,
     pub dry_run_mode: bool,
+    /// When set, data is stored as private content, encrypted to the group key if
+    /// one was set, or otherwise to the key the client is connected with.
+    pub private_mode: bool,
+    group_key: Option<BlsSecretKey>,
 }
 
 impl Safe {
@@ -57,6 +67,8 @@ impl Safe {
             client: None,
             xorurl_base: xorurl_base.unwrap_or(DEFAULT_XORURL_BASE),
             dry_run_mode: true,
+            private_mode: false,
+            group_key: None,
         }
     }
 
@@ -71,6 +83,8 @@ impl Safe {
             client: None,
             xorurl_base: xorurl_base.unwrap_or(DEFAULT_XORURL_BASE),
             dry_run_mode: false,
+            private_mode: false,
+            group_key: None,
         };
 
         safe.connect(keypair, timeout, dbc_owner).await?;
@@ -122,6 +136,56 @@ impl Safe {
         Ok(section_tree)
     }
 
+    /// Crawl the network, querying the elders of every section for their view of its
+    /// membership and their storage levels.
+    ///
+    /// Sections not known beforehand are discovered through the Anti-Entropy responses
+    /// received while crawling, so the `SectionTree` is updated as a result too.
+    pub async fn crawl_network(&self) -> Result<NetworkHealthReport> {
+        let report = self.get_safe_client()?.crawl_network().await?;
+        Ok(report)
+    }
+
+    /// Query the elders of the section matching the prefix for its current members, each
+    /// signed by the section.
+    ///
+    /// The proofs received are verified against our `SectionTree`, which is updated with
+    /// the latest SAP of the section as a result.
+    pub async fn section_members(&self, prefix: &Prefix) -> Result<SectionMembersReport> {
+        let report = self.get_safe_client()?.section_members(prefix).await?;
+        Ok(report)
+    }
+
+    /// Set a key shared among a group of users, to which the content stored in private mode
+    /// is then encrypted, so any of them can read it back when setting the same key.
+    pub fn set_group_key(&mut self, group_key: Option<BlsSecretKey>) {
+        self.group_key = group_key;
+    }
+
+    // Private helper to obtain the key private content is to be encrypted to
+    pub(crate) fn private_content_key(&self) -> Result<BlsPublicKey> {
+        if let Some(group_key) = &self.group_key {
+            return Ok(group_key.public_key());
+        }
+
+        match self.get_safe_client()?.keypair() {
+            Keypair::Bls(keypair) => Ok(keypair.public),
+            _ => Err(Error::InvalidInput(
+                "Private content can only be encrypted to a BLS key, either the one the client is connected with or a group key".to_string(),
+            )),
+        }
+    }
+
+    // Private helper to obtain the keys private content can be decrypted with,
+    // the group key being tried first
+    pub(crate) fn private_content_secret_keys(&self) -> Vec<BlsSecretKey> {
+        let mut keys: Vec<_> = self.group_key.iter().cloned().collect();
+        if let Some(Keypair::Bls(keypair)) = self.client.as_ref().map(|client| client.keypair()) {
+            keys.push(keypair.secret.inner().clone());
+        }
+        keys
+    }
+
     // Private helper to obtain the Client instance
     pub(crate) fn get_safe_client(&self) -> Result<&Client> {
         match &self.client {
@@ -10,17 +10,33 @@ mod nrs_map;
 
 pub use crate::app::multimap::Multimap;
 pub use crate::safeurl::{ContentType, DataType, VersionHash};
-pub use nrs_map::NrsMap;
+pub use nrs_map::{NrsMap, NrsRecord};
 
-use crate::{app::Safe, register::EntryHash, Error, Result, SafeUrl};
+use crate::{app::Safe, register::EntryHash, Error, PublicKey, Result, SafeUrl};
 
 use log::{debug, info};
+use sn_client::Error as ClientError;
+use sn_interface::{messaging::data::Error as ErrorMsg, types::register::User};
 use std::collections::{BTreeMap, BTreeSet};
 use std::str;
+use xor_name::XorName;
 
 /// Type tag to use for the NrsMapContainer stored on Register
 pub const NRS_MAP_TYPE_TAG: u64 = 1_500;
 
+// Whether the delegation of the public name itself is followed when looking up the NRS map
+// holding its records. Delegations of its parent subnames are always followed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NameDelegation {
+    // Readers get the records set by the delegated owner.
+    Follow,
+    // Writers only write to the delegated map if the subname was delegated to them, so the
+    // owner of the parent map can still change or remove the delegation.
+    FollowIfOwned,
+    // The delegation record itself is kept in the parent map.
+    Ignore,
+}
+
 impl Safe {
     /// # Creates a `nrs_map_container` for a chosen top name
     /// ```ignore
@@ -50,6 +66,65 @@ impl Safe {
         Ok(nrs_url)
     }
 
+    /// # Delegates a subname to another owner
+    /// ```ignore
+    /// safe://<subName>.<topName>/path/to/whatever?var=value
+    ///        |-----------------|
+    ///            Public Name
+    /// ```
+    /// Associates the given subname to a delegation record, so its records, and those of its own
+    /// subnames, are kept in a separate NRS map owned by the given key rather than in the map of
+    /// its topname. The delegated owner needs to create that map with `nrs_create_delegated`
+    /// before the subname can be resolved. Delegations can be nested, e.g. a team which was
+    /// delegated `team.example` can delegate `project.team.example` in turn.
+    /// Returns the versioned NRS `SafeUrl` (containing a `VersionHash`) of the delegation record.
+    pub async fn nrs_delegate(&self, public_name: &str, owner: PublicKey) -> Result<SafeUrl> {
+        self.nrs_associate_record(public_name, &NrsRecord::Delegation(owner))
+            .await
+    }
+
+    /// # Creates the NRS map of a subname delegated to this client
+    /// Creates the NRS map for a subname delegated to the client's key with `nrs_delegate`, at an
+    /// address derived from the subname and the key.
+    /// Records can then be associated to the subname, and to its own subnames, with
+    /// `nrs_associate` or `nrs_add` as for any other public name.
+    /// Returns the XOR-URL of the delegated NRS map, since it isn't found at the address of the
+    /// topname as other NRS maps are.
+    pub async fn nrs_create_delegated(&self, public_name: &str) -> Result<SafeUrl> {
+        info!("Creating a delegated NRS map for: {}", public_name);
+
+        let _ = validate_nrs_public_name(public_name)?;
+        let client_pk = self.get_safe_client()?.public_key();
+
+        let (parent_url, _) = self
+            .find_nrs_map(public_name, NameDelegation::Ignore)
+            .await?;
+        let parent_map = self.fetch_nrs_map(&parent_url, public_name, None).await?;
+        match parent_map.map.get(public_name) {
+            Some(NrsRecord::Delegation(owner)) if *owner == client_pk => {}
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "The NRS public name \"{public_name}\" has not been delegated to this \
+                    client's key {client_pk:x}",
+                )))
+            }
+        }
+
+        let map_url = delegated_nrs_map_url(public_name, &client_pk)?;
+        if self
+            .delegated_nrs_map(public_name, &client_pk)
+            .await
+            .is_ok()
+        {
+            return Err(Error::NrsNameAlreadyExists(public_name.to_owned()));
+        }
+        let _ = self
+            .multimap_create(Some(map_url.xorname()), NRS_MAP_TYPE_TAG)
+            .await?;
+
+        Ok(map_url)
+    }
+
     /// # Associates a public name to a link
     /// The top name of the input public name needs to be registered first with `nrs_create`
     ///
@@ -63,16 +138,38 @@ impl Safe {
     /// Returns the versioned NRS `SafeUrl` (containing a `VersionHash`) now pointing to the provided link:
     /// `safe://{public_name}?v={version_hash}`
     pub async fn nrs_associate(&self, public_name: &str, link: &SafeUrl) -> Result<SafeUrl> {
+        self.nrs_associate_record(public_name, &NrsRecord::Link(link.clone()))
+            .await
+    }
+
+    /// # Associates a public name to a record
+    /// The top name of the input public name needs to be registered first with `nrs_create`
+    ///
+    /// Associates the given `public_name` to any type of `NrsRecord`, e.g. an alias to another
+    /// public name, or a text record, replacing any record it was associated to.
+    /// Errors out if the topname is not registered.
+    /// Returns the versioned NRS `SafeUrl` (containing a `VersionHash`) now pointing to the provided record:
+    /// `safe://{public_name}?v={version_hash}`
+    pub async fn nrs_associate_record(
+        &self,
+        public_name: &str,
+        record: &NrsRecord,
+    ) -> Result<SafeUrl> {
         info!(
             "Associating public name \"{}\" to \"{}\" in NRS map container",
-            public_name, link
+            public_name, record
         );
 
         let mut url = validate_nrs_public_name(public_name)?;
-        validate_nrs_url(link)?;
+        validate_nrs_record(public_name, record)?;
+        let name_delegation = match record {
+            NrsRecord::Delegation(_) => NameDelegation::Ignore,
+            _ => NameDelegation::FollowIfOwned,
+        };
+        let (map_url, _) = self.find_nrs_map(public_name, name_delegation).await?;
 
         let current_versions = self
-            .fetch_multimap_values_by_key(&url, public_name.as_bytes())
+            .fetch_multimap_values_by_key(&map_url, public_name.as_bytes())
             .await?
             .into_iter()
             .map(|(hash, _)| hash)
@@ -80,10 +177,10 @@ impl Safe {
 
         let entry = (
             public_name.as_bytes().to_vec(),
-            link.to_string().as_bytes().to_vec(),
+            record.to_string().as_bytes().to_vec(),
         );
         let entry_hash = self
-            .multimap_insert(&url.to_string(), entry, current_versions)
+            .multimap_insert(&map_url.to_string(), entry, current_versions)
             .await?;
         set_nrs_url_props(&mut url, entry_hash)?;
 
@@ -97,9 +194,25 @@ impl Safe {
     /// `safe://{public_name}?v={version_hash}`
     /// Also returns a bool to indicate whether it registered the topname in the process or not.
     pub async fn nrs_add(&self, public_name: &str, link: &SafeUrl) -> Result<(SafeUrl, bool)> {
+        self.nrs_add_record(public_name, &NrsRecord::Link(link.clone()))
+            .await
+    }
+
+    /// # Associates any public name to a record
+    ///
+    /// Associates the given `public_name` to any type of `NrsRecord` registering the topname on
+    /// the way if needed, as `nrs_add` does for links.
+    /// Returns the versioned NRS `SafeUrl` (containing a `VersionHash`) now pointing to the provided record:
+    /// `safe://{public_name}?v={version_hash}`
+    /// Also returns a bool to indicate whether it registered the topname in the process or not.
+    pub async fn nrs_add_record(
+        &self,
+        public_name: &str,
+        record: &NrsRecord,
+    ) -> Result<(SafeUrl, bool)> {
         info!(
             "Adding public name \"{}\" to \"{}\" in an NRS map container",
-            public_name, link
+            public_name, record
         );
 
         let url = validate_nrs_public_name(public_name)?;
@@ -111,7 +224,7 @@ impl Safe {
             Err(e) => Err(e),
         }?;
 
-        let new_url = self.nrs_associate(public_name, link).await?;
+        let new_url = self.nrs_associate_record(public_name, record).await?;
         Ok((new_url, did_register_topname))
     }
 
@@ -134,15 +247,18 @@ impl Safe {
         );
 
         let mut url = validate_nrs_public_name(public_name)?;
+        let (map_url, _) = self
+            .find_nrs_map(public_name, NameDelegation::FollowIfOwned)
+            .await?;
         let current_versions = self
-            .fetch_multimap_values_by_key(&url, public_name.as_bytes())
+            .fetch_multimap_values_by_key(&map_url, public_name.as_bytes())
             .await?
             .into_iter()
             .map(|(hash, _)| hash)
             .collect();
 
         let entry_hash = self
-            .multimap_remove(&url.to_string(), current_versions)
+            .multimap_remove(&map_url.to_string(), current_versions)
             .await?;
         set_nrs_url_props(&mut url, entry_hash)?;
         Ok(url)
@@ -162,31 +278,54 @@ impl Safe {
     /// but the `NrsMap` returned will ignore those conflicts.
     /// Otherwise, it returns an error.
     /// Returns the associated `SafeUrl` for the given public name for that version along with an `NrsMap`
+    /// The `SafeUrl` is only returned for `Link` and `Service` records, `nrs_get_record` returns any record.
     pub async fn nrs_get(
         &self,
         public_name: &str,
         version: Option<VersionHash>,
     ) -> Result<(Option<SafeUrl>, NrsMap)> {
+        let (record, nrs_map) = self.nrs_get_record(public_name, version).await?;
+        let url = record.and_then(|record| record.link().cloned());
+        Ok((url, nrs_map))
+    }
+
+    /// # Gets a public name's associated record
+    /// Same as `nrs_get`, but returning the associated `NrsRecord` of any type,
+    /// e.g. an alias to another public name, which is not followed.
+    /// Delegations are followed though, so the record and `NrsMap` returned for a delegated
+    /// subname are those set by its delegated owner, and the version refers to their map.
+    pub async fn nrs_get_record(
+        &self,
+        public_name: &str,
+        version: Option<VersionHash>,
+    ) -> Result<(Option<NrsRecord>, NrsMap)> {
         info!(
-            "Getting link for public name: {} for version: {:?}",
+            "Getting record for public name: {} for version: {:?}",
             public_name, version
         );
 
+        let (map_url, latest_map) = self
+            .find_nrs_map(public_name, NameDelegation::Follow)
+            .await?;
+
         // get nrs_map, ignoring conflicting entries if they are not the ones we're getting
-        let nrs_map = match self.nrs_get_subnames_map(public_name, version).await {
-            Ok(result) => Ok(result),
-            Err(Error::ConflictingNrsEntries(str, conflicting_entries, map)) => {
-                if conflicting_entries.iter().any(|(p, _)| p == public_name) {
-                    Err(Error::ConflictingNrsEntries(str, conflicting_entries, map))
-                } else {
-                    Ok(map)
+        let nrs_map = match (version, latest_map) {
+            (None, Some(nrs_map)) => nrs_map,
+            _ => match self.fetch_nrs_map(&map_url, public_name, version).await {
+                Ok(result) => Ok(result),
+                Err(Error::ConflictingNrsEntries(str, conflicting_entries, map)) => {
+                    if conflicting_entries.iter().any(|(p, _)| p == public_name) {
+                        Err(Error::ConflictingNrsEntries(str, conflicting_entries, map))
+                    } else {
+                        Ok(map)
+                    }
                 }
-            }
-            Err(e) => Err(e),
-        }?;
+                Err(e) => Err(e),
+            }?,
+        };
 
-        let url = nrs_map.get(public_name)?;
-        Ok((url, nrs_map))
+        let record = nrs_map.get_record(public_name)?;
+        Ok((record, nrs_map))
     }
 
     /// Get the mapping of all subNames and their associated `SafeUrl` for the Nrs Map Container at the given public name
@@ -196,7 +335,93 @@ impl Safe {
         version: Option<VersionHash>,
     ) -> Result<NrsMap> {
         let url = SafeUrl::from_url(&format!("safe://{public_name}"))?;
-        let mut multimap = match self.fetch_multimap(&url).await {
+        self.fetch_nrs_map(&url, public_name, version).await
+    }
+
+    // Finds the NRS map holding the records of the given public name, which is the map of its
+    // topname unless the public name, or any of its parent subnames, was delegated to another
+    // owner. The latest version of the map is also returned if it was fetched on the way
+    // without any conflicting entries, sparing callers from fetching it again.
+    async fn find_nrs_map(
+        &self,
+        public_name: &str,
+        name_delegation: NameDelegation,
+    ) -> Result<(SafeUrl, Option<NrsMap>)> {
+        let mut map_url = SafeUrl::from_url(&format!("safe://{public_name}"))?;
+        let top_name = map_url.top_name().to_string();
+        let sub_names = map_url.sub_names_vec().to_vec();
+        let mut latest_map = None;
+
+        // walk down from the parent subname closest to the topname, e.g. for "a.b.example"
+        // look for delegations of "b.example" and then "a.b.example"
+        for i in (0..sub_names.len()).rev() {
+            let name = format!("{}.{top_name}", sub_names[i..].join("."));
+            let is_public_name = i == 0;
+            if is_public_name && name_delegation == NameDelegation::Ignore {
+                break;
+            }
+
+            let nrs_map = match self.fetch_nrs_map(&map_url, &name, None).await {
+                Ok(nrs_map) => {
+                    if is_public_name {
+                        latest_map = Some(nrs_map.clone());
+                    }
+                    nrs_map
+                }
+                // conflicts of other names are resolved by the callers if they care about them
+                Err(Error::ConflictingNrsEntries(_, _, nrs_map)) => nrs_map,
+                Err(e) => return Err(e),
+            };
+
+            let owner = match nrs_map.map.get(&name) {
+                Some(NrsRecord::Delegation(owner)) => *owner,
+                _ => continue,
+            };
+            if is_public_name
+                && name_delegation == NameDelegation::FollowIfOwned
+                && owner != self.get_safe_client()?.public_key()
+            {
+                break;
+            }
+            debug!("NRS: subname \"{}\" is delegated to {:x}", name, owner);
+            map_url = self.delegated_nrs_map(&name, &owner).await?;
+            latest_map = None;
+        }
+
+        Ok((map_url, latest_map))
+    }
+
+    // The NRS map of a delegated subname is stored at an address anyone could derive, so it's
+    // only trusted if the key the subname was delegated to created it.
+    async fn delegated_nrs_map(&self, public_name: &str, owner: &PublicKey) -> Result<SafeUrl> {
+        let map_url = delegated_nrs_map_url(public_name, owner)?;
+        let client = self.get_safe_client()?;
+        match client
+            .get_register_owner(self.get_register_address(&map_url)?)
+            .await
+        {
+            Ok(User::Key(map_owner)) if map_owner == *owner => Ok(map_url),
+            Ok(_) => Err(Error::ContentError(format!(
+                "The NRS map of the subname \"{public_name}\" is not owned by the key it was delegated to"
+            ))),
+            Err(ClientError::ErrorMsg {
+                source: ErrorMsg::DataNotFound(_),
+                ..
+            }) => Err(Error::ContentNotFound(format!(
+                "The subname \"{public_name}\" is delegated to {owner:x}, but its NRS map hasn't been created yet"
+            ))),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // Fetches the NRS map stored at the given URL, with the given version of the public name.
+    async fn fetch_nrs_map(
+        &self,
+        url: &SafeUrl,
+        public_name: &str,
+        version: Option<VersionHash>,
+    ) -> Result<NrsMap> {
+        let mut multimap = match self.fetch_multimap(url).await {
             Ok(s) => Ok(s),
             Err(Error::EmptyContent(_)) => Ok(BTreeSet::new()),
             Err(Error::ContentNotFound(e)) => Err(Error::ContentNotFound(format!(
@@ -213,7 +438,7 @@ impl Safe {
                 .any(|(h, _)| VersionHash::from(h) == version)
             {
                 let key_val = self
-                    .fetch_multimap_value_by_hash(&url, version.entry_hash())
+                    .fetch_multimap_value_by_hash(url, version.entry_hash())
                     .await?;
                 multimap.insert((version.entry_hash(), key_val));
             }
@@ -224,8 +449,8 @@ impl Safe {
         let nrs_map = get_nrs_map_from_set(&subnames_set)?;
 
         if nrs_map.map.len() != subnames_set.len() {
-            let diff_set: BTreeSet<(String, SafeUrl)> = nrs_map.map.clone().into_iter().collect();
-            let conflicting_entries: Vec<(String, SafeUrl)> =
+            let diff_set: BTreeSet<(String, NrsRecord)> = nrs_map.map.clone().into_iter().collect();
+            let conflicting_entries: Vec<(String, NrsRecord)> =
                 subnames_set.difference(&diff_set).cloned().collect();
             return Err(Error::ConflictingNrsEntries(
                 "Found multiple entries for the same name. This happens when 2 clients write \
@@ -249,61 +474,74 @@ fn convert_multimap_to_nrs_set(
     multimap: &Multimap,
     public_name: &str,
     subname_version: Option<VersionHash>,
-) -> Result<BTreeSet<(String, SafeUrl)>> {
+) -> Result<BTreeSet<(String, NrsRecord)>> {
     if let Some(version) = subname_version {
-        let mut versioned_set: BTreeSet<(VersionHash, String, SafeUrl)> = multimap
+        let mut versioned_set: BTreeSet<(VersionHash, String, NrsRecord)> = multimap
             .clone()
             .into_iter()
             .map(|x| {
                 let version = VersionHash::from(&x.0);
                 let kv = x.1;
                 let public_name = str::from_utf8(&kv.0)?;
-                let url = SafeUrl::from_url(str::from_utf8(&kv.1)?)?;
-                Ok((version, public_name.to_owned(), url))
+                let record = str::from_utf8(&kv.1)?.parse()?;
+                Ok((version, public_name.to_owned(), record))
             })
-            .collect::<Result<BTreeSet<(VersionHash, String, SafeUrl)>>>()?;
+            .collect::<Result<BTreeSet<(VersionHash, String, NrsRecord)>>>()?;
         let duplicate_entries = versioned_set
             .clone()
             .into_iter()
             .filter(|x| x.1 == public_name)
             .filter(|x| x.0 != version)
-            .collect::<BTreeSet<(VersionHash, String, SafeUrl)>>();
+            .collect::<BTreeSet<(VersionHash, String, NrsRecord)>>();
         for entry in &duplicate_entries {
             versioned_set.remove(entry);
         }
-        let set: BTreeSet<(String, SafeUrl)> = versioned_set
+        let set: BTreeSet<(String, NrsRecord)> = versioned_set
             .iter()
             .map(|x| (x.1.clone(), x.2.clone()))
-            .collect::<BTreeSet<(String, SafeUrl)>>();
+            .collect::<BTreeSet<(String, NrsRecord)>>();
         return Ok(set);
     }
 
-    let set: BTreeSet<(String, SafeUrl)> = multimap
+    let set: BTreeSet<(String, NrsRecord)> = multimap
         .clone()
         .into_iter()
         .map(|x| {
             let kv = x.1;
             let public_name = str::from_utf8(&kv.0)?;
-            let url = SafeUrl::from_url(str::from_utf8(&kv.1)?)?;
-            Ok((public_name.to_owned(), url))
+            let record = str::from_utf8(&kv.1)?.parse()?;
+            Ok((public_name.to_owned(), record))
         })
-        .collect::<Result<BTreeSet<(String, SafeUrl)>>>()?;
+        .collect::<Result<BTreeSet<(String, NrsRecord)>>>()?;
     Ok(set)
 }
 
-fn get_nrs_map_from_set(set: &BTreeSet<(String, SafeUrl)>) -> Result<NrsMap> {
+fn get_nrs_map_from_set(set: &BTreeSet<(String, NrsRecord)>) -> Result<NrsMap> {
     // Duplicate entries are automatically removed from the set -> map conversion.
-    let public_names_map: BTreeMap<String, SafeUrl> = set
+    let public_names_map: BTreeMap<String, NrsRecord> = set
         .clone()
         .into_iter()
         .map(|x| (x.0, x.1))
-        .collect::<BTreeMap<String, SafeUrl>>();
+        .collect::<BTreeMap<String, NrsRecord>>();
     let nrs_map = NrsMap {
         map: public_names_map,
     };
     Ok(nrs_map)
 }
 
+// The NRS map of a delegated subname is stored at an address derived from both the subname
+// and the key it's delegated to, so delegating it to another key gives it a new map.
+fn delegated_nrs_map_url(public_name: &str, owner: &PublicKey) -> Result<SafeUrl> {
+    let mut content = public_name.as_bytes().to_vec();
+    content.extend(owner.to_bytes());
+    let url = SafeUrl::from_register(
+        XorName::from_content(&content),
+        NRS_MAP_TYPE_TAG,
+        ContentType::NrsMapContainer,
+    )?;
+    Ok(url)
+}
+
 fn set_nrs_url_props(url: &mut SafeUrl, entry_hash: EntryHash) -> Result<()> {
     url.set_content_version(Some(VersionHash::from(&entry_hash)));
     url.set_content_type(ContentType::NrsMapContainer)?;
@@ -332,14 +570,60 @@ fn validate_nrs_public_name(public_name: &str) -> Result<SafeUrl> {
     Ok(url)
 }
 
+/// Helper to check if an `NrsRecord` can be associated to the public name:
+/// - links, including those of services, are valid NRS `SafeUrl`s
+/// - services are named with ASCII alphanumeric, '-' or '_' characters
+/// - aliases target a valid public name other than the aliased one
+/// - only subnames are delegated
+fn validate_nrs_record(public_name: &str, record: &NrsRecord) -> Result<()> {
+    match record {
+        NrsRecord::Link(link) => validate_nrs_url(link),
+        NrsRecord::Service { service, link } => {
+            if service.is_empty()
+                || !service
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            {
+                return Err(Error::InvalidInput(format!(
+                    "The service name \"{service}\" is invalid, it can only contain ASCII \
+                    alphanumeric, '-' or '_' characters.",
+                )));
+            }
+            validate_nrs_url(link)
+        }
+        NrsRecord::Alias(target) => {
+            let _ = validate_nrs_public_name(target)?;
+            if target == public_name {
+                return Err(Error::InvalidInput(format!(
+                    "The NRS public name \"{public_name}\" cannot be an alias to itself.",
+                )));
+            }
+            Ok(())
+        }
+        NrsRecord::Delegation(_) => {
+            let url = validate_nrs_public_name(public_name)?;
+            if url.sub_names().is_empty() {
+                return Err(Error::InvalidInput(format!(
+                    "The NRS public name \"{public_name}\" cannot be delegated, only subnames \
+                    can be delegated to another owner.",
+                )));
+            }
+            Ok(())
+        }
+        NrsRecord::Text(_) | NrsRecord::PublicKey(_) => Ok(()),
+    }
+}
+
 /// Helper to check if an NRS `SafeUrl`:
 /// - is valid
-/// - has a version (if its data is versionable)
+/// - has a version (if its data is versionable), or a version tag for `FilesContainer`s
 fn validate_nrs_url(link: &SafeUrl) -> Result<()> {
     if link.content_version().is_none() {
         let content_type = link.content_type();
         let data_type = link.data_type();
-        if content_type == ContentType::FilesContainer
+        if content_type == ContentType::FilesContainer && link.content_version_tag().is_some() {
+            return Ok(());
+        } else if content_type == ContentType::FilesContainer
             || content_type == ContentType::NrsMapContainer
         {
             return Err(Error::UnversionedContentError(format!(
@@ -359,7 +643,9 @@ fn validate_nrs_url(link: &SafeUrl) -> Result<()> {
 mod tests {
     use super::*;
     use crate::{
-        app::test_helpers::{new_safe_instance, random_nrs_name, TestDataFilesContainer},
+        app::test_helpers::{
+            new_read_only_safe_instance, new_safe_instance, random_nrs_name, TestDataFilesContainer,
+        },
         Error, SafeUrl,
     };
     use anyhow::{anyhow, bail, Context, Result};
@@ -425,7 +711,7 @@ mod tests {
                 "'{}' subname should have been present in retrieved NRS map",
                 site_name
             ))?,
-            files_container.url
+            NrsRecord::Link(files_container.url)
         );
         Ok(())
     }
@@ -464,7 +750,7 @@ mod tests {
                 .ok_or_else(|| anyhow!(format!(
                     "'test.{site_name}' subname should have been present in retrieved NRS map"
                 )))?,
-            files_container["/testdata/test.md"],
+            NrsRecord::Link(files_container["/testdata/test.md"].clone()),
             "expected nrs map container to match local"
         );
         Ok(())
@@ -501,7 +787,7 @@ mod tests {
                 .ok_or_else(|| anyhow!(format!(
                     "'test.{site_name}' subname should have been present in retrieved NRS map"
                 )))?,
-            files_container["/testdata/test.md"]
+            NrsRecord::Link(files_container["/testdata/test.md"].clone())
         );
         assert_eq!(
             *nrs_map
@@ -510,7 +796,7 @@ mod tests {
                 .ok_or_else(|| anyhow!(format!(
                     "'another.{site_name}' subname should have been present in retrieved NRS map"
                 )))?,
-            files_container["/testdata/another.md"]
+            NrsRecord::Link(files_container["/testdata/another.md"].clone())
         );
         Ok(())
     }
@@ -595,6 +881,131 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_nrs_associate_with_alias_to_itself() -> Result<()> {
+        let site_name = random_nrs_name();
+        let safe = new_safe_instance().await?;
+
+        safe.nrs_create(&site_name).await?;
+        let result = safe
+            .nrs_associate_record(&site_name, &NrsRecord::Alias(site_name.clone()))
+            .await;
+        assert_matches!(
+            result, Err(Error::InvalidInput(err))
+            if err == format!("The NRS public name \"{site_name}\" cannot be an alias to itself.")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nrs_associate_with_invalid_service_name() -> Result<()> {
+        let site_name = random_nrs_name();
+        let safe = new_safe_instance().await?;
+
+        let files_container = TestDataFilesContainer::get_container([]).await?;
+        safe.nrs_create(&site_name).await?;
+        let record = NrsRecord::Service {
+            service: "web site".to_string(),
+            link: files_container.url,
+        };
+        let result = safe.nrs_associate_record(&site_name, &record).await;
+        assert_matches!(result, Err(Error::InvalidInput(_)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nrs_add_record_and_get_record() -> Result<()> {
+        let site_name = random_nrs_name();
+        let safe = new_safe_instance().await?;
+
+        let public_name = &format!("wallet.{site_name}");
+        let record = NrsRecord::PublicKey(bls::SecretKey::random().public_key());
+        let (_, topname_registered) = safe.nrs_add_record(public_name, &record).await?;
+        assert!(topname_registered);
+
+        let (got_record, _) = safe.nrs_get_record(public_name, None).await?;
+        assert_eq!(got_record, Some(record));
+
+        // records other than links don't have a URL to return
+        let (url, _) = safe.nrs_get(public_name, None).await?;
+        assert!(url.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nrs_delegate_subname() -> Result<()> {
+        let site_name = random_nrs_name();
+        let safe = new_safe_instance().await?;
+        // a read-only instance connects with a random key we can delegate to
+        let team_safe = new_read_only_safe_instance().await?;
+        let team_pk = team_safe.get_safe_client()?.public_key();
+
+        let files_container = TestDataFilesContainer::get_container(["/testdata/test.md"]).await?;
+        let delegated_name = format!("team.{site_name}");
+        let public_name = format!("app.{delegated_name}");
+
+        safe.nrs_create(&site_name).await?;
+        let _ = safe.nrs_delegate(&delegated_name, team_pk).await?;
+        let _ = team_safe.nrs_create_delegated(&delegated_name).await?;
+        let (_, topname_registered) = team_safe
+            .nrs_add(&public_name, &files_container["/testdata/test.md"])
+            .await?;
+        assert!(!topname_registered);
+
+        // the subname is resolved from the delegated map by anyone
+        let (url, nrs_map) = safe.nrs_get(&public_name, None).await?;
+        assert_eq!(url, Some(files_container["/testdata/test.md"].clone()));
+        assert!(nrs_map.map.contains_key(&public_name));
+
+        // while the topname map only holds the delegation
+        let topname_map = safe.nrs_get_subnames_map(&site_name, None).await?;
+        assert_eq!(
+            topname_map.map.get(&delegated_name),
+            Some(&NrsRecord::Delegation(team_pk))
+        );
+        assert!(!topname_map.map.contains_key(&public_name));
+
+        // only the delegated owner can change the records of the subname
+        assert!(safe
+            .nrs_associate(&public_name, &files_container["/testdata/test.md"])
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nrs_delegate_with_topname() -> Result<()> {
+        let site_name = random_nrs_name();
+        let safe = new_safe_instance().await?;
+        let team_pk = new_read_only_safe_instance()
+            .await?
+            .get_safe_client()?
+            .public_key();
+
+        safe.nrs_create(&site_name).await?;
+        assert_matches!(
+            safe.nrs_delegate(&site_name, team_pk).await,
+            Err(Error::InvalidInput(_))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nrs_create_delegated_without_delegation() -> Result<()> {
+        let site_name = random_nrs_name();
+        let safe = new_safe_instance().await?;
+        let team_safe = new_read_only_safe_instance().await?;
+
+        safe.nrs_create(&site_name).await?;
+        assert_matches!(
+            team_safe
+                .nrs_create_delegated(&format!("team.{site_name}"))
+                .await,
+            Err(Error::InvalidInput(_))
+        );
+        Ok(())
+    }
+
     /// Since nrs_add is a wrapper around nrs_create and nrs_associate, we won't re-test all
     /// the scenarios already covered by those and instead just provide this one test.
     #[tokio::test]
@@ -623,7 +1034,7 @@ mod tests {
             *nrs_map.map.get(public_name).ok_or_else(|| anyhow!(format!(
                 "'{public_name}' subname should have been present in retrieved NRS map"
             )))?,
-            files_container["/testdata/test.md"],
+            NrsRecord::Link(files_container["/testdata/test.md"].clone()),
             "added subname is correct"
         );
         Ok(())
@@ -668,7 +1079,7 @@ mod tests {
                 .ok_or_else(|| anyhow!(format!(
                     "'test.{site_name}' subname should have been present in retrieved NRS map"
                 )))?,
-            files_container["/testdata/test.md"]
+            NrsRecord::Link(files_container["/testdata/test.md"].clone())
         );
         Ok(())
     }
@@ -704,7 +1115,7 @@ mod tests {
 
         // let's create an empty files container so we have a valid to link
         let (link, _, _) = safe
-            .files_container_create_from(TEST_DATA_FILE, None, false, false)
+            .files_container_create_from(TEST_DATA_FILE, None, false, false, None)
             .await
             .context("failed to create container")?;
         let (version0, _) = safe
@@ -779,10 +1190,10 @@ mod tests {
         debug!("--------------------------------------->44444");
         // check for the error content
         if let Err(Error::ConflictingNrsEntries(_, dups, _)) = conflict_error {
-            let got_entries: Result<()> = dups.into_iter().try_for_each(|(public_name, url)| {
+            let got_entries: Result<()> = dups.into_iter().try_for_each(|(public_name, record)| {
                 assert_eq!(public_name, site_name, "problematic names match");
                 assert!(
-                    url == valid_link || url == another_valid_url,
+                    record.link() == Some(&valid_link) || record.link() == Some(&another_valid_url),
                     "theres a url conflict"
                 );
                 Ok(())
@@ -860,7 +1271,7 @@ mod tests {
                 .ok_or_else(|| anyhow!(format!(
                     "'test.{site_name}' subname should have been present in retrieved NRS map"
                 )))?,
-            files_container["/testdata/test.md"]
+            NrsRecord::Link(files_container["/testdata/test.md"].clone())
         );
         assert_eq!(
             *nrs_map
@@ -869,7 +1280,7 @@ mod tests {
                 .ok_or_else(|| anyhow!(format!(
                     "'another.{site_name}' subname should have been present in retrieved NRS map"
                 )))?,
-            files_container["/testdata/another.md"]
+            NrsRecord::Link(files_container["/testdata/another.md"].clone())
         );
         Ok(())
     }
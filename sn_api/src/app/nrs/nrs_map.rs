@@ -6,33 +6,137 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::{Error, Result, SafeUrl};
+use crate::{app::helpers::pk_from_hex, Error, PublicKey, Result, SafeUrl};
+use bls::PublicKey as BlsPublicKey;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt, str::FromStr};
 
 pub(crate) type PublicName = String;
 
+// Prefixes of the records other than links, as they are stored in the Multimap.
+// Links are stored as their URL, as they were before other types of records existed.
+const ALIAS_RECORD_PREFIX: &str = "alias:";
+const TEXT_RECORD_PREFIX: &str = "txt:";
+const PUBLIC_KEY_RECORD_PREFIX: &str = "key:";
+const SERVICE_RECORD_PREFIX: &str = "srv:";
+const DELEGATION_RECORD_PREFIX: &str = "delegate:";
+
+/// A record associated with a public name in an `NrsMap`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum NrsRecord {
+    /// Link to some content, e.g. a `FilesContainer`.
+    Link(SafeUrl),
+    /// Alias to another public name, which is resolved in place of this one.
+    Alias(String),
+    /// Text or metadata about the name, like DNS TXT records.
+    Text(String),
+    /// Public key advertised by the name, e.g. of a wallet or of its owner.
+    PublicKey(BlsPublicKey),
+    /// Link to the content of a service, e.g. "git" or "http", provided by the name.
+    Service { service: String, link: SafeUrl },
+    /// Delegation of a subname to another owner, whose records, and those of its own subnames,
+    /// are kept in a separate NRS map owned by the given key.
+    Delegation(PublicKey),
+}
+
+impl NrsRecord {
+    /// Returns the link of `Link` and `Service` records.
+    pub fn link(&self) -> Option<&SafeUrl> {
+        match self {
+            Self::Link(link) | Self::Service { link, .. } => Some(link),
+            Self::Alias(_) | Self::Text(_) | Self::PublicKey(_) | Self::Delegation(_) => None,
+        }
+    }
+
+    /// Returns the name of the type of the record: link, alias, txt, key, srv or delegate.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Link(_) => "link",
+            Self::Alias(_) => "alias",
+            Self::Text(_) => "txt",
+            Self::PublicKey(_) => "key",
+            Self::Service { .. } => "srv",
+            Self::Delegation(_) => "delegate",
+        }
+    }
+
+    /// Returns the value of the record, without its type.
+    pub fn value(&self) -> String {
+        match self {
+            Self::Link(link) => link.to_string(),
+            Self::Alias(public_name) => public_name.clone(),
+            Self::Text(text) => text.clone(),
+            Self::PublicKey(public_key) => public_key.to_hex(),
+            Self::Service { service, link } => format!("{service}={link}"),
+            Self::Delegation(owner) => format!("{owner:x}"),
+        }
+    }
+}
+
+impl fmt::Display for NrsRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let prefix = match self {
+            Self::Link(_) => "",
+            Self::Alias(_) => ALIAS_RECORD_PREFIX,
+            Self::Text(_) => TEXT_RECORD_PREFIX,
+            Self::PublicKey(_) => PUBLIC_KEY_RECORD_PREFIX,
+            Self::Service { .. } => SERVICE_RECORD_PREFIX,
+            Self::Delegation(_) => DELEGATION_RECORD_PREFIX,
+        };
+        write!(f, "{prefix}{}", self.value())
+    }
+}
+
+impl FromStr for NrsRecord {
+    type Err = Error;
+
+    fn from_str(record: &str) -> Result<Self> {
+        if let Some(public_name) = record.strip_prefix(ALIAS_RECORD_PREFIX) {
+            Ok(Self::Alias(public_name.to_string()))
+        } else if let Some(text) = record.strip_prefix(TEXT_RECORD_PREFIX) {
+            Ok(Self::Text(text.to_string()))
+        } else if let Some(public_key) = record.strip_prefix(PUBLIC_KEY_RECORD_PREFIX) {
+            let public_key = BlsPublicKey::from_hex(public_key).map_err(|err| {
+                Error::ContentError(format!("Invalid public key in NRS record: {err:?}"))
+            })?;
+            Ok(Self::PublicKey(public_key))
+        } else if let Some(service_record) = record.strip_prefix(SERVICE_RECORD_PREFIX) {
+            let (service, link) = service_record.split_once('=').ok_or_else(|| {
+                Error::ContentError(format!("Invalid NRS service record: {record}"))
+            })?;
+            Ok(Self::Service {
+                service: service.to_string(),
+                link: SafeUrl::from_url(link)?,
+            })
+        } else if let Some(owner) = record.strip_prefix(DELEGATION_RECORD_PREFIX) {
+            Ok(Self::Delegation(pk_from_hex(owner)?))
+        } else {
+            Ok(Self::Link(SafeUrl::from_url(record)?))
+        }
+    }
+}
+
 /// An NRS map is a description of a registered topname and all subnames associated with that.
 ///
-/// Each subname will link to some content, e.g., a `FilesContainer`, and the topname can also
-/// optionally link to something.
+/// Each subname will be associated with a record, usually a link to some content, e.g., a
+/// `FilesContainer`, and the topname can also optionally be associated with one.
 ///
-/// The struct is stored on the network using a Multimap. The entries are public name -> `SafeUrl`
-/// mappings.
+/// The struct is stored on the network using a Multimap. The entries are public name -> `NrsRecord`
+/// mappings, where links are stored as their URL, and other records with a prefix of their type.
 ///
-/// | `PublicName` Key    | Full Name        | `SafeUrl` Value            |
+/// | `PublicName` Key    | Full Name        | `NrsRecord` Value          |
 /// |-------------------|------------------|--------------------------|
 /// | "example"         | "example"        | "safe://example"         |
-/// | "sub.example"     | "sub.example"    | "safe://sub.example"     |
-/// | "sub.sub.example" | "sub.sub.example"| "safe://sub.sub.example" |
+/// | "sub.example"     | "sub.example"    | "alias:example"          |
+/// | "sub.sub.example" | "sub.sub.example"| "txt:some text"          |
 ///
 /// The map also has a subname version field that optionally specifies a subname at a particular
 /// version, since it's possible to have multiple entries for a given subname. If no version was
 /// requested when the map is retrieved, it will be set to `None`.
 #[derive(Debug, PartialEq, Eq, Default, Serialize, Deserialize, Clone)]
 pub struct NrsMap {
-    pub map: BTreeMap<PublicName, SafeUrl>,
+    pub map: BTreeMap<PublicName, NrsRecord>,
 }
 
 impl NrsMap {
@@ -53,17 +157,27 @@ impl NrsMap {
     ///
     /// We're doing this because we want to return no target link if the address of the container
     /// has been passed to `nrs_get`.
+    ///
+    /// Only `Link` and `Service` records have a link, `get_record` returns any type of record.
     pub fn get(&self, public_name: &str) -> Result<Option<SafeUrl>> {
+        Ok(self
+            .get_record(public_name)?
+            .and_then(|record| record.link().cloned()))
+    }
+
+    /// Get the `NrsRecord` associated with the given public name, which can be any of the
+    /// inputs supported by `get`.
+    pub fn get_record(&self, public_name: &str) -> Result<Option<NrsRecord>> {
         match self.map.get(public_name) {
-            Some(link) => {
+            Some(record) => {
                 debug!(
                     "NRS: public name resolution is: {} => {}",
-                    public_name, link
+                    public_name, record
                 );
-                Ok(Some(link.clone()))
+                Ok(Some(record.clone()))
             }
             None => {
-                debug!("NRS: No link found for public name: {}", public_name);
+                debug!("NRS: No record found for public name: {}", public_name);
                 if self.public_name_contains_subname(public_name) {
                     return Err(Error::ContentError(format!(
                         "Link not found in NRS Map Container for public name: \"{public_name}\""
@@ -114,13 +228,15 @@ mod tests {
         let mut nrs_map = NrsMap {
             map: BTreeMap::new(),
         };
-        nrs_map
-            .map
-            .insert("example".to_string(), SafeUrl::from_url("safe://example")?);
+        nrs_map.map.insert(
+            "example".to_string(),
+            NrsRecord::Link(SafeUrl::from_url("safe://example")?),
+        );
         let subname_url = SafeUrl::from_url("safe://a.example")?;
-        nrs_map
-            .map
-            .insert("a.example".to_string(), subname_url.clone());
+        nrs_map.map.insert(
+            "a.example".to_string(),
+            NrsRecord::Link(subname_url.clone()),
+        );
 
         let url = nrs_map.get("a.example")?;
 
@@ -136,17 +252,19 @@ mod tests {
         let mut nrs_map = NrsMap {
             map: BTreeMap::new(),
         };
-        nrs_map
-            .map
-            .insert("example".to_string(), SafeUrl::from_url("safe://example")?);
+        nrs_map.map.insert(
+            "example".to_string(),
+            NrsRecord::Link(SafeUrl::from_url("safe://example")?),
+        );
         nrs_map.map.insert(
             "a.example".to_string(),
-            SafeUrl::from_url("safe://a.example")?,
+            NrsRecord::Link(SafeUrl::from_url("safe://a.example")?),
         );
         let subname_url = SafeUrl::from_url("safe://a.b.example")?;
-        nrs_map
-            .map
-            .insert("a.b.example".to_string(), subname_url.clone());
+        nrs_map.map.insert(
+            "a.b.example".to_string(),
+            NrsRecord::Link(subname_url.clone()),
+        );
 
         let url = nrs_map.get("a.b.example")?;
 
@@ -165,10 +283,10 @@ mod tests {
         let topname_url = SafeUrl::from_url("safe://example")?;
         nrs_map
             .map
-            .insert("example".to_string(), topname_url.clone());
+            .insert("example".to_string(), NrsRecord::Link(topname_url.clone()));
         nrs_map.map.insert(
             "a.example".to_string(),
-            SafeUrl::from_url("safe://a.example")?,
+            NrsRecord::Link(SafeUrl::from_url("safe://a.example")?),
         );
 
         let url = nrs_map.get("example")?;
@@ -185,16 +303,17 @@ mod tests {
         let mut nrs_map = NrsMap {
             map: BTreeMap::new(),
         };
-        nrs_map
-            .map
-            .insert("example".to_string(), SafeUrl::from_url("safe://example")?);
+        nrs_map.map.insert(
+            "example".to_string(),
+            NrsRecord::Link(SafeUrl::from_url("safe://example")?),
+        );
         nrs_map.map.insert(
             "a.example".to_string(),
-            SafeUrl::from_url("safe://a.example")?,
+            NrsRecord::Link(SafeUrl::from_url("safe://a.example")?),
         );
         nrs_map.map.insert(
             "a.b.example".to_string(),
-            SafeUrl::from_url("safe://a.b.example")?,
+            NrsRecord::Link(SafeUrl::from_url("safe://a.b.example")?),
         );
 
         assert_matches!(
@@ -213,14 +332,14 @@ mod tests {
         let topname_url = SafeUrl::from_url("safe://example")?;
         nrs_map
             .map
-            .insert("example".to_string(), topname_url.clone());
+            .insert("example".to_string(), NrsRecord::Link(topname_url.clone()));
         nrs_map.map.insert(
             "a.example".to_string(),
-            SafeUrl::from_url("safe://a.example")?,
+            NrsRecord::Link(SafeUrl::from_url("safe://a.example")?),
         );
         nrs_map.map.insert(
             "a.b.example".to_string(),
-            SafeUrl::from_url("safe://a.b.example")?,
+            NrsRecord::Link(SafeUrl::from_url("safe://a.b.example")?),
         );
 
         let container_xorurl = SafeUrl::from_url(&topname_url.to_xorurl_string())?;
@@ -236,11 +355,11 @@ mod tests {
         };
         nrs_map.map.insert(
             "a.example".to_string(),
-            SafeUrl::from_url("safe://a.example")?,
+            NrsRecord::Link(SafeUrl::from_url("safe://a.example")?),
         );
         nrs_map.map.insert(
             "a.b.example".to_string(),
-            SafeUrl::from_url("safe://a.b.example")?,
+            NrsRecord::Link(SafeUrl::from_url("safe://a.b.example")?),
         );
 
         let url = nrs_map.get("example")?;
@@ -259,11 +378,13 @@ mod tests {
 
         nrs_map
             .map
-            .insert("example".to_string(), topname_url.clone());
-        nrs_map.map.insert("a.example".to_string(), a_url.clone());
+            .insert("example".to_string(), NrsRecord::Link(topname_url.clone()));
+        nrs_map
+            .map
+            .insert("a.example".to_string(), NrsRecord::Link(a_url.clone()));
         nrs_map
             .map
-            .insert("a.b.example".to_string(), a_b_url.clone());
+            .insert("a.b.example".to_string(), NrsRecord::Link(a_b_url.clone()));
 
         let summary = nrs_map.get_map_summary();
         assert_eq!(summary.len(), 3);
@@ -275,4 +396,54 @@ mod tests {
         assert_eq!(summary[2].1, a_b_url.to_string());
         Ok(())
     }
+
+    #[test]
+    fn records_should_round_trip_through_their_string_form() -> Result<()> {
+        let link = SafeUrl::from_url("safe://example/path")?;
+        let records = vec![
+            NrsRecord::Link(link.clone()),
+            NrsRecord::Alias("a.example".to_string()),
+            NrsRecord::Text("some: text=with separators".to_string()),
+            NrsRecord::PublicKey(bls::SecretKey::random().public_key()),
+            NrsRecord::Service {
+                service: "git".to_string(),
+                link: link.clone(),
+            },
+            NrsRecord::Delegation(PublicKey::Bls(bls::SecretKey::random().public_key())),
+        ];
+
+        for record in records {
+            assert_eq!(record.to_string().parse::<NrsRecord>()?, record);
+        }
+
+        // links are stored as they were before other records existed
+        assert_eq!(NrsRecord::Link(link.clone()).to_string(), link.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn get_should_return_link_only_for_link_records() -> Result<()> {
+        let mut nrs_map = NrsMap {
+            map: BTreeMap::new(),
+        };
+        let service_url = SafeUrl::from_url("safe://service.example")?;
+        nrs_map
+            .map
+            .insert("a.example".to_string(), NrsRecord::Text("text".to_string()));
+        nrs_map.map.insert(
+            "b.example".to_string(),
+            NrsRecord::Service {
+                service: "http".to_string(),
+                link: service_url.clone(),
+            },
+        );
+
+        assert!(nrs_map.get("a.example")?.is_none());
+        assert_eq!(
+            nrs_map.get_record("a.example")?,
+            Some(NrsRecord::Text("text".to_string()))
+        );
+        assert_eq!(nrs_map.get("b.example")?, Some(service_url));
+        Ok(())
+    }
 }
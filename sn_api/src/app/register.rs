@@ -91,6 +91,11 @@ impl Safe {
         url: &SafeUrl,
     ) -> Result<BTreeSet<(EntryHash, Entry)>> {
         debug!("Fetching Register entries from {}", url);
+        if let Some(tag) = url.content_version_tag() {
+            return Err(Error::InvalidInput(format!(
+                "Version tag '{tag}' cannot be used with {url}, version tags are only supported on FilesContainers"
+            )));
+        }
         let result = match url.content_version() {
             Some(v) => {
                 let hash = v.entry_hash();
@@ -10,38 +10,89 @@ use super::{Range, SafeData};
 use crate::app::{
     files::{self, FileInfo, FilesMap},
     multimap::Multimap,
+    nrs::NrsRecord,
     DataType, Safe, SafeUrl,
 };
-use crate::{Error, Result};
+use crate::{ContentType, Error, Result};
 use bytes::Bytes;
 use log::{debug, warn};
 use std::collections::BTreeSet;
 
 impl Safe {
     pub(crate) async fn resolve_nrs_map_container(&self, input_url: SafeUrl) -> Result<SafeData> {
-        let (target_url, nrs_map) = self
-            .nrs_get(input_url.public_name(), input_url.content_version())
+        // A version tag is for the FilesContainer the public name resolves to, thus
+        // the latest version of the NRS map is resolved, and the tag carried over.
+        let version_tag = input_url.content_version_tag();
+        let (record, nrs_map) = self
+            .nrs_get_record(input_url.public_name(), input_url.content_version())
             .await
             .map_err(|e| {
                 warn!("NRS failed to resolve {}: {}", input_url, e);
                 Error::ContentNotFound(format!("Content not found at {input_url}"))
             })?;
-        if let Some(mut target_url) = target_url {
-            debug!("NRS Resolved {} => {}", input_url, target_url);
+        if let Some(record) = record {
+            debug!("NRS Resolved {} => {}", input_url, record);
             let url_path = input_url.path_decoded()?;
-            let target_path = target_url.path_decoded()?;
-            target_url.set_path(&format!("{target_path}{url_path}"));
+            let target_url = match &record {
+                NrsRecord::Link(link) | NrsRecord::Service { link, .. } => Some(link.clone()),
+                // aliases are followed to the latest version of the target public name
+                NrsRecord::Alias(target) => {
+                    Some(SafeUrl::from_nrsurl(&format!("safe://{target}"))?)
+                }
+                // delegations are already followed when getting the record, one can only be
+                // found here if a delegated map delegates its own subname again
+                NrsRecord::Text(_) | NrsRecord::PublicKey(_) | NrsRecord::Delegation(_) => {
+                    if !url_path.is_empty() && url_path != "/" {
+                        return Err(Error::ContentNotFound(format!(
+                            "Content not found at {input_url}, the public name is associated to a '{}' record",
+                            record.type_name()
+                        )));
+                    }
+                    None
+                }
+            };
             let version = input_url.content_version().map(|v| v.entry_hash());
-            let safe_data = SafeData::NrsEntry {
-                xorurl: target_url.to_xorurl_string(),
-                public_name: input_url.public_name().to_string(),
-                data_type: target_url.data_type(),
-                resolves_into: target_url,
-                resolved_from: input_url.to_string(),
-                version,
+            let safe_data = match target_url {
+                Some(mut target_url) => {
+                    let target_path = target_url.path_decoded()?;
+                    target_url.set_path(&format!("{target_path}{url_path}"));
+                    if let Some(tag) = version_tag {
+                        ensure_version_tag_target(&input_url, &target_url, tag)?;
+                        target_url.set_content_version_tag(Some(tag))?;
+                    }
+                    SafeData::NrsEntry {
+                        xorurl: target_url.to_xorurl_string(),
+                        public_name: input_url.public_name().to_string(),
+                        data_type: target_url.data_type(),
+                        record,
+                        resolves_into: Some(target_url),
+                        resolved_from: input_url.to_string(),
+                        version,
+                    }
+                }
+                None if version_tag.is_some() => {
+                    return Err(Error::InvalidInput(format!(
+                        "Version tag cannot be used with {input_url}, as the public name is associated to a '{}' record",
+                        record.type_name()
+                    )))
+                }
+                None => SafeData::NrsEntry {
+                    xorurl: input_url.to_xorurl_string(),
+                    public_name: input_url.public_name().to_string(),
+                    data_type: input_url.data_type(),
+                    record,
+                    resolves_into: None,
+                    resolved_from: input_url.to_string(),
+                    version,
+                },
             };
             return Ok(safe_data);
         }
+        if let Some(tag) = version_tag {
+            return Err(Error::InvalidInput(format!(
+                "Version tag '{tag}' cannot be used with {input_url}, as the public name isn't associated to any content"
+            )));
+        }
         debug!("No target associated with input {}", input_url);
         debug!("Returning NrsMapContainer with NRS Map.");
         let safe_data = SafeData::NrsMapContainer {
@@ -103,7 +154,7 @@ impl Safe {
                 };
                 Ok(safe_data)
             }
-            DataType::File => {
+            DataType::File | DataType::PrivateFile => {
                 self.retrieve_data(&input_url, retrieve_data, None, &metadata, range)
                     .await
             }
@@ -158,7 +209,7 @@ impl Safe {
         ensure_no_subnames(&input_url, "media type")?;
 
         match input_url.data_type() {
-            DataType::File => {
+            DataType::File | DataType::PrivateFile => {
                 self.retrieve_data(
                     &input_url,
                     retrieve_data,
@@ -181,6 +232,12 @@ impl Safe {
     ) -> Result<SafeData> {
         ensure_no_subnames(&input_url, "file container")?;
 
+        // Version tags are resolved into the version hash they point to
+        if let Some(tag) = input_url.content_version_tag() {
+            let version = self.resolve_files_container_tag(&input_url, tag).await?;
+            input_url.set_content_version(Some(version));
+        }
+
         // Fetch files container
         let (version, files_map) = match self.fetch_files_container(&input_url).await? {
             Some((version, files_map)) => (Some(version), files_map),
@@ -281,6 +338,17 @@ impl Safe {
     }
 }
 
+// private helper to ensure the target of an NRS URL with a version tag can be tagged, i.e. it's
+// a FilesContainer, or another public name its FilesContainer is to be resolved from
+fn ensure_version_tag_target(input_url: &SafeUrl, target_url: &SafeUrl, tag: &str) -> Result<()> {
+    match target_url.content_type() {
+        ContentType::FilesContainer | ContentType::NrsMapContainer => Ok(()),
+        other => Err(Error::InvalidInput(format!(
+            "Version tag '{tag}' cannot be used with {input_url}, as it resolves to {other}, and version tags are only supported on FilesContainers"
+        ))),
+    }
+}
+
 // private helper to ensure the SafeUrl contains no subnames
 fn ensure_no_subnames(url: &SafeUrl, data_type: &str) -> Result<()> {
     if !url.sub_names_vec().is_empty() {
@@ -16,6 +16,7 @@ pub use super::{ContentType, DataType, SafeUrl, VersionHash, XorUrlBase};
 use crate::{Error, Result};
 use log::{debug, info};
 pub use safe_data::SafeData;
+use std::collections::BTreeSet;
 
 pub type Range = Option<(Option<u64>, Option<u64>)>;
 
@@ -59,7 +60,7 @@ impl Safe {
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   let safe = Safe::connected(None, None, None, None).await.unwrap();
-    ///     let (xorurl, _, _) = safe.files_container_create_from("./testdata/", None, true, false).await.unwrap();
+    ///     let (xorurl, _, _) = safe.files_container_create_from("./testdata/", None, true, false, None).await.unwrap();
     ///
     ///     let safe_data = safe.fetch( &format!( "{}/test.md", &xorurl.replace("?v=0", "") ), None ).await.unwrap();
     ///     let data_string = match safe_data {
@@ -107,7 +108,7 @@ impl Safe {
     /// # let rt = tokio::runtime::Runtime::new().unwrap();
     /// # rt.block_on(async {
     /// #   let mut safe = Safe::connected(None, None, None, None).await.unwrap();
-    ///     let (container_xorurl, _, _) = safe.files_container_create_from("./testdata/", None, true, false).await.unwrap();
+    ///     let (container_xorurl, _, _) = safe.files_container_create_from("./testdata/", None, true, false, None).await.unwrap();
     ///
     ///     let inspected_content = safe.inspect( &format!( "{}/test.md", &container_xorurl.replace("?v=0", "") ) ).await.unwrap();
     ///     match &inspected_content[0] {
@@ -168,9 +169,8 @@ impl Safe {
     // URL -> FileContainer (has metadata..) -> Actual data in a file (..that we attach here)
     // devs can leave a None there when using this function
     //
-    // NB: recursive (resolutions that resolve to themselves) aren't managed but since the
-    // indirections are limited, it's probably not worth the overhead check.
-    // Will need it if we allow infinite indirections though.
+    // NB: NRS aliases can resolve back to a public name already visited, such loops are
+    // detected and reported before reaching the indirection limit.
     async fn fully_resolve_url(
         &self,
         input_url: SafeUrl,
@@ -190,7 +190,20 @@ impl Safe {
         let mut safe_data_vec = vec![];
         let mut next_step = Some(input_url);
         let mut metadata = attached_metadata;
+        let mut visited_public_names = BTreeSet::new();
         while let Some(next_url) = next_step {
+            if next_url.content_type() == ContentType::NrsMapContainer
+                && !visited_public_names.insert((
+                    next_url.public_name().to_string(),
+                    next_url.content_version(),
+                ))
+            {
+                return Err(Error::ContentError(format!(
+                    "NRS alias loop detected when resolving the URL provided, the public name '{}' was reached more than once",
+                    next_url.public_name()
+                )));
+            }
+
             // fetch safe_data from URL
             let safe_data = self
                 .resolve_url(next_url, metadata, retrieve_data, range, resolve_path)
@@ -258,6 +271,7 @@ mod tests {
     use super::*;
     use crate::{
         app::files,
+        app::nrs::NrsRecord,
         app::test_helpers::{new_safe_instance, random_nrs_name, TestDataFilesContainer},
         SafeUrl,
     };
@@ -271,7 +285,7 @@ mod tests {
     async fn test_fetch_files_container() -> Result<()> {
         let safe = new_safe_instance().await?;
         let (fc_xorurl, _, original_files_map) = safe
-            .files_container_create_from("./testdata/", None, true, false)
+            .files_container_create_from("./testdata/", None, true, false, None)
             .await?;
 
         let safe_url = SafeUrl::from_url(&fc_xorurl)?;
@@ -326,7 +340,7 @@ mod tests {
 
         // create file container
         let (xorurl, _, the_files_map) = safe
-            .files_container_create_from("./testdata/", None, true, false)
+            .files_container_create_from("./testdata/", None, true, false, None)
             .await?;
         let _ = safe.fetch(&xorurl, None).await?;
         let (version0, _) = safe
@@ -388,7 +402,7 @@ mod tests {
 
         // create file container
         let (xorurl, _, _the_files_map) = safe
-            .files_container_create_from("./testdata/", None, true, false)
+            .files_container_create_from("./testdata/", None, true, false, None)
             .await?;
         let _ = safe.fetch(&xorurl, None).await?;
         let (version0, _) = safe
@@ -435,6 +449,7 @@ mod tests {
                 xorurl,
                 public_name,
                 data_type,
+                record,
                 resolves_into,
                 resolved_from,
                 version,
@@ -442,7 +457,8 @@ mod tests {
                 assert_eq!(*xorurl, files_container_url.to_xorurl_string());
                 assert_eq!(*public_name, nrs_resolution_url.public_name());
                 assert_eq!(*data_type, nrs_resolution_url.data_type());
-                assert_eq!(*resolves_into, files_container_url);
+                assert_eq!(*record, NrsRecord::Link(files_container_url.clone()));
+                assert_eq!(*resolves_into, Some(files_container_url));
                 assert_eq!(*resolved_from, nrs_url.to_string());
                 assert_eq!(*version, None);
             }
@@ -454,6 +470,129 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_fetch_nrs_alias() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let files_container = TestDataFilesContainer::get_container([]).await?;
+        let site_name = random_nrs_name();
+        let alias_name = random_nrs_name();
+        let _ = safe.nrs_add(&site_name, &files_container.url).await?;
+        let _ = safe
+            .nrs_add_record(&alias_name, &NrsRecord::Alias(site_name.clone()))
+            .await?;
+
+        // the alias should be followed to the files container the target public name links to
+        let inspected_content = safe.inspect(&format!("safe://{alias_name}")).await?;
+        assert_eq!(inspected_content.len(), 3);
+        match &inspected_content[0] {
+            SafeData::NrsEntry {
+                record,
+                resolves_into,
+                ..
+            } => {
+                assert_eq!(*record, NrsRecord::Alias(site_name.clone()));
+                assert_eq!(
+                    resolves_into.as_ref().map(|url| url.public_name()),
+                    Some(site_name.as_str())
+                );
+            }
+            _ => bail!("NrsEntry was not returned".to_string()),
+        }
+        assert!(matches!(
+            &inspected_content[2],
+            SafeData::FilesContainer { xorurl, .. } if *xorurl == files_container.url.to_string()
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_nrs_url_with_version_tag() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, _, _) = safe
+            .files_container_create_from("./testdata/subfolder/", None, false, true, None)
+            .await?;
+        let version0 = safe
+            .files_container_tag_add(&xorurl, "v1.0.0", false)
+            .await?;
+        let (version1, _, _) = safe
+            .files_container_copy(
+                &format!("{xorurl}/sub2.md"),
+                &format!("{xorurl}/copy.md"),
+                false,
+                false,
+            )
+            .await?;
+        let _ = safe
+            .files_container_tag_add(&xorurl, "v2.0.0", false)
+            .await?;
+
+        // the public name links to the first version, but the tag is carried over to the container
+        let site_name = random_nrs_name();
+        let mut link = SafeUrl::from_url(&xorurl)?;
+        link.set_content_version(Some(version0));
+        let _ = safe.nrs_add(&site_name, &link).await?;
+
+        for (tag, expected_version) in [("v1.0.0", version0), ("v2.0.0", version1)] {
+            let content = safe
+                .fetch(&format!("safe://{site_name}?v={tag}"), None)
+                .await?;
+            match content {
+                SafeData::FilesContainer { version, .. } => {
+                    assert_eq!(version, Some(expected_version))
+                }
+                other => bail!("FilesContainer was not returned: {other:?}"),
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_nrs_alias_loop() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let site_name = random_nrs_name();
+        let alias_name = random_nrs_name();
+        let _ = safe
+            .nrs_add_record(&site_name, &NrsRecord::Alias(alias_name.clone()))
+            .await?;
+        let _ = safe
+            .nrs_add_record(&alias_name, &NrsRecord::Alias(site_name.clone()))
+            .await?;
+
+        match safe.fetch(&format!("safe://{site_name}"), None).await {
+            Err(Error::ContentError(msg)) => assert!(msg.contains("NRS alias loop")),
+            other => bail!("expected an NRS alias loop error, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_nrs_text_record() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let site_name = random_nrs_name();
+        let text = NrsRecord::Text("owner=maidsafe".to_string());
+        let _ = safe.nrs_add_record(&site_name, &text).await?;
+
+        let content = safe.fetch(&format!("safe://{site_name}"), None).await?;
+        match content {
+            SafeData::NrsEntry {
+                record,
+                resolves_into,
+                ..
+            } => {
+                assert_eq!(record, text);
+                assert!(resolves_into.is_none());
+            }
+            _ => bail!("NrsEntry was not returned".to_string()),
+        }
+
+        // a text record has no content to look a path up in
+        assert!(safe
+            .fetch(&format!("safe://{site_name}/some/path"), None)
+            .await
+            .is_err());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_fetch_public_file() -> Result<()> {
         let safe = new_safe_instance().await?;
@@ -570,7 +709,7 @@ mod tests {
 
         // create file container
         let (xorurl, _, _files_map) = safe
-            .files_container_create_from("./testdata/", None, true, false)
+            .files_container_create_from("./testdata/", None, true, false, None)
             .await?;
         let _ = safe.fetch(&xorurl, None).await?;
         let (version0, _) = safe
@@ -954,6 +1093,7 @@ mod tests {
                 xorurl,
                 public_name,
                 data_type,
+                record,
                 resolves_into,
                 resolved_from,
                 version,
@@ -964,7 +1104,14 @@ mod tests {
                 );
                 assert_eq!(*public_name, nrs_url.public_name());
                 assert_eq!(*data_type, files_container["/testdata/test.md"].data_type());
-                assert_eq!(*resolves_into, files_container["/testdata/test.md"]);
+                assert_eq!(
+                    *record,
+                    NrsRecord::Link(files_container["/testdata/test.md"].clone())
+                );
+                assert_eq!(
+                    *resolves_into,
+                    Some(files_container["/testdata/test.md"].clone())
+                );
                 assert_eq!(*resolved_from, nrs_url.to_string());
 
                 let left = VersionHash::from(
@@ -10,7 +10,7 @@ pub use super::{ContentType, DataType, SafeUrl, VersionHash, XorUrlBase};
 use crate::app::{
     files::{FileInfo, FilesMap},
     multimap::Multimap,
-    nrs::NrsMap,
+    nrs::{NrsMap, NrsRecord},
     register::{Entry, EntryHash},
     XorName,
 };
@@ -54,12 +54,14 @@ pub enum SafeData {
         nrs_map: NrsMap,
         data_type: DataType,
     },
-    /// The xorurl and data_type are those the target entry points to.
+    /// The xorurl and data_type are those the target entry points to, or those of the
+    /// NRS Map Container for records which don't point to any content, e.g. text records.
     NrsEntry {
         xorurl: String,
         public_name: String,
         data_type: DataType,
-        resolves_into: SafeUrl,
+        record: NrsRecord,
+        resolves_into: Option<SafeUrl>,
         resolved_from: String,
         version: Option<EntryHash>,
     },
@@ -114,8 +116,9 @@ impl SafeData {
             | NrsMapContainer { .. }
             | PublicFile { .. }
             | Register { .. } => None,
-            FilesContainer { resolves_into, .. } => resolves_into.clone(),
-            NrsEntry { resolves_into, .. } => Some(resolves_into.clone()),
+            FilesContainer { resolves_into, .. } | NrsEntry { resolves_into, .. } => {
+                resolves_into.clone()
+            }
         }
     }
 
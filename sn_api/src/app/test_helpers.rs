@@ -146,7 +146,7 @@ impl TestDataFilesContainer {
         let mut map: HashMap<String, SafeUrl> = HashMap::new();
         let safe = new_safe_instance().await?;
         let (container_xorurl, _, files_map) = safe
-            .files_container_create_from("./testdata", None, false, false)
+            .files_container_create_from("./testdata", None, false, false, None)
             .await?;
         let container_url = SafeUrl::from_url(&container_xorurl)?;
         for file in files {
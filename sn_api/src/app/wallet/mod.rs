@@ -6,7 +6,12 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod transaction;
+
 pub use sn_dbc::{self as dbc, Dbc, Token};
+pub use transaction::{
+    SealedTransaction, SignedTransaction, TransactionInput, UnsignedTransaction,
+};
 
 use super::{helpers::parse_tokens_amount, register::EntryHash};
 use crate::{
@@ -17,10 +22,11 @@ use bytes::Bytes;
 use log::{debug, warn};
 use sn_client::Client;
 use sn_dbc::{
-    rng, AmountSecrets, Error as DbcError, Hash, KeyImage, Owner, OwnerOnce, PublicKey,
-    RingCtTransaction, SpentProof, SpentProofShare, TransactionBuilder,
+    rng, AmountSecrets, Error as DbcError, Hash, KeyImage, Owner, OwnerOnce, PublicKey, SpentProof,
+    SpentProofShare,
 };
 use std::collections::{BTreeMap, BTreeSet, HashSet};
+use transaction::build_signed_transaction;
 
 /// Type tag to use for the Wallet stored on Register
 pub const WALLET_TYPE_TAG: u64 = 1_000;
@@ -412,6 +418,27 @@ impl Safe {
         Ok(output_dbcs.into_iter().map(|(dbc, _, _)| dbc).collect())
     }
 
+    /// Broadcast a transaction whose inputs were signed with `SealedTransaction::sign`,
+    /// possibly on different and offline machines.
+    ///
+    /// The input DBCs are logged as spent on the network, and the output DBCs are returned,
+    /// with the change DBC returned separately if there is one.
+    pub async fn broadcast_transaction(
+        &self,
+        signed_tx: SignedTransaction,
+    ) -> Result<(Vec<Dbc>, Option<Dbc>)> {
+        debug!(
+            "Broadcasting signed transaction spending {} input/s",
+            signed_tx.dbc_builder().inputs().len()
+        );
+        let (output_dbcs, change_dbc) = self.spend_transaction(signed_tx).await?;
+
+        Ok((
+            output_dbcs.into_iter().map(|(dbc, _, _)| dbc).collect(),
+            change_dbc,
+        ))
+    }
+
     ///
     /// Private helpers
     ///
@@ -451,34 +478,25 @@ impl Safe {
         outputs: Vec<(Token, OwnerOnce)>,
         change_amount: Token,
     ) -> Result<(Vec<(Dbc, OwnerOnce, AmountSecrets)>, Option<Dbc>)> {
-        // TODO: enable the use of decoys
-        let mut tx_builder = TransactionBuilder::default()
-            .set_decoys_per_input(0)
-            .set_require_all_decoys(false)
-            .add_inputs_dbc_bearer(input_dbcs.iter())?
-            .add_outputs_by_amount(outputs.into_iter().map(|(token, owner)| (token, owner)));
-
         let client = self.get_safe_client()?;
         let change_owneronce =
             OwnerOnce::from_owner_base(client.dbc_owner().clone(), &mut rng::thread_rng());
-        if change_amount.as_nano() > 0 {
-            tx_builder = tx_builder.add_output_by_amount(change_amount, change_owneronce.clone());
-        }
 
-        let spent_proofs: BTreeSet<SpentProof> = input_dbcs
-            .iter()
-            .flat_map(|dbc| dbc.spent_proofs.clone())
-            .collect();
+        let signed_tx =
+            build_signed_transaction(&input_dbcs, outputs, change_amount, change_owneronce)?;
 
-        let spent_transactions: BTreeSet<RingCtTransaction> = input_dbcs
-            .iter()
-            .flat_map(|dbc| dbc.spent_transactions.clone())
-            .collect();
+        self.spend_transaction(signed_tx).await
+    }
 
+    /// Spend the inputs of a signed transaction, logging them on the network, and build its
+    /// output DBCs, returning the change DBC separately if there is one.
+    async fn spend_transaction(
+        &self,
+        signed_tx: SignedTransaction,
+    ) -> Result<(Vec<(Dbc, OwnerOnce, AmountSecrets)>, Option<Dbc>)> {
+        let client = self.get_safe_client()?;
         let proof_key_verifier = SpentProofKeyVerifier { client };
-
-        // Let's build the output DBCs
-        let mut dbc_builder = tx_builder.build(rng::thread_rng())?;
+        let mut dbc_builder = signed_tx.dbc_builder();
 
         // Spend all the input DBCs, collecting the spent proof shares for each of them
         for (key_image, tx) in dbc_builder.inputs() {
@@ -491,8 +509,8 @@ impl Safe {
                     .spend_dbc(
                         key_image,
                         tx.clone(),
-                        spent_proofs.clone(),
-                        spent_transactions.clone(),
+                        signed_tx.spent_proofs.clone(),
+                        signed_tx.spent_transactions.clone(),
                     )
                     .await?;
 
@@ -537,7 +555,7 @@ impl Safe {
 
         let mut change_dbc = None;
         output_dbcs.retain(|(dbc, owneronce, _)| {
-            if owneronce == &change_owneronce && change_amount.as_nano() > 0 {
+            if Some(owneronce) == signed_tx.change_owner.as_ref() {
                 change_dbc = Some(dbc.clone());
                 false
             } else {
@@ -1302,4 +1320,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_wallet_broadcast_offline_signed_transaction() -> Result<()> {
+        let (safe, dbc, dbc_balance) = new_safe_instance_with_dbc().await?;
+        let owner_sk = bls::SecretKey::random();
+        let change_sk = bls::SecretKey::random();
+
+        let mut unsigned_tx = UnsignedTransaction::new();
+        unsigned_tx.add_input(&dbc, None)?;
+        unsigned_tx.add_output(Token::from_nano(1_000), Some(owner_sk.public_key()))?;
+
+        // the transaction is sealed and signed without a connection to the network,
+        // and passed around in its hex-encoded form
+        let mut sealed_tx = unsigned_tx.seal(change_sk.public_key())?;
+        sealed_tx.sign(&dbc, None)?;
+        let sealed_tx = SealedTransaction::from_hex(&sealed_tx.to_hex()?)?;
+        let signed_tx = SignedTransaction::from_hex(&sealed_tx.into_signed()?.to_hex()?)?;
+
+        let (output_dbcs, change_dbc) = safe.broadcast_transaction(signed_tx).await?;
+        assert_eq!(output_dbcs.len(), 1);
+        assert!(safe.is_dbc_spent(dbc.key_image_bearer()?).await?);
+
+        let mut output_dbc = output_dbcs[0].clone();
+        output_dbc.to_bearer(&owner_sk)?;
+        assert_eq!(
+            output_dbc.amount_secrets_bearer()?.amount(),
+            Token::from_nano(1_000)
+        );
+
+        let mut change_dbc = change_dbc.ok_or_else(|| anyhow!("Change DBC not found"))?;
+        change_dbc.to_bearer(&change_sk)?;
+        assert_eq!(
+            change_dbc.amount_secrets_bearer()?.amount(),
+            Token::from_nano(dbc_balance.as_nano() - 1_000)
+        );
+
+        Ok(())
+    }
 }
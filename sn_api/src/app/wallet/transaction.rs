@@ -0,0 +1,798 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{Error, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sn_dbc::{
+    bls_ringct::{
+        bls_bulletproofs::PedersenGens,
+        blstrs::G1Affine,
+        group::{ff::Field, Curve},
+        mlsag::MlsagSignature,
+        ringct::OutputProof,
+    },
+    rng, BlindingFactor, Commitment, Dbc, DbcBuilder, KeyImage, MlsagMaterial, Output,
+    OutputOwnerMap, Owner, OwnerOnce, RevealedCommitment, RingCtMaterial, RingCtTransaction,
+    SpentProof, Token, TransactionBuilder, TrueInput,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A DBC transaction which has been built but not yet sealed nor signed.
+///
+/// It holds the public information about the input DBCs to be spent, i.e. their key images,
+/// one-time owner public keys, commitments and amounts, and the amounts and owners of the
+/// outputs to reissue. No input DBC nor secret key is kept in it, and it doesn't require a
+/// connection to the network to be created. Several owners can contribute inputs and outputs
+/// to the same transaction by merging their unsigned transactions, passing it around in its
+/// hex-encoded form, before it's sealed with `UnsignedTransaction::seal`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    inputs: Vec<TransactionInput>,
+    outputs: Vec<(Token, Option<bls::PublicKey>)>,
+}
+
+/// The public information about a DBC to be spent by a transaction.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionInput {
+    key_image: KeyImage,
+    // One-time public key the DBC is owned by
+    public_key: G1Affine,
+    commitment: Commitment,
+    amount: Token,
+    // Spent proofs and transactions of the DBC, to be provided to the spentbook
+    spent_proofs: BTreeSet<SpentProof>,
+    spent_transactions: BTreeSet<RingCtTransaction>,
+}
+
+/// A DBC transaction whose outputs have been fixed, and which is ready to be signed by the
+/// owner of each of its inputs.
+///
+/// Each owner signs their own inputs with `SealedTransaction::sign`, independently from the
+/// others, and the signatures are then merged with `SealedTransaction::merge_signatures`.
+/// Once all its inputs are signed, it can be turned into a `SignedTransaction`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SealedTransaction {
+    inputs: Vec<TransactionInput>,
+    // Blinding factors of the inputs' pseudo commitments, which add up to the outputs' ones
+    pseudo_blindings: Vec<BlindingFactor>,
+    outputs: Vec<Output>,
+    output_proofs: Vec<OutputProof>,
+    revealed_commitments: Vec<RevealedCommitment>,
+    output_owner_map: OutputOwnerMap,
+    change_owner: Option<OwnerOnce>,
+    signatures: BTreeMap<KeyImage, MlsagSignature>,
+}
+
+/// A DBC transaction which has been signed, and which is ready to be broadcast to the
+/// network with `Safe::broadcast_transaction`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    pub(super) transaction: RingCtTransaction,
+    pub(super) revealed_commitments: Vec<RevealedCommitment>,
+    pub(super) output_owner_map: OutputOwnerMap,
+    // Only the outputs are kept in the material, since the inputs' one holds their secret keys
+    pub(super) ringct_material: RingCtMaterial,
+    // Spent proofs and transactions of the inputs, to be provided to the spentbook
+    pub(super) spent_proofs: BTreeSet<SpentProof>,
+    pub(super) spent_transactions: BTreeSet<RingCtTransaction>,
+    pub(super) change_owner: Option<OwnerOnce>,
+}
+
+impl TransactionInput {
+    /// The key image of the DBC, which identifies it as an input once spent.
+    pub fn key_image(&self) -> &KeyImage {
+        &self.key_image
+    }
+
+    /// The amount of the DBC.
+    pub fn amount(&self) -> Token {
+        self.amount
+    }
+}
+
+impl UnsignedTransaction {
+    /// Create an empty unsigned transaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The inputs to be spent by this transaction.
+    pub fn inputs(&self) -> &[TransactionInput] {
+        &self.inputs
+    }
+
+    /// The amounts and owners of the output DBCs, a `None` owner meaning a bearer DBC.
+    pub fn outputs(&self) -> &[(Token, Option<bls::PublicKey>)] {
+        &self.outputs
+    }
+
+    /// Add a DBC to be spent by this transaction. The secret key of its owner is required
+    /// for an owned DBC, to reveal its amount and key image, and it's not kept in the
+    /// transaction, as neither is the DBC itself.
+    pub fn add_input(&mut self, dbc: &Dbc, owner_sk: Option<&bls::SecretKey>) -> Result<()> {
+        let true_input = true_input(dbc, owner_sk)?;
+        let key_image: KeyImage = true_input.key_image().to_affine().into();
+        let revealed_commitment = *true_input.revealed_commitment();
+
+        let input = TransactionInput {
+            key_image,
+            public_key: true_input.public_key().to_affine(),
+            commitment: revealed_commitment
+                .commit(&PedersenGens::default())
+                .to_affine(),
+            amount: Token::from_nano(revealed_commitment.value),
+            spent_proofs: dbc.spent_proofs.clone(),
+            spent_transactions: dbc.spent_transactions.clone(),
+        };
+
+        self.push_input(input)
+    }
+
+    /// Add an output DBC to be reissued for the given amount, owned by the given public key,
+    /// or as bearer if no public key is provided.
+    pub fn add_output(&mut self, amount: Token, owner: Option<bls::PublicKey>) -> Result<()> {
+        if amount.as_nano() == 0 {
+            return Err(Error::InvalidAmount(
+                "Output amount to reissue needs to be larger than zero (0).".to_string(),
+            ));
+        }
+        self.outputs.push((amount, owner));
+        Ok(())
+    }
+
+    /// Merge the inputs and outputs contributed by another party into this transaction.
+    pub fn merge(&mut self, other: UnsignedTransaction) -> Result<()> {
+        for input in other.inputs {
+            self.push_input(input)?;
+        }
+        self.outputs.extend(other.outputs);
+        Ok(())
+    }
+
+    /// Serialise the unsigned transaction into a hex-encoded string.
+    pub fn to_hex(&self) -> Result<String> {
+        to_hex(self, "unsigned transaction")
+    }
+
+    /// Deserialise an unsigned transaction from a hex-encoded string.
+    pub fn from_hex(hex_str: &str) -> Result<Self> {
+        from_hex(hex_str, "unsigned transaction")
+    }
+
+    /// Seal the transaction, fixing its outputs so each of its inputs can then be signed
+    /// independently by their owners. It doesn't require any secret key nor a connection
+    /// to the network.
+    ///
+    /// The difference between the inputs and outputs total amounts is reissued as a change
+    /// DBC owned by `change_owner`.
+    pub fn seal(&self, change_owner: bls::PublicKey) -> Result<SealedTransaction> {
+        if self.inputs.is_empty() {
+            return Err(Error::InvalidInput(
+                "The transaction doesn't have any input to spend".to_string(),
+            ));
+        }
+        if self.outputs.is_empty() {
+            return Err(Error::InvalidInput(
+                "The transaction doesn't have any output to reissue".to_string(),
+            ));
+        }
+
+        let mut total_input_amount = Token::zero();
+        for input in &self.inputs {
+            total_input_amount = total_input_amount
+                .checked_add(input.amount)
+                .ok_or_else(|| {
+                    Error::DbcReissueError(
+                        "Overflow occurred while calculating the total amount of the input DBCs"
+                            .to_string(),
+                    )
+                })?;
+        }
+
+        let mut rng = rng::thread_rng();
+        let mut total_output_amount = Token::zero();
+        let mut outputs = Vec::with_capacity(self.outputs.len() + 1);
+        let mut output_owner_map = OutputOwnerMap::new();
+        for (amount, owner_pk) in &self.outputs {
+            total_output_amount = total_output_amount.checked_add(*amount).ok_or_else(|| {
+                Error::DbcReissueError(
+                    "Overflow occurred while calculating the total amount for the output DBCs"
+                        .to_string(),
+                )
+            })?;
+
+            let owner = match owner_pk {
+                Some(pk) => Owner::from(*pk),
+                None => Owner::from_random_secret_key(&mut rng),
+            };
+            let owner_once = OwnerOnce::from_owner_base(owner, &mut rng);
+            let public_key = owner_once.as_owner().public_key();
+            outputs.push(Output::new(public_key, amount.as_nano()));
+            let _ = output_owner_map.insert(public_key, owner_once);
+        }
+
+        let change_amount = total_input_amount
+            .checked_sub(total_output_amount)
+            .ok_or_else(|| Error::NotEnoughBalance(total_input_amount.to_string()))?;
+
+        let change_owner = if change_amount.as_nano() > 0 {
+            let owner_once = OwnerOnce::from_owner_base(Owner::from(change_owner), &mut rng);
+            let public_key = owner_once.as_owner().public_key();
+            outputs.push(Output::new(public_key, change_amount.as_nano()));
+            let _ = output_owner_map.insert(public_key, owner_once.clone());
+            Some(owner_once)
+        } else {
+            None
+        };
+
+        // The output range proofs and blinding factors are generated by signing the outputs
+        // as spent by a single throwaway input, the blinding factors of the actual inputs'
+        // pseudo commitments are then chosen to add up to the outputs' ones.
+        let throwaway_input = MlsagMaterial::new(
+            TrueInput::new(
+                bls::SecretKey::random(),
+                RevealedCommitment::from_value(total_input_amount.as_nano(), &mut rng),
+            ),
+            vec![],
+            &mut rng,
+        );
+        let (transaction, revealed_commitments) = RingCtMaterial {
+            inputs: vec![throwaway_input],
+            outputs: outputs.clone(),
+        }
+        .sign(&mut rng)
+        .map_err(sn_dbc::Error::from)?;
+
+        let outputs_blinding = revealed_commitments
+            .iter()
+            .fold(BlindingFactor::zero(), |sum, c| sum + c.blinding);
+        let mut pseudo_blindings: Vec<BlindingFactor> = (1..self.inputs.len())
+            .map(|_| BlindingFactor::random(&mut rng))
+            .collect();
+        let last_blinding = pseudo_blindings
+            .iter()
+            .fold(outputs_blinding, |rest, blinding| rest - blinding);
+        pseudo_blindings.push(last_blinding);
+
+        Ok(SealedTransaction {
+            inputs: self.inputs.clone(),
+            pseudo_blindings,
+            outputs,
+            output_proofs: transaction.outputs,
+            revealed_commitments,
+            output_owner_map,
+            change_owner,
+            signatures: BTreeMap::new(),
+        })
+    }
+
+    fn push_input(&mut self, input: TransactionInput) -> Result<()> {
+        if self
+            .inputs
+            .iter()
+            .any(|existing| existing.key_image == input.key_image)
+        {
+            return Err(Error::InvalidInput(format!(
+                "DBC with key image {} is already an input of this transaction",
+                input.key_image.to_hex()
+            )));
+        }
+        self.inputs.push(input);
+        Ok(())
+    }
+}
+
+impl SealedTransaction {
+    /// The inputs to be spent by this transaction.
+    pub fn inputs(&self) -> &[TransactionInput] {
+        &self.inputs
+    }
+
+    /// The key images of the inputs which haven't been signed yet.
+    pub fn unsigned_inputs(&self) -> Vec<KeyImage> {
+        self.inputs
+            .iter()
+            .map(|input| input.key_image)
+            .filter(|key_image| !self.signatures.contains_key(key_image))
+            .collect()
+    }
+
+    /// Sign the input of the transaction corresponding to the given DBC, which doesn't
+    /// require a connection to the network. The secret key of its owner is required for
+    /// an owned DBC.
+    pub fn sign(&mut self, dbc: &Dbc, owner_sk: Option<&bls::SecretKey>) -> Result<()> {
+        let true_input = true_input(dbc, owner_sk)?;
+        let key_image: KeyImage = true_input.key_image().to_affine().into();
+        let index = self
+            .inputs
+            .iter()
+            .position(|input| input.key_image == key_image)
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "DBC {} is not an input of this transaction",
+                    hex::encode(dbc.hash())
+                ))
+            })?;
+
+        let revealed_pseudo_commitment = RevealedCommitment {
+            value: true_input.revealed_commitment().value,
+            blinding: self.pseudo_blindings[index],
+        };
+        // TODO: enable the use of decoys
+        let signature = MlsagMaterial::new(true_input, vec![], rng::thread_rng()).sign(
+            &self.message(),
+            &revealed_pseudo_commitment,
+            &PedersenGens::default(),
+        );
+
+        self.add_signature(index, signature)
+    }
+
+    /// Merge the signatures of the inputs signed by another party into this transaction,
+    /// which must have been obtained from the same sealed transaction.
+    pub fn merge_signatures(&mut self, other: SealedTransaction) -> Result<()> {
+        if other.message() != self.message() {
+            return Err(Error::InvalidInput(
+                "Cannot merge the signatures of a different sealed transaction".to_string(),
+            ));
+        }
+
+        for (key_image, signature) in other.signatures {
+            if self.signatures.contains_key(&key_image) {
+                continue;
+            }
+            let index = self
+                .inputs
+                .iter()
+                .position(|input| input.key_image == key_image)
+                .ok_or_else(|| {
+                    Error::InvalidInput(format!(
+                        "Signature for key image {} which is not an input of this transaction",
+                        key_image.to_hex()
+                    ))
+                })?;
+            self.add_signature(index, signature)?;
+        }
+
+        Ok(())
+    }
+
+    /// Turn the transaction into a signed one, once all its inputs are signed, so it can be
+    /// broadcast to the network.
+    pub fn into_signed(self) -> Result<SignedTransaction> {
+        let unsigned_inputs = self.unsigned_inputs();
+        if !unsigned_inputs.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "{} input/s of the transaction are still to be signed: {:?}",
+                unsigned_inputs.len(),
+                unsigned_inputs
+                    .iter()
+                    .map(|key_image| key_image.to_hex())
+                    .collect::<Vec<_>>()
+            )));
+        }
+
+        let mut signatures = self.signatures;
+        let transaction = RingCtTransaction {
+            mlsags: self
+                .inputs
+                .iter()
+                .filter_map(|input| signatures.remove(&input.key_image))
+                .collect(),
+            outputs: self.output_proofs,
+        };
+        let public_commitments: Vec<Vec<Commitment>> = self
+            .inputs
+            .iter()
+            .map(|input| vec![input.commitment])
+            .collect();
+        transaction
+            .verify(&public_commitments)
+            .map_err(sn_dbc::Error::from)?;
+
+        Ok(SignedTransaction {
+            transaction,
+            revealed_commitments: self.revealed_commitments,
+            output_owner_map: self.output_owner_map,
+            ringct_material: RingCtMaterial {
+                inputs: vec![],
+                outputs: self.outputs,
+            },
+            spent_proofs: self
+                .inputs
+                .iter()
+                .flat_map(|input| input.spent_proofs.clone())
+                .collect(),
+            spent_transactions: self
+                .inputs
+                .iter()
+                .flat_map(|input| input.spent_transactions.clone())
+                .collect(),
+            change_owner: self.change_owner,
+        })
+    }
+
+    /// Serialise the sealed transaction into a hex-encoded string.
+    pub fn to_hex(&self) -> Result<String> {
+        to_hex(self, "sealed transaction")
+    }
+
+    /// Deserialise a sealed transaction from a hex-encoded string.
+    pub fn from_hex(hex_str: &str) -> Result<Self> {
+        from_hex(hex_str, "sealed transaction")
+    }
+
+    // Verify the signature of the input at the given index before keeping it
+    fn add_signature(&mut self, index: usize, signature: MlsagSignature) -> Result<()> {
+        let input = &self.inputs[index];
+        let pseudo_commitment = RevealedCommitment {
+            value: input.amount.as_nano(),
+            blinding: self.pseudo_blindings[index],
+        }
+        .commit(&PedersenGens::default())
+        .to_affine();
+
+        if signature.key_image != G1Affine::from(input.key_image)
+            || signature.public_keys() != vec![input.public_key]
+            || signature.pseudo_commitment() != pseudo_commitment
+        {
+            return Err(Error::InvalidInput(format!(
+                "Signature doesn't match the input with key image {}",
+                input.key_image.to_hex()
+            )));
+        }
+        signature
+            .verify(&self.message(), &[input.commitment])
+            .map_err(sn_dbc::Error::from)?;
+
+        let _ = self.signatures.insert(input.key_image, signature);
+        Ok(())
+    }
+
+    // The message signed by each of the inputs, which only depends on the inputs' public
+    // keys, key images and pseudo commitments, and on the output proofs, so it's known
+    // before any of the inputs is signed.
+    fn message(&self) -> Vec<u8> {
+        let mlsags = self
+            .inputs
+            .iter()
+            .zip(self.pseudo_blindings.iter())
+            .map(|(input, blinding)| {
+                let pseudo_commitment = RevealedCommitment {
+                    value: input.amount.as_nano(),
+                    blinding: *blinding,
+                }
+                .commit(&PedersenGens::default())
+                .to_affine();
+                MlsagSignature {
+                    c0: BlindingFactor::zero(),
+                    r: vec![],
+                    key_image: input.key_image.into(),
+                    // only the public keys of the ring are part of the message
+                    ring: vec![(input.public_key, pseudo_commitment)],
+                    pseudo_commitment,
+                }
+            })
+            .collect();
+
+        RingCtTransaction {
+            mlsags,
+            outputs: self.output_proofs.clone(),
+        }
+        .gen_message()
+    }
+}
+
+impl SignedTransaction {
+    /// Serialise the signed transaction into a hex-encoded string.
+    pub fn to_hex(&self) -> Result<String> {
+        to_hex(self, "signed transaction")
+    }
+
+    /// Deserialise a signed transaction from a hex-encoded string.
+    pub fn from_hex(hex_str: &str) -> Result<Self> {
+        from_hex(hex_str, "signed transaction")
+    }
+
+    // Rebuild the DBC builder used to collect the spent proof shares and build the outputs
+    pub(super) fn dbc_builder(&self) -> DbcBuilder {
+        DbcBuilder::new(
+            self.transaction.clone(),
+            self.revealed_commitments.clone(),
+            self.output_owner_map.clone(),
+            self.ringct_material.clone(),
+        )
+    }
+}
+
+// Build and sign the transaction spending the given bearer DBCs, adding a change output
+// if the change amount is not zero.
+pub(super) fn build_signed_transaction(
+    input_dbcs: &[Dbc],
+    outputs: Vec<(Token, OwnerOnce)>,
+    change_amount: Token,
+    change_owneronce: OwnerOnce,
+) -> Result<SignedTransaction> {
+    // TODO: enable the use of decoys
+    let mut tx_builder = TransactionBuilder::default()
+        .set_decoys_per_input(0)
+        .set_require_all_decoys(false)
+        .add_inputs_dbc_bearer(input_dbcs.iter())?
+        .add_outputs_by_amount(outputs);
+
+    let change_owner = if change_amount.as_nano() > 0 {
+        tx_builder = tx_builder.add_output_by_amount(change_amount, change_owneronce.clone());
+        Some(change_owneronce)
+    } else {
+        None
+    };
+
+    let spent_proofs = input_dbcs
+        .iter()
+        .flat_map(|dbc| dbc.spent_proofs.clone())
+        .collect();
+
+    let spent_transactions = input_dbcs
+        .iter()
+        .flat_map(|dbc| dbc.spent_transactions.clone())
+        .collect();
+
+    let dbc_builder = tx_builder.build(rng::thread_rng())?;
+
+    Ok(SignedTransaction {
+        transaction: dbc_builder.transaction,
+        revealed_commitments: dbc_builder.revealed_commitments,
+        output_owner_map: dbc_builder.output_owner_map,
+        ringct_material: RingCtMaterial {
+            inputs: vec![],
+            outputs: dbc_builder.ringct_material.outputs,
+        },
+        spent_proofs,
+        spent_transactions,
+        change_owner,
+    })
+}
+
+// The true input of the given DBC, using the owner's secret key if it's not a bearer DBC
+fn true_input(dbc: &Dbc, owner_sk: Option<&bls::SecretKey>) -> Result<TrueInput> {
+    match owner_sk {
+        Some(sk) => Ok(dbc.as_true_input(sk)?),
+        None if dbc.is_bearer() => Ok(dbc.as_true_input_bearer()?),
+        None => Err(Error::InvalidInput(format!(
+            "The secret key of the owner of DBC {} is required",
+            hex::encode(dbc.hash())
+        ))),
+    }
+}
+
+fn to_hex<T: Serialize>(value: &T, what: &str) -> Result<String> {
+    let bytes = rmp_serde::to_vec_named(value)
+        .map_err(|err| Error::Serialisation(format!("Failed to serialise {what}: {err:?}")))?;
+    Ok(hex::encode(bytes))
+}
+
+fn from_hex<T: DeserializeOwned>(hex_str: &str, what: &str) -> Result<T> {
+    let bytes = hex::decode(hex_str.trim())
+        .map_err(|err| Error::InvalidInput(format!("Invalid hex-encoded {what}: {err}")))?;
+    rmp_serde::from_slice(&bytes)
+        .map_err(|err| Error::Serialisation(format!("Failed to deserialise {what}: {err:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::test_helpers::GENESIS_DBC;
+    use anyhow::{anyhow, Result};
+
+    fn genesis_amount() -> Result<Token> {
+        Ok(GENESIS_DBC.amount_secrets_bearer()?.amount())
+    }
+
+    // Reissue the genesis DBC to DBCs owned by the given keys, without spending it on the
+    // network, returning them in the same order as the keys.
+    fn reissue_genesis_to(amount: Token, owners: &[bls::PublicKey]) -> Result<Vec<Dbc>> {
+        let outputs = owners
+            .iter()
+            .map(|pk| {
+                (
+                    amount,
+                    OwnerOnce::from_owner_base(Owner::from(*pk), &mut rng::thread_rng()),
+                )
+            })
+            .collect();
+        let change_amount =
+            Token::from_nano(genesis_amount()?.as_nano() - amount.as_nano() * owners.len() as u64);
+        let signed_tx = build_signed_transaction(
+            std::slice::from_ref(&*GENESIS_DBC),
+            outputs,
+            change_amount,
+            OwnerOnce::from_owner_base(
+                Owner::from_random_secret_key(&mut rng::thread_rng()),
+                &mut rng::thread_rng(),
+            ),
+        )?;
+        let output_dbcs = signed_tx.dbc_builder().build_without_verifying()?;
+
+        owners
+            .iter()
+            .map(|pk| {
+                output_dbcs
+                    .iter()
+                    .find(|(_, owner_once, _)| owner_once.owner_base().public_key() == *pk)
+                    .map(|(dbc, _, _)| dbc.clone())
+                    .ok_or_else(|| anyhow!("Missing output DBC"))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unsigned_transaction_hex_roundtrip() -> Result<()> {
+        let mut unsigned_tx = UnsignedTransaction::new();
+        unsigned_tx.add_input(&GENESIS_DBC, None)?;
+        unsigned_tx.add_output(
+            Token::from_nano(10),
+            Some(bls::SecretKey::random().public_key()),
+        )?;
+        unsigned_tx.add_output(Token::from_nano(5), None)?;
+
+        let hex_str = unsigned_tx.to_hex()?;
+        let deserialised_tx = UnsignedTransaction::from_hex(&hex_str)?;
+        assert_eq!(deserialised_tx.to_hex()?, hex_str);
+        assert_eq!(
+            deserialised_tx.inputs()[0].key_image(),
+            &GENESIS_DBC.key_image_bearer()?
+        );
+        assert_eq!(deserialised_tx.inputs()[0].amount(), genesis_amount()?);
+        assert_eq!(deserialised_tx.outputs(), unsigned_tx.outputs());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsigned_transaction_merge() -> Result<()> {
+        let mut unsigned_tx = UnsignedTransaction::new();
+        unsigned_tx.add_input(&GENESIS_DBC, None)?;
+        unsigned_tx.add_output(Token::from_nano(10), None)?;
+
+        let mut other_tx = UnsignedTransaction::new();
+        other_tx.add_output(Token::from_nano(20), None)?;
+        unsigned_tx.merge(other_tx)?;
+        assert_eq!(unsigned_tx.inputs().len(), 1);
+        assert_eq!(unsigned_tx.outputs().len(), 2);
+
+        // the same DBC cannot be contributed twice as input
+        let mut other_tx = UnsignedTransaction::new();
+        other_tx.add_input(&GENESIS_DBC, None)?;
+        match unsigned_tx.merge(other_tx) {
+            Err(Error::InvalidInput(_)) => Ok(()),
+            other => Err(anyhow!(
+                "Unexpected result when merging a duplicated input: {other:?}"
+            )),
+        }
+    }
+
+    #[test]
+    fn test_sealed_transaction_sign() -> Result<()> {
+        let change_sk = bls::SecretKey::random();
+        let mut unsigned_tx = UnsignedTransaction::new();
+        unsigned_tx.add_input(&GENESIS_DBC, None)?;
+        unsigned_tx.add_output(Token::from_nano(10), None)?;
+
+        let mut sealed_tx = unsigned_tx.seal(change_sk.public_key())?;
+        assert_eq!(sealed_tx.unsigned_inputs().len(), 1);
+        sealed_tx.sign(&GENESIS_DBC, None)?;
+        assert!(sealed_tx.unsigned_inputs().is_empty());
+
+        let sealed_tx = SealedTransaction::from_hex(&sealed_tx.to_hex()?)?;
+        let signed_tx = sealed_tx.into_signed()?;
+        assert_eq!(signed_tx.output_owner_map.len(), 2);
+        assert_eq!(signed_tx.ringct_material.outputs.len(), 2);
+        assert!(signed_tx.ringct_material.inputs.is_empty());
+        assert!(signed_tx.change_owner.is_some());
+
+        let signed_tx = SignedTransaction::from_hex(&signed_tx.to_hex()?)?;
+        let dbc_builder = signed_tx.dbc_builder();
+        assert_eq!(dbc_builder.inputs().len(), 1);
+        assert_eq!(dbc_builder.build_without_verifying()?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsigned_transaction_seal_not_enough_balance() -> Result<()> {
+        let mut unsigned_tx = UnsignedTransaction::new();
+        unsigned_tx.add_input(&GENESIS_DBC, None)?;
+        let amount = genesis_amount()?
+            .checked_add(Token::from_nano(1))
+            .ok_or_else(|| anyhow!("Unexpected overflow"))?;
+        unsigned_tx.add_output(amount, None)?;
+
+        match unsigned_tx.seal(bls::SecretKey::random().public_key()) {
+            Err(Error::NotEnoughBalance(_)) => Ok(()),
+            other => Err(anyhow!(
+                "Unexpected result when sealing with not enough balance: {other:?}"
+            )),
+        }
+    }
+
+    #[test]
+    fn test_unsigned_transaction_add_input_without_owner_key() -> Result<()> {
+        let owner_sk = bls::SecretKey::random();
+        let owned_dbc =
+            reissue_genesis_to(Token::from_nano(100), &[owner_sk.public_key()])?.remove(0);
+
+        let mut unsigned_tx = UnsignedTransaction::new();
+        match unsigned_tx.add_input(&owned_dbc, None) {
+            Err(Error::InvalidInput(_)) => {}
+            other => {
+                return Err(anyhow!(
+                    "Unexpected result when adding an input without the owner's key: {other:?}"
+                ))
+            }
+        }
+        assert!(unsigned_tx
+            .add_input(&owned_dbc, Some(&bls::SecretKey::random()))
+            .is_err());
+
+        unsigned_tx.add_input(&owned_dbc, Some(&owner_sk))?;
+        assert_eq!(unsigned_tx.inputs()[0].amount(), Token::from_nano(100));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sealed_transaction_independent_signers() -> Result<()> {
+        let alice_sk = bls::SecretKey::random();
+        let bob_sk = bls::SecretKey::random();
+        let dbcs = reissue_genesis_to(
+            Token::from_nano(100),
+            &[alice_sk.public_key(), bob_sk.public_key()],
+        )?;
+        let (alice_dbc, bob_dbc) = (&dbcs[0], &dbcs[1]);
+
+        // each owner contributes their own input, without sharing their secret key
+        let mut unsigned_tx = UnsignedTransaction::new();
+        unsigned_tx.add_input(alice_dbc, Some(&alice_sk))?;
+        unsigned_tx.add_output(Token::from_nano(150), None)?;
+        let mut bob_tx = UnsignedTransaction::new();
+        bob_tx.add_input(bob_dbc, Some(&bob_sk))?;
+        unsigned_tx.merge(bob_tx)?;
+
+        let sealed_tx = unsigned_tx.seal(alice_sk.public_key())?;
+        let mut alice_sealed_tx = sealed_tx.clone();
+        let mut bob_sealed_tx = sealed_tx;
+
+        // an owner cannot sign an input which isn't theirs
+        assert!(alice_sealed_tx.sign(bob_dbc, Some(&alice_sk)).is_err());
+        alice_sealed_tx.sign(alice_dbc, Some(&alice_sk))?;
+        bob_sealed_tx.sign(bob_dbc, Some(&bob_sk))?;
+
+        match alice_sealed_tx.clone().into_signed() {
+            Err(Error::InvalidInput(_)) => {}
+            other => {
+                return Err(anyhow!(
+                    "Unexpected result when finalising with an unsigned input: {other:?}"
+                ))
+            }
+        }
+
+        // the signatures of a differently sealed transaction cannot be merged
+        let other_sealed_tx = unsigned_tx.seal(alice_sk.public_key())?;
+        assert!(alice_sealed_tx
+            .clone()
+            .merge_signatures(other_sealed_tx)
+            .is_err());
+
+        alice_sealed_tx.merge_signatures(SealedTransaction::from_hex(&bob_sealed_tx.to_hex()?)?)?;
+        let signed_tx = alice_sealed_tx.into_signed()?;
+        assert_eq!(signed_tx.dbc_builder().inputs().len(), 2);
+        assert_eq!(signed_tx.output_owner_map.len(), 2);
+
+        Ok(())
+    }
+}
@@ -8,8 +8,8 @@
 
 use super::{
     ipc::IpcError,
-    nrs::NrsMap,
-    safeurl::{Error as UrlError, SafeUrl, XorUrl},
+    nrs::{NrsMap, NrsRecord},
+    safeurl::{Error as UrlError, XorUrl},
 };
 
 use bls::Error as BlsError;
@@ -46,7 +46,7 @@ pub enum Error {
     AuthenticatorError(String),
     /// ConflictingNrsEntries
     #[error("ConflictingNrsEntries: {0}")]
-    ConflictingNrsEntries(String, Vec<(String, SafeUrl)>, NrsMap),
+    ConflictingNrsEntries(String, Vec<(String, NrsRecord)>, NrsMap),
     /// ConnectionError
     #[error("ConnectionError: {0}")]
     ConnectionError(String),
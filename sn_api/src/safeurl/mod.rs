@@ -39,6 +39,7 @@ const XOR_URL_VERSION_1: u64 = 0x1; // TODO: consider using 16 bits
 const XOR_URL_STR_MAX_LENGTH: usize = 44;
 const XOR_NAME_BYTES_OFFSET: usize = 4; // offset where to find the XoR name bytes
 const URL_VERSION_QUERY_NAME: &str = "v";
+const VERSION_TAG_MAX_LENGTH: usize = 64;
 
 /// The XOR-URL type
 pub type XorUrl = String;
@@ -163,6 +164,8 @@ pub enum DataType {
     Register = 0x02,
     #[allow(missing_docs)]
     Spentbook = 0x03,
+    /// A file whose data map is encrypted to a key, thus only readable by its holder.
+    PrivateFile = 0x04,
 }
 
 impl std::fmt::Display for DataType {
@@ -237,7 +240,9 @@ pub struct SafeUrl {
     query_string: String,                 // query-string, no separator, url-encoded
     fragment: String,                     // fragment, no separator
     content_version: Option<VersionHash>, // convenience for ?v=<version
+    content_version_tag: Option<String>,  // convenience for ?v=<tag>
     url_type: UrlType,                    // nrsurl or xorurl
+    private: bool,                        // content encrypted to a key, only for Bytes
 }
 
 /// This implementation performs semi-rigorous validation,
@@ -345,8 +350,10 @@ impl SafeUrl {
             path: String::default(),         // set below.
             query_string: String::default(), // set below.
             fragment: fragment.unwrap_or("").to_string(),
-            content_version: None, // set below.
+            content_version: None,     // set below.
+            content_version_tag: None, // set below.
             url_type,
+            private: false,
         };
 
         // now we can call ::name_to_base(), to generate the top_name.
@@ -378,6 +385,18 @@ impl SafeUrl {
         MEDIA_TYPE_CODES.get(media_type).is_some()
     }
 
+    /// A non-member utility function to check if a string can be used as a version tag,
+    /// i.e. it has up to 64 ASCII alphanumeric, '.', '-' or '_' characters, starting with an
+    /// alphanumeric one, and it's not a version hash.
+    pub fn is_version_tag_valid(tag: &str) -> bool {
+        tag.len() <= VERSION_TAG_MAX_LENGTH
+            && tag.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && tag
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+            && tag.parse::<VersionHash>().is_err()
+    }
+
     /// Parses a safe url into `SafeUrl`
     ///
     /// # Arguments
@@ -488,10 +507,14 @@ impl SafeUrl {
         type_tag_bytes[8 - type_tag_bytes_len..].copy_from_slice(&xorurl_bytes[type_tag_offset..]);
         let type_tag: u64 = u64::from_be_bytes(type_tag_bytes);
 
-        let address = match xorurl_bytes[3] {
-            0 => DataAddress::SafeKey(xor_name),
-            1 => DataAddress::Bytes(ChunkAddress(xor_name)),
-            2 => DataAddress::Register(RegisterAddress::new(xor_name, type_tag)),
+        let (address, private) = match xorurl_bytes[3] {
+            0 => (DataAddress::SafeKey(xor_name), false),
+            1 => (DataAddress::Bytes(ChunkAddress(xor_name)), false),
+            2 => (
+                DataAddress::Register(RegisterAddress::new(xor_name, type_tag)),
+                false,
+            ),
+            4 => (DataAddress::Bytes(ChunkAddress(xor_name)), true),
             other => {
                 return Err(Error::InvalidXorUrl(format!(
                     "Invalid data type encoded in the XOR-URL string: {other}"
@@ -499,7 +522,7 @@ impl SafeUrl {
             }
         };
 
-        Self::new(
+        let mut url = Self::new(
             address,
             None, // no nrs_name for an xorurl
             type_tag,
@@ -509,7 +532,13 @@ impl SafeUrl {
             Some(&parts.query_string),
             Some(&parts.fragment),
             None,
-        )
+        )?;
+
+        if private {
+            url.set_private_internal();
+        }
+
+        Ok(url)
     }
 
     pub fn from_safekey(xor_name: XorName) -> Result<Self> {
@@ -540,6 +569,14 @@ impl SafeUrl {
         )
     }
 
+    /// Instantiates a `SafeUrl` for private content, i.e. Bytes whose data map is
+    /// encrypted to a key, thus only the holder of the matching secret key can read them.
+    pub fn from_private_bytes(address: XorName, content_type: ContentType) -> Result<Self> {
+        let mut url = Self::from_bytes(address, content_type)?;
+        url.set_private_internal();
+        Ok(url)
+    }
+
     pub fn from_register(
         xor_name: XorName,
         type_tag: u64,
@@ -571,6 +608,7 @@ impl SafeUrl {
     /// returns SAFE data type
     pub fn data_type(&self) -> DataType {
         match self.address {
+            DataAddress::Bytes(_) if self.private => DataType::PrivateFile,
             DataAddress::Bytes(_) => DataType::File,
             DataAddress::Register(_) => DataType::Register,
             DataAddress::SafeKey(_) => DataType::SafeKey,
@@ -578,6 +616,11 @@ impl SafeUrl {
         }
     }
 
+    /// returns true if the content is private, i.e. encrypted to a key
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
     /// returns SAFE content type
     pub fn content_type(&self) -> ContentType {
         self.content_type.clone()
@@ -684,6 +727,27 @@ impl SafeUrl {
         self.content_version
     }
 
+    /// gets content version tag
+    ///
+    /// This is a shortcut method for getting the "?v=" query param, when
+    /// it's the name of a version tag rather than a version hash.
+    pub fn content_version_tag(&self) -> Option<&str> {
+        self.content_version_tag.as_deref()
+    }
+
+    /// sets content version tag
+    ///
+    /// This is a shortcut method for setting the "?v=" query param to the
+    /// name of a version tag, replacing any version hash set.
+    pub fn set_content_version_tag(&mut self, tag: Option<&str>) -> Result<()> {
+        if let Some(tag) = tag {
+            if !Self::is_version_tag_valid(tag) {
+                return Err(Error::InvalidInput(format!("Invalid version tag: '{tag}'")));
+            }
+        }
+        self.set_query_key(URL_VERSION_QUERY_NAME, tag)
+    }
+
     /// sets content version
     ///
     /// This is a shortcut method for setting the "?v=" query param.
@@ -1015,21 +1079,47 @@ impl SafeUrl {
     // This should never be called directly.
     // Use ::set_content_version() or ::set_query_key() instead.
     fn set_content_version_internal(&mut self, version_option: Option<&str>) -> Result<()> {
-        if let Some(version_str) = version_option {
-            let version = version_str.parse::<VersionHash>().map_err(|_e| {
-                let msg = format!(
-                    "{URL_VERSION_QUERY_NAME} param could not be parsed as VersionHash. invalid: '{version_str}'",
-                );
-                Error::InvalidInput(msg)
-            })?;
-            self.content_version = Some(version);
-        } else {
-            self.content_version = None;
+        match version_option {
+            Some(version_str) => match version_str.parse::<VersionHash>() {
+                Ok(version) => {
+                    self.content_version = Some(version);
+                    self.content_version_tag = None;
+                }
+                Err(_) if Self::is_version_tag_valid(version_str) => {
+                    self.content_version = None;
+                    self.content_version_tag = Some(version_str.to_string());
+                }
+                Err(_) => {
+                    let msg = format!(
+                        "{URL_VERSION_QUERY_NAME} param could not be parsed as VersionHash or version tag. invalid: '{version_str}'",
+                    );
+                    return Err(Error::InvalidInput(msg));
+                }
+            },
+            None => {
+                self.content_version = None;
+                self.content_version_tag = None;
+            }
         }
-        trace!("Set version: {:#?}", self.content_version);
+        trace!(
+            "Set version: {:#?}, version tag: {:?}",
+            self.content_version,
+            self.content_version_tag
+        );
         Ok(())
     }
 
+    // marks the content as private, re-encoding the xorurl name since
+    // private content is encoded with its own data type.
+    fn set_private_internal(&mut self) {
+        self.private = true;
+        if self.url_type == UrlType::XorUrl {
+            self.top_name = self.name_to_base(DEFAULT_XORURL_BASE, false);
+            let sep = if self.sub_names.is_empty() { "" } else { "." };
+            self.public_name = format!("{}{}{}", self.sub_names(), sep, self.top_name);
+        }
+    }
+
     // sets path portion of URL
     //
     // input path may be percent-encoded or not, but
@@ -1410,6 +1500,22 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn encode_private_bytes_should_set_data_type() -> Result<()> {
+        let xor_name = XorName(*b"12345678901234567890123456789012");
+        let public_url = SafeUrl::from_bytes(xor_name, ContentType::Raw)?;
+        let private_url = SafeUrl::from_private_bytes(xor_name, ContentType::Raw)?;
+        assert_ne!(public_url.to_string(), private_url.to_string());
+
+        let url = SafeUrl::from_url(&private_url.encode(XorUrlBase::Base32z))?;
+        assert_eq!(url.data_type(), DataType::PrivateFile);
+        assert!(url.is_private());
+        assert_eq!(url.xorname(), xor_name);
+        assert_eq!(url.address(), public_url.address());
+        assert_eq!(url, private_url);
+        Ok(())
+    }
+
     #[test]
     fn test_url_too_long() -> Result<()> {
         let xorurl =
@@ -1513,8 +1619,21 @@ mod tests {
         assert_eq!(x.query_key_last(URL_VERSION_QUERY_NAME), None);
         assert_eq!(x.content_version(), None);
 
-        // Test parse error for version via ?v=non-hash
-        let result = x.set_query_key(URL_VERSION_QUERY_NAME, Some("non-hash"));
+        // Test setting a version tag via ?v=non-hash
+        x.set_query_key(URL_VERSION_QUERY_NAME, Some("non-hash"))?;
+        assert_eq!(x.content_version(), None);
+        assert_eq!(x.content_version_tag(), Some("non-hash"));
+        assert_eq!(x.to_string(), "safe://myname?name=&age=25&v=non-hash");
+
+        // Test setting a version hash replaces the version tag
+        x.set_content_version(Some(version_hash));
+        assert_eq!(x.content_version(), Some(version_hash));
+        assert_eq!(x.content_version_tag(), None);
+
+        // Test parse error for version via ?v=<invalid tag>
+        let result = x.set_query_key(URL_VERSION_QUERY_NAME, Some("non hash!"));
+        assert!(result.is_err());
+        let result = x.set_query_key(URL_VERSION_QUERY_NAME, Some(".hidden"));
         assert!(result.is_err());
 
         Ok(())
@@ -7,13 +7,14 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
-    operations::auth_and_connect::connect,
+    operations::auth_and_connect::{connect, read_credentials},
     operations::config::{Config, SnLaunchToolNetworkLauncher},
     subcommands::{
         cat::cat_commander,
         config::config_commander,
         dog::dog_commander,
         files::files_commander,
+        gateway::gateway_commander,
         keys::key_commander,
         networks::networks_commander,
         node::node_commander,
@@ -21,6 +22,7 @@ use crate::{
         setup::setup_commander,
         update::update_commander,
         wallet::wallet_commander,
+        webdav::webdav_commander,
         xorurl::{xorurl_commander, xorurl_of_files},
         OutputFmt, SubCommands,
     },
@@ -55,6 +57,14 @@ pub struct CmdArgs {
     /// Base encoding for XOR-URLs. Currently supported: base32z (default), base32 and base64
     #[clap(long = "xorurl", global(true))]
     xorurl_base: Option<XorUrlBase>,
+    /// Store the data as private content, encrypted to the group key if provided, or otherwise
+    /// to the key of the CLI credentials.
+    #[clap(long = "private", global(true))]
+    private: bool,
+    /// Path to a file with a BLS secret key, in hex, shared among a group of users. Private
+    /// content is encrypted to it, and it's used to decrypt the private content fetched.
+    #[clap(long = "group-key", env = "SAFE_GROUP_KEY_PATH", global(true))]
+    group_key_path: Option<PathBuf>,
 }
 
 pub async fn run() -> Result<()> {
@@ -117,7 +127,7 @@ async fn process_commands(mut safe: &mut Safe, args: CmdArgs, config: &mut Confi
 
     match args.cmd {
         SubCommands::Config { cmd } => config_commander(cmd, config).await,
-        SubCommands::Networks { cmd } => networks_commander(cmd, output_fmt, config).await,
+        SubCommands::Networks { cmd } => networks_commander(cmd, output_fmt, config, safe).await,
         SubCommands::Update { no_confirm } => {
             // We run this command in a separate thread to overcome a conflict with
             // the self_update crate as it seems to be creating its own runtime.
@@ -137,6 +147,10 @@ async fn process_commands(mut safe: &mut Safe, args: CmdArgs, config: &mut Confi
             node_commander(cmd, config, &mut launcher).await
         }
         SubCommands::Keys(cmd) => key_commander(cmd, output_fmt, config),
+        // Building and signing transactions is meant to be possible on an offline machine
+        SubCommands::Wallet(cmd) if cmd.is_offline() => {
+            wallet_commander(cmd, output_fmt, safe, config).await
+        }
         SubCommands::Xorurl {
             cmd,
             location,
@@ -159,6 +173,22 @@ async fn process_commands(mut safe: &mut Safe, args: CmdArgs, config: &mut Confi
         other => {
             // Set dry run mode in Safe instance as per arg provide
             safe.dry_run_mode = args.dry;
+            safe.private_mode = args.private;
+            if let Some(group_key_path) = &args.group_key_path {
+                let group_key = Safe::deserialize_bls_key(group_key_path).map_err(|err| {
+                    eyre!(
+                        "Failed to read group key from {}: {err}",
+                        group_key_path.display()
+                    )
+                })?;
+                safe.set_group_key(Some(group_key));
+            } else if args.private && !args.dry && read_credentials(config)?.1.is_none() {
+                // A read-only connection uses a random key, thus the content would be lost
+                return Err(eyre!(
+                    "Private content can only be stored with a group key or the CLI credentials, \
+                    neither of which were found. Please pass a '--group-key' or set up credentials with 'safe keys create --for-cli'"
+                ));
+            }
             // We treat these commands separatelly since we use the credentials if they are
             // available to connect to the network with them (unless dry-run was set),
             // otherwise the connection created will be with read-only access and some
@@ -171,8 +201,10 @@ async fn process_commands(mut safe: &mut Safe, args: CmdArgs, config: &mut Confi
                 SubCommands::Cat(cmd) => cat_commander(cmd, output_fmt, safe).await,
                 SubCommands::Dog(cmd) => dog_commander(cmd, output_fmt, safe).await,
                 SubCommands::Files(cmd) => files_commander(cmd, output_fmt, safe).await,
+                SubCommands::Gateway(cmd) => gateway_commander(cmd, safe).await,
                 SubCommands::Nrs(cmd) => nrs_commander(cmd, output_fmt, safe).await,
                 SubCommands::Wallet(cmd) => wallet_commander(cmd, output_fmt, safe, config).await,
+                SubCommands::Webdav(cmd) => webdav_commander(cmd, safe).await,
                 _ => Err(eyre!("Unknown safe subcommand")),
             }
         }
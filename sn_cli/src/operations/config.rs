@@ -7,14 +7,14 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::subcommands::OutputFmt;
-use bls::PublicKey as BlsPublicKey;
+use bls::{PublicKey as BlsPublicKey, SecretKey as BlsSecretKey};
 use bytes::Bytes;
 use clap::Parser;
 use color_eyre::{eyre::bail, eyre::eyre, eyre::WrapErr, Help, Report, Result};
 use comfy_table::Table;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use sn_api::{Safe, SectionTree, DEFAULT_NETWORK_CONTACTS_FILE_NAME};
+use sn_api::{NetworkContacts, Safe, SectionTree, DEFAULT_NETWORK_CONTACTS_FILE_NAME};
 use sn_dbc::Owner;
 use std::{
     collections::BTreeMap,
@@ -359,27 +359,55 @@ impl Config {
         self.settings.networks.iter()
     }
 
-    pub async fn add_network(
+    /// Adds the network, once its contacts have been verified.
+    pub async fn add_network(&mut self, name: &str, net_info: NetworkInfo) -> Result<NetworkInfo> {
+        self.add_network_with_publisher(name, net_info, None).await
+    }
+
+    /// Adds the network, once its contacts have been verified.
+    ///
+    /// If a `trusted_publisher` is provided, the network contacts must be signed by it.
+    pub async fn add_network_with_publisher(
         &mut self,
         name: &str,
         mut net_info: NetworkInfo,
+        trusted_publisher: Option<&BlsPublicKey>,
     ) -> Result<NetworkInfo> {
-        match net_info {
-            NetworkInfo::Local(ref mut path, ref mut genesis_key) => {
+        let network_contacts = match net_info {
+            NetworkInfo::Local(ref mut path, _) => {
                 if !path.is_absolute() {
                     *path = fs::canonicalize(&path).await?
                 }
-                let network_contacts = Self::retrieve_local_network_contacts(path).await?;
-                self.write_network_contacts(&network_contacts).await?;
-                *genesis_key = Some(*network_contacts.genesis_key());
+                Self::retrieve_local_network_contacts_file(path).await?
             }
-            NetworkInfo::Remote(ref url, ref mut genesis_key) => {
+            NetworkInfo::Remote(ref url, _) => {
                 let url = Url::parse(url)?;
-                let network_contacts = Self::retrieve_remote_network_contacts(&url).await?;
-                self.write_network_contacts(&network_contacts).await?;
-                *genesis_key = Some(*network_contacts.genesis_key());
+                Self::retrieve_remote_network_contacts_file(&url).await?
             }
         };
+
+        network_contacts
+            .verify(trusted_publisher)
+            .wrap_err_with(|| format!("Network contacts for '{name}' cannot be trusted"))?;
+        debug!("Verified {}", network_contacts.header());
+        match network_contacts.publisher() {
+            Some(publisher) => println!(
+                "Network contacts are signed by publisher {}",
+                publisher.to_hex()
+            ),
+            None => println!("Network contacts are not signed by any publisher"),
+        }
+
+        // keep the file as is, so its signature can still be checked later on
+        Self::write_network_contacts_file_to_dir(&self.network_contacts_dir, &network_contacts)
+            .await?;
+        let genesis_key = *network_contacts.section_tree().genesis_key();
+        match net_info {
+            NetworkInfo::Local(_, ref mut key) | NetworkInfo::Remote(_, ref mut key) => {
+                *key = Some(genesis_key)
+            }
+        }
+
         self.settings
             .networks
             .insert(name.to_string(), net_info.clone());
@@ -390,6 +418,33 @@ impl Config {
         Ok(net_info)
     }
 
+    /// Writes the contacts of the network (or the default network if no name is provided) to
+    /// `dst` in the network contacts file format, signed by the publisher if a key is provided.
+    pub async fn export_network_contacts(
+        &mut self,
+        name: Option<&str>,
+        dst: &Path,
+        publisher_sk: Option<&BlsSecretKey>,
+    ) -> Result<NetworkContacts> {
+        let (section_tree, _) = match name {
+            Some(name) => self.read_network_contacts(name).await?,
+            None => self.read_default_network_contacts().await?,
+        };
+
+        let mut network_contacts = NetworkContacts::new(section_tree)?;
+        if let Some(sk) = publisher_sk {
+            network_contacts.sign(sk)?;
+        }
+        network_contacts.write_to_disk(dst).await?;
+
+        debug!(
+            "Exported {} to {}",
+            network_contacts.header(),
+            dst.display()
+        );
+        Ok(network_contacts)
+    }
+
     pub async fn remove_network(&mut self, name: &str) -> Result<()> {
         match self.settings.networks.remove(name) {
             Some(NetworkInfo::Local(_, genesis_key)) => {
@@ -591,11 +646,21 @@ impl Config {
     }
 
     pub async fn retrieve_local_network_contacts(location: &Path) -> Result<SectionTree> {
-        let pm = SectionTree::from_disk(location).await?;
-        Ok(pm)
+        let network_contacts = Self::retrieve_local_network_contacts_file(location).await?;
+        Ok(network_contacts.into_section_tree())
     }
 
     pub async fn retrieve_remote_network_contacts(url: &Url) -> Result<SectionTree> {
+        let network_contacts = Self::retrieve_remote_network_contacts_file(url).await?;
+        Ok(network_contacts.into_section_tree())
+    }
+
+    pub async fn retrieve_local_network_contacts_file(location: &Path) -> Result<NetworkContacts> {
+        let network_contacts = NetworkContacts::from_disk(location).await?;
+        Ok(network_contacts)
+    }
+
+    pub async fn retrieve_remote_network_contacts_file(url: &Url) -> Result<NetworkContacts> {
         let mut retry = REMOTE_RETRY_COUNT;
         let mut bytes: Option<Bytes> = None;
         let mut status: StatusCode;
@@ -615,8 +680,8 @@ impl Config {
         }
         match bytes {
             Some(b) => {
-                let pm = SectionTree::from_bytes(&b[..])?;
-                Ok(pm)
+                let network_contacts = NetworkContacts::from_bytes(&b[..])?;
+                Ok(network_contacts)
             }
             None => Err(eyre!(
                 "{:?} Failed to fetch network map ({} retries) from '{}'",
@@ -642,6 +707,17 @@ impl Config {
         Ok(())
     }
 
+    // Same as `write_network_contacts_to_dir`, but keeping the file's header and signature.
+    async fn write_network_contacts_file_to_dir(
+        dir: &Path,
+        network_contacts: &NetworkContacts,
+    ) -> Result<()> {
+        let genesis_key = network_contacts.section_tree().genesis_key();
+        let path = dir.join(format!("{genesis_key:?}"));
+        network_contacts.write_to_disk(&path).await?;
+        Ok(())
+    }
+
     fn get_dbc_owner(dbc_sk_path: &Path) -> Result<Option<Owner>> {
         if dbc_sk_path.exists() {
             let sk = Safe::deserialize_bls_key(dbc_sk_path)?;
@@ -1253,11 +1329,72 @@ mod sync_network_contacts_and_settings {
 #[cfg(test)]
 mod networks {
     use super::{test_utils::store_dummy_network_contacts, Config, NetworkInfo};
+    use bls::SecretKey;
     use color_eyre::eyre::eyre;
     use color_eyre::Result;
     use httpmock::MockServer;
+    use sn_api::{test_helpers::TestSectionTree, NetworkContacts};
     use tokio::fs;
 
+    #[tokio::test]
+    async fn network_signed_by_trusted_publisher_should_be_added() -> Result<()> {
+        let tmp_dir = assert_fs::TempDir::new()?;
+        let mut config = Config::create_config(&tmp_dir, None).await?;
+
+        let (section_tree, _) = TestSectionTree::random_tree();
+        let publisher_sk = SecretKey::random();
+        let mut network_contacts = NetworkContacts::new(section_tree)?;
+        network_contacts.sign(&publisher_sk)?;
+        let path = tmp_dir.path().join("signed_network_contacts");
+        network_contacts.write_to_disk(&path).await?;
+
+        let untrusted = SecretKey::random().public_key();
+        let network_1 = NetworkInfo::Local(path.clone(), None);
+        assert!(config
+            .add_network_with_publisher("network_1", network_1, Some(&untrusted))
+            .await
+            .is_err());
+        assert!(config.settings.networks.is_empty());
+
+        let trusted = publisher_sk.public_key();
+        let network_1 = NetworkInfo::Local(path, None);
+        config
+            .add_network_with_publisher("network_1", network_1, Some(&trusted))
+            .await?;
+        assert_eq!(config.settings.networks.len(), 1);
+        config.compare_settings_and_network_contacts_dir().await?;
+
+        // the signature is kept, so it can still be checked once added
+        let (section_tree, _) = config.read_network_contacts("network_1").await?;
+        let genesis_key = section_tree.genesis_key();
+        let stored = Config::retrieve_local_network_contacts_file(
+            &config.network_contacts_dir.join(format!("{genesis_key:?}")),
+        )
+        .await?;
+        stored.verify(Some(&trusted))?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn exported_network_contacts_should_be_verifiable() -> Result<()> {
+        let tmp_dir = assert_fs::TempDir::new()?;
+        let mut config = Config::create_config(&tmp_dir, None).await?;
+        let _ = config
+            .store_dummy_network_contacts_and_set_default(None, 1)
+            .await?;
+
+        let publisher_sk = SecretKey::random();
+        let dst = tmp_dir.path().join("exported_network_contacts");
+        let _ = config
+            .export_network_contacts(None, &dst, Some(&publisher_sk))
+            .await?;
+
+        let exported = Config::retrieve_local_network_contacts_file(&dst).await?;
+        exported.verify(Some(&publisher_sk.public_key()))?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn local_and_remote_networks_should_be_added() -> Result<()> {
         let tmp_dir = assert_fs::TempDir::new()?;
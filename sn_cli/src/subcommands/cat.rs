@@ -103,7 +103,18 @@ pub async fn cat_commander(cmd: CatCommands, output_fmt: OutputFmt, safe: &Safe)
                 println!("Type of content not supported yet by 'cat' command.");
             }
         }
-        SafeData::NrsEntry { .. } | SafeData::Register { .. } => {
+        SafeData::NrsEntry { record, .. } => {
+            // only records not pointing to any content, e.g. text records, are not followed
+            if OutputFmt::Pretty == output_fmt {
+                println!("{}", record.value());
+            } else {
+                println!(
+                    "{}",
+                    serialise_output(&(url.to_string(), record), output_fmt)
+                );
+            }
+        }
+        SafeData::Register { .. } => {
             println!("Type of content not supported yet by 'cat' command.");
         }
     }
@@ -86,6 +86,7 @@ pub async fn dog_commander(cmd: DogCommands, output_fmt: OutputFmt, safe: &Safe)
                     xorurl,
                     public_name,
                     data_type,
+                    record,
                     resolves_into,
                     resolved_from,
                     version,
@@ -93,9 +94,13 @@ pub async fn dog_commander(cmd: DogCommands, output_fmt: OutputFmt, safe: &Safe)
                     println!("Resolved from: {resolved_from}");
                     println!("= NrsEntry =");
                     println!("Public name: {public_name}");
+                    println!("Record type: {}", record.type_name());
+                    println!("Record value: {}", record.value());
                     println!("Target XOR-URL: {xorurl}");
                     println!("Target native data type: {data_type}");
-                    println!("Resolves into: {resolves_into}");
+                    if let Some(resolves_into) = resolves_into {
+                        println!("Resolves into: {resolves_into}");
+                    }
                     println!(
                         "Version: {}",
                         version.map_or("none".to_string(), |v| v.to_string())
@@ -21,7 +21,7 @@ use color_eyre::{eyre::bail, eyre::eyre, Result};
 use comfy_table::Table;
 use serde::Serialize;
 use sn_api::{
-    files::{FilesMap, ProcessedFiles},
+    files::{FileDiff, FileInfo, FilesDiff, FilesDiffStat, FilesFilter, FilesMap, ProcessedFiles},
     nrs::VersionHash,
     resolver::SafeData,
     Safe, SafeUrl, XorUrl,
@@ -105,6 +105,12 @@ pub enum FilesSubCommands {
         /// Follow symlinks
         #[clap(short = 'l', long = "follow-links")]
         follow_links: bool,
+        /// Only upload the files matching this pattern, with gitignore syntax. Can be passed multiple times
+        #[clap(long = "include")]
+        include: Vec<String>,
+        /// Don't upload the files matching this pattern, with gitignore syntax. Can be passed multiple times
+        #[clap(long = "exclude")]
+        exclude: Vec<String>,
     },
     /// Get a file or folder from the SAFE Network
     Get {
@@ -141,6 +147,12 @@ pub enum FilesSubCommands {
         /// Automatically update the NRS name to link to the new version of the FilesContainer. This is only allowed if an NRS URL was provided, and if the NRS name is currently linked to a specific version of the FilesContainer
         #[clap(short = 'u', long = "update-nrs")]
         update_nrs: bool,
+        /// Only upload the files matching this pattern, with gitignore syntax. Can be passed multiple times
+        #[clap(long = "include")]
+        include: Vec<String>,
+        /// Don't upload the files matching this pattern, with gitignore syntax. Can be passed multiple times
+        #[clap(long = "exclude")]
+        exclude: Vec<String>,
     },
     #[clap(name = "add")]
     /// Add a file to an existing FilesContainer on the network
@@ -164,6 +176,12 @@ pub enum FilesSubCommands {
         /// Follow symlinks
         #[clap(short = 'l', long = "follow-links")]
         follow_links: bool,
+        /// Only upload the files matching this pattern, with gitignore syntax. Can be passed multiple times
+        #[clap(long = "include")]
+        include: Vec<String>,
+        /// Don't upload the files matching this pattern, with gitignore syntax. Can be passed multiple times
+        #[clap(long = "exclude")]
+        exclude: Vec<String>,
     },
     #[clap(name = "rm")]
     /// Remove a file from an existing FilesContainer on the network
@@ -177,6 +195,70 @@ pub enum FilesSubCommands {
         #[clap(short = 'r', long = "recursive")]
         recursive: bool,
     },
+    #[clap(name = "cp")]
+    /// Copy a file, or a folder with all its content, within or across FilesContainers, without uploading it again
+    Cp {
+        /// The full URL of the file or folder to copy, optionally including a version to copy it from
+        source: String,
+        /// The URL to copy it to. If it's an existing folder, or ends with '/', the source is copied into it
+        target: String,
+        /// Overwrite the files found at the target location
+        #[clap(short = 'f', long = "force")]
+        force: bool,
+        /// Automatically update the NRS name to link to the new version of the FilesContainer. This is only allowed if an NRS URL was provided as the target, and if the NRS name is currently linked to a specific version of the FilesContainer
+        #[clap(short = 'u', long = "update-nrs")]
+        update_nrs: bool,
+    },
+    #[clap(name = "mv")]
+    /// Move, or rename, a file or a folder with all its content, within or across FilesContainers, without uploading it again
+    Mv {
+        /// The full URL of the file or folder to move
+        source: String,
+        /// The URL to move it to. If it's an existing folder, or ends with '/', the source is moved into it
+        target: String,
+        /// Overwrite the files found at the target location
+        #[clap(short = 'f', long = "force")]
+        force: bool,
+        /// Automatically update the NRS name to link to the new version of the FilesContainer. This is only allowed if an NRS URL was provided as the target, and if the NRS name is currently linked to a specific version of the FilesContainer
+        #[clap(short = 'u', long = "update-nrs")]
+        update_nrs: bool,
+    },
+    #[clap(name = "diff")]
+    /// Show the differences between two versions of a FilesContainer, or between a FilesContainer and a local folder
+    Diff {
+        /// The FilesContainer to compare, optionally including a path to only compare the files within it. When comparing with a local location, the version in the URL is compared, or the latest one if none
+        target: String,
+        /// The local file or folder to compare the FilesContainer with, mapping its files to the container as 'files sync' does
+        #[clap(long = "local", conflicts_with_all = &["from", "to"], required_unless_present = "from")]
+        location: Option<String>,
+        /// The version of the FilesContainer to compare from
+        #[clap(long = "from")]
+        from: Option<VersionHash>,
+        /// The version of the FilesContainer to compare to (default is the latest)
+        #[clap(long = "to")]
+        to: Option<VersionHash>,
+        /// Recursively compare the folders and files found in the local location
+        #[clap(short = 'r', long = "recursive")]
+        recursive: bool,
+        /// Follow symlinks
+        #[clap(short = 'l', long = "follow-links")]
+        follow_links: bool,
+        /// Only compare the local files matching this pattern, with gitignore syntax. Can be passed multiple times
+        #[clap(long = "include")]
+        include: Vec<String>,
+        /// Don't compare the local files matching this pattern, with gitignore syntax. Can be passed multiple times
+        #[clap(long = "exclude")]
+        exclude: Vec<String>,
+        /// Only output the number of items, and of bytes, which differ
+        #[clap(long = "stat")]
+        stat: bool,
+    },
+    #[clap(name = "tag")]
+    /// Manage the named tags of FilesContainer versions, which can be used instead of version hashes in URLs, e.g. 'safe://<container>?v=v1.2.0'
+    Tag {
+        #[clap(subcommand)]
+        cmd: FilesTagSubCommands,
+    },
     #[clap(name = "ls")]
     /// List files found in an existing FilesContainer on the network
     Ls {
@@ -194,6 +276,35 @@ pub enum FilesSubCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum FilesTagSubCommands {
+    #[clap(name = "add")]
+    /// Tag a version of a FilesContainer
+    Add {
+        /// The FilesContainer to tag, including the version to tag (default is the latest)
+        target: String,
+        /// The name of the tag, with up to 64 alphanumeric, '.', '-' or '_' characters, e.g. 'v1.2.0' or 'latest-stable'
+        tag: String,
+        /// Move the tag to this version if it already points to another one
+        #[clap(short = 'f', long = "force")]
+        force: bool,
+    },
+    #[clap(name = "ls")]
+    /// List the tags of a FilesContainer
+    Ls {
+        /// The FilesContainer to list the tags of
+        target: String,
+    },
+    #[clap(name = "rm")]
+    /// Remove a tag from a FilesContainer. The version it points to is not affected
+    Rm {
+        /// The FilesContainer to remove the tag from
+        target: String,
+        /// The name of the tag to remove
+        tag: String,
+    },
+}
+
 pub async fn files_commander(
     cmd: FilesSubCommands,
     output_fmt: OutputFmt,
@@ -205,13 +316,22 @@ pub async fn files_commander(
             dst,
             recursive,
             follow_links,
+            include,
+            exclude,
         } => {
             // create FilesContainer from a given path to local files/folders
             if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
                 notice_dry_run();
             }
+            let filter = files_filter(include, exclude);
             let (files_container_xorurl, processed_files, _) = safe
-                .files_container_create_from(&location, dst.as_deref(), recursive, follow_links)
+                .files_container_create_from(
+                    &location,
+                    dst.as_deref(),
+                    recursive,
+                    follow_links,
+                    filter.as_ref(),
+                )
                 .await?;
 
             // Now let's just print out a list of the files uploaded/processed
@@ -237,6 +357,8 @@ pub async fn files_commander(
             follow_links,
             delete,
             update_nrs,
+            include,
+            exclude,
         } => {
             let target = get_from_arg_or_stdin(target, None)?;
             let mut target_url = get_target_url(&target)?;
@@ -252,6 +374,7 @@ pub async fn files_commander(
                     follow_links,
                     delete,
                     update_nrs,
+                    files_filter(include, exclude).as_ref(),
                 )
                 .await?;
             let version = content.map(|(version, _)| version);
@@ -287,6 +410,8 @@ pub async fn files_commander(
             update_nrs,
             follow_links,
             force,
+            include,
+            exclude,
         } => {
             // Validate that location and target are not both "", ie stdin.
             let target_url = target.unwrap_or_default();
@@ -309,7 +434,8 @@ pub async fn files_commander(
                     safe.files_container_add_from_raw(Bytes::from(file_content), &target_url, force, update_nrs).await?
                 } else {
                     // Update the FilesContainer on the Network
-                    safe.files_container_add(&location, &target_url, force, update_nrs, follow_links).await?
+                    let filter = files_filter(include, exclude);
+                    safe.files_container_add(&location, &target_url, force, update_nrs, follow_links, filter.as_ref()).await?
                 };
 
             // Now let's just print out a list of the files synced/processed
@@ -342,6 +468,87 @@ pub async fn files_commander(
             output_processed_files_list(output_fmt, &processed_files, Some(version), target_url);
             Ok(())
         }
+        FilesSubCommands::Cp {
+            source,
+            target,
+            force,
+            update_nrs,
+        } => {
+            if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
+                notice_dry_run();
+            }
+
+            // Update the target FilesContainer on the Network
+            let (version, processed_files, _) = safe
+                .files_container_copy(&source, &target, force, update_nrs)
+                .await?;
+
+            // Now let's just print out a list of the files copied
+            output_processed_files_list(output_fmt, &processed_files, Some(version), target);
+            Ok(())
+        }
+        FilesSubCommands::Mv {
+            source,
+            target,
+            force,
+            update_nrs,
+        } => {
+            if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
+                notice_dry_run();
+            }
+
+            // Update the FilesContainers on the Network
+            let (version, processed_files, _) = safe
+                .files_container_move(&source, &target, force, update_nrs)
+                .await?;
+
+            // Now let's just print out a list of the files moved and removed
+            output_processed_files_list(output_fmt, &processed_files, Some(version), target);
+            Ok(())
+        }
+        FilesSubCommands::Diff {
+            target,
+            location,
+            from,
+            to,
+            recursive,
+            follow_links,
+            include,
+            exclude,
+            stat,
+        } => {
+            let (from_desc, to_desc, diff) = match (location, from) {
+                (Some(location), _) => {
+                    let filter = files_filter(include, exclude);
+                    let (version, diff) = safe
+                        .files_container_diff_local(
+                            &target,
+                            &location,
+                            recursive,
+                            follow_links,
+                            filter.as_ref(),
+                        )
+                        .await?;
+                    let version = version.map_or_else(|| "empty".to_string(), |v| v.to_string());
+                    (format!("version {version}"), format!("'{location}'"), diff)
+                }
+                (None, Some(from)) => {
+                    let (version, diff) = safe.files_container_diff(&target, from, to).await?;
+                    (
+                        format!("version {from}"),
+                        format!("version {version}"),
+                        diff,
+                    )
+                }
+                (None, None) => {
+                    bail!("Either a local location or a version to compare from must be provided")
+                }
+            };
+
+            output_files_diff(output_fmt, &target, &from_desc, &to_desc, &diff, stat);
+            Ok(())
+        }
+        FilesSubCommands::Tag { cmd } => files_tag_commander(cmd, output_fmt, safe).await,
         FilesSubCommands::Ls { target } => {
             let target_url =
                 get_from_arg_or_stdin(target, Some("...awaiting target URl from STDIN"))?;
@@ -453,6 +660,135 @@ async fn process_tree_command(
     Ok(())
 }
 
+async fn files_tag_commander(
+    cmd: FilesTagSubCommands,
+    output_fmt: OutputFmt,
+    safe: &Safe,
+) -> Result<()> {
+    match cmd {
+        FilesTagSubCommands::Add { target, tag, force } => {
+            if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
+                notice_dry_run();
+            }
+
+            let version = safe.files_container_tag_add(&target, &tag, force).await?;
+            if OutputFmt::Pretty == output_fmt {
+                println!("Tag '{tag}' now points to version {version} of \"{target}\"");
+            } else {
+                println!("{}", serialise_output(&(tag, version), output_fmt));
+            }
+        }
+        FilesTagSubCommands::Ls { target } => {
+            let tags = safe.files_container_tags(&target).await?;
+            if OutputFmt::Pretty != output_fmt {
+                println!("{}", serialise_output(&(target, tags), output_fmt));
+            } else if tags.is_empty() {
+                println!("No tags found for FilesContainer at \"{target}\"");
+            } else {
+                println!("Tags of FilesContainer at \"{target}\":");
+                let mut table = Table::new();
+                table.add_row(&vec!["TAG", "VERSION"]);
+                for (tag, version) in tags.iter() {
+                    table.add_row(&vec![tag.clone(), version.to_string()]);
+                }
+                println!("{table}");
+            }
+        }
+        FilesTagSubCommands::Rm { target, tag } => {
+            if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
+                notice_dry_run();
+            }
+
+            let version = safe.files_container_tag_remove(&target, &tag).await?;
+            if OutputFmt::Pretty == output_fmt {
+                println!("Tag '{tag}' removed, it pointed to version {version} of \"{target}\"");
+            } else {
+                println!("{}", serialise_output(&(tag, version), output_fmt));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn output_files_diff(
+    output_fmt: OutputFmt,
+    target_url: &str,
+    from_desc: &str,
+    to_desc: &str,
+    diff: &FilesDiff,
+    stat: bool,
+) {
+    let stat = if stat {
+        Some(FilesDiffStat::new(diff))
+    } else {
+        None
+    };
+
+    if OutputFmt::Pretty != output_fmt {
+        match stat {
+            Some(stat) => println!("{}", serialise_output(&(target_url, stat), output_fmt)),
+            None => println!("{}", serialise_output(&(target_url, diff), output_fmt)),
+        }
+        return;
+    }
+
+    if diff.is_empty() {
+        println!("No differences found in \"{target_url}\" from {from_desc} to {to_desc}");
+        return;
+    }
+
+    println!("Differences found in \"{target_url}\" from {from_desc} to {to_desc}:");
+    match stat {
+        Some(stat) => {
+            println!(
+                "{} added, {} removed, {} modified, {} with only metadata changes",
+                stat.added, stat.removed, stat.modified, stat.metadata_changed
+            );
+            println!(
+                "{} added, {} removed",
+                if_tty(
+                    &format!("+{} bytes", stat.bytes_added),
+                    Colour::Green.normal()
+                ),
+                if_tty(
+                    &format!("-{} bytes", stat.bytes_removed),
+                    Colour::Red.normal()
+                )
+            );
+        }
+        None => {
+            let mut table = Table::new();
+            for (path, file_diff) in diff.iter() {
+                let (change_sign, details) = match file_diff {
+                    FileDiff::Added(new) => ("+", file_item_link(new)),
+                    FileDiff::Removed(old) => ("-", file_item_link(old)),
+                    FileDiff::Modified { new, .. } => ("*", file_item_link(new)),
+                    FileDiff::MetadataChanged { changed, .. } => {
+                        ("M", format!("<{}>", changed.join(", ")))
+                    }
+                };
+                table.add_row(&vec![change_sign.to_string(), path.clone(), details]);
+            }
+            println!("{table}");
+        }
+    }
+}
+
+// The link of a file, or an empty string for folders and symlinks
+fn file_item_link(file_item: &FileInfo) -> String {
+    file_item.get("link").cloned().unwrap_or_default()
+}
+
+// The filter for the local files to upload, if any pattern was provided
+fn files_filter(include: Vec<String>, exclude: Vec<String>) -> Option<FilesFilter> {
+    if include.is_empty() && exclude.is_empty() {
+        None
+    } else {
+        Some(FilesFilter::new(include, exclude))
+    }
+}
+
 fn print_serialized_output(
     xorurl: XorUrl,
     change_version: Option<VersionHash>,
@@ -680,7 +680,7 @@ fn create_dir_all(dir_path: &Path) -> Result<()> {
 /// Get immutable files from the network.
 pub async fn files_get(safe: &Safe, url: &str, range: Range) -> Result<Vec<u8>> {
     match SafeUrl::from_url(url)?.data_type() {
-        DataType::File => {
+        DataType::File | DataType::PrivateFile => {
             let bytes = safe.files_get(url, range).await?;
             Ok(bytes.chunk().to_vec())
         }
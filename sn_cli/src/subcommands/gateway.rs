@@ -0,0 +1,588 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use clap::Args;
+use color_eyre::{eyre::WrapErr, Result};
+use hyper::{
+    header::{self, HeaderValue},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use sn_api::{
+    files::FilesMap, resolver::SafeData, Error as ApiError, Result as ApiResult, Safe, SafeUrl,
+};
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
+use tracing::{debug, info, warn};
+
+// File type of the directories in a FilesMap
+pub(super) const DIRECTORY_TYPE: &str = "inode/directory";
+// File served when a directory is requested, if the directory contains it
+const INDEX_FILE: &str = "index.html";
+pub(super) const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+#[derive(Args, Debug)]
+pub struct GatewayCommands {
+    /// Port to serve the content on
+    #[clap(long = "port", default_value = "8080")]
+    port: u16,
+    /// Address to listen on. Defaults to localhost so the gateway is not exposed to other hosts
+    #[clap(long = "bind", default_value = "127.0.0.1")]
+    bind: IpAddr,
+}
+
+pub async fn gateway_commander(cmd: GatewayCommands, safe: &Safe) -> Result<()> {
+    let addr = SocketAddr::new(cmd.bind, cmd.port);
+    let safe = safe.clone();
+    let make_service = make_service_fn(move |_conn| {
+        let safe = safe.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let safe = safe.clone();
+                async move { Ok::<_, Infallible>(serve(&safe, req).await) }
+            }))
+        }
+    });
+
+    let server = Server::try_bind(&addr)
+        .wrap_err_with(|| format!("Failed to listen on {addr}"))?
+        .serve(make_service);
+
+    println!("Serving safe:// content at http://{addr}/<nrs-name>/<path>");
+    println!("Press Ctrl+C to stop the gateway");
+
+    server
+        .await
+        .wrap_err("The gateway stopped serving requests")
+}
+
+// What a gateway path resolved to
+#[derive(Debug)]
+enum Target {
+    File {
+        xorurl: String,
+        content_type: String,
+        size: Option<u64>,
+        etag: Option<String>,
+        // encrypted to a key, thus only served if we hold it
+        private: bool,
+    },
+    Directory {
+        files_map: FilesMap,
+        etag: Option<String>,
+    },
+}
+
+async fn serve(safe: &Safe, req: Request<Body>) -> Response<Body> {
+    let head_only = match *req.method() {
+        Method::GET => false,
+        Method::HEAD => true,
+        _ => {
+            return error_response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Only GET and HEAD requests are supported",
+            )
+        }
+    };
+
+    let request_path = req.uri().path().to_string();
+    let (name, path) = match split_gateway_path(&request_path) {
+        Some(name_and_path) => name_and_path,
+        None => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "Expected a path of the form /<nrs-name>/<path>",
+            )
+        }
+    };
+    let url = match gateway_safe_url(name, path) {
+        Ok(url) => url,
+        Err(err) => return api_error_response(&request_path, err),
+    };
+    debug!("Gateway request for {request_path} resolving {url}");
+
+    let target = match resolve_target(safe, &url).await {
+        Ok(Target::Directory { .. }) if !path.ends_with('/') => {
+            // relative links within the directory's index need the trailing slash
+            return redirect_response(&format!("{request_path}/"));
+        }
+        Ok(Target::Directory { files_map, etag }) => {
+            if has_index_file(&files_map) {
+                match resolve_target(safe, &format!("{url}{INDEX_FILE}")).await {
+                    Ok(index @ Target::File { .. }) => index,
+                    Ok(Target::Directory { .. }) => Target::Directory { files_map, etag },
+                    Err(err) => return api_error_response(&url, err),
+                }
+            } else {
+                Target::Directory { files_map, etag }
+            }
+        }
+        Ok(file) => file,
+        Err(err) => return api_error_response(&url, err),
+    };
+
+    let if_none_match = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok());
+    let etag = match &target {
+        Target::File { etag, .. } | Target::Directory { etag, .. } => etag.clone(),
+    };
+    if let (Some(etag), Some(if_none_match)) = (&etag, if_none_match) {
+        if if_none_match == etag || if_none_match == "*" {
+            let mut response = Response::new(Body::empty());
+            *response.status_mut() = StatusCode::NOT_MODIFIED;
+            set_header(&mut response, header::ETAG, etag);
+            return response;
+        }
+    }
+
+    let mut response = match target {
+        Target::Directory { files_map, .. } => {
+            let listing = directory_listing(&request_path, &files_map);
+            let mut response = Response::new(Body::from(listing));
+            set_header(
+                &mut response,
+                header::CONTENT_TYPE,
+                "text/html; charset=utf-8",
+            );
+            response
+        }
+        Target::File {
+            xorurl,
+            content_type,
+            size,
+            private,
+            ..
+        } => {
+            let range_header = req
+                .headers()
+                .get(header::RANGE)
+                .and_then(|value| value.to_str().ok());
+            match serve_file(safe, &xorurl, size, range_header, head_only).await {
+                Ok(mut response) => {
+                    set_header(&mut response, header::CONTENT_TYPE, &content_type);
+                    if private {
+                        // decrypted content mustn't be kept by any shared cache
+                        set_header(&mut response, header::CACHE_CONTROL, "private, no-store");
+                    }
+                    response
+                }
+                Err(err) => return api_error_response(&url, err),
+            }
+        }
+    };
+
+    if let Some(etag) = etag {
+        set_header(&mut response, header::ETAG, &etag);
+    }
+    if head_only {
+        *response.body_mut() = Body::empty();
+    }
+
+    response
+}
+
+// Fetches the file, or the range of it requested, unless only its headers were requested.
+pub(super) async fn serve_file(
+    safe: &Safe,
+    xorurl: &str,
+    size: Option<u64>,
+    range_header: Option<&str>,
+    head_only: bool,
+) -> ApiResult<Response<Body>> {
+    let range = match (range_header, size) {
+        (Some(range_header), Some(size)) => match parse_range(range_header, size) {
+            Ok(range) => range,
+            Err(()) => {
+                let mut response = error_response(
+                    StatusCode::RANGE_NOT_SATISFIABLE,
+                    &format!("Range '{range_header}' can't be satisfied"),
+                );
+                set_header(
+                    &mut response,
+                    header::CONTENT_RANGE,
+                    &format!("bytes */{size}"),
+                );
+                return Ok(response);
+            }
+        },
+        // without the size of the file we can't tell whether the range is satisfiable
+        _ => None,
+    };
+
+    let mut response = match range {
+        Some((start, end)) => {
+            let data = if head_only {
+                Default::default()
+            } else {
+                safe.files_get(xorurl, Some((Some(start), Some(end))))
+                    .await?
+            };
+            let mut response = Response::new(Body::from(data));
+            *response.status_mut() = StatusCode::PARTIAL_CONTENT;
+            let size = size.unwrap_or_default();
+            set_header(
+                &mut response,
+                header::CONTENT_RANGE,
+                &format!("bytes {start}-{}/{size}", end - 1),
+            );
+            set_header(
+                &mut response,
+                header::CONTENT_LENGTH,
+                &(end - start).to_string(),
+            );
+            response
+        }
+        None => {
+            let data = if head_only {
+                Default::default()
+            } else {
+                safe.files_get(xorurl, None).await?
+            };
+            let length = if head_only {
+                size.unwrap_or_default()
+            } else {
+                data.len() as u64
+            };
+            let mut response = Response::new(Body::from(data));
+            set_header(&mut response, header::CONTENT_LENGTH, &length.to_string());
+            response
+        }
+    };
+
+    set_header(&mut response, header::ACCEPT_RANGES, "bytes");
+    Ok(response)
+}
+
+// Resolves the safe:// URL, without fetching the content of the file it resolves to, if any.
+// The version of the FilesContainer it's found in is used as the ETag.
+async fn resolve_target(safe: &Safe, url: &str) -> ApiResult<Target> {
+    let resolution_chain = safe.inspect(url).await?;
+    let etag = resolution_chain.iter().rev().find_map(|data| match data {
+        SafeData::FilesContainer {
+            version: Some(version),
+            ..
+        } => Some(format!("\"{version}\"")),
+        _ => None,
+    });
+
+    match resolution_chain.into_iter().last() {
+        Some(SafeData::PublicFile {
+            xorurl,
+            media_type,
+            metadata,
+            ..
+        }) => {
+            let content_type = media_type
+                .or_else(|| metadata.as_ref().and_then(|info| info.get("type").cloned()))
+                .unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_string());
+            let size = metadata
+                .as_ref()
+                .and_then(|info| info.get("size"))
+                .and_then(|size| size.parse().ok());
+            // private files resolve as any other file, their content being decrypted when fetched
+            let private = SafeUrl::from_url(&xorurl)?.is_private();
+
+            Ok(Target::File {
+                xorurl,
+                content_type,
+                size,
+                etag,
+                private,
+            })
+        }
+        Some(SafeData::FilesContainer { files_map, .. }) => {
+            Ok(Target::Directory { files_map, etag })
+        }
+        Some(other) => Err(ApiError::ContentError(format!(
+            "Content at {url} can't be served over HTTP: {}",
+            other.xorurl()
+        ))),
+        None => Err(ApiError::ContentNotFound(format!(
+            "Failed to resolve {url}"
+        ))),
+    }
+}
+
+// Builds the safe:// URL for the NRS name, or XOR-URL, and the percent-encoded request path.
+// The path is decoded first, to be encoded as the SafeUrl does, so it's resolved to the same
+// FilesMap path whichever characters the HTTP client chose to encode.
+fn gateway_safe_url(name: &str, path: &str) -> ApiResult<String> {
+    let path = percent_encoding::percent_decode_str(path)
+        .decode_utf8()
+        .map_err(|err| ApiError::InvalidInput(format!("Invalid UTF-8 path '{path}': {err}")))?;
+    let mut safe_url = SafeUrl::from_url(&format!("safe://{name}"))?;
+    safe_url.set_path(&path);
+    Ok(safe_url.to_string())
+}
+
+// Splits a request path into the NRS name, or XOR-URL, and the path within its content.
+fn split_gateway_path(request_path: &str) -> Option<(&str, &str)> {
+    let request_path = request_path.strip_prefix('/')?;
+    let (name, path) = match request_path.find('/') {
+        Some(index) => request_path.split_at(index),
+        None => (request_path, ""),
+    };
+
+    if name.is_empty() {
+        None
+    } else {
+        Some((name, path))
+    }
+}
+
+// Parses the range of an HTTP `Range` header for a file of the given size, into the start
+// and end (exclusive) offsets to fetch. Multiple ranges aren't supported, in which case the
+// header is ignored, as well as when it's malformed, as per RFC 7233.
+fn parse_range(header: &str, size: u64) -> Result<Option<(u64, u64)>, ()> {
+    let spec = match header.trim().strip_prefix("bytes=") {
+        Some(spec) if !spec.contains(',') => spec,
+        _ => return Ok(None),
+    };
+    let (start, end) = match spec.split_once('-') {
+        Some(bounds) => bounds,
+        None => return Ok(None),
+    };
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return Ok(None),
+        // suffix range, i.e. the last bytes of the file
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return Err(()),
+            Ok(suffix) => (size.saturating_sub(suffix), size),
+            Err(_) => return Ok(None),
+        },
+        (start, end) => {
+            let start = match start.parse::<u64>() {
+                Ok(start) => start,
+                Err(_) => return Ok(None),
+            };
+            let end = if end.is_empty() {
+                size
+            } else {
+                match end.parse::<u64>() {
+                    Ok(end) if end < start => return Ok(None),
+                    Ok(end) => end.saturating_add(1).min(size),
+                    Err(_) => return Ok(None),
+                }
+            };
+            (start, end)
+        }
+    };
+
+    if start >= size {
+        return Err(());
+    }
+
+    Ok(Some((start, end)))
+}
+
+fn has_index_file(files_map: &FilesMap) -> bool {
+    files_map.iter().any(|(path, file_item)| {
+        path.trim_start_matches('/') == INDEX_FILE
+            && file_item
+                .get("type")
+                .map_or(false, |file_type| file_type != DIRECTORY_TYPE)
+    })
+}
+
+// Returns the entries found directly within the directory the FilesMap is for, with the
+// size of those which are files.
+fn directory_entries(files_map: &FilesMap) -> BTreeMap<String, Option<String>> {
+    let mut entries = BTreeMap::new();
+    for (path, file_item) in files_map.iter() {
+        let path = path.trim_start_matches('/');
+        if path.is_empty() {
+            continue;
+        }
+
+        match path.split_once('/') {
+            Some((dir, _)) => {
+                let _ = entries.insert(format!("{dir}/"), None);
+            }
+            None if file_item.get("type").map(String::as_str) == Some(DIRECTORY_TYPE) => {
+                let _ = entries.insert(format!("{path}/"), None);
+            }
+            None => {
+                let _ = entries.insert(path.to_string(), file_item.get("size").cloned());
+            }
+        }
+    }
+
+    entries
+}
+
+fn directory_listing(request_path: &str, files_map: &FilesMap) -> String {
+    let title = html_escape(request_path);
+    let mut listing = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Index of {title}</title></head>\n<body>\n<h1>Index of {title}</h1>\n<ul>\n<li><a href=\"../\">../</a></li>\n"
+    );
+    for (name, size) in directory_entries(files_map) {
+        let href =
+            html_escape(&percent_encoding::utf8_percent_encode(&name, PATH_SEGMENT).to_string());
+        let name = html_escape(&name);
+        let size = size.map_or(String::new(), |size| format!(" ({size} bytes)"));
+        listing.push_str(&format!("<li><a href=\"{href}\">{name}</a>{size}</li>\n"));
+    }
+    listing.push_str("</ul>\n</body>\n</html>\n");
+
+    listing
+}
+
+// Characters to percent-encode in the entries of directory listings, keeping the trailing
+// slash of the directories
+pub(super) const PATH_SEGMENT: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+pub(super) fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+pub(super) fn set_header(response: &mut Response<Body>, name: header::HeaderName, value: &str) {
+    match HeaderValue::from_str(value) {
+        Ok(value) => {
+            let _ = response.headers_mut().insert(name, value);
+        }
+        Err(err) => warn!("Not setting invalid value '{value}' for header {name}: {err}"),
+    }
+}
+
+fn redirect_response(location: &str) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = StatusCode::MOVED_PERMANENTLY;
+    set_header(&mut response, header::LOCATION, location);
+    response
+}
+
+pub(super) fn error_response(status: StatusCode, msg: &str) -> Response<Body> {
+    let mut response = Response::new(Body::from(format!("{msg}\n")));
+    *response.status_mut() = status;
+    set_header(
+        &mut response,
+        header::CONTENT_TYPE,
+        "text/plain; charset=utf-8",
+    );
+    response
+}
+
+pub(super) fn api_error_response(url: &str, err: ApiError) -> Response<Body> {
+    info!("Failed to serve {url}: {err}");
+    let status = match err {
+        ApiError::ContentNotFound(_)
+        | ApiError::ContentError(_)
+        | ApiError::EmptyContent(_)
+        | ApiError::VersionNotFound(_)
+        | ApiError::HashNotFound(_)
+        | ApiError::EntryNotFound(_) => StatusCode::NOT_FOUND,
+        ApiError::InvalidInput(_) | ApiError::InvalidXorUrl(_) | ApiError::UrlError(_) => {
+            StatusCode::BAD_REQUEST
+        }
+        ApiError::AccessDenied(_) => StatusCode::FORBIDDEN,
+        _ => StatusCode::BAD_GATEWAY,
+    };
+    error_response(status, &format!("Failed to serve {url}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sn_api::files::FileInfo;
+
+    fn file_item(file_type: &str, size: &str) -> FileInfo {
+        let mut item = FileInfo::new();
+        let _ = item.insert("type".to_string(), file_type.to_string());
+        let _ = item.insert("size".to_string(), size.to_string());
+        item
+    }
+
+    #[test]
+    fn gateway_path_is_split_into_name_and_path() {
+        assert_eq!(split_gateway_path("/site"), Some(("site", "")));
+        assert_eq!(split_gateway_path("/site/"), Some(("site", "/")));
+        assert_eq!(
+            split_gateway_path("/site/docs/a.md"),
+            Some(("site", "/docs/a.md"))
+        );
+        assert_eq!(split_gateway_path("/"), None);
+    }
+
+    #[test]
+    fn gateway_path_is_decoded_into_the_safe_url_path() -> ApiResult<()> {
+        for path in ["/my%20docs/a%23b%3Fc.md", "/my docs/a%23b%3Fc.md"] {
+            let url = gateway_safe_url("site", path)?;
+            let safe_url = SafeUrl::from_url(&url)?;
+            assert_eq!(safe_url.path_decoded()?, "/my docs/a#b?c.md");
+            assert_eq!(safe_url.content_version(), None);
+        }
+        assert!(gateway_safe_url("site", "/%FF").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ranges_are_parsed_into_exclusive_end_offsets() {
+        assert_eq!(parse_range("bytes=0-9", 100), Ok(Some((0, 10))));
+        assert_eq!(parse_range("bytes=90-", 100), Ok(Some((90, 100))));
+        assert_eq!(parse_range("bytes=-10", 100), Ok(Some((90, 100))));
+        assert_eq!(parse_range("bytes=50-500", 100), Ok(Some((50, 100))));
+        // unsatisfiable
+        assert_eq!(parse_range("bytes=100-", 100), Err(()));
+        assert_eq!(parse_range("bytes=-0", 100), Err(()));
+        // ignored
+        assert_eq!(parse_range("bytes=0-1,5-6", 100), Ok(None));
+        assert_eq!(parse_range("bytes=9-0", 100), Ok(None));
+        assert_eq!(parse_range("items=0-9", 100), Ok(None));
+    }
+
+    #[test]
+    fn directory_listing_has_the_direct_entries_only() {
+        let mut files_map = FilesMap::default();
+        let _ = files_map.insert("/index.md".to_string(), file_item("text/markdown", "12"));
+        let _ = files_map.insert("/docs".to_string(), file_item(DIRECTORY_TYPE, "0"));
+        let _ = files_map.insert("/docs/a b.md".to_string(), file_item("text/markdown", "3"));
+        let _ = files_map.insert("/img/logo.png".to_string(), file_item("image/png", "42"));
+
+        let entries = directory_entries(&files_map);
+        let names: Vec<_> = entries.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["docs/", "img/", "index.md"]);
+        assert_eq!(entries.get("index.md"), Some(&Some("12".to_string())));
+
+        let listing = directory_listing("/site/", &files_map);
+        assert!(listing.contains("<a href=\"docs/\">docs/</a>"));
+        assert!(listing.contains("<a href=\"index.md\">index.md</a> (12 bytes)"));
+        assert!(!listing.contains("a b.md"));
+        assert!(!has_index_file(&files_map));
+    }
+
+    #[test]
+    fn index_file_is_found_in_root_and_chrooted_files_maps() {
+        let mut files_map = FilesMap::default();
+        let _ = files_map.insert("/index.html".to_string(), file_item("text/html", "10"));
+        assert!(has_index_file(&files_map));
+
+        let mut chrooted = FilesMap::default();
+        let _ = chrooted.insert("index.html".to_string(), file_item("text/html", "10"));
+        assert!(has_index_file(&chrooted));
+    }
+}
@@ -135,6 +135,7 @@ pub fn gen_processed_files_table(
             FilesMapChange::Added(link) => ("+".to_string(), link.clone()),
             FilesMapChange::Updated(link) => ("*".to_string(), link.clone()),
             FilesMapChange::Removed(link) => ("-".to_string(), link.clone()),
+            FilesMapChange::Skipped(reason) => ("I".to_string(), format!("<{reason}>")),
         };
 
         if show_change_sign {
@@ -13,6 +13,7 @@ pub mod config;
 pub mod dog;
 pub mod files;
 mod files_get;
+pub mod gateway;
 mod helpers;
 pub mod keys;
 pub mod networks;
@@ -22,6 +23,7 @@ pub mod safe_id;
 pub mod setup;
 pub mod update;
 pub mod wallet;
+pub mod webdav;
 pub mod xorurl;
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
@@ -85,6 +87,12 @@ pub enum SubCommands {
     #[clap(name = "files", subcommand, global_settings(&[AppSettings::DisableVersion]))]
     /// Manage files on the SAFE Network
     Files(files::FilesSubCommands),
+    #[clap(name = "gateway", global_settings(&[AppSettings::DisableVersion]))]
+    /// Serve safe:// content over HTTP on localhost, to browse it with a web browser
+    Gateway(gateway::GatewayCommands),
+    #[clap(name = "webdav", global_settings(&[AppSettings::DisableVersion]))]
+    /// Serve a FilesContainer as a WebDAV drive on localhost, to mount it and edit its files with any tool
+    Webdav(webdav::WebDavCommands),
     #[clap(name = "setup", subcommand, global_settings(&[AppSettings::DisableVersion]))]
     /// Perform setup tasks
     Setup(setup::SetupSubCommands),
@@ -7,15 +7,22 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
-    operations::config::{Config, NetworkInfo},
-    subcommands::OutputFmt,
+    operations::{
+        auth_and_connect::connect,
+        config::{Config, NetworkInfo},
+    },
+    subcommands::{helpers::serialise_output, OutputFmt},
 };
+use bls::PublicKey as BlsPublicKey;
 use clap::Subcommand;
-use color_eyre::Result;
+use color_eyre::{eyre::eyre, eyre::WrapErr, Result};
 use comfy_table::{Cell, CellAlignment, Table};
-use std::path::PathBuf;
+use serde::Serialize;
+use sn_api::{NetworkHealthReport, Safe, SectionHealthReport, SectionMembersReport};
+use std::{collections::BTreeMap, path::PathBuf};
 use tracing::debug;
 use url::Url;
+use xor_name::Prefix;
 
 #[derive(Subcommand, Debug)]
 pub enum NetworksSubCommands {
@@ -35,6 +42,24 @@ pub enum NetworksSubCommands {
         network_name: String,
         /// Local path or a remote URL to fetch the network contacts from
         contacts_file_location: String,
+        /// Hex-encoded BLS public key of the publisher the network contacts must be signed by.
+        /// Signed network contacts are rejected if it's not provided.
+        #[clap(long = "publisher-key")]
+        publisher_key: Option<String>,
+    },
+    #[clap(name = "export")]
+    /// Export the contacts of a network to a file, optionally signed by a publisher key, to share
+    /// them with others
+    Export {
+        /// Path to write the network contacts file to
+        dst: PathBuf,
+        /// Network to export the contacts of, or default network if no name is provided
+        #[clap(long = "network")]
+        network_name: Option<String>,
+        /// Path to a file containing the hex-encoded BLS secret key to sign the network contacts
+        /// with, as their publisher
+        #[clap(long = "sign-with")]
+        publisher_sk_path: Option<PathBuf>,
     },
     #[clap(name = "remove")]
     /// Remove a network from the CLI config
@@ -48,12 +73,128 @@ pub enum NetworksSubCommands {
         /// Network to show sections information from, or default network if no name is provided
         network_name: Option<String>,
     },
+    #[clap(name = "crawl")]
+    /// Crawl the default network, querying the elders of every section for their view of its
+    /// membership and their storage levels, and report on the health of each section
+    Crawl {},
+    #[clap(name = "members")]
+    /// Query the elders of a section of the default network for its current members, each
+    /// signed by the section, and verify them against the network's section keys
+    Members {
+        /// Prefix of the section, as a binary string, e.g. '01'. All the known sections are
+        /// queried if no prefix is provided
+        prefix: Option<String>,
+    },
+}
+
+/// Health of a section as output by the `crawl` command.
+#[derive(Serialize)]
+struct SectionHealthOutput {
+    prefix: String,
+    section_key: String,
+    section_key_generation: usize,
+    stale_sap: bool,
+    elders: usize,
+    adults: Option<usize>,
+    members_disagree: bool,
+    lagging_elders: Vec<String>,
+    unreachable_elders: BTreeMap<String, String>,
+    /// Percentage of max capacity used by each responding elder
+    elders_used_space: BTreeMap<String, f64>,
+}
+
+impl From<&SectionHealthReport> for SectionHealthOutput {
+    fn from(report: &SectionHealthReport) -> Self {
+        Self {
+            prefix: format!("{}", report.prefix),
+            section_key: report.section_key.to_hex(),
+            section_key_generation: report.section_key_generation,
+            stale_sap: report.stale_sap,
+            elders: report.elder_count(),
+            adults: report.adult_count(),
+            members_disagree: report.members_disagree(),
+            lagging_elders: report
+                .lagging_elders()
+                .map(|elder| elder.to_string())
+                .collect(),
+            unreachable_elders: report
+                .unreachable_elders
+                .iter()
+                .map(|(elder, error)| (elder.to_string(), error.clone()))
+                .collect(),
+            elders_used_space: report
+                .elders
+                .iter()
+                .map(|(elder, health)| (elder.to_string(), health.used_space_percent()))
+                .collect(),
+        }
+    }
+}
+
+/// Section signed members of a section as output by the `members` command.
+#[derive(Serialize)]
+struct SectionMembersOutput {
+    prefix: String,
+    section_key: String,
+    members: Vec<MemberOutput>,
+    agreeing_elders: Vec<String>,
+    disagreeing_elders: Vec<String>,
+    failed_elders: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct MemberOutput {
+    name: String,
+    age: u8,
+    addr: String,
+    elder: bool,
+    /// Section key which signed the member's joining
+    signed_by: String,
+}
+
+impl From<&SectionMembersReport> for SectionMembersOutput {
+    fn from(report: &SectionMembersReport) -> Self {
+        let mut members: Vec<_> = report
+            .members
+            .iter()
+            .map(|member| MemberOutput {
+                name: member.name().to_string(),
+                age: member.age(),
+                addr: member.addr().to_string(),
+                elder: report.signed_sap.contains_elder(&member.name()),
+                signed_by: member.sig.public_key.to_hex(),
+            })
+            .collect();
+        members.sort_by_key(|member| (!member.elder, member.age));
+
+        Self {
+            prefix: format!("{}", report.prefix()),
+            section_key: report.signed_sap.section_key().to_hex(),
+            members,
+            agreeing_elders: report
+                .agreeing_elders
+                .iter()
+                .map(|elder| elder.to_string())
+                .collect(),
+            disagreeing_elders: report
+                .disagreeing_elders
+                .iter()
+                .map(|elder| elder.to_string())
+                .collect(),
+            failed_elders: report
+                .failed_elders
+                .iter()
+                .map(|(elder, error)| (elder.to_string(), error.clone()))
+                .collect(),
+        }
+    }
 }
 
 pub async fn networks_commander(
     cmd: Option<NetworksSubCommands>,
     output_fmt: OutputFmt,
     config: &mut Config,
+    safe: &mut Safe,
 ) -> Result<()> {
     match cmd {
         Some(NetworksSubCommands::Switch { network_name }) => {
@@ -91,24 +232,43 @@ pub async fn networks_commander(
         Some(NetworksSubCommands::Add {
             network_name,
             contacts_file_location,
+            publisher_key,
         }) => {
+            let publisher_key = publisher_key
+                .map(|key| BlsPublicKey::from_hex(key.trim()))
+                .transpose()
+                .wrap_err("Invalid publisher key")?;
             let net_info = if Url::parse(contacts_file_location.as_str()).is_ok() {
-                config
-                    .add_network(
-                        &network_name,
-                        NetworkInfo::Remote(contacts_file_location, None),
-                    )
-                    .await?
+                NetworkInfo::Remote(contacts_file_location, None)
             } else {
-                let path = PathBuf::from(contacts_file_location);
-                config
-                    .add_network(&network_name, NetworkInfo::Local(path, None))
-                    .await?
+                NetworkInfo::Local(PathBuf::from(contacts_file_location), None)
             };
+            let net_info = config
+                .add_network_with_publisher(&network_name, net_info, publisher_key.as_ref())
+                .await?;
             println!(
                 "Network '{network_name}' was added to the list. Network Map is located at '{net_info}'",
             );
         }
+        Some(NetworksSubCommands::Export {
+            dst,
+            network_name,
+            publisher_sk_path,
+        }) => {
+            let publisher_sk = publisher_sk_path
+                .map(Safe::deserialize_bls_key)
+                .transpose()
+                .wrap_err("Failed to read the publisher secret key")?;
+            let network_contacts = config
+                .export_network_contacts(network_name.as_deref(), &dst, publisher_sk.as_ref())
+                .await?;
+
+            println!("{}", network_contacts.header());
+            if let Some(publisher) = network_contacts.publisher() {
+                println!("Signed by publisher {}", publisher.to_hex());
+            }
+            println!("Network contacts exported to '{}'", dst.display());
+        }
         Some(NetworksSubCommands::Remove { network_name }) => {
             config.remove_network(&network_name).await?
         }
@@ -160,6 +320,30 @@ pub async fn networks_commander(
                 println!();
             }
         }
+        Some(NetworksSubCommands::Crawl {}) => {
+            connect(safe, config).await?;
+            if OutputFmt::Pretty == output_fmt {
+                println!("Crawling the network, this may take a while...");
+            }
+            let report = safe.crawl_network().await?;
+            print_network_health(&report, output_fmt);
+        }
+        Some(NetworksSubCommands::Members { prefix }) => {
+            connect(safe, config).await?;
+            let prefixes = match prefix {
+                Some(prefix) => vec![prefix
+                    .parse::<Prefix>()
+                    .map_err(|err| eyre!("Invalid prefix '{prefix}': {err:?}"))?],
+                None => safe.section_tree().await?.prefixes().copied().collect(),
+            };
+
+            let mut sections = vec![];
+            for prefix in prefixes {
+                let report = safe.section_members(&prefix).await?;
+                sections.push(SectionMembersOutput::from(&report));
+            }
+            print_section_members(&sections, output_fmt);
+        }
         None => {
             config.print_network(output_fmt).await?;
         }
@@ -167,3 +351,120 @@ pub async fn networks_commander(
 
     Ok(())
 }
+
+fn print_network_health(report: &NetworkHealthReport, output_fmt: OutputFmt) {
+    let sections: Vec<SectionHealthOutput> = report
+        .sections
+        .values()
+        .map(SectionHealthOutput::from)
+        .collect();
+
+    if OutputFmt::Pretty != output_fmt {
+        println!("{}", serialise_output(&sections, output_fmt));
+        return;
+    }
+
+    println!("Genesis Key: {:?}", report.genesis_key);
+    println!("Sections found: {}", sections.len());
+    println!();
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    table.add_row(&vec![
+        "Prefix",
+        "Key gen.",
+        "Elders",
+        "Adults",
+        "Unreachable",
+        "Lagging",
+        "Stale SAP",
+        "Max used space",
+    ]);
+    for section in &sections {
+        let max_used_space = section
+            .elders_used_space
+            .values()
+            .fold(None, |max: Option<f64>, used| {
+                Some(max.map_or(*used, |max| max.max(*used)))
+            });
+        table.add_row(vec![
+            Cell::new(&section.prefix),
+            Cell::new(section.section_key_generation).set_alignment(CellAlignment::Right),
+            Cell::new(section.elders).set_alignment(CellAlignment::Right),
+            Cell::new(
+                section
+                    .adults
+                    .map_or_else(|| "?".to_string(), |adults| adults.to_string()),
+            )
+            .set_alignment(CellAlignment::Right),
+            Cell::new(section.unreachable_elders.len()).set_alignment(CellAlignment::Right),
+            Cell::new(section.lagging_elders.len()).set_alignment(CellAlignment::Right),
+            Cell::new(if section.stale_sap { "yes" } else { "no" }),
+            Cell::new(max_used_space.map_or_else(|| "?".to_string(), |used| format!("{used:.1}%")))
+                .set_alignment(CellAlignment::Right),
+        ]);
+    }
+    println!("{table}");
+
+    for section in &sections {
+        if section.members_disagree {
+            println!(
+                "Elders of section '{}' disagree on its members",
+                section.prefix
+            );
+        }
+        for (elder, error) in &section.unreachable_elders {
+            println!(
+                "Elder {elder} of section '{}' is unreachable: {error}",
+                section.prefix
+            );
+        }
+        for elder in &section.lagging_elders {
+            println!(
+                "Elder {elder} of section '{}' isn't aware of the latest section key",
+                section.prefix
+            );
+        }
+    }
+}
+
+fn print_section_members(sections: &[SectionMembersOutput], output_fmt: OutputFmt) {
+    if OutputFmt::Pretty != output_fmt {
+        println!("{}", serialise_output(&sections, output_fmt));
+        return;
+    }
+
+    for section in sections {
+        println!("Prefix '{}'", section.prefix);
+        println!("----------------------------------");
+        println!("Section key: {}", section.section_key);
+        println!("Members: {}", section.members.len());
+        println!();
+
+        let mut table = Table::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.add_row(&vec!["XorName", "Age", "Address", "Elder", "Signed by"]);
+        for member in &section.members {
+            table.add_row(vec![
+                Cell::new(&member.name),
+                Cell::new(member.age).set_alignment(CellAlignment::Right),
+                Cell::new(&member.addr),
+                Cell::new(if member.elder { "yes" } else { "no" }),
+                Cell::new(&member.signed_by),
+            ]);
+        }
+        println!("{table}");
+
+        println!(
+            "Proven by {} elder(s) of the section",
+            section.agreeing_elders.len()
+        );
+        for elder in &section.disagreeing_elders {
+            println!("Elder {elder} proved a different set of members");
+        }
+        for (elder, error) in &section.failed_elders {
+            println!("Elder {elder} didn't provide a valid proof: {error}");
+        }
+        println!();
+    }
+}
@@ -16,13 +16,13 @@ use clap::Subcommand;
 use color_eyre::{eyre::eyre, Help, Result};
 use comfy_table::Table;
 use sn_api::Error::{InvalidInput, NetDataError, NrsNameAlreadyExists, UnversionedContentError};
-use sn_api::{Safe, SafeUrl};
+use sn_api::{nrs::NrsRecord, PublicKey, Safe, SafeUrl};
 
 #[derive(Subcommand, Debug)]
 pub enum NrsSubCommands {
     #[clap(name = "add")]
     /// Add a subname to a registered NRS name and link it to some content, or update an existing
-    /// subname with a new link.
+    /// subname with a new link. Other types of records can be set with the `--type` argument.
     Add {
         /// Specify the public name, which is the subname you wish to use, and the registered
         /// topname. For example, "new.topname". If the topname has not already been registered
@@ -31,9 +31,19 @@ pub enum NrsSubCommands {
         /// The safe:// URL to link to. Usually a FilesContainer for a website. This should be
         /// wrapped in double quotes on bash based systems. A link must be provided for a subname.
         /// If you don't provide it with this argument, you will be prompted to provide it
-        /// interactively.
+        /// interactively. For other types of records this is the record's value, i.e. the target
+        /// public name of an alias, the text of a txt record, or the hex-encoded BLS public key
+        /// of a key record.
         #[clap(short = 'l', long = "link")]
         link: Option<String>,
+        /// The type of record to associate the public name to: a link to some content, an alias
+        /// to another public name, a text record, a public key record, or a service record
+        /// linking a named service to some content.
+        #[clap(short = 't', long = "type", possible_values = &["link", "alias", "txt", "key", "srv"], default_value = "link")]
+        record_type: String,
+        /// The name of the service, e.g. "wallet", when adding a service record.
+        #[clap(long = "service", required_if_eq("record_type", "srv"))]
+        service: Option<String>,
         /// Set this flag to register the topname if it hasn't already been registered.
         #[clap(short = 'y', long = "register-top-name")]
         register_top_name: bool,
@@ -42,10 +52,20 @@ pub enum NrsSubCommands {
         #[clap(long = "default")]
         default: bool,
     },
+    #[clap(name = "delegate")]
+    /// Delegate a subname to another owner, who keeps its records, and those of its own subnames,
+    /// in a separate NRS map. The owner then needs to register the delegated subname to create it.
+    Delegate {
+        /// The subname to delegate, with its registered topname. For example, "team.topname".
+        public_name: String,
+        /// The hex-encoded (Ed25519 or BLS) public key of the owner the subname is delegated to.
+        #[clap(long = "owner")]
+        owner: String,
+    },
     #[clap(name = "register")]
-    /// Register a new top name in Safe NRS
+    /// Register a new top name in Safe NRS, or create the NRS map of a subname delegated to you
     Register {
-        /// The name of the new topname to register
+        /// The name of the new topname to register, or the subname delegated to you
         name: String,
         /// Optional safe:// URL to link the topname to. Usually a FilesContainer for a website.
         /// This should be wrapped in double quotes on bash based systems.
@@ -68,9 +88,27 @@ pub async fn nrs_commander(cmd: NrsSubCommands, output_fmt: OutputFmt, safe: &Sa
         NrsSubCommands::Add {
             public_name: name,
             link,
+            record_type,
+            service,
             register_top_name,
             default,
-        } => run_add_subcommand(name, link, register_top_name, default, safe, output_fmt).await,
+        } => {
+            let value = get_from_arg_or_stdin(link, Some("...awaiting link URL from stdin"))?;
+            let record = get_nrs_record(&record_type, &value, service)?;
+            run_add_subcommand(
+                name,
+                value,
+                record,
+                register_top_name,
+                default,
+                safe,
+                output_fmt,
+            )
+            .await
+        }
+        NrsSubCommands::Delegate { public_name, owner } => {
+            run_delegate_subcommand(public_name, owner, safe, output_fmt).await
+        }
         NrsSubCommands::Remove { name } => run_remove_subcommand(name, safe, output_fmt).await,
     }
 }
@@ -81,7 +119,13 @@ async fn run_register_subcommand(
     safe: &Safe,
     output_fmt: OutputFmt,
 ) -> Result<()> {
-    match safe.nrs_create(&name).await {
+    // a name with subnames can only be registered if it was delegated to us
+    let result = if name.contains('.') {
+        safe.nrs_create_delegated(&name).await
+    } else {
+        safe.nrs_create(&name).await
+    };
+    match result {
         Ok(topname_url) => {
             let mut summary = String::new();
             write!(
@@ -90,8 +134,8 @@ async fn run_register_subcommand(
                 topname_url.to_xorurl_string()
             )?;
             if let Some(ref link) = link {
-                let url = get_target_url(link)?;
-                let _ = associate_url_with_public_name(&name, safe, &url).await?;
+                let record = NrsRecord::Link(get_target_url(link)?);
+                let _ = associate_record_with_public_name(&name, safe, &record).await?;
                 write!(summary, "\nThe entry points to {link}")?;
             }
             print_summary(
@@ -108,10 +152,11 @@ async fn run_register_subcommand(
             Ok(())
         }
         Err(error) => match error {
-            InvalidInput(_) => Err(eyre!(error)
-                .wrap_err(
-                    "The register command can only register a topname, it cannot add subnames.",
-                )
+            InvalidInput(_) if name.contains('.') => Err(eyre!(error)
+                .wrap_err(format!(
+                    "The register command can only register a topname, or a subname which was \
+                    delegated to you. {name} was not delegated to the current credentials.",
+                ))
                 .suggestion(
                     "Please use the nrs add command with the --register-top-name \
                         argument to register a topname and add a subname at the same time.",
@@ -132,19 +177,18 @@ async fn run_register_subcommand(
 
 async fn run_add_subcommand(
     name: String,
-    link: Option<String>,
+    link: String,
+    record: NrsRecord,
     register_top_name: bool,
     default: bool,
     safe: &Safe,
     output_fmt: OutputFmt,
 ) -> Result<()> {
-    let link = get_from_arg_or_stdin(link, Some("...awaiting link URL from stdin"))?;
-    let link_url = get_target_url(&link)?;
     let (url, topname_was_registered) = if register_top_name {
-        add_public_name_for_url(&name, safe, &link_url).await?
+        add_public_name_for_record(&name, safe, &record).await?
     } else {
         (
-            associate_url_with_public_name(&name, safe, &link_url).await?,
+            associate_record_with_public_name(&name, safe, &record).await?,
             false,
         )
     };
@@ -168,19 +212,52 @@ async fn run_add_subcommand(
 
     if default {
         let topname = get_topname_from_public_name(&name)?;
-        associate_url_with_public_name(&topname, safe, &link_url).await?;
+        associate_record_with_public_name(&topname, safe, &record).await?;
         write!(
             summary_header,
-            "This link was also set as the default location for {topname}."
+            "This {} record was also set as the default for {topname}.",
+            record.type_name()
         )?;
     }
+    let entry = match record {
+        NrsRecord::Link(_) => link,
+        _ => record.to_string(),
+    };
     print_summary(
         output_fmt,
         &summary_header,
         "".to_string(),
         &SafeUrl::from_url(&format!("safe://{}", url.top_name()))?.to_xorurl_string(),
         &url,
-        ("+", &name, &link),
+        ("+", &name, &entry),
+    );
+    Ok(())
+}
+
+async fn run_delegate_subcommand(
+    name: String,
+    owner: String,
+    safe: &Safe,
+    output_fmt: OutputFmt,
+) -> Result<()> {
+    let owner = PublicKey::ed25519_from_hex(&owner)
+        .or_else(|_| PublicKey::bls_from_hex(&owner))
+        .map_err(|_| {
+            eyre!("Invalid public key '{owner}'")
+                .suggestion("The owner must be a hex-encoded Ed25519 or BLS public key.")
+        })?;
+    let url = safe.nrs_delegate(&name, owner).await?;
+    let version = url
+        .content_version()
+        .ok_or_else(|| eyre!("Content version not set for returned NRS SafeUrl"))?
+        .to_string();
+    print_summary(
+        output_fmt,
+        &format!("NRS Map updated (version {version})"),
+        format!("{name} is now delegated to {owner:x}, who can register it to add its records."),
+        &SafeUrl::from_url(&format!("safe://{}", url.top_name()))?.to_xorurl_string(),
+        &url,
+        ("+", &name, &NrsRecord::Delegation(owner).to_string()),
     );
     Ok(())
 }
@@ -221,12 +298,12 @@ async fn run_remove_subcommand(name: String, safe: &Safe, output_fmt: OutputFmt)
     }
 }
 
-async fn associate_url_with_public_name(
+async fn associate_record_with_public_name(
     public_name: &str,
     safe: &Safe,
-    url: &SafeUrl,
+    record: &NrsRecord,
 ) -> Result<SafeUrl> {
-    match safe.nrs_associate(public_name, url).await {
+    match safe.nrs_associate_record(public_name, record).await {
         Ok(new_url) => Ok(new_url),
         Err(error) => match error {
             UnversionedContentError(_) => Err(eyre!(error)
@@ -244,12 +321,12 @@ async fn associate_url_with_public_name(
     }
 }
 
-async fn add_public_name_for_url(
+async fn add_public_name_for_record(
     public_name: &str,
     safe: &Safe,
-    url: &SafeUrl,
+    record: &NrsRecord,
 ) -> Result<(SafeUrl, bool)> {
-    match safe.nrs_add(public_name, url).await {
+    match safe.nrs_add_record(public_name, record).await {
         Ok((new_url, topname_was_registered)) => Ok((new_url, topname_was_registered)),
         Err(error) => match error {
             UnversionedContentError(_) => Err(eyre!(error)
@@ -292,6 +369,25 @@ fn print_summary(
     }
 }
 
+fn get_nrs_record(record_type: &str, value: &str, service: Option<String>) -> Result<NrsRecord> {
+    let record = match record_type {
+        "link" => NrsRecord::Link(get_target_url(value)?),
+        "alias" => NrsRecord::Alias(value.trim_start_matches("safe://").to_string()),
+        "txt" => NrsRecord::Text(value.to_string()),
+        "key" => NrsRecord::PublicKey(bls::PublicKey::from_hex(value).map_err(|err| {
+            eyre!("Invalid BLS public key '{value}': {err:?}")
+                .suggestion("The public key must be provided as a hex-encoded string.")
+        })?),
+        "srv" => NrsRecord::Service {
+            service: service
+                .ok_or_else(|| eyre!("A service name must be provided for srv records"))?,
+            link: get_target_url(value)?,
+        },
+        other => return Err(eyre!("Unsupported NRS record type: {other}")),
+    };
+    Ok(record)
+}
+
 fn get_topname_from_public_name(public_name: &str) -> Result<String> {
     let mut parts = public_name.split('.');
     let topname = parts
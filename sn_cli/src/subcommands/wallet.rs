@@ -14,9 +14,13 @@ use crate::operations::config::Config;
 use bls::{PublicKey, SecretKey};
 use clap::Subcommand;
 use color_eyre::{eyre::eyre, eyre::Error, Help, Result};
-use sn_api::{Error as ApiError, Safe};
-use sn_dbc::{Dbc, Error as DbcError};
+use sn_api::{
+    wallet::{SealedTransaction, UnsignedTransaction},
+    Error as ApiError, Safe,
+};
+use sn_dbc::{Dbc, Error as DbcError, Token};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use tokio::fs;
 
 #[derive(Subcommand, Debug)]
@@ -75,6 +79,92 @@ pub enum WalletSubCommands {
         #[clap(long = "save")]
         save: Option<PathBuf>,
     },
+    #[clap(name = "build")]
+    /// Build an unsigned transaction, to be sealed with the 'wallet seal' command. This doesn't
+    /// require a connection to the network. Several owners can contribute inputs and outputs to
+    /// the same transaction by merging their unsigned transactions with the --tx argument. The
+    /// unsigned transaction doesn't contain the input DBCs nor any secret key.
+    Build {
+        /// A DBC to be spent by the transaction. This can be a path to a file containing hex
+        /// encoded DBC data, or the data itself. Can be passed multiple times.
+        #[clap(long = "input")]
+        inputs: Vec<String>,
+        /// The secret key of the owner of owned input DBCs, required to reveal their amounts. It
+        /// should be a hex-encoded BLS key. If not provided, the secret key configured for use
+        /// with safe will be used.
+        #[clap(long = "secret-key")]
+        secret_key_hex: Option<String>,
+        /// An output DBC to reissue, in the form '<amount>' for a bearer DBC, or
+        /// '<amount>:<public key>' for a DBC owned by a hex-encoded BLS public key. Can be passed
+        /// multiple times.
+        #[clap(long = "output")]
+        outputs: Vec<String>,
+        /// An unsigned transaction to merge into the one being built. This can be a path to a
+        /// file containing the hex encoded transaction, or the data itself. Can be passed multiple
+        /// times.
+        #[clap(long = "tx")]
+        txs: Vec<String>,
+        /// A file path to store the unsigned transaction.
+        #[clap(long = "save")]
+        save: Option<PathBuf>,
+    },
+    #[clap(name = "seal")]
+    /// Seal a transaction built with the 'wallet build' command, fixing its outputs so the owner
+    /// of each input can then sign it with the 'wallet sign' command. This doesn't require a
+    /// connection to the network nor any secret key.
+    Seal {
+        /// A path to a file containing the hex encoded unsigned transaction, or the data itself.
+        tx: String,
+        /// The public key to own the change DBC, if there is one. This should be a hex-encoded
+        /// BLS key. Defaults to the public key configured for use with safe.
+        #[clap(long = "change-to")]
+        change_to: Option<String>,
+        /// A file path to store the sealed transaction.
+        #[clap(long = "save")]
+        save: Option<PathBuf>,
+    },
+    #[clap(name = "sign")]
+    /// Sign inputs of a transaction sealed with the 'wallet seal' command. This doesn't require a
+    /// connection to the network, so it can be done on an offline machine. Each owner signs their
+    /// own inputs independently, the signatures being merged when broadcasting the transaction.
+    Sign {
+        /// A path to a file containing the hex encoded sealed transaction, or the data itself.
+        tx: String,
+        /// An input DBC to sign. This can be a path to a file containing hex encoded DBC data,
+        /// or the data itself. Can be passed multiple times.
+        #[clap(long = "input")]
+        inputs: Vec<String>,
+        /// The secret key of the owner of owned input DBCs. It should be a hex-encoded BLS key.
+        /// If not provided, the secret key configured for use with safe will be used.
+        #[clap(long = "secret-key")]
+        secret_key_hex: Option<String>,
+        /// A file path to store the signed transaction.
+        #[clap(long = "save")]
+        save: Option<PathBuf>,
+    },
+    #[clap(name = "broadcast")]
+    /// Broadcast a transaction signed with the 'wallet sign' command, spending its input DBCs
+    /// and reissuing its output DBCs.
+    Broadcast {
+        /// A path to a file containing the hex encoded signed transaction, or the data itself.
+        /// Can be passed multiple times, to merge the signatures of inputs signed independently
+        /// by their owners.
+        #[clap(required = true)]
+        txs: Vec<String>,
+        /// A directory path to store the content of the reissued DBCs.
+        #[clap(long = "save")]
+        save: Option<PathBuf>,
+    },
+}
+
+impl WalletSubCommands {
+    /// Whether the command can be run without a connection to the network.
+    pub fn is_offline(&self) -> bool {
+        matches!(
+            self,
+            Self::Build { .. } | Self::Seal { .. } | Self::Sign { .. }
+        )
+    }
 }
 
 pub async fn wallet_commander(
@@ -269,6 +359,242 @@ pub async fn wallet_commander(
 
             Ok(())
         }
+        WalletSubCommands::Build {
+            inputs,
+            secret_key_hex,
+            outputs,
+            txs,
+            save,
+        } => {
+            let mut unsigned_tx = UnsignedTransaction::new();
+            for tx in txs {
+                let tx_hex = read_hex_data(&tx)?;
+                unsigned_tx.merge(UnsignedTransaction::from_hex(&tx_hex)?)?;
+            }
+            for input in inputs {
+                let dbc = Dbc::from_hex(&read_hex_data(&input)?)?;
+                let owner_sk = input_owner_key(&dbc, secret_key_hex.as_deref(), config)?;
+                unsigned_tx.add_input(&dbc, owner_sk.as_ref())?;
+            }
+            for output in outputs {
+                let (amount, owner) = match output.split_once(':') {
+                    Some((amount, pk_hex)) => (amount, Some(PublicKey::from_hex(pk_hex)?)),
+                    None => (output.as_str(), None),
+                };
+                let amount = Token::from_str(amount)
+                    .map_err(|err| eyre!("Invalid output amount '{amount}': {err}"))?;
+                unsigned_tx.add_output(amount, owner)?;
+            }
+
+            let tx_hex = unsigned_tx.to_hex()?;
+            let print_out_tx = save_hex_data(&tx_hex, save, "unsigned transaction").await;
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "Built unsigned transaction with {} input/s and {} output/s.",
+                    unsigned_tx.inputs().len(),
+                    unsigned_tx.outputs().len()
+                );
+                print_tx_data(&tx_hex, print_out_tx);
+            } else if print_out_tx {
+                println!("{tx_hex}");
+            }
+
+            Ok(())
+        }
+        WalletSubCommands::Seal {
+            tx,
+            change_to,
+            save,
+        } => {
+            let unsigned_tx = UnsignedTransaction::from_hex(&read_hex_data(&tx)?)?;
+            let change_pk = match change_to {
+                Some(pk_hex) => PublicKey::from_hex(&pk_hex)?,
+                None => read_key_from_configured_credentials(
+                    config,
+                    "No public key was provided to own the change and credentials are not \
+                    configured for safe."
+                        .to_string(),
+                    "Use the --change-to argument to provide the public key, or run the 'keys \
+                    create --for-cli' command to generate credentials then run this command again."
+                        .to_string(),
+                )?
+                .public_key(),
+            };
+
+            let sealed_tx = unsigned_tx.seal(change_pk)?;
+            let tx_hex = sealed_tx.to_hex()?;
+            let print_out_tx = save_hex_data(&tx_hex, save, "sealed transaction").await;
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "Sealed transaction, its {} input/s can now be signed with 'wallet sign'.",
+                    sealed_tx.inputs().len()
+                );
+                print_tx_data(&tx_hex, print_out_tx);
+            } else if print_out_tx {
+                println!("{tx_hex}");
+            }
+
+            Ok(())
+        }
+        WalletSubCommands::Sign {
+            tx,
+            inputs,
+            secret_key_hex,
+            save,
+        } => {
+            let mut sealed_tx = SealedTransaction::from_hex(&read_hex_data(&tx)?)?;
+            for input in inputs {
+                let dbc = Dbc::from_hex(&read_hex_data(&input)?)?;
+                let owner_sk = input_owner_key(&dbc, secret_key_hex.as_deref(), config)?;
+                sealed_tx.sign(&dbc, owner_sk.as_ref())?;
+            }
+
+            let tx_hex = sealed_tx.to_hex()?;
+            let print_out_tx = save_hex_data(&tx_hex, save, "signed transaction").await;
+            if OutputFmt::Pretty == output_fmt {
+                let unsigned_inputs = sealed_tx.unsigned_inputs().len();
+                if unsigned_inputs == 0 {
+                    println!(
+                        "Signed transaction, it can now be broadcast with 'wallet broadcast'."
+                    );
+                } else {
+                    println!(
+                        "Signed transaction, {unsigned_inputs} input/s still need to be signed by \
+                        their owners before broadcasting it."
+                    );
+                }
+                print_tx_data(&tx_hex, print_out_tx);
+            } else if print_out_tx {
+                println!("{tx_hex}");
+            }
+
+            Ok(())
+        }
+        WalletSubCommands::Broadcast { txs, save } => {
+            let mut sealed_tx: Option<SealedTransaction> = None;
+            for tx in txs {
+                let other_tx = SealedTransaction::from_hex(&read_hex_data(&tx)?)?;
+                match sealed_tx.as_mut() {
+                    Some(sealed_tx) => sealed_tx.merge_signatures(other_tx)?,
+                    None => sealed_tx = Some(other_tx),
+                }
+            }
+            let signed_tx = sealed_tx
+                .ok_or_else(|| eyre!("No signed transaction was provided"))?
+                .into_signed()?;
+            let (output_dbcs, change_dbc) = safe.broadcast_transaction(signed_tx).await?;
+
+            let mut dbcs = Vec::new();
+            for dbc in output_dbcs {
+                dbcs.push((
+                    format!("dbc-{}", &hex::encode(dbc.hash())[0..8]),
+                    dbc.to_hex()?,
+                ));
+            }
+            if let Some(dbc) = change_dbc {
+                dbcs.push((
+                    format!("change-dbc-{}", &hex::encode(dbc.hash())[0..8]),
+                    dbc.to_hex()?,
+                ));
+            }
+
+            let mut print_out_dbcs = true;
+            if let Some(dir) = save {
+                fs::create_dir_all(&dir).await?;
+                for (name, dbc_hex) in &dbcs {
+                    let path = dir.join(name);
+                    fs::write(&path, dbc_hex).await?;
+                    if OutputFmt::Pretty == output_fmt {
+                        println!("DBC content written at '{}'.", path.display());
+                    }
+                }
+                print_out_dbcs = false;
+            }
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Transaction broadcast, {} DBC/s reissued.", dbcs.len());
+                if print_out_dbcs {
+                    for (name, dbc_hex) in &dbcs {
+                        println!("-------- DBC DATA ({name}) --------");
+                        println!("{dbc_hex}");
+                        println!("--------------------------");
+                    }
+                }
+            } else if print_out_dbcs {
+                println!("{}", serialise_output(&dbcs, output_fmt));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Helper to read hex encoded data which is either in the file at the given path, or is the
+/// argument itself.
+fn read_hex_data(arg: &str) -> Result<String> {
+    let path = Path::new(arg);
+    if path.is_file() {
+        Ok(std::fs::read_to_string(path)?.trim().to_string())
+    } else {
+        Ok(arg.trim().to_string())
+    }
+}
+
+/// Helper to print out hex encoded transaction data if it wasn't written to a file.
+fn print_tx_data(tx_hex: &str, print_out_tx: bool) {
+    if print_out_tx {
+        println!("-------- TRANSACTION DATA --------");
+        println!("{tx_hex}");
+        println!("----------------------------------");
+    }
+}
+
+/// Helper to get the secret key of the owner of a DBC to be spent, which is either the one
+/// provided, or the one configured for use with safe. No key is needed for a bearer DBC.
+fn input_owner_key(
+    dbc: &Dbc,
+    secret_key_hex: Option<&str>,
+    config: &Config,
+) -> Result<Option<SecretKey>> {
+    if dbc.is_bearer() {
+        return Ok(None);
+    }
+    let sk = match secret_key_hex {
+        Some(sk_hex) => SecretKey::from_hex(sk_hex)?,
+        None => read_key_from_configured_credentials(
+            config,
+            "The input DBC is owned and no secret key was provided, and credentials are not \
+            configured for safe."
+                .to_string(),
+            "Use the --secret-key argument to provide the secret key of its owner, or run the \
+            'keys create --for-cli' command to generate credentials then run this command again."
+                .to_string(),
+        )?,
+    };
+    Ok(Some(sk))
+}
+
+/// Helper to write hex encoded data to a file if the user requested it.
+///
+/// Returns whether the data still needs to be printed out, i.e. if no file path was provided or
+/// writing to the file failed.
+async fn save_hex_data(data: &str, save: Option<PathBuf>, what: &str) -> bool {
+    match save {
+        None => true,
+        Some(path) => match fs::write(&path, data).await {
+            Ok(()) => {
+                println!("The {what} was written at '{}'.", path.display());
+                false
+            }
+            Err(err) => {
+                eprintln!(
+                    "Error: Unable to write the {what} at '{}': {}.",
+                    path.display(),
+                    err
+                );
+                true
+            }
+        },
     }
 }
 
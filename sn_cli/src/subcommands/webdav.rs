@@ -0,0 +1,861 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::gateway::{
+    api_error_response, error_response, html_escape, serve_file, set_header, DEFAULT_CONTENT_TYPE,
+    DIRECTORY_TYPE, PATH_SEGMENT,
+};
+use clap::Args;
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use hyper::{
+    header,
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use sn_api::{
+    files::{dir_file_item, file_item_for_link, FileInfo, FilesMap},
+    nrs::VersionHash,
+    Safe, SafeUrl,
+};
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, UNIX_EPOCH},
+};
+use tracing::{debug, info, warn};
+
+// File type of the files written on the drive, as for the files added to containers from raw bytes
+const RAW_FILE_TYPE: &str = "Raw";
+const ALLOWED_METHODS: &str = "OPTIONS, PROPFIND, GET, HEAD, PUT, DELETE, MKCOL, MOVE";
+
+#[derive(Args, Debug)]
+pub struct WebDavCommands {
+    /// The URL of the FilesContainer to expose as a drive
+    location: String,
+    /// Port to serve the drive on
+    #[clap(long = "port", default_value = "8081")]
+    port: u16,
+    /// Address to listen on. Defaults to localhost so the drive is not exposed to other hosts
+    #[clap(long = "bind", default_value = "127.0.0.1")]
+    bind: IpAddr,
+    /// Number of seconds between syncs of the changes made on the drive. All the changes made within an interval are stored as a single new version of the FilesContainer
+    #[clap(long = "sync-interval", default_value = "30")]
+    sync_interval: u64,
+    /// Automatically update the NRS name to link to the new versions of the FilesContainer. This is only allowed if an NRS URL was provided, and if the NRS name is currently linked to a specific version of the FilesContainer
+    #[clap(short = 'u', long = "update-nrs")]
+    update_nrs: bool,
+}
+
+pub async fn webdav_commander(cmd: WebDavCommands, safe: &Safe) -> Result<()> {
+    if cmd.sync_interval == 0 {
+        bail!("The sync interval must be of at least one second");
+    }
+    if !SafeUrl::from_url(&cmd.location)?.path().is_empty() {
+        bail!("The URL of the FilesContainer to expose can't have a path, the whole FilesContainer is exposed as a drive");
+    }
+
+    let (version, files_map) = match safe.files_container_get(&cmd.location).await? {
+        Some((version, files_map)) => (Some(version), files_map),
+        None => (None, FilesMap::default()),
+    };
+    let drive = Arc::new(Drive {
+        safe: safe.clone(),
+        url: cmd.location,
+        update_nrs: cmd.update_nrs,
+        state: Mutex::new(DriveState {
+            version,
+            files_map,
+            dirty: false,
+        }),
+    });
+
+    let addr = SocketAddr::new(cmd.bind, cmd.port);
+    let service_drive = drive.clone();
+    let make_service = make_service_fn(move |_conn| {
+        let drive = service_drive.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let drive = drive.clone();
+                async move { Ok::<_, Infallible>(serve(&drive, req).await) }
+            }))
+        }
+    });
+
+    let server = Server::try_bind(&addr)
+        .wrap_err_with(|| format!("Failed to listen on {addr}"))?
+        .serve(make_service)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        });
+    // the server runs on its own task so requests are served while syncing
+    let mut server = tokio::spawn(server);
+
+    println!(
+        "Serving FilesContainer {} as a WebDAV drive at http://{addr}/",
+        drive.url
+    );
+    println!(
+        "Changes are synced every {} seconds, press Ctrl+C to sync the last ones and stop the drive",
+        cmd.sync_interval
+    );
+
+    let mut sync_interval = tokio::time::interval(Duration::from_secs(cmd.sync_interval));
+    let served = loop {
+        tokio::select! {
+            served = &mut server => break served,
+            _ = sync_interval.tick() => {
+                if let Err(err) = drive.sync().await {
+                    warn!("{err:?}");
+                }
+            }
+        }
+    };
+
+    drive.sync().await?;
+    served
+        .map_err(|err| eyre!(err))
+        .and_then(|served| served.wrap_err("The WebDAV drive stopped serving requests"))
+}
+
+// A FilesContainer exposed as a drive, the changes made on it being applied to its FilesMap,
+// which is then synced periodically as a new version of the container.
+struct Drive {
+    safe: Safe,
+    url: String,
+    update_nrs: bool,
+    state: Mutex<DriveState>,
+}
+
+struct DriveState {
+    // Version of the FilesContainer last synced, if it wasn't empty
+    version: Option<VersionHash>,
+    files_map: FilesMap,
+    // Whether the FilesMap has changes not synced yet
+    dirty: bool,
+}
+
+impl Drive {
+    // The state is never kept locked while awaiting network operations
+    fn state(&self) -> MutexGuard<'_, DriveState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    // Stores the FilesMap as a new version of the FilesContainer, if it changed since last synced.
+    async fn sync(&self) -> Result<()> {
+        let (version, files_map) = {
+            let mut state = self.state();
+            if !state.dirty {
+                return Ok(());
+            }
+            state.dirty = false;
+            (state.version, state.files_map.clone())
+        };
+
+        match self
+            .safe
+            .files_container_store_map(&self.url, version, &files_map, self.update_nrs)
+            .await
+        {
+            Ok(new_version) => {
+                self.state().version = Some(new_version);
+                info!(
+                    "Changes made on the drive synced as version {new_version} of {}",
+                    self.url
+                );
+                Ok(())
+            }
+            Err(err) => {
+                // the changes will be synced along with the next ones
+                self.state().dirty = true;
+                Err(err).wrap_err_with(|| {
+                    format!(
+                        "Failed to sync the changes made on the drive to {}",
+                        self.url
+                    )
+                })
+            }
+        }
+    }
+}
+
+async fn serve(drive: &Drive, req: Request<Body>) -> Response<Body> {
+    let path = match drive_path(req.uri().path()) {
+        Some(path) => path,
+        None => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid path: {}", req.uri().path()),
+            )
+        }
+    };
+    debug!("WebDAV {} request for {path}", req.method());
+
+    match req.method().as_str() {
+        "OPTIONS" => {
+            let mut response = Response::new(Body::empty());
+            set_header(&mut response, header::HeaderName::from_static("dav"), "1");
+            set_header(&mut response, header::ALLOW, ALLOWED_METHODS);
+            response
+        }
+        "PROPFIND" => propfind(drive, &req, &path),
+        "GET" => get(drive, &req, &path, false).await,
+        "HEAD" => get(drive, &req, &path, true).await,
+        "PUT" => put(drive, req, &path).await,
+        "DELETE" => delete(drive, &path),
+        "MKCOL" => mkcol(drive, &path),
+        "MOVE" => move_item(drive, &req, &path),
+        other => {
+            let mut response = error_response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                &format!("{other} requests are not supported"),
+            );
+            set_header(&mut response, header::ALLOW, ALLOWED_METHODS);
+            response
+        }
+    }
+}
+
+fn propfind(drive: &Drive, req: &Request<Body>, path: &str) -> Response<Body> {
+    // the request body is not parsed, all the properties being returned
+    let with_children = req
+        .headers()
+        .get("depth")
+        .map_or(true, |depth| depth.as_bytes() != b"0");
+
+    let state = drive.state();
+    let item = match find_item(&state.files_map, path) {
+        Some(item) => item,
+        None => return not_found(path),
+    };
+
+    let mut response = Response::new(Body::from(multistatus(
+        &state.files_map,
+        path,
+        &item,
+        with_children,
+    )));
+    *response.status_mut() = StatusCode::MULTI_STATUS;
+    set_header(
+        &mut response,
+        header::CONTENT_TYPE,
+        "application/xml; charset=utf-8",
+    );
+    response
+}
+
+async fn get(drive: &Drive, req: &Request<Body>, path: &str, head_only: bool) -> Response<Body> {
+    let file_item = match find_item(&drive.state().files_map, path) {
+        Some(Item {
+            is_dir: false,
+            file_item: Some(file_item),
+        }) => file_item.clone(),
+        Some(_) => {
+            return error_response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Directories can't be fetched, their content is listed with PROPFIND requests",
+            )
+        }
+        None => return not_found(path),
+    };
+
+    let link = file_item.get("link").cloned().unwrap_or_default();
+    let size = file_item.get("size").and_then(|size| size.parse().ok());
+    let mut response = if link.is_empty() {
+        // empty files are not stored on the network
+        let mut response = Response::new(Body::empty());
+        set_header(&mut response, header::CONTENT_LENGTH, "0");
+        response
+    } else {
+        let range_header = req
+            .headers()
+            .get(header::RANGE)
+            .and_then(|value| value.to_str().ok());
+        match serve_file(&drive.safe, &link, size, range_header, head_only).await {
+            Ok(response) => response,
+            Err(err) => return api_error_response(path, err),
+        }
+    };
+
+    set_header(
+        &mut response,
+        header::CONTENT_TYPE,
+        &content_type(&file_item),
+    );
+    set_header(&mut response, header::ETAG, &etag(&link));
+    if let Some(modified) = last_modified(&file_item) {
+        set_header(&mut response, header::LAST_MODIFIED, &modified);
+    }
+    if head_only {
+        *response.body_mut() = Body::empty();
+    }
+
+    response
+}
+
+async fn put(drive: &Drive, req: Request<Body>, path: &str) -> Response<Body> {
+    if let Err(response) = check_writable(&drive.state().files_map, path) {
+        return response;
+    }
+
+    let data = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Failed to read the content to write at {path}: {err}"),
+            )
+        }
+    };
+    let size = data.len() as u64;
+    let link = if data.is_empty() {
+        String::new()
+    } else {
+        match drive.safe.store_bytes(data, None).await {
+            Ok(link) => link,
+            Err(err) => return api_error_response(path, err),
+        }
+    };
+
+    let mut state = drive.state();
+    // the drive may have changed while storing the content
+    if let Err(response) = check_writable(&state.files_map, path) {
+        return response;
+    }
+    let mut file_item = file_item_for_link(&link, RAW_FILE_TYPE, size);
+    let replaced = state.files_map.get(path).cloned();
+    if let Some(created) = replaced
+        .as_ref()
+        .and_then(|replaced| replaced.get("created"))
+    {
+        let _ = file_item.insert("created".to_string(), created.clone());
+    }
+    let _ = state.files_map.insert(path.to_string(), file_item);
+    state.dirty = true;
+    debug!("{size} bytes written at {path} of the drive, stored at {link}");
+
+    status_response(if replaced.is_some() {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::CREATED
+    })
+}
+
+fn delete(drive: &Drive, path: &str) -> Response<Body> {
+    if path == "/" {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "The root of the drive can't be deleted",
+        );
+    }
+
+    let mut state = drive.state();
+    if remove_item(&mut state.files_map, path) == 0 {
+        return not_found(path);
+    }
+    state.dirty = true;
+
+    status_response(StatusCode::NO_CONTENT)
+}
+
+fn mkcol(drive: &Drive, path: &str) -> Response<Body> {
+    let mut state = drive.state();
+    if find_item(&state.files_map, path).is_some() {
+        return error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            &format!("{path} already exists"),
+        );
+    }
+    if !parent_exists(&state.files_map, path) {
+        return error_response(
+            StatusCode::CONFLICT,
+            &format!("The parent directory of {path} doesn't exist"),
+        );
+    }
+
+    let _ = state.files_map.insert(path.to_string(), dir_file_item());
+    state.dirty = true;
+
+    status_response(StatusCode::CREATED)
+}
+
+fn move_item(drive: &Drive, req: &Request<Body>, path: &str) -> Response<Body> {
+    let destination = match req
+        .headers()
+        .get("destination")
+        .and_then(|value| value.to_str().ok())
+        .and_then(destination_path)
+    {
+        Some(destination) => destination,
+        None => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "A valid Destination header is required to move an item",
+            )
+        }
+    };
+    let overwrite = req
+        .headers()
+        .get("overwrite")
+        .map_or(true, |overwrite| overwrite.as_bytes() != b"F");
+
+    if path == "/" || destination == "/" {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "The root of the drive can't be moved or replaced",
+        );
+    }
+    if destination == path || destination.starts_with(&dir_prefix(path)) {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            &format!("{path} can't be moved to {destination}, within itself"),
+        );
+    }
+
+    let mut state = drive.state();
+    if find_item(&state.files_map, path).is_none() {
+        return not_found(path);
+    }
+    if !parent_exists(&state.files_map, &destination) {
+        return error_response(
+            StatusCode::CONFLICT,
+            &format!("The parent directory of {destination} doesn't exist"),
+        );
+    }
+    let replaced = find_item(&state.files_map, &destination).is_some();
+    if replaced {
+        if !overwrite {
+            return error_response(
+                StatusCode::PRECONDITION_FAILED,
+                &format!("{destination} already exists"),
+            );
+        }
+        let _ = remove_item(&mut state.files_map, &destination);
+    }
+
+    move_items(&mut state.files_map, path, &destination);
+    state.dirty = true;
+
+    status_response(if replaced {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::CREATED
+    })
+}
+
+// Checks a file can be written at the path, returning the response to the request otherwise.
+fn check_writable(files_map: &FilesMap, path: &str) -> Result<(), Response<Body>> {
+    if matches!(find_item(files_map, path), Some(Item { is_dir: true, .. })) {
+        return Err(error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            &format!("{path} is a directory"),
+        ));
+    }
+    if !parent_exists(files_map, path) {
+        return Err(error_response(
+            StatusCode::CONFLICT,
+            &format!("The parent directory of {path} doesn't exist"),
+        ));
+    }
+    Ok(())
+}
+
+// A file or directory of the drive. Directories only have a FileInfo if they were added
+// explicitly to the FilesMap, rather than being part of the path of files.
+#[derive(Debug, PartialEq)]
+struct Item<'a> {
+    is_dir: bool,
+    file_item: Option<&'a FileInfo>,
+}
+
+// Maps the path of a request to the path of the item it is for in the FilesMap, i.e. decoded
+// and without trailing slash, the root of the drive being "/". Returns None for paths
+// reaching out of the drive.
+fn drive_path(request_path: &str) -> Option<String> {
+    let decoded = percent_encoding::percent_decode_str(request_path)
+        .decode_utf8()
+        .ok()?;
+
+    let mut path = String::new();
+    for segment in decoded.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => return None,
+            segment => {
+                path.push('/');
+                path.push_str(segment);
+            }
+        }
+    }
+    if path.is_empty() {
+        path.push('/');
+    }
+
+    Some(path)
+}
+
+// Maps the Destination header of a MOVE request, an absolute URL or path, to a drive path.
+fn destination_path(destination: &str) -> Option<String> {
+    match url::Url::parse(destination) {
+        Ok(url) => drive_path(url.path()),
+        Err(_) => drive_path(destination),
+    }
+}
+
+// Prefix of the paths of the items within the directory
+fn dir_prefix(path: &str) -> String {
+    if path == "/" {
+        path.to_string()
+    } else {
+        format!("{path}/")
+    }
+}
+
+fn parent_path(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(index) => &path[..index],
+    }
+}
+
+fn is_dir_item(file_item: &FileInfo) -> bool {
+    file_item.get("type").map(String::as_str) == Some(DIRECTORY_TYPE)
+}
+
+fn find_item<'a>(files_map: &'a FilesMap, path: &str) -> Option<Item<'a>> {
+    if path == "/" {
+        return Some(Item {
+            is_dir: true,
+            file_item: None,
+        });
+    }
+
+    match files_map.get(path) {
+        Some(file_item) => Some(Item {
+            is_dir: is_dir_item(file_item),
+            file_item: Some(file_item),
+        }),
+        None => {
+            let prefix = dir_prefix(path);
+            files_map
+                .range(prefix.clone()..)
+                .next()
+                .filter(|(item_path, _)| item_path.starts_with(&prefix))
+                .map(|_| Item {
+                    is_dir: true,
+                    file_item: None,
+                })
+        }
+    }
+}
+
+fn parent_exists(files_map: &FilesMap, path: &str) -> bool {
+    matches!(
+        find_item(files_map, parent_path(path)),
+        Some(Item { is_dir: true, .. })
+    )
+}
+
+// Returns the items found directly within the directory, by name.
+fn children<'a>(files_map: &'a FilesMap, path: &str) -> BTreeMap<String, Item<'a>> {
+    let prefix = dir_prefix(path);
+    let mut children = BTreeMap::new();
+    for (item_path, file_item) in files_map.range(prefix.clone()..) {
+        let name = match item_path.strip_prefix(&prefix) {
+            Some(name) if !name.is_empty() => name,
+            Some(_) => continue,
+            None => break,
+        };
+
+        match name.split_once('/') {
+            Some((dir, _)) => {
+                let _ = children.entry(dir.to_string()).or_insert(Item {
+                    is_dir: true,
+                    file_item: None,
+                });
+            }
+            None => {
+                let _ = children.insert(
+                    name.to_string(),
+                    Item {
+                        is_dir: is_dir_item(file_item),
+                        file_item: Some(file_item),
+                    },
+                );
+            }
+        }
+    }
+
+    children
+}
+
+// Removes the item, and all the items within it if it's a directory, returning how many
+// entries of the FilesMap were removed.
+fn remove_item(files_map: &mut FilesMap, path: &str) -> usize {
+    let prefix = dir_prefix(path);
+    let count = files_map.len();
+    files_map.retain(|item_path, _| item_path != path && !item_path.starts_with(&prefix));
+    let removed = count - files_map.len();
+
+    if removed > 0 {
+        keep_parent(files_map, path);
+    }
+    removed
+}
+
+// Moves the item, and all the items within it if it's a directory, to the destination path.
+fn move_items(files_map: &mut FilesMap, path: &str, destination: &str) {
+    let prefix = dir_prefix(path);
+    let moved: Vec<String> = files_map
+        .keys()
+        .filter(|item_path| *item_path == path || item_path.starts_with(&prefix))
+        .cloned()
+        .collect();
+
+    for item_path in moved {
+        if let Some(file_item) = files_map.remove(&item_path) {
+            let new_path = format!("{destination}{}", &item_path[path.len()..]);
+            let _ = files_map.insert(new_path, file_item);
+        }
+    }
+
+    keep_parent(files_map, path);
+}
+
+// Directories which are only part of the path of files disappear along with their last file,
+// so the parent of an item removed is added explicitly to the FilesMap if that's the case.
+fn keep_parent(files_map: &mut FilesMap, path: &str) {
+    let parent = parent_path(path);
+    if find_item(files_map, parent).is_none() {
+        let _ = files_map.insert(parent.to_string(), dir_file_item());
+    }
+}
+
+fn multistatus(files_map: &FilesMap, path: &str, item: &Item, with_children: bool) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<D:multistatus xmlns:D=\"DAV:\">\n",
+    );
+    xml.push_str(&prop_response(path, item));
+    if with_children && item.is_dir {
+        let prefix = dir_prefix(path);
+        for (name, child) in children(files_map, path) {
+            xml.push_str(&prop_response(&format!("{prefix}{name}"), &child));
+        }
+    }
+    xml.push_str("</D:multistatus>\n");
+
+    xml
+}
+
+fn prop_response(path: &str, item: &Item) -> String {
+    let mut href = percent_encoding::utf8_percent_encode(path, PATH_SEGMENT).to_string();
+    if item.is_dir && !href.ends_with('/') {
+        href.push('/');
+    }
+    let name = path.rsplit('/').next().unwrap_or_default();
+
+    let mut props = format!("<D:displayname>{}</D:displayname>", html_escape(name));
+    if item.is_dir {
+        props.push_str("<D:resourcetype><D:collection/></D:resourcetype>");
+    } else {
+        props.push_str("<D:resourcetype/>");
+    }
+    if let Some(file_item) = item.file_item {
+        if !item.is_dir {
+            let size = file_item.get("size").map_or("0", String::as_str);
+            let link = file_item.get("link").map_or("", String::as_str);
+            props.push_str(&format!(
+                "<D:getcontentlength>{}</D:getcontentlength><D:getcontenttype>{}</D:getcontenttype><D:getetag>{}</D:getetag>",
+                html_escape(size),
+                html_escape(&content_type(file_item)),
+                html_escape(&etag(link)),
+            ));
+        }
+        if let Some(modified) = last_modified(file_item) {
+            props.push_str(&format!(
+                "<D:getlastmodified>{modified}</D:getlastmodified>"
+            ));
+        }
+    }
+
+    format!(
+        "<D:response><D:href>{}</D:href><D:propstat><D:prop>{props}</D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>\n",
+        html_escape(&href)
+    )
+}
+
+// The content of files is immutable on the network, so their link identifies their content
+fn etag(link: &str) -> String {
+    format!("\"{link}\"")
+}
+
+fn content_type(file_item: &FileInfo) -> String {
+    match file_item.get("type") {
+        Some(file_type) if file_type.contains('/') => file_type.clone(),
+        _ => DEFAULT_CONTENT_TYPE.to_string(),
+    }
+}
+
+// The FileInfo timestamps are in seconds since the Unix epoch
+fn last_modified(file_item: &FileInfo) -> Option<String> {
+    let secs = file_item.get("modified")?.parse().ok()?;
+    Some(httpdate::fmt_http_date(
+        UNIX_EPOCH + Duration::from_secs(secs),
+    ))
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+fn not_found(path: &str) -> Response<Body> {
+    error_response(StatusCode::NOT_FOUND, &format!("{path} not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_item(size: u64) -> FileInfo {
+        file_item_for_link("safe://link", RAW_FILE_TYPE, size)
+    }
+
+    fn files_map_of(paths: &[&str]) -> FilesMap {
+        paths
+            .iter()
+            .map(|path| (path.to_string(), file_item(1)))
+            .collect()
+    }
+
+    #[test]
+    fn request_paths_are_mapped_to_drive_paths() {
+        assert_eq!(drive_path("/"), Some("/".to_string()));
+        assert_eq!(drive_path(""), Some("/".to_string()));
+        assert_eq!(drive_path("/docs/"), Some("/docs".to_string()));
+        assert_eq!(
+            drive_path("/docs/./a%20b.md"),
+            Some("/docs/a b.md".to_string())
+        );
+        assert_eq!(drive_path("/docs/../../etc"), None);
+
+        assert_eq!(
+            destination_path("http://127.0.0.1:8081/docs/new%20name.md"),
+            Some("/docs/new name.md".to_string())
+        );
+        assert_eq!(
+            destination_path("/docs/b.md"),
+            Some("/docs/b.md".to_string())
+        );
+    }
+
+    #[test]
+    fn directories_are_found_from_the_paths_of_their_files() {
+        let mut files_map = files_map_of(&["/a.md", "/docs/b.md", "/docs/img/c.png"]);
+        let _ = files_map.insert("/empty".to_string(), dir_file_item());
+
+        assert!(matches!(
+            find_item(&files_map, "/a.md"),
+            Some(Item { is_dir: false, .. })
+        ));
+        assert!(matches!(
+            find_item(&files_map, "/docs"),
+            Some(Item {
+                is_dir: true,
+                file_item: None
+            })
+        ));
+        assert!(matches!(
+            find_item(&files_map, "/empty"),
+            Some(Item {
+                is_dir: true,
+                file_item: Some(_)
+            })
+        ));
+        assert_eq!(find_item(&files_map, "/doc"), None);
+        assert!(parent_exists(&files_map, "/docs/img/new.png"));
+        assert!(!parent_exists(&files_map, "/other/new.png"));
+        assert!(!parent_exists(&files_map, "/a.md/new.png"));
+
+        let root: Vec<_> = children(&files_map, "/").into_keys().collect();
+        assert_eq!(root, vec!["a.md", "docs", "empty"]);
+        let docs = children(&files_map, "/docs");
+        assert!(docs["img"].is_dir);
+        assert!(!docs["b.md"].is_dir);
+        assert_eq!(docs.len(), 2);
+    }
+
+    #[test]
+    fn removing_the_last_file_of_a_directory_keeps_the_directory() {
+        let mut files_map = files_map_of(&["/a.md", "/docs/b.md", "/docs/img/c.png"]);
+
+        assert_eq!(remove_item(&mut files_map, "/docs/img/c.png"), 1);
+        assert!(matches!(
+            find_item(&files_map, "/docs/img"),
+            Some(Item {
+                is_dir: true,
+                file_item: Some(_)
+            })
+        ));
+
+        assert_eq!(remove_item(&mut files_map, "/docs"), 2);
+        assert_eq!(find_item(&files_map, "/docs"), None);
+        assert_eq!(remove_item(&mut files_map, "/docs"), 0);
+        assert_eq!(files_map.len(), 1);
+    }
+
+    #[test]
+    fn directories_are_moved_with_their_content() {
+        let mut files_map = files_map_of(&["/a.md", "/docs/b.md", "/docs/img/c.png"]);
+
+        move_items(&mut files_map, "/docs", "/archive");
+        let paths: Vec<_> = files_map.keys().map(String::as_str).collect();
+        assert_eq!(paths, vec!["/a.md", "/archive/b.md", "/archive/img/c.png"]);
+
+        move_items(&mut files_map, "/archive/img/c.png", "/c.png");
+        assert!(matches!(
+            find_item(&files_map, "/archive/img"),
+            Some(Item {
+                is_dir: true,
+                file_item: Some(_)
+            })
+        ));
+        assert!(matches!(
+            find_item(&files_map, "/c.png"),
+            Some(Item { is_dir: false, .. })
+        ));
+    }
+
+    #[test]
+    fn multistatus_lists_the_direct_children_of_directories() {
+        let files_map = files_map_of(&["/a b.md", "/docs/b.md"]);
+        let root = Item {
+            is_dir: true,
+            file_item: None,
+        };
+
+        let xml = multistatus(&files_map, "/", &root, true);
+        assert_eq!(xml.matches("<D:response>").count(), 3);
+        assert!(xml.contains("<D:href>/</D:href>"));
+        assert!(xml.contains("<D:href>/a%20b.md</D:href>"));
+        assert!(xml.contains("<D:href>/docs/</D:href>"));
+        assert!(!xml.contains("/docs/b.md"));
+        assert!(xml.contains("<D:getcontentlength>1</D:getcontentlength>"));
+
+        let xml = multistatus(&files_map, "/", &root, false);
+        assert_eq!(xml.matches("<D:response>").count(), 1);
+    }
+}
@@ -101,7 +101,7 @@ pub async fn xorurl_of_files(
 
     let location = get_from_arg_or_stdin(location, Some("...awaiting location path from stdin"))?;
     let (_, processed_files, _) = safe
-        .files_container_create_from(&location, None, recursive, follow_symlinks)
+        .files_container_create_from(&location, None, recursive, follow_symlinks, None)
         .await?;
 
     // Now let's just print out a list of the xorurls
@@ -117,7 +117,7 @@ pub async fn xorurl_of_files(
         let mut list = Vec::<(String, String)>::new();
         for (file_name, change) in processed_files {
             let link = match change {
-                FilesMapChange::Failed(err) => format!("<{err}>"),
+                FilesMapChange::Failed(err) | FilesMapChange::Skipped(err) => format!("<{err}>"),
                 FilesMapChange::Added(link)
                 | FilesMapChange::Updated(link)
                 | FilesMapChange::Removed(link) => link,
@@ -1114,3 +1114,124 @@ async fn wallet_deposit_dbc_reissued_to_file() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn wallet_broadcast_should_reissue_a_dbc_from_an_offline_signed_transaction() -> Result<()> {
+    let config_dir = use_isolated_safe_config_dir()?;
+    let tmp_data_dir = assert_fs::TempDir::new()?;
+    let (dbc_file_path, owner_sk, _) = get_owned_dbc_on_file(&tmp_data_dir).await?;
+    let unsigned_tx_path = tmp_data_dir.child(get_random_string());
+    let sealed_tx_path = tmp_data_dir.child(get_random_string());
+    let signed_tx_path = tmp_data_dir.child(get_random_string());
+    let reissued_dir = tmp_data_dir.child(get_random_string());
+
+    let recipient_sk = bls::SecretKey::random();
+    safe_cmd(
+        &config_dir,
+        [
+            "wallet",
+            "build",
+            "--input",
+            &dbc_file_path.display().to_string(),
+            "--secret-key",
+            &owner_sk.to_hex(),
+            "--output",
+            &format!("1.5:{}", recipient_sk.public_key().to_hex()),
+            "--save",
+            &unsigned_tx_path.display().to_string(),
+        ],
+        Some(0),
+    )?
+    .assert()
+    .stdout(predicate::str::contains(
+        "Built unsigned transaction with 1 input/s and 1 output/s.",
+    ))
+    .success();
+
+    safe_cmd(
+        &config_dir,
+        [
+            "wallet",
+            "seal",
+            &unsigned_tx_path.display().to_string(),
+            "--change-to",
+            &owner_sk.public_key().to_hex(),
+            "--save",
+            &sealed_tx_path.display().to_string(),
+        ],
+        Some(0),
+    )?;
+
+    safe_cmd(
+        &config_dir,
+        [
+            "wallet",
+            "sign",
+            &sealed_tx_path.display().to_string(),
+            "--input",
+            &dbc_file_path.display().to_string(),
+            "--secret-key",
+            &owner_sk.to_hex(),
+            "--save",
+            &signed_tx_path.display().to_string(),
+        ],
+        Some(0),
+    )?
+    .assert()
+    .stdout(predicate::str::contains(
+        "Signed transaction, it can now be broadcast with 'wallet broadcast'.",
+    ))
+    .success();
+
+    safe_cmd(
+        &config_dir,
+        [
+            "wallet",
+            "broadcast",
+            &signed_tx_path.display().to_string(),
+            "--save",
+            &reissued_dir.display().to_string(),
+        ],
+        Some(0),
+    )?
+    .assert()
+    .stdout(predicate::str::contains(
+        "Transaction broadcast, 2 DBC/s reissued.",
+    ))
+    .success();
+
+    let output_dbc_path = std::fs::read_dir(reissued_dir.path())?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().starts_with("dbc-"))
+                .unwrap_or(false)
+        })
+        .ok_or_else(|| eyre!("The reissued DBC was not written to the directory"))?;
+
+    let json_output = safe_cmd_stdout(&config_dir, ["wallet", "create", "--json"], Some(0))?;
+    let wallet_xorurl = parse_wallet_create_output(&json_output)?;
+    safe_cmd(
+        &config_dir,
+        [
+            "wallet",
+            "deposit",
+            "--name",
+            "reissued-dbc",
+            "--dbc",
+            &output_dbc_path.display().to_string(),
+            "--secret-key",
+            &recipient_sk.to_hex(),
+            &wallet_xorurl,
+        ],
+        Some(0),
+    )?
+    .assert()
+    .stdout(format!(
+        "Spendable DBC deposited (1.500000000 safecoins) with name 'reissued-dbc' in wallet located at \"{wallet_xorurl}\"\n",
+    ))
+    .success();
+
+    Ok(())
+}
@@ -145,6 +145,8 @@ impl ClientBuilder {
                     .map_err(|err| Error::NetworkContacts(err.to_string()))?
             }
         };
+        // elder and data copy counts are those the network was started with
+        let network_params = network_contacts.network_params();
 
         let session = Session::new(
             self.local_addr
@@ -165,6 +167,7 @@ impl ClientBuilder {
             max_backoff_interval,
             cmd_timeout,
             chunks_cache: Arc::new(RwLock::new(Default::default())),
+            network_params,
         };
         client.connect().await?;
 
@@ -8,7 +8,7 @@
 
 mod pac_man;
 
-pub(crate) use pac_man::{encrypt_large, to_chunk, DataMapLevel};
+pub(crate) use pac_man::{encrypt_large, to_chunk, DataMapLevel, PrivateHead};
 
 use crate::{Error, Result};
 
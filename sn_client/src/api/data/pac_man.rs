@@ -27,6 +27,15 @@ pub(crate) enum DataMapLevel {
     Additional(DataMap),
 }
 
+// Content of the head chunk of private data, i.e. the content of the head chunk of
+// the data once encrypted to a key. The key is kept along, since decrypting with
+// any other key would silently produce garbage rather than fail.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PrivateHead {
+    pub(crate) recipient: bls::PublicKey,
+    pub(crate) ciphertext: bls::Ciphertext,
+}
+
 #[allow(unused)]
 pub(crate) fn encrypt_from_path(path: &Path) -> Result<(XorName, Vec<Chunk>)> {
     let (data_map, encrypted_chunks) = encrypt_file(path)?;
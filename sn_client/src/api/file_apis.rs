@@ -7,7 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{
-    data::{encrypt_large, to_chunk, LargeFile, SmallFile},
+    data::{encrypt_large, to_chunk, LargeFile, PrivateHead, SmallFile},
     Client,
 };
 use crate::{api::data::DataMapLevel, Error, Result};
@@ -17,7 +17,7 @@ use sn_interface::{
     types::{Chunk, ChunkAddress},
 };
 
-use bincode::deserialize;
+use bincode::{deserialize, serialize};
 use bytes::Bytes;
 use futures::future::join_all;
 use itertools::Itertools;
@@ -45,14 +45,19 @@ impl Client {
     /// Reads [`Bytes`] from the network, whose contents are contained within on or more chunks.
     pub async fn read_bytes(&self, address: XorName) -> Result<Bytes> {
         let chunk = self.get_chunk(&address).await?;
+        self.read_head_chunk(chunk).await
+    }
 
-        // first try to deserialize a LargeFile, if it works, we go and seek it
-        if let Ok(data_map) = self.unpack_chunk(chunk.clone()).await {
-            self.read_all(data_map).await
-        } else {
-            // if an error occurs, we assume it's a SmallFile
-            Ok(chunk.value().clone())
-        }
+    #[instrument(skip(self, secret_key), level = "debug")]
+    /// Reads private [`Bytes`] from the network, i.e. stored with `upload_private`,
+    /// decrypting them with the secret key matching the public key they were encrypted to.
+    pub async fn read_private_bytes(
+        &self,
+        address: XorName,
+        secret_key: &bls::SecretKey,
+    ) -> Result<Bytes> {
+        let chunk = self.get_private_chunk(&address, secret_key).await?;
+        self.read_head_chunk(chunk).await
     }
 
     #[instrument(skip(self), level = "debug")]
@@ -95,7 +100,44 @@ impl Client {
     {
         trace!("Reading {length} bytes at: {address}, starting from position: {position}");
         let chunk = self.get_chunk(&address).await?;
+        self.read_head_chunk_from(chunk, position, length).await
+    }
+
+    /// Read private bytes from the network, i.e. stored with `upload_private`, decrypting
+    /// them with the secret key matching the public key they were encrypted to.
+    ///
+    /// Takes `position` and `length` arguments as `read_from` does.
+    #[instrument(skip_all, level = "trace")]
+    pub async fn read_private_from(
+        &self,
+        address: XorName,
+        secret_key: &bls::SecretKey,
+        position: usize,
+        length: usize,
+    ) -> Result<Bytes> {
+        trace!("Reading {length} private bytes at: {address}, starting from position: {position}");
+        let chunk = self.get_private_chunk(&address, secret_key).await?;
+        self.read_head_chunk_from(chunk, position, length).await
+    }
 
+    // Reads all the bytes of a file given the chunk at the head of it.
+    async fn read_head_chunk(&self, chunk: Chunk) -> Result<Bytes> {
+        // first try to deserialize a LargeFile, if it works, we go and seek it
+        if let Ok(data_map) = self.unpack_chunk(chunk.clone()).await {
+            self.read_all(data_map).await
+        } else {
+            // if an error occurs, we assume it's a SmallFile
+            Ok(chunk.value().clone())
+        }
+    }
+
+    // Reads `length` bytes of a file, starting at `position`, given the chunk at the head of it.
+    async fn read_head_chunk_from(
+        &self,
+        chunk: Chunk,
+        position: usize,
+        length: usize,
+    ) -> Result<Bytes> {
         // First try to deserialize a LargeFile, if it works, we go and seek it.
         // If an error occurs, we consider it to be a SmallFile.
         if let Ok(data_map) = self.unpack_chunk(chunk.clone()).await {
@@ -155,6 +197,27 @@ impl Client {
         }
     }
 
+    /// Tries to chunk the bytes as private data, returning an address and chunks, without storing
+    /// anything to network. The chunk at the head of the data, i.e. holding its data map, or
+    /// the content itself for a SmallFile, is encrypted to the provided public key.
+    #[instrument(skip_all, level = "trace")]
+    pub fn chunk_private_bytes(
+        bytes: Bytes,
+        public_key: &bls::PublicKey,
+    ) -> Result<(XorName, Vec<Chunk>)> {
+        let (_, mut chunks) = Self::chunk_bytes(bytes)?;
+        // the head chunk is always the last one produced
+        let head_chunk = chunks.pop().ok_or(Error::EmptyFileProvided)?;
+        let private_head = PrivateHead {
+            recipient: *public_key,
+            ciphertext: public_key.encrypt(head_chunk.value()),
+        };
+        let private_head_chunk = to_chunk(Bytes::from(serialize(&private_head)?));
+        let address = *private_head_chunk.name();
+        chunks.push(private_head_chunk);
+        Ok((address, chunks))
+    }
+
     /// Encrypts a [`LargeFile`] and returns the resulting address and all chunks.
     /// Does not store anything to the network.
     #[instrument(skip(file), level = "trace")]
@@ -188,6 +251,33 @@ impl Client {
         self.upload_bytes(bytes, true).await
     }
 
+    /// Writes [`Bytes`] to the network as private data, in the form of immutable chunks
+    /// where the one at the head of the data is encrypted to the provided public key.
+    /// Only the holder of the matching secret key can then read them with `read_private_bytes`.
+    #[instrument(skip(self, bytes), level = "debug")]
+    pub async fn upload_private(
+        &self,
+        bytes: Bytes,
+        public_key: &bls::PublicKey,
+    ) -> Result<XorName> {
+        let (address, chunks) = Self::chunk_private_bytes(bytes, public_key)?;
+        self.store_chunks(chunks, false).await?;
+        Ok(address)
+    }
+
+    /// Writes [`Bytes`] to the network as private data, as `upload_private` does, and
+    /// attempts to verify that all the data was uploaded to the network before returning.
+    #[instrument(skip_all, level = "trace")]
+    pub async fn upload_private_and_verify(
+        &self,
+        bytes: Bytes,
+        public_key: &bls::PublicKey,
+    ) -> Result<XorName> {
+        let (address, chunks) = Self::chunk_private_bytes(bytes, public_key)?;
+        self.store_chunks(chunks, true).await?;
+        Ok(address)
+    }
+
     /// Calculates a LargeFile's/SmallFile's address from self encrypted chunks,
     /// without storing them onto the network.
     #[instrument(skip(bytes), level = "debug")]
@@ -216,6 +306,13 @@ impl Client {
     #[instrument(skip_all, level = "trace")]
     async fn upload_large(&self, large: LargeFile, verify: bool) -> Result<XorName> {
         let (head_address, all_chunks) = Self::encrypt_large(large)?;
+        self.store_chunks(all_chunks, verify).await?;
+        Ok(head_address)
+    }
+
+    // Stores the chunks in batches, optionally verifying each of them was stored.
+    #[instrument(skip_all, level = "trace")]
+    async fn store_chunks(&self, all_chunks: Vec<Chunk>, verify: bool) -> Result<()> {
         for next_batch in all_chunks.chunks(CHUNKS_BATCH_MAX_SIZE) {
             // Connect to all relevant elders before we fire off all msgs...
             self.session
@@ -246,7 +343,7 @@ impl Client {
             }
         }
 
-        Ok(head_address)
+        Ok(())
     }
 
     /// Directly writes a [`SmallFile`] to the network in the
@@ -264,6 +361,25 @@ impl Client {
         Ok(address)
     }
 
+    // Gets the head chunk of private data and decrypts it with the secret key,
+    // returning the chunk which was encrypted upon upload.
+    async fn get_private_chunk(
+        &self,
+        address: &XorName,
+        secret_key: &bls::SecretKey,
+    ) -> Result<Chunk> {
+        let chunk = self.get_chunk(address).await?;
+        let private_head: PrivateHead =
+            deserialize(chunk.value()).map_err(|_| Error::PrivateDataDecryption(*address))?;
+        if private_head.recipient != secret_key.public_key() {
+            return Err(Error::PrivateDataDecryption(*address));
+        }
+        let head_chunk_content = secret_key
+            .decrypt(&private_head.ciphertext)
+            .ok_or(Error::PrivateDataDecryption(*address))?;
+        Ok(to_chunk(Bytes::from(head_chunk_content)))
+    }
+
     // Verify a chunk is stored at provided address
     async fn verify_chunk_is_stored(&self, address: XorName) -> Result<()> {
         // `read_bytes` could return earlier than query_timeout
@@ -442,7 +558,7 @@ impl Client {
 
 #[cfg(test)]
 mod tests {
-    use super::LargeFile;
+    use super::{LargeFile, PrivateHead};
     use crate::{
         utils::test_utils::{create_test_client, init_logger, try_create_test_client},
         Client,
@@ -491,6 +607,40 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn private_chunking_encrypts_head_chunk() -> Result<()> {
+        init_logger();
+        let secret_key = bls::SecretKey::random();
+        let file = random_bytes(MIN_ENCRYPTABLE_BYTES);
+
+        let (public_address, public_chunks) = Client::chunk_bytes(file.clone())?;
+        let (private_address, private_chunks) =
+            Client::chunk_private_bytes(file, &secret_key.public_key())?;
+        assert_ne!(public_address, private_address);
+        assert_eq!(public_chunks.len(), private_chunks.len());
+        // only the head chunk differs
+        assert_eq!(
+            public_chunks[..public_chunks.len() - 1],
+            private_chunks[..private_chunks.len() - 1]
+        );
+
+        let private_head = private_chunks
+            .last()
+            .ok_or_else(|| eyre!("no head chunk"))?;
+        assert_eq!(*private_head.name(), private_address);
+        let private_head: PrivateHead = bincode::deserialize(private_head.value())?;
+        assert_eq!(private_head.recipient, secret_key.public_key());
+        let decrypted = secret_key
+            .decrypt(&private_head.ciphertext)
+            .ok_or_else(|| eyre!("failed to decrypt head chunk"))?;
+        assert_eq!(
+            Some(&Bytes::from(decrypted)),
+            public_chunks.last().map(|chunk| chunk.value())
+        );
+
+        Ok(())
+    }
+
     // Test storing and reading min sized LargeFile.
     #[tokio::test(flavor = "multi_thread")]
     async fn store_and_read_3kb() -> Result<()> {
@@ -519,6 +669,39 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn store_and_read_private_data() -> Result<()> {
+        init_logger();
+        let _start_span = tracing::info_span!("store_and_read_private_data").entered();
+
+        let client = create_test_client().await?;
+        let secret_key = bls::SecretKey::random();
+
+        for size in [MIN_ENCRYPTABLE_BYTES / 2, 2 * MIN_ENCRYPTABLE_BYTES] {
+            let bytes = random_bytes(size);
+            let address = client
+                .upload_private_and_verify(bytes.clone(), &secret_key.public_key())
+                .await?;
+
+            let read_data = client.read_private_bytes(address, &secret_key).await?;
+            compare(bytes.clone(), read_data);
+
+            let read_data = client
+                .read_private_from(address, &secret_key, size / 2, usize::MAX)
+                .await?;
+            compare(bytes.slice(size / 2..), read_data);
+
+            assert_matches::assert_matches!(
+                client
+                    .read_private_bytes(address, &bls::SecretKey::random())
+                    .await,
+                Err(crate::Error::PrivateDataDecryption(name)) if name == address
+            );
+        }
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn seek_with_unknown_length() -> Result<()> {
         init_logger();
@@ -0,0 +1,249 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+
+use crate::{Error, Result};
+
+use sn_interface::{
+    messaging::{
+        data::{ClientMsg, SectionHealth},
+        ClientAuth, WireMsg,
+    },
+    network_knowledge::{SectionAuthorityProvider, SectionTree},
+    types::Peer,
+};
+
+use std::collections::BTreeMap;
+use xor_name::Prefix;
+
+// Maximum number of rounds of querying all the sections we know of when crawling the network.
+// Each round can discover new SAPs from the AE responses of the previous one.
+const MAX_CRAWL_ROUNDS: usize = 5;
+
+/// Health of a section, as reported by each of its elders.
+#[derive(Clone, Debug)]
+pub struct SectionHealthReport {
+    /// Prefix of the section
+    pub prefix: Prefix,
+    /// Key of the latest SAP we know of for the section
+    pub section_key: bls::PublicKey,
+    /// Number of section keys preceding the current one, as per our `SectionsDAG`
+    pub section_key_generation: usize,
+    /// Whether the SAP we held for the section before querying it was outdated, or missing
+    pub stale_sap: bool,
+    /// Elders of the SAP which responded, and their view of the section
+    pub elders: BTreeMap<Peer, SectionHealth>,
+    /// Elders of the SAP we couldn't get a response from, and why
+    pub unreachable_elders: BTreeMap<Peer, String>,
+}
+
+impl SectionHealthReport {
+    /// Number of elders in the section's SAP.
+    pub fn elder_count(&self) -> usize {
+        self.elders.len() + self.unreachable_elders.len()
+    }
+
+    /// Number of adults in the section, as reported by the first up to date elder, if any.
+    pub fn adult_count(&self) -> Option<usize> {
+        self.elders
+            .values()
+            .find(|health| health.section_key == self.section_key)
+            .map(|health| health.members.len().saturating_sub(self.elder_count()))
+    }
+
+    /// Elders which responded with a section key other than the one of the section's SAP.
+    pub fn lagging_elders(&self) -> impl Iterator<Item = &Peer> {
+        self.elders
+            .iter()
+            .filter(|(_, health)| health.section_key != self.section_key)
+            .map(|(elder, _)| elder)
+    }
+
+    /// Whether the elders which responded disagree on the members of the section.
+    pub fn members_disagree(&self) -> bool {
+        let mut members = self.elders.values().map(|health| &health.members);
+        match members.next() {
+            Some(first) => members.any(|others| others != first),
+            None => false,
+        }
+    }
+}
+
+/// Health of all the sections of the network found by crawling it.
+#[derive(Clone, Debug)]
+pub struct NetworkHealthReport {
+    /// Genesis key of the network
+    pub genesis_key: bls::PublicKey,
+    /// Health report of each section found
+    pub sections: BTreeMap<Prefix, SectionHealthReport>,
+}
+
+impl Client {
+    /// Query all the elders of the section matching the prefix for its health.
+    ///
+    /// The latest SAP we know of for the section is used, which will be updated from the AE
+    /// responses to the query if outdated.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn section_health(&self, prefix: &Prefix) -> Result<SectionHealthReport> {
+        let tree = self.session.network.read().await.clone();
+        let sap = tree
+            .get(prefix)
+            .ok_or_else(|| Error::NoNetworkKnowledge(prefix.name()))?;
+
+        let mut report = self.query_section_health(&sap).await?;
+        let latest_tree = self.session.network.read().await.clone();
+        report.stale_sap = latest_tree
+            .get(prefix)
+            .map_or(true, |latest| latest.section_key() != sap.section_key());
+        report.section_key_generation = section_key_generation(&latest_tree, &sap.section_key());
+
+        Ok(report)
+    }
+
+    /// Crawl the network, querying the elders of every section for its health.
+    ///
+    /// Starting from the sections in our network knowledge, each section is queried, and
+    /// the AE responses received update our knowledge with any new SAPs, e.g. from splits or
+    /// elder changes. This is repeated until no new SAPs are found, or up to
+    /// `MAX_CRAWL_ROUNDS` times. A section which can't be queried doesn't abort the crawl,
+    /// it's reported with all its elders as unreachable instead.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn crawl_network(&self) -> Result<NetworkHealthReport> {
+        let initial_tree = self.session.network.read().await.clone();
+        let mut sections = BTreeMap::<Prefix, SectionHealthReport>::new();
+
+        for round in 0..MAX_CRAWL_ROUNDS {
+            let saps: Vec<_> = self.session.network.read().await.all().cloned().collect();
+            let to_query: Vec<_> = saps
+                .into_iter()
+                .filter(|sap| {
+                    sections
+                        .get(&sap.prefix())
+                        .map_or(true, |report| report.section_key != sap.section_key())
+                })
+                .collect();
+
+            debug!(
+                "Crawl round #{round}: querying {} sections for their health",
+                to_query.len()
+            );
+            if to_query.is_empty() {
+                break;
+            }
+
+            let mut tasks = vec![];
+            for sap in to_query {
+                let client = self.clone();
+                tasks.push(async move {
+                    let result = client.query_section_health(&sap).await;
+                    (sap, result)
+                });
+            }
+            for (sap, result) in futures::future::join_all(tasks).await {
+                let report = match result {
+                    Ok(report) => report,
+                    Err(error) => {
+                        // carry on crawling, reporting all the elders of the section as unreachable
+                        warn!(
+                            "Failed to query section {:?} for its health: {error:?}",
+                            sap.prefix()
+                        );
+                        SectionHealthReport {
+                            prefix: sap.prefix(),
+                            section_key: sap.section_key(),
+                            section_key_generation: 0,
+                            stale_sap: false,
+                            elders: BTreeMap::new(),
+                            unreachable_elders: sap
+                                .elders()
+                                .map(|elder| (*elder, error.to_string()))
+                                .collect(),
+                        }
+                    }
+                };
+                let _ = sections.insert(report.prefix, report);
+            }
+
+            // drop the sections which have split, or merged, since we queried them
+            let tree = self.session.network.read().await;
+            sections.retain(|prefix, _| tree.get_signed(prefix).is_some());
+        }
+
+        let tree = self.session.network.read().await.clone();
+        for (prefix, report) in sections.iter_mut() {
+            report.stale_sap = initial_tree
+                .get_signed(prefix)
+                .map_or(true, |sap| sap.section_key() != report.section_key);
+            report.section_key_generation = section_key_generation(&tree, &report.section_key);
+        }
+
+        Ok(NetworkHealthReport {
+            genesis_key: *tree.genesis_key(),
+            sections,
+        })
+    }
+
+    // Sends a `SectionHealthQuery` to all the elders of the SAP, and gathers their responses.
+    async fn query_section_health(
+        &self,
+        sap: &SectionAuthorityProvider,
+    ) -> Result<SectionHealthReport> {
+        let payload = WireMsg::serialize_msg_payload(&ClientMsg::SectionHealthQuery)?;
+        let auth = ClientAuth {
+            public_key: self.public_key(),
+            signature: self.keypair.sign(&payload),
+        };
+
+        let elders = sap.elders_vec();
+        let mut responses = self
+            .session
+            .send_section_health_query(
+                sap.prefix().name(),
+                sap.section_key(),
+                elders.clone(),
+                auth,
+                payload,
+            )
+            .await?;
+
+        let mut report = SectionHealthReport {
+            prefix: sap.prefix(),
+            section_key: sap.section_key(),
+            section_key_generation: 0,
+            stale_sap: false,
+            elders: BTreeMap::new(),
+            unreachable_elders: BTreeMap::new(),
+        };
+
+        for elder in elders {
+            match responses.remove(&elder.addr()) {
+                Some(Ok(health)) => {
+                    let _ = report.elders.insert(elder, health);
+                }
+                Some(Err(error)) => {
+                    let _ = report.unreachable_elders.insert(elder, error.to_string());
+                }
+                None => {
+                    let _ = report
+                        .unreachable_elders
+                        .insert(elder, "No response received".to_string());
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+// Number of keys preceding the section key in the tree's `SectionsDAG`.
+fn section_key_generation(tree: &SectionTree, section_key: &bls::PublicKey) -> usize {
+    tree.get_sections_dag()
+        .get_ancestors(section_key)
+        .map_or(0, |ancestors| ancestors.len())
+}
@@ -0,0 +1,177 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+
+use crate::{Error, Result};
+
+use sn_interface::{
+    messaging::{
+        data::{ClientMsg, SectionMembersProof},
+        system::SectionSigned,
+        ClientAuth, WireMsg,
+    },
+    network_knowledge::{NodeState, SectionAuthorityProvider},
+    types::Peer,
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+use xor_name::Prefix;
+
+/// Members of a section, each signed by the section, as proven by its elders.
+#[derive(Clone, Debug)]
+pub struct SectionMembersReport {
+    /// SAP the members were proven with
+    pub signed_sap: SectionSigned<SectionAuthorityProvider>,
+    /// Current members of the section, each with the signature of the section
+    /// which agreed on its membership
+    pub members: BTreeSet<SectionSigned<NodeState>>,
+    /// Elders which proved the same members
+    pub agreeing_elders: BTreeSet<Peer>,
+    /// Elders which proved a different set of members, e.g. due to ongoing churn
+    pub disagreeing_elders: BTreeSet<Peer>,
+    /// Elders we couldn't get a valid proof from, and why
+    pub failed_elders: BTreeMap<Peer, String>,
+}
+
+impl SectionMembersReport {
+    /// Prefix of the section.
+    pub fn prefix(&self) -> Prefix {
+        self.signed_sap.prefix()
+    }
+
+    /// Members of the section which are not elders.
+    pub fn adults(&self) -> impl Iterator<Item = &SectionSigned<NodeState>> {
+        self.members
+            .iter()
+            .filter(|member| !self.signed_sap.contains_elder(&member.name()))
+    }
+}
+
+impl Client {
+    /// Query all the elders of the section matching the prefix for the section signed
+    /// state of each of its current members.
+    ///
+    /// Each proof received is verified against our `SectionTree`, which is updated with the
+    /// SAP it was provided with. The members from the elders aware of the latest SAP are
+    /// returned, or an error if no elder provided a valid proof.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn section_members(&self, prefix: &Prefix) -> Result<SectionMembersReport> {
+        let sap = self
+            .session
+            .network
+            .read()
+            .await
+            .get(prefix)
+            .ok_or_else(|| Error::NoNetworkKnowledge(prefix.name()))?;
+
+        let payload = WireMsg::serialize_msg_payload(&ClientMsg::SectionMembersQuery)?;
+        let auth = ClientAuth {
+            public_key: self.public_key(),
+            signature: self.keypair.sign(&payload),
+        };
+
+        let elders = sap.elders_vec();
+        let mut responses = self
+            .session
+            .send_section_members_query(
+                prefix.name(),
+                sap.section_key(),
+                elders.clone(),
+                auth,
+                payload,
+            )
+            .await?;
+
+        let mut proofs = BTreeMap::new();
+        let mut failed_elders = BTreeMap::new();
+        for elder in elders {
+            let failure = match responses.remove(&elder.addr()) {
+                Some(Ok(proof)) => match self.verify_section_members_proof(prefix, &proof).await {
+                    Ok(()) => {
+                        let _ = proofs.insert(elder, proof);
+                        continue;
+                    }
+                    Err(reason) => reason,
+                },
+                Some(Err(error)) => error.to_string(),
+                None => "No response received".to_string(),
+            };
+            let _ = failed_elders.insert(elder, failure);
+        }
+
+        // favour the proofs from the elders aware of the latest SAP we now know of
+        let latest_key = self
+            .session
+            .network
+            .read()
+            .await
+            .get(prefix)
+            .map(|sap| sap.section_key());
+        let chosen = proofs
+            .values()
+            .find(|proof| Some(proof.signed_sap.section_key()) == latest_key)
+            .or_else(|| proofs.values().next())
+            .cloned()
+            .ok_or_else(|| Error::NoValidSectionMembersProof {
+                prefix: *prefix,
+                errors: failed_elders.clone(),
+            })?;
+
+        let (agreeing_elders, disagreeing_elders): (Vec<_>, Vec<_>) = proofs
+            .iter()
+            .partition(|(_, proof)| proof.members == chosen.members);
+
+        Ok(SectionMembersReport {
+            signed_sap: chosen.signed_sap,
+            members: chosen.members,
+            agreeing_elders: agreeing_elders
+                .into_iter()
+                .map(|(elder, _)| *elder)
+                .collect(),
+            disagreeing_elders: disagreeing_elders
+                .into_iter()
+                .map(|(elder, _)| *elder)
+                .collect(),
+            failed_elders,
+        })
+    }
+
+    // Verifies the proof against our `SectionsDAG` merged with its proof chain, updating our
+    // `SectionTree` with its SAP if we didn't know of it.
+    async fn verify_section_members_proof(
+        &self,
+        prefix: &Prefix,
+        proof: &SectionMembersProof,
+    ) -> std::result::Result<(), String> {
+        if proof.signed_sap.prefix() != *prefix {
+            return Err(format!(
+                "Proof is for section {:?} instead",
+                proof.signed_sap.prefix()
+            ));
+        }
+
+        let mut network = self.session.network.write().await;
+        let mut sections_dag = network.get_sections_dag().clone();
+        sections_dag
+            .merge(proof.proof_chain.clone())
+            .map_err(|err| format!("Untrusted proof chain: {err}"))?;
+        if !proof.verify(&sections_dag) {
+            return Err("Proof failed verification against our SectionTree".to_string());
+        }
+
+        if let Err(err) = network.update_the_section_tree(proof.section_tree_update()) {
+            // the SAP is valid, so it must be one we can't replace our current one with
+            debug!(
+                "SAP proving the members of {prefix:?} wasn't applied to our SectionTree: {err:?}"
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -11,12 +11,16 @@ pub mod client_builder;
 mod cmds;
 mod data;
 mod file_apis;
+mod health_apis;
+mod membership_apis;
 mod queries;
 mod register_apis;
 mod spentbook_apis;
 
 pub use client_builder::ClientBuilder;
 pub use file_apis::QueriedDataReplicas;
+pub use health_apis::{NetworkHealthReport, SectionHealthReport};
+pub use membership_apis::SectionMembersReport;
 pub use register_apis::RegisterWriteAheadLog;
 
 use crate::{
@@ -27,7 +31,7 @@ use crate::{
 use sn_dbc::Owner;
 use sn_interface::{
     messaging::data::{DataQueryVariant, RegisterQuery},
-    network_knowledge::SectionTree,
+    network_knowledge::{NetworkParams, SectionTree},
     types::{Chunk, Keypair, PublicKey, RegisterAddress},
 };
 
@@ -61,6 +65,7 @@ pub struct Client {
     pub(crate) max_backoff_interval: Duration,
     pub(crate) cmd_timeout: Duration,
     chunks_cache: Arc<RwLock<ChunksCache>>,
+    network_params: NetworkParams,
 }
 
 /// Easily manage connections to/from The Safe Network with the client and its APIs.
@@ -127,6 +132,11 @@ impl Client {
         &self.keypair
     }
 
+    /// Return the params of the network the client is connected to.
+    pub fn network_params(&self) -> NetworkParams {
+        self.network_params
+    }
+
     /// Return the client's `PublicKey`.
     pub fn public_key(&self) -> PublicKey {
         self.keypair().public_key()
@@ -12,7 +12,6 @@ use super::Client;
 use crate::errors::{Error, Result};
 
 use sn_interface::{
-    data_copy_count,
     messaging::{
         data::{ClientMsg, DataQuery, DataQueryVariant, QueryResponse},
         ClientAuth, WireMsg,
@@ -99,8 +98,8 @@ impl Client {
 
             // There should not be more than a certain number of nodes holding
             // copies of the data. Retry the closest node again.
-            if !retry || query.node_index >= data_copy_count() - 1 {
-                // we don't want to retry beyond `data_copy_count()` nodes
+            if !retry || query.node_index >= self.network_params.data_copy_count - 1 {
+                // we don't want to retry beyond `data_copy_count` nodes
                 return res;
             }
 
@@ -246,7 +245,7 @@ impl Client {
         let _ = span.enter();
 
         // Send queries to all replicas concurrently
-        let num_of_replicas = data_copy_count();
+        let num_of_replicas = self.network_params.data_copy_count;
         let all_replicas: Vec<usize> = (0..num_of_replicas).collect();
         let results = self
             .send_query_to_replicas(query.clone(), &all_replicas)
@@ -7,7 +7,6 @@ use rand::{thread_rng, Rng};
 use self_encryption::MIN_ENCRYPTABLE_BYTES;
 use sn_client::{Client, Error};
 use sn_interface::{
-    data_copy_count,
     messaging::{
         data::{ClientMsg, DataQuery, DataQueryVariant, QueryResponse},
         WireMsg,
@@ -35,9 +34,9 @@ struct Args {
     #[clap(short, long)]
     upload: bool,
 
-    /// Query up to nth node
-    #[clap(long, default_value_t = data_copy_count() - 1)]
-    up_to_node: usize,
+    /// Query up to nth node, by default up to the last one holding a copy of the data
+    #[clap(long)]
+    up_to_node: Option<usize>,
 }
 
 #[tokio::main]
@@ -72,15 +71,18 @@ async fn main() -> Result<()> {
 
     // Query nodes for chunks
 
+    let up_to_node = args
+        .up_to_node
+        .unwrap_or_else(|| client.network_params().data_copy_count - 1);
     let mut error_occurred = false;
     for chunk in chunks {
         println!(
             "Querying node 0-{} for Chunk({})",
-            args.up_to_node,
+            up_to_node,
             chunk.address().0
         );
 
-        for i in 0..=args.up_to_node {
+        for i in 0..=up_to_node {
             let query_fut = query_chunk(&client, i, chunk.address().0);
             let res = match timeout(Duration::from_secs(10), query_fut).await {
                 Ok(res) => res,
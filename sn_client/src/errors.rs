@@ -19,9 +19,9 @@ use sn_interface::{
 
 use bls::PublicKey;
 use sn_dbc::KeyImage;
-use std::{io, time::Duration};
+use std::{collections::BTreeMap, io, time::Duration};
 use thiserror::Error;
-use xor_name::XorName;
+use xor_name::{Prefix, XorName};
 
 /// Specialisation of `std::Result` for Client.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -253,6 +253,11 @@ pub enum Error {
         /// MsgId of the msg attempted to send
         msg_id: MsgId,
     },
+    /// Private data could not be decrypted with the secret key provided
+    #[error(
+        "Could not decrypt the private data at xorname address {0} with the secret key provided"
+    )]
+    PrivateDataDecryption(XorName),
     /// Bincode error
     #[error(transparent)]
     Serialisation(#[from] Box<bincode::ErrorKind>),
@@ -294,6 +299,14 @@ pub enum Error {
     /// Occurs if a section key is not found when searching the sections DAG.
     #[error("Section key {0:?} was not found in the sections DAG")]
     SectionsDagKeyNotFound(PublicKey),
+    /// Occurs if none of the elders of a section responded with a valid proof of its members.
+    #[error("No valid proof of the members of section {prefix:?} was received: {errors:?}")]
+    NoValidSectionMembersProof {
+        /// Prefix of the section queried
+        prefix: Prefix,
+        /// Why each of the elders queried failed to provide a valid proof
+        errors: BTreeMap<Peer, String>,
+    },
     /// Data replicas check errors
     #[cfg(feature = "check-replicas")]
     #[error(transparent)]
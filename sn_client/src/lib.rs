@@ -61,7 +61,8 @@ mod errors;
 
 // Export public API.
 pub use api::{
-    Client, QueriedDataReplicas, RegisterWriteAheadLog, DEFAULT_NETWORK_CONTACTS_FILE_NAME,
+    Client, NetworkHealthReport, QueriedDataReplicas, RegisterWriteAheadLog, SectionHealthReport,
+    SectionMembersReport, DEFAULT_NETWORK_CONTACTS_FILE_NAME,
 };
 pub use connections::LinkError;
 pub use errors::{Error, Result};
@@ -80,7 +81,7 @@ mod testnet_grep;
 #[cfg(test)]
 mod tests {
     use crate::testnet_grep::search_testnet_results_per_node;
-    use sn_interface::{network_knowledge::elder_count, types::log_markers::LogMarker};
+    use sn_interface::{network_knowledge::NetworkParams, types::log_markers::LogMarker};
 
     use eyre::Result;
 
@@ -116,12 +117,10 @@ mod tests {
         let split_count =
             search_testnet_results_per_node(LogMarker::SplitSuccess.to_string())?.len();
 
-        let desired_elder_count = elder_count();
+        // the testnet's genesis node takes its elder count from the env, if set there
+        let desired_elder_count = NetworkParams::default().with_env_overrides().elder_count;
         println!("Found splits: {:?}", split_count);
-        println!(
-            "Desired elder_count() per section: {:?}",
-            desired_elder_count
-        );
+        println!("Desired elder_count per section: {:?}", desired_elder_count);
         println!("Promoted to elder so far: {:?}", promoted_to_elder_nodes);
 
         let total_elders = prefix0_prior_elder_nodes
@@ -117,6 +117,26 @@ impl Session {
                     );
                     break MsgResponse::CmdResponse(addr, Box::new(response));
                 }
+                ClientDataResponse::SectionHealth {
+                    health,
+                    correlation_id,
+                } => {
+                    trace!(
+                        "SectionHealth with id {msg_id:?} regarding correlation_id \
+                        {correlation_id:?} from {peer:?} with response {health:?}"
+                    );
+                    break MsgResponse::SectionHealth(addr, health);
+                }
+                ClientDataResponse::SectionMembers {
+                    proof,
+                    correlation_id,
+                } => {
+                    trace!(
+                        "SectionMembers with id {msg_id:?} regarding correlation_id \
+                        {correlation_id:?} from {peer:?} with response {proof:?}"
+                    );
+                    break MsgResponse::SectionMembers(addr, proof);
+                }
                 ClientDataResponse::AntiEntropy {
                     section_tree_update,
                     bounced_msg,
@@ -11,7 +11,7 @@ use crate::{Error, Result};
 
 use sn_interface::{
     messaging::{
-        data::{DataQuery, DataQueryVariant, QueryResponse},
+        data::{DataQuery, DataQueryVariant, QueryResponse, SectionHealth, SectionMembersProof},
         ClientAuth, Dst, MsgId, MsgKind, WireMsg,
     },
     network_knowledge::supermajority,
@@ -20,7 +20,10 @@ use sn_interface::{
 
 use bytes::Bytes;
 use rand::{rngs::OsRng, seq::SliceRandom};
-use std::collections::BTreeSet;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::SocketAddr,
+};
 use tokio::task::JoinSet;
 use tracing::{debug, error, trace, warn};
 use xor_name::XorName;
@@ -239,6 +242,16 @@ impl Session {
                     let _ = received_errors.insert(src);
                     continue;
                 }
+                Ok(MsgResponse::SectionHealth(src, health)) => {
+                    debug!("Unexpected section health received from {src:?} for {msg_id:?} when awaiting a CmdAck: {health:?}");
+                    let _ = received_errors.insert(src);
+                    continue;
+                }
+                Ok(MsgResponse::SectionMembers(src, proof)) => {
+                    debug!("Unexpected section members received from {src:?} for {msg_id:?} when awaiting a CmdAck: {proof:?}");
+                    let _ = received_errors.insert(src);
+                    continue;
+                }
                 Ok(MsgResponse::Failure(src, error)) => {
                     debug!("Failure occurred with msg {msg_id:?} from {src:?}: {error:?}");
                     let _ = failures.insert(src);
@@ -390,6 +403,16 @@ impl Session {
                     discarded_responses += 1;
                     continue;
                 }
+                Ok(MsgResponse::SectionHealth(src, health)) => {
+                    debug!("Unexpected section health received from {src:?} for {msg_id:?} when awaiting a QueryResponse: {health:?}");
+                    discarded_responses += 1;
+                    continue;
+                }
+                Ok(MsgResponse::SectionMembers(src, proof)) => {
+                    debug!("Unexpected section members received from {src:?} for {msg_id:?} when awaiting a QueryResponse: {proof:?}");
+                    discarded_responses += 1;
+                    continue;
+                }
                 Ok(MsgResponse::Failure(src, error)) => {
                     debug!("Failure occurred with msg {msg_id:?} from {src:?}: {error:?}");
                     discarded_responses += 1;
@@ -498,6 +521,111 @@ impl Session {
         })
     }
 
+    /// Send a `SectionHealthQuery` to each of the given elders, awaiting for all their responses.
+    ///
+    /// Responses are keyed by the address of the node which sent them, which may not be one of
+    /// the given elders if the query was resent to others upon an AE response.
+    /// Elders we failed to reach are returned along with the error we failed with.
+    pub(crate) async fn send_section_health_query(
+        &self,
+        dst: XorName,
+        section_key: bls::PublicKey,
+        elders: Vec<Peer>,
+        auth: ClientAuth,
+        payload: Bytes,
+    ) -> Result<BTreeMap<SocketAddr, Result<SectionHealth>>> {
+        let msg_id = MsgId::new();
+        debug!(
+            "Sending section health query {msg_id:?}, from {}, to {} Elders of section \
+            with key {section_key:?}: {elders:?}",
+            self.endpoint.local_addr(),
+            elders.len(),
+        );
+
+        let dst = Dst {
+            name: dst,
+            section_key,
+        };
+        let kind = MsgKind::Client(auth);
+        let wire_msg = WireMsg::new_msg(msg_id, payload, kind, dst);
+
+        let mut tasks = self.send_msg(elders, wire_msg).await?;
+
+        let mut responses = BTreeMap::new();
+        while let Some(msg_resp) = tasks.join_next().await {
+            let (src, response) = match msg_resp {
+                Ok(MsgResponse::SectionHealth(src, health)) => (src, Ok(*health)),
+                Ok(MsgResponse::Failure(src, error)) => {
+                    debug!("Failure occurred with msg {msg_id:?} from {src:?}: {error:?}");
+                    (src, Err(error))
+                }
+                Ok(other) => {
+                    debug!("Unexpected response received for {msg_id:?} when awaiting a SectionHealth: {other:?}");
+                    continue;
+                }
+                Err(join_err) => {
+                    warn!("Join failure occurred with msg {msg_id:?}: {join_err:?}");
+                    continue;
+                }
+            };
+            let _ = responses.insert(src, response);
+        }
+
+        Ok(responses)
+    }
+
+    /// Send a `SectionMembersQuery` to each of the given elders, awaiting for all their responses.
+    ///
+    /// Responses are keyed by the address of the node which sent them, as per
+    /// `send_section_health_query`. The proofs received are not verified here.
+    pub(crate) async fn send_section_members_query(
+        &self,
+        dst: XorName,
+        section_key: bls::PublicKey,
+        elders: Vec<Peer>,
+        auth: ClientAuth,
+        payload: Bytes,
+    ) -> Result<BTreeMap<SocketAddr, Result<SectionMembersProof>>> {
+        let msg_id = MsgId::new();
+        debug!(
+            "Sending section members query {msg_id:?}, from {}, to {} Elders of section \
+            with key {section_key:?}: {elders:?}",
+            self.endpoint.local_addr(),
+            elders.len(),
+        );
+
+        let dst = Dst {
+            name: dst,
+            section_key,
+        };
+        let kind = MsgKind::Client(auth);
+        let wire_msg = WireMsg::new_msg(msg_id, payload, kind, dst);
+
+        let mut tasks = self.send_msg(elders, wire_msg).await?;
+
+        let mut responses = BTreeMap::new();
+        while let Some(msg_resp) = tasks.join_next().await {
+            let (src, response) = match msg_resp {
+                Ok(MsgResponse::SectionMembers(src, proof)) => (src, Ok(*proof)),
+                Ok(MsgResponse::Failure(src, error)) => {
+                    debug!("Failure occurred with msg {msg_id:?} from {src:?}: {error:?}");
+                    (src, Err(error))
+                }
+                Ok(other) => {
+                    debug!("Unexpected response received for {msg_id:?} when awaiting a SectionMembers: {other:?}");
+                    continue;
+                }
+                Err(join_err) => {
+                    warn!("Join failure occurred with msg {msg_id:?}: {join_err:?}");
+                    continue;
+                }
+            };
+            let _ = responses.insert(src, response);
+        }
+
+        Ok(responses)
+    }
+
     /// Get DataSection elders details. Resort to own section if DataSection is not available.
     /// Takes a random subset (NUM_OF_ELDERS_SUBSET_FOR_QUERIES) of the avialable elders as targets
     pub(crate) async fn get_query_elders(
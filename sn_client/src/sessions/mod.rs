@@ -12,7 +12,7 @@ mod messaging;
 use crate::{connections::PeerLinks, Error, Result};
 
 use sn_interface::{
-    messaging::data::{CmdResponse, QueryResponse},
+    messaging::data::{CmdResponse, QueryResponse, SectionHealth, SectionMembersProof},
     network_knowledge::SectionTree,
 };
 
@@ -26,6 +26,8 @@ use tokio::sync::RwLock;
 pub(super) enum MsgResponse {
     CmdResponse(SocketAddr, Box<CmdResponse>),
     QueryResponse(SocketAddr, Box<QueryResponse>),
+    SectionHealth(SocketAddr, Box<SectionHealth>),
+    SectionMembers(SocketAddr, Box<SectionMembersProof>),
     Failure(SocketAddr, Error),
 }
 
@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use sn_interface::types::Peer;
+use std::net::SocketAddr;
 use thiserror::Error;
 
 /// The type returned by the `sn_routing` message handling methods.
@@ -27,6 +28,8 @@ pub enum Error {
     InvalidMessage,
     #[error("Failed to send a message to {0}")]
     FailedSend(Peer),
+    #[error("Failed to register with the relay at {0}")]
+    RelayRegistration(SocketAddr),
 }
 
 impl From<qp2p::SendError> for Error {
@@ -0,0 +1,201 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! In-memory transport, for tests to run many nodes without any socket.
+//!
+//! Every msg a `Comm` created with [`Comm::new_in_memory`](super::Comm::new_in_memory) sends,
+//! be it a msg, a request awaiting a response, or a response to one, comes out as a [`Packet`]
+//! on the channel the test holds the other end of. The test decides when, and whether, each
+//! packet reaches its destination, and [`Packet::deliver`]s it then.
+
+use super::{Error, MsgFromPeer, Result, SendStream};
+
+use sn_interface::{
+    messaging::{MsgId, MsgKind, WireMsg},
+    types::Peer,
+};
+
+use qp2p::UsrMsgBytes;
+use std::net::SocketAddr;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+/// A msg in flight over the in-memory network.
+#[derive(custom_debug::Debug)]
+pub struct Packet {
+    /// The address of the sender.
+    pub src: SocketAddr,
+    /// The address of the recipient.
+    pub dst: SocketAddr,
+    /// The id of the msg carried.
+    pub msg_id: MsgId,
+    #[debug(skip)]
+    bytes: UsrMsgBytes,
+    kind: PacketKind,
+    #[debug(skip)]
+    network: UnboundedSender<Packet>,
+}
+
+#[derive(Debug)]
+enum PacketKind {
+    Msg,
+    Request(oneshot::Sender<UsrMsgBytes>),
+    Response(oneshot::Sender<UsrMsgBytes>),
+}
+
+impl Packet {
+    /// Whether this is a response to a request, rather than a msg to be handled by the recipient.
+    pub fn is_response(&self) -> bool {
+        matches!(self.kind, PacketKind::Response(_))
+    }
+
+    /// Delivers the packet to its recipient.
+    ///
+    /// Msgs and requests come out as the recipient's listener would have received them, along
+    /// with a stream to respond on for requests. Responses are handed to the requester
+    /// awaiting them instead, so `None` is returned.
+    /// Dropping a packet rather than delivering it makes the request it carries, or responds to,
+    /// fail as if the connection was lost.
+    pub fn deliver(self) -> Result<Option<MsgFromPeer>> {
+        let Self {
+            src,
+            dst,
+            msg_id,
+            bytes,
+            kind,
+            network,
+        } = self;
+
+        let send_stream = match kind {
+            PacketKind::Msg => None,
+            PacketKind::Request(responder) => Some(SendStream::in_memory(ResponseStream {
+                addr: dst,
+                requester: src,
+                responder: Some(responder),
+                network,
+            })),
+            PacketKind::Response(responder) => {
+                if responder.send(bytes).is_err() {
+                    trace!("Requester at {dst:?} is no longer awaiting the response {msg_id:?}");
+                }
+                return Ok(None);
+            }
+        };
+
+        let wire_msg = WireMsg::from(bytes).map_err(|_| Error::InvalidMessage)?;
+        let name = match wire_msg.kind() {
+            MsgKind::Client(auth) => auth.public_key.into(),
+            MsgKind::Node { name, .. }
+            | MsgKind::ClientDataResponse(name)
+            | MsgKind::NodeDataResponse(name) => *name,
+        };
+
+        Ok(Some(MsgFromPeer {
+            sender: Peer::new(name, src),
+            wire_msg,
+            send_stream,
+        }))
+    }
+}
+
+/// The end of the in-memory network a `Comm` sends its packets through.
+#[derive(Clone, Debug)]
+pub(crate) struct Link {
+    addr: SocketAddr,
+    network: UnboundedSender<Packet>,
+}
+
+impl Link {
+    pub(crate) fn new(addr: SocketAddr, network: UnboundedSender<Packet>) -> Self {
+        Self { addr, network }
+    }
+
+    pub(crate) fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub(crate) fn send(&self, peer: Peer, msg_id: MsgId, bytes: UsrMsgBytes) -> Result<()> {
+        self.push(peer.connect_addr(), msg_id, bytes, PacketKind::Msg)
+            .map_err(|_| Error::FailedSend(peer))
+    }
+
+    pub(crate) async fn send_and_await_response(
+        &self,
+        peer: Peer,
+        msg_id: MsgId,
+        bytes: UsrMsgBytes,
+    ) -> Result<UsrMsgBytes> {
+        let (responder, response) = oneshot::channel();
+        self.push(
+            peer.connect_addr(),
+            msg_id,
+            bytes,
+            PacketKind::Request(responder),
+        )
+        .map_err(|_| Error::FailedSend(peer))?;
+
+        response.await.map_err(|_| Error::FailedSend(peer))
+    }
+
+    fn push(
+        &self,
+        dst: SocketAddr,
+        msg_id: MsgId,
+        bytes: UsrMsgBytes,
+        kind: PacketKind,
+    ) -> Result<(), ()> {
+        let packet = Packet {
+            src: self.addr,
+            dst,
+            msg_id,
+            bytes,
+            kind,
+            network: self.network.clone(),
+        };
+        self.network.send(packet).map_err(|_| ())
+    }
+}
+
+/// Stream to send the response to a request on, over the in-memory network.
+#[derive(custom_debug::Debug)]
+pub(crate) struct ResponseStream {
+    addr: SocketAddr,
+    requester: SocketAddr,
+    // taken by the first response, as only that one is read by the requester
+    responder: Option<oneshot::Sender<UsrMsgBytes>>,
+    #[debug(skip)]
+    network: UnboundedSender<Packet>,
+}
+
+impl ResponseStream {
+    pub(crate) fn id(&self) -> String {
+        format!("in-memory stream from {} to {}", self.addr, self.requester)
+    }
+
+    pub(crate) fn send_user_msg(&mut self, bytes: UsrMsgBytes) -> Result<()> {
+        let Some(responder) = self.responder.take() else {
+            trace!("Dropping a further response over the {}", self.id());
+            return Ok(());
+        };
+        let msg_id = WireMsg::from(bytes.clone())
+            .map_err(|_| Error::InvalidMessage)?
+            .msg_id();
+        let packet = Packet {
+            src: self.addr,
+            dst: self.requester,
+            msg_id,
+            bytes,
+            kind: PacketKind::Response(responder),
+            network: self.network.clone(),
+        };
+        // as with a lost connection, the requester will fail to get a response
+        if self.network.send(packet).is_err() {
+            trace!("In-memory network gone, dropping the response {msg_id:?}");
+        }
+        Ok(())
+    }
+}
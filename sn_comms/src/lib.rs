@@ -45,23 +45,49 @@
 extern crate tracing;
 
 mod error;
+#[cfg(feature = "test")]
+pub mod in_memory;
 mod listener;
 mod peer_session;
+mod relay;
+mod send_stream;
+mod stats;
+
+pub use self::{
+    error::{Error, Result},
+    send_stream::SendStream,
+    stats::PeerStats,
+};
 
-pub use self::error::{Error, Result};
-
-use self::{listener::MsgListener, peer_session::PeerSession};
+use self::{
+    listener::MsgListener,
+    peer_session::PeerSession,
+    relay::{RelayRegistration, RelayedPeers},
+};
 
 use sn_interface::{
     messaging::{MsgId, WireMsg},
-    types::Peer,
+    types::{
+        keys::ed25519::{self, Keypair},
+        Peer,
+    },
 };
 
-use qp2p::{Endpoint, SendStream, UsrMsgBytes};
+use qp2p::{Connection, Endpoint, UsrMsgBytes};
 
 use dashmap::DashMap;
-use std::{collections::BTreeSet, net::SocketAddr, sync::Arc};
-use tokio::sync::mpsc::Sender;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+#[cfg(feature = "test")]
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::{
+    sync::{mpsc::Sender, Notify},
+    time::timeout,
+};
+use xor_name::XorName;
 
 /// Standard channel size, to allow for large swings in throughput
 static STANDARD_CHANNEL_SIZE: usize = 100_000;
@@ -82,8 +108,23 @@ pub struct MsgFromPeer {
 #[allow(missing_debug_implementations)]
 #[derive(Clone)]
 pub struct Comm {
-    our_endpoint: Endpoint,
+    transport: Transport,
     sessions: Arc<DashMap<Peer, PeerSession>>,
+    // our targets by name, to find who sent a msg when it didn't come from their address
+    targets_by_name: Arc<DashMap<XorName, Peer>>,
+    msg_listener: MsgListener,
+    relayed_peers: Arc<RelayedPeers>,
+    // our registration with the relay we are reachable through, if any
+    relay_registration: Arc<RwLock<Option<RelayRegistration>>>,
+    renew_relay_registration: Arc<Notify>,
+}
+
+// What the msgs are sent over.
+#[derive(Clone)]
+enum Transport {
+    Quic(Endpoint),
+    #[cfg(feature = "test")]
+    InMemory(in_memory::Link),
 }
 
 impl Comm {
@@ -99,23 +140,183 @@ impl Comm {
             .idle_timeout(70_000)
             .server()?;
 
-        let msg_listener = MsgListener::new(incoming_msg_pipe);
-        msg_listener.listen_for_incoming_msgs(incoming_connections);
+        let sessions = Arc::new(DashMap::new());
+        let targets_by_name = Arc::new(DashMap::new());
+        let relayed_peers = Arc::new(RelayedPeers::default());
+
+        let msg_listener = MsgListener::new(
+            incoming_msg_pipe,
+            sessions.clone(),
+            targets_by_name.clone(),
+            relayed_peers.clone(),
+        );
+        msg_listener
+            .clone()
+            .listen_for_incoming_msgs(incoming_connections);
 
         Ok(Self {
-            our_endpoint,
-            sessions: Arc::new(DashMap::new()),
+            transport: Transport::Quic(our_endpoint),
+            sessions,
+            targets_by_name,
+            msg_listener,
+            relayed_peers,
+            relay_registration: Arc::new(RwLock::new(None)),
+            renew_relay_registration: Arc::new(Notify::new()),
         })
     }
 
+    /// Creates a new instance of Comm at `addr` on the in-memory network, i.e. sending its msgs
+    /// as packets on the `network` channel, for the test holding its other end to deliver.
+    /// Msgs are received by the test rather than listened for, see [`in_memory::Packet`].
+    #[cfg(feature = "test")]
+    pub fn new_in_memory(addr: SocketAddr, network: UnboundedSender<in_memory::Packet>) -> Self {
+        // never used, as there's no incoming connection to listen to
+        let (incoming_msg_pipe, _) = tokio::sync::mpsc::channel(1);
+        let sessions = Arc::new(DashMap::new());
+        let targets_by_name = Arc::new(DashMap::new());
+        let relayed_peers = Arc::new(RelayedPeers::default());
+        let msg_listener = MsgListener::new(
+            incoming_msg_pipe,
+            sessions.clone(),
+            targets_by_name.clone(),
+            relayed_peers.clone(),
+        );
+
+        Self {
+            transport: Transport::InMemory(in_memory::Link::new(addr, network)),
+            sessions,
+            targets_by_name,
+            msg_listener,
+            relayed_peers,
+            relay_registration: Arc::new(RwLock::new(None)),
+            renew_relay_registration: Arc::new(Notify::new()),
+        }
+    }
+
     /// The socket address of our endpoint.
     pub fn socket_addr(&self) -> SocketAddr {
-        self.our_endpoint.local_addr()
+        match &self.transport {
+            Transport::Quic(endpoint) => endpoint.local_addr(),
+            #[cfg(feature = "test")]
+            Transport::InMemory(link) => link.addr(),
+        }
     }
 
     /// Closes the endpoint.
     pub fn close_endpoint(&self) {
-        self.our_endpoint.close()
+        match &self.transport {
+            Transport::Quic(endpoint) => endpoint.close(),
+            #[cfg(feature = "test")]
+            Transport::InMemory(_) => {}
+        }
+    }
+
+    /// Relays msgs to the nodes which register with us, for them to be reachable
+    /// even though they cannot accept incoming connections.
+    ///
+    /// Registrations under our own name are refused, as msgs to us would then be relayed away.
+    pub fn enable_relaying(&self, our_name: XorName) {
+        info!("Relaying msgs for nodes which register with us");
+        self.relayed_peers.enable(our_name)
+    }
+
+    /// The nodes we are currently relaying msgs to.
+    pub fn relayed_peers(&self) -> BTreeSet<XorName> {
+        self.relayed_peers.names()
+    }
+
+    /// Registers with the publicly reachable node at `relay_addr`, for it to relay msgs to us,
+    /// as we cannot accept incoming connections.
+    ///
+    /// The registration is renewed periodically, reconnecting to the relay if needed,
+    /// for as long as this `Comm` is around. The registrations are signed with our keypair,
+    /// as the relay only relays to us the msgs destined to the name derived from its public key.
+    pub async fn register_with_relay(
+        &self,
+        relay_addr: SocketAddr,
+        keypair: Arc<Keypair>,
+    ) -> Result<()> {
+        let endpoint = match &self.transport {
+            Transport::Quic(endpoint) => endpoint.clone(),
+            #[cfg(feature = "test")]
+            Transport::InMemory(_) => return Err(Error::RelayRegistration(relay_addr)),
+        };
+        let mut conn =
+            register_with_relay(&endpoint, self.msg_listener.clone(), relay_addr, &keypair).await?;
+
+        let our_name = ed25519::name(&keypair.public);
+        if let Ok(mut registration) = self.relay_registration.write() {
+            *registration = Some(RelayRegistration {
+                relay_addr,
+                keypair,
+            });
+        }
+        info!("Registered with relay at {relay_addr} as {our_name:?}");
+
+        let msg_listener = self.msg_listener.clone();
+        let registration = self.relay_registration.clone();
+        let renew = self.renew_relay_registration.clone();
+        let comm_alive = Arc::downgrade(&self.sessions);
+        let _handle = tokio::spawn(async move {
+            loop {
+                // renew either periodically or as soon as our name changes
+                let _ = timeout(relay::REGISTRATION_RENEWAL_INTERVAL, renew.notified()).await;
+                if comm_alive.upgrade().is_none() {
+                    break;
+                }
+
+                let keypair = match registration
+                    .read()
+                    .ok()
+                    .and_then(|r| r.as_ref().map(|r| r.keypair.clone()))
+                {
+                    Some(keypair) => keypair,
+                    None => break,
+                };
+
+                let renewal = conn.send_with(relay::registration_msg(&keypair), 0).await;
+                if let Err(error) = renewal {
+                    warn!("Failed to renew registration with relay at {relay_addr}, reconnecting: {error:?}");
+                    match register_with_relay(&endpoint, msg_listener.clone(), relay_addr, &keypair)
+                        .await
+                    {
+                        Ok(new_conn) => conn = new_conn,
+                        Err(error) => error!("Could not reconnect to relay: {error:?}"),
+                    }
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// To be called once our keypair changes (e.g. once relocated), to renew our registration
+    /// with our relay, if any, and to refuse registrations under our new name if relaying.
+    pub fn update_our_keypair(&self, keypair: Arc<Keypair>) {
+        self.relayed_peers
+            .update_our_name(ed25519::name(&keypair.public));
+        if let Ok(mut registration) = self.relay_registration.write() {
+            if let Some(registration) = registration.as_mut() {
+                registration.keypair = keypair;
+                self.renew_relay_registration.notify_one();
+            }
+        }
+    }
+
+    /// The address of the relay we are reachable through, if we registered with one.
+    pub fn relay(&self) -> Option<SocketAddr> {
+        self.relay_registration
+            .read()
+            .ok()
+            .and_then(|registration| registration.as_ref().map(|r| r.relay_addr))
+    }
+
+    /// The connection and bandwidth statistics of each of our current targets.
+    pub fn peer_stats(&self) -> BTreeMap<Peer, PeerStats> {
+        self.sessions
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().stats()))
+            .collect()
     }
 
     /// Sets the available targets to be only those in the passed in set.
@@ -126,13 +327,22 @@ impl Comm {
 
         // Drops sessions that not among the targets.
         self.sessions.retain(|p, _| targets.contains(p));
+        self.targets_by_name
+            .retain(|_, peer| targets.contains(peer));
 
         // Adds new sessions for each new target.
         targets.iter().for_each(|peer| {
-            if self.sessions.get(peer).is_none() {
-                let session = PeerSession::new(*peer, self.our_endpoint.clone());
-                let _ = self.sessions.insert(*peer, session);
+            match &self.transport {
+                Transport::Quic(endpoint) => {
+                    if self.sessions.get(peer).is_none() {
+                        let session = PeerSession::new(*peer, endpoint.clone());
+                        let _ = self.sessions.insert(*peer, session);
+                    }
+                }
+                #[cfg(feature = "test")]
+                Transport::InMemory(_) => {}
             }
+            let _ = self.targets_by_name.insert(peer.name(), *peer);
         });
     }
 
@@ -148,6 +358,12 @@ impl Comm {
         let bytes_len = h.len() + d.len() + p.len();
         trace!("Sending message bytes ({bytes_len} bytes) w/ {msg_id:?} to {peer:?}");
 
+        #[cfg(feature = "test")]
+        if let Transport::InMemory(link) = &self.transport {
+            self.check_is_target(&peer)?;
+            return link.send(peer, msg_id, bytes);
+        }
+
         let peer_session = self.get_session(&peer).await?;
         debug!("Peer session retrieved: {peer:?}");
 
@@ -177,6 +393,13 @@ impl Comm {
         // TODO: tweak messaging to just allow passthrough
         debug!("Trying to get {peer:?} session in order to send: {msg_id:?}");
 
+        #[cfg(feature = "test")]
+        if let Transport::InMemory(link) = &self.transport {
+            self.check_is_target(&peer)?;
+            let response_bytes = link.send_and_await_response(peer, msg_id, bytes).await?;
+            return WireMsg::from(response_bytes).map_err(|_| Error::InvalidMessage);
+        }
+
         let mut session = self.get_session(&peer).await?;
         debug!("Session of {peer:?} retrieved for {msg_id:?}");
         let adult_response_bytes = session
@@ -190,6 +413,15 @@ impl Comm {
         WireMsg::from(adult_response_bytes).map_err(|_| Error::InvalidMessage)
     }
 
+    // There are no sessions over the in-memory network, but msgs are still only sent to targets.
+    #[cfg(feature = "test")]
+    fn check_is_target(&self, peer: &Peer) -> Result<()> {
+        match self.targets_by_name.get(&peer.name()) {
+            Some(target) if *target == *peer => Ok(()),
+            _ => Err(Error::CreatingConnectionToUnknownNode(*peer)),
+        }
+    }
+
     /// Get a PeerSession
     #[instrument(skip(self))]
     async fn get_session(&self, peer: &Peer) -> Result<PeerSession> {
@@ -204,6 +436,32 @@ impl Comm {
     }
 }
 
+/// Connects to the relay, listening for the msgs it relays to us over the connection,
+/// and registers with it.
+async fn register_with_relay(
+    endpoint: &Endpoint,
+    msg_listener: MsgListener,
+    relay_addr: SocketAddr,
+    keypair: &Keypair,
+) -> Result<Arc<Connection>> {
+    let (conn, incoming_msgs) = endpoint.connect_to(&relay_addr).await.map_err(|error| {
+        error!("Failed to connect to relay at {relay_addr}: {error:?}");
+        Error::RelayRegistration(relay_addr)
+    })?;
+    let conn = Arc::new(conn);
+
+    let _handle = msg_listener.listen_for_relayed_msgs(conn.clone(), incoming_msgs);
+
+    conn.send_with(relay::registration_msg(keypair), 0)
+        .await
+        .map_err(|error| {
+            error!("Failed to register with relay at {relay_addr}: {error:?}");
+            Error::RelayRegistration(relay_addr)
+        })?;
+
+    Ok(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,6 +483,7 @@ mod tests {
         sync::mpsc::{self, Receiver},
         time,
     };
+    use xor_name::Prefix;
 
     const TIMEOUT: Duration = Duration::from_secs(1);
 
@@ -258,6 +517,118 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn peer_stats_are_tracked() -> Result<()> {
+        let (tx, _rx) = mpsc::channel(1);
+        let comm = Comm::new(local_addr(), tx).await?;
+
+        let (peer, mut rx) = new_peer().await?;
+        let invalid_peer = get_invalid_peer().await?;
+        comm.set_comm_targets([peer, invalid_peer].into());
+
+        let msg = new_test_msg(dst(peer))?;
+        let bytes = msg.serialize()?;
+        let (h, d, p) = &bytes;
+        let bytes_len = (h.len() + d.len() + p.len()) as u64;
+        comm.send_out_bytes(peer, msg.msg_id(), bytes).await?;
+        assert!(rx.recv().await.is_some());
+
+        let invalid_msg = new_test_msg(dst(invalid_peer))?;
+        let result = comm
+            .send_out_bytes(invalid_peer, invalid_msg.msg_id(), invalid_msg.serialize()?)
+            .await;
+        assert_matches!(result, Err(Error::FailedSend(_)));
+
+        let stats = comm.peer_stats();
+
+        let peer_stats = stats.get(&peer).expect("stats for the peer");
+        assert_eq!(peer_stats.msgs_sent, 1);
+        assert_eq!(peer_stats.bytes_sent, bytes_len);
+        assert_eq!(peer_stats.failed_sends, 0);
+        assert_eq!(peer_stats.open_connections, 1);
+        assert_eq!(peer_stats.reconnects, 0);
+
+        let invalid_peer_stats = stats
+            .get(&invalid_peer)
+            .expect("stats for the invalid peer");
+        assert_eq!(invalid_peer_stats.msgs_sent, 0);
+        assert_eq!(invalid_peer_stats.failed_sends, 1);
+        assert_eq!(invalid_peer_stats.open_connections, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_to_relayed_peer() -> Result<()> {
+        let (tx, _rx) = mpsc::channel(1);
+        let relay = Comm::new(local_addr(), tx).await?;
+        relay.enable_relaying(xor_name::rand::random());
+
+        let (tx, mut relayed_rx) = mpsc::channel(1);
+        let relayed = Comm::new(local_addr(), tx).await?;
+        let relayed_keypair = gen_keypair();
+        let relayed_name = ed25519::name(&relayed_keypair.public);
+        relayed
+            .register_with_relay(relay.socket_addr(), relayed_keypair)
+            .await?;
+        assert_eq!(relayed.relay(), Some(relay.socket_addr()));
+        wait_until_relaying(&relay, relayed_name).await?;
+
+        // the relayed node's own address would be unreachable, so make sure it is
+        let unreachable_addr = get_invalid_peer().await?.addr();
+        let relayed_peer = Peer::new_relayed(relayed_name, unreachable_addr, relay.socket_addr());
+
+        let (tx, _rx) = mpsc::channel(1);
+        let sender = Comm::new(local_addr(), tx).await?;
+        sender.set_comm_targets([relayed_peer].into());
+
+        let msg = new_test_msg(dst(relayed_peer))?;
+        sender
+            .send_out_bytes(relayed_peer, msg.msg_id(), msg.serialize()?)
+            .await?;
+
+        let received = time::timeout(TIMEOUT, relayed_rx.recv()).await?;
+        assert_matches!(received, Some(MsgFromPeer { wire_msg, .. }) => assert_eq!(wire_msg, msg));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn relay_registration_is_ignored_unless_relaying() -> Result<()> {
+        let (tx, _rx) = mpsc::channel(1);
+        let not_relay = Comm::new(local_addr(), tx).await?;
+
+        let (tx, _rx) = mpsc::channel(1);
+        let comm = Comm::new(local_addr(), tx).await?;
+        let keypair = gen_keypair();
+        let name = ed25519::name(&keypair.public);
+        comm.register_with_relay(not_relay.socket_addr(), keypair)
+            .await?;
+
+        assert_matches!(wait_until_relaying(&not_relay, name).await, Err(_));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn relay_registration_under_the_relay_name_is_refused() -> Result<()> {
+        let keypair = gen_keypair();
+        let name = ed25519::name(&keypair.public);
+
+        let (tx, _rx) = mpsc::channel(1);
+        let relay = Comm::new(local_addr(), tx).await?;
+        relay.enable_relaying(name);
+
+        let (tx, _rx) = mpsc::channel(1);
+        let comm = Comm::new(local_addr(), tx).await?;
+        comm.register_with_relay(relay.socket_addr(), keypair)
+            .await?;
+
+        assert_matches!(wait_until_relaying(&relay, name).await, Err(_));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn failed_send() -> Result<()> {
         let (tx, _rx) = mpsc::channel(1);
@@ -367,6 +738,23 @@ mod tests {
         Ok(())
     }
 
+    fn gen_keypair() -> Arc<ed25519::Keypair> {
+        Arc::new(ed25519::gen_keypair(
+            &Prefix::default().range_inclusive(),
+            5,
+        ))
+    }
+
+    async fn wait_until_relaying(relay: &Comm, name: XorName) -> Result<()> {
+        time::timeout(TIMEOUT, async {
+            while !relay.relayed_peers().contains(&name) {
+                time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await?;
+        Ok(())
+    }
+
     fn dst(peer: Peer) -> Dst {
         Dst {
             name: peer.name(),
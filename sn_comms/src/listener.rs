@@ -6,25 +6,47 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::MsgFromPeer;
+use super::{
+    peer_session::PeerSession,
+    relay::{self, RelayedPeers},
+    stats::msg_bytes_len,
+    MsgFromPeer, SendStream,
+};
 
 use sn_interface::{
     messaging::{MsgKind, WireMsg},
     types::{log_markers::LogMarker, Peer},
 };
 
-use qp2p::{ConnectionIncoming, IncomingConnections};
+use dashmap::DashMap;
+use qp2p::{Connection, ConnectionIncoming, IncomingConnections};
+use std::{net::SocketAddr, sync::Arc};
 use tokio::{sync::mpsc, task};
 use tracing::Instrument;
+use xor_name::XorName;
 
 #[derive(Clone)]
 pub(crate) struct MsgListener {
     receive_msg: mpsc::Sender<MsgFromPeer>,
+    // used to record the received msgs in the stats of the sender's session, if any
+    sessions: Arc<DashMap<Peer, PeerSession>>,
+    targets_by_name: Arc<DashMap<XorName, Peer>>,
+    relayed_peers: Arc<RelayedPeers>,
 }
 
 impl MsgListener {
-    pub(crate) fn new(receive_msg: mpsc::Sender<MsgFromPeer>) -> Self {
-        Self { receive_msg }
+    pub(crate) fn new(
+        receive_msg: mpsc::Sender<MsgFromPeer>,
+        sessions: Arc<DashMap<Peer, PeerSession>>,
+        targets_by_name: Arc<DashMap<XorName, Peer>>,
+        relayed_peers: Arc<RelayedPeers>,
+    ) -> Self {
+        Self {
+            receive_msg,
+            sessions,
+            targets_by_name,
+            relayed_peers,
+        }
     }
 
     #[tracing::instrument(skip_all)]
@@ -39,14 +61,31 @@ impl MsgListener {
                 );
 
                 let clone = self.clone();
-                let _handle =
-                    task::spawn(clone.listen(connection, incoming_msgs).in_current_span());
+                let _handle = task::spawn(
+                    clone
+                        .listen(Arc::new(connection), incoming_msgs, false)
+                        .in_current_span(),
+                );
             }
         });
     }
 
+    /// Listens for the msgs our relay forwards to us over the connection we made to it.
+    pub(crate) fn listen_for_relayed_msgs(
+        self,
+        conn: Arc<Connection>,
+        incoming_msgs: ConnectionIncoming,
+    ) -> task::JoinHandle<()> {
+        task::spawn(self.listen(conn, incoming_msgs, true).in_current_span())
+    }
+
     #[tracing::instrument(skip_all)]
-    async fn listen(self, conn: qp2p::Connection, mut incoming_msgs: ConnectionIncoming) {
+    async fn listen(
+        self,
+        conn: Arc<Connection>,
+        mut incoming_msgs: ConnectionIncoming,
+        from_our_relay: bool,
+    ) {
         let conn_id = conn.id();
         let remote_address = conn.remote_address();
 
@@ -62,6 +101,22 @@ impl MsgListener {
                         "New msg arrived over conn_id={conn_id} from {remote_address:?}{stream_info}"
                     );
 
+                    let msg_len = msg_bytes_len(&msg_bytes.0);
+
+                    if let Some(registration) = relay::parse_registration(&msg_bytes.0) {
+                        match registration {
+                            Ok(name) => {
+                                let _ = self.relayed_peers.register(name, conn.clone());
+                            }
+                            Err(error) => warn!(
+                                "Ignoring invalid relay registration from {remote_address:?}: {error}"
+                            ),
+                        }
+                        continue;
+                    }
+
+                    // cheap clone, in case the msg is to be relayed
+                    let bytes = msg_bytes.0.clone();
                     let wire_msg = match WireMsg::from(msg_bytes.0) {
                         Ok(wire_msg) => wire_msg,
                         Err(error) => {
@@ -78,7 +133,22 @@ impl MsgListener {
                         | MsgKind::NodeDataResponse(name) => *name,
                     };
 
-                    let peer = Peer::new(src_name, remote_address);
+                    if let Some(relayed_conn) = self.relayed_peers.connection(&wire_msg.dst().name)
+                    {
+                        let msg_id = wire_msg.msg_id();
+                        trace!(
+                            "Relaying {msg_id:?} from {src_name:?} at {remote_address:?} to {:?}",
+                            wire_msg.dst().name
+                        );
+                        let _handle =
+                            tokio::spawn(relay::forward(relayed_conn, bytes, send_stream));
+                        continue;
+                    }
+
+                    let peer = self.sender_peer(src_name, remote_address, from_our_relay);
+                    if let Some(session) = self.sessions.get(&peer) {
+                        session.record_received(msg_len);
+                    }
 
                     let msg_id = wire_msg.msg_id();
                     debug!(
@@ -89,7 +159,7 @@ impl MsgListener {
                     let msg = MsgFromPeer {
                         sender: peer,
                         wire_msg,
-                        send_stream,
+                        send_stream: send_stream.map(SendStream::from),
                     };
                     // move this channel sending off thread so we don't hold up incoming msgs at all.
                     let _handle = tokio::spawn(async move {
@@ -105,6 +175,22 @@ impl MsgListener {
             }
         }
 
+        self.relayed_peers.connection_closed(&conn_id);
+
         trace!(%conn_id, %remote_address, "{}", LogMarker::ConnectionClosed);
     }
+
+    /// The peer as we know it, which for relayed peers (or any peer if the msg was relayed
+    /// to us) is not at the address the msg came from, so it's looked up by name instead.
+    fn sender_peer(&self, name: XorName, remote_address: SocketAddr, from_our_relay: bool) -> Peer {
+        let peer = Peer::new(name, remote_address);
+        if self.sessions.contains_key(&peer) {
+            return peer;
+        }
+
+        match self.targets_by_name.get(&name) {
+            Some(known) if from_our_relay || known.is_relayed() => *known,
+            _ => peer,
+        }
+    }
 }
@@ -6,7 +6,10 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{Result, STANDARD_CHANNEL_SIZE};
+use super::{
+    stats::{msg_bytes_len, PeerStats, PeerStatsCounters},
+    Result, STANDARD_CHANNEL_SIZE,
+};
 
 use qp2p::{Connection, Endpoint, UsrMsgBytes};
 
@@ -20,7 +23,7 @@ use std::sync::Arc;
 use thiserror::Error;
 use tokio::{
     sync::mpsc,
-    time::{sleep, Duration},
+    time::{sleep, Duration, Instant},
 };
 
 type ConnId = String;
@@ -45,6 +48,7 @@ pub(crate) struct PeerSession {
     endpoint: Endpoint,
     connections: PeerConnections,
     queue: mpsc::Sender<SendJob>,
+    stats: Arc<PeerStatsCounters>,
 }
 
 type PeerConnections = Arc<DashMap<ConnId, Arc<Connection>>>;
@@ -53,20 +57,38 @@ impl PeerSession {
     pub(crate) fn new(peer: Peer, endpoint: Endpoint) -> Self {
         let (sender, receiver) = mpsc::channel(STANDARD_CHANNEL_SIZE);
         let connections = PeerConnections::default();
+        let stats = Arc::new(PeerStatsCounters::default());
 
         // Spawn the peer session worker, which will stop automatically when
         // the PeerSession is dropped as the channel will be dropped too.
-        PeerSessionWorker::new(peer, connections.clone(), endpoint.clone(), sender.clone())
-            .run(receiver);
+        PeerSessionWorker::new(
+            peer,
+            connections.clone(),
+            endpoint.clone(),
+            sender.clone(),
+            stats.clone(),
+        )
+        .run(receiver);
 
         Self {
             peer,
             endpoint,
             connections,
             queue: sender,
+            stats,
         }
     }
 
+    /// The connection and bandwidth statistics of this session so far.
+    pub(crate) fn stats(&self) -> PeerStats {
+        self.stats.snapshot(self.connections.len())
+    }
+
+    /// Records a msg received from the peer, outside of a bi-stream response.
+    pub(crate) fn record_received(&self, bytes_len: u64) {
+        self.stats.record_received(bytes_len)
+    }
+
     /// Sends out a UsrMsg on a bidi connection and awaits response bytes.
     /// As such this may be long running if response is returned slowly.
     /// When sending a msg to a peer, if it fails with an existing
@@ -78,6 +100,18 @@ impl PeerSession {
         &mut self,
         bytes: UsrMsgBytes,
         msg_id: MsgId,
+    ) -> Result<UsrMsgBytes, PeerSessionError> {
+        let result = self.send_bi_and_await_response(bytes, msg_id).await;
+        if result.is_err() {
+            self.stats.record_failed_send();
+        }
+        result
+    }
+
+    async fn send_bi_and_await_response(
+        &mut self,
+        bytes: UsrMsgBytes,
+        msg_id: MsgId,
     ) -> Result<UsrMsgBytes, PeerSessionError> {
         let peer = self.peer;
         trace!(
@@ -100,9 +134,14 @@ impl PeerSession {
                 (conn, false)
             } else {
                 trace!("Sending {msg_id:?} via bi-di-stream over new connection to {peer:?}, attempt #{attempt}.");
-                let conn =
-                    create_connection(peer, &self.endpoint, self.connections.clone(), msg_id)
-                        .await?;
+                let conn = create_connection(
+                    peer,
+                    &self.endpoint,
+                    self.connections.clone(),
+                    &self.stats,
+                    msg_id,
+                )
+                .await?;
                 (conn, true)
             };
 
@@ -133,6 +172,7 @@ impl PeerSession {
             let stream_id = send_stream.id();
             trace!("bidi {stream_id} opened for {msg_id:?} to {peer:?}");
             send_stream.set_priority(10);
+            let sent_at = Instant::now();
             if let Err(err) = send_stream.send_user_msg(bytes.clone()).await {
                 error!("Error sending bytes for {msg_id:?} over {stream_id}: {err:?}");
                 // remove that broken conn
@@ -148,6 +188,7 @@ impl PeerSession {
             }
 
             trace!("{msg_id:?} sent on {stream_id} to {peer:?}");
+            self.stats.record_sent(msg_bytes_len(&bytes));
 
             // unblock + move finish off thread as it's not strictly related to the sending of the msg.
             let stream_id_clone = stream_id.clone();
@@ -159,7 +200,11 @@ impl PeerSession {
             });
 
             match recv_stream.read().await {
-                Ok(response) => break Ok(response),
+                Ok(response) => {
+                    self.stats.record_rtt(sent_at.elapsed());
+                    self.stats.record_received(msg_bytes_len(&response));
+                    break Ok(response);
+                }
                 Err(err) => {
                     error!("Error receiving response to {msg_id:?} from {peer:?} over {stream_id}: {err:?}");
                     let _conn = self.connections.remove(&conn_id);
@@ -191,6 +236,7 @@ impl PeerSession {
 
         self.queue.send(job).await.map_err(|err| {
             error!("Failed to enqueue send job for {msg_id:?}: {err:?}");
+            self.stats.record_failed_send();
             PeerSessionError::PeerSessionJobsQueue
         })?;
 
@@ -200,11 +246,13 @@ impl PeerSession {
             Some(Ok(())) => Ok(()),
             Some(Err(err)) => {
                 error!("Sending message {msg_id:?} to {peer:?}, possibly failed: {err:?}");
+                self.stats.record_failed_send();
                 Err(err)
             }
             None => {
                 // the result sharing channel is closed for some unknown reason,
                 error!("Sending message {msg_id:?} to {peer:?} possibly failed, as monitoring of the send job was aborted");
+                self.stats.record_failed_send();
                 Err(PeerSessionError::UnknownSendJobOutcome)
             }
         }
@@ -215,11 +263,12 @@ async fn create_connection(
     peer: Peer,
     endpoint: &Endpoint,
     connections: PeerConnections,
+    stats: &PeerStatsCounters,
     msg_id: MsgId,
 ) -> Result<Arc<Connection>, PeerSessionError> {
     debug!("{msg_id:?} create conn attempt to {peer:?}");
     let (conn, _) = endpoint
-        .connect_to(&peer.addr())
+        .connect_to(&peer.connect_addr())
         .await
         .map_err(PeerSessionError::Connection)?;
 
@@ -230,6 +279,8 @@ async fn create_connection(
         conn.id()
     );
 
+    stats.record_connection_opened();
+
     let conn_id = conn.id();
     debug!("Inserting connection into peer session: {conn_id}");
 
@@ -245,6 +296,7 @@ struct PeerSessionWorker {
     connections: PeerConnections,
     endpoint: Endpoint,
     queue: mpsc::Sender<SendJob>,
+    stats: Arc<PeerStatsCounters>,
 }
 
 impl PeerSessionWorker {
@@ -253,12 +305,14 @@ impl PeerSessionWorker {
         connections: PeerConnections,
         endpoint: Endpoint,
         queue: mpsc::Sender<SendJob>,
+        stats: Arc<PeerStatsCounters>,
     ) -> Self {
         Self {
             peer,
             connections,
             endpoint,
             queue,
+            stats,
         }
     }
 
@@ -328,6 +382,7 @@ impl PeerSessionWorker {
         let conns_count = connections.len();
         let peer = self.peer;
         let queue = self.queue.clone();
+        let stats = self.stats.clone();
         let _handle = tokio::spawn(async move {
             let conn_id = conn.id();
             debug!("Connection exists for sendjob: {msg_id:?}, and has conn_id: {conn_id:?}");
@@ -336,6 +391,7 @@ impl PeerSessionWorker {
 
             match send_resp {
                 Ok(()) => {
+                    stats.record_sent(msg_bytes_len(&job.bytes));
                     if let Err(err) = job.reporter.try_send(Ok(())) {
                         error!("Couldn't report sucessful sent to {peer:?}: {err:?}");
                     }
@@ -382,7 +438,14 @@ impl PeerSessionWorker {
             Ok(conn)
         } else {
             trace!("{msg_id:?} No connection found to {peer:?}, creating a new one.");
-            create_connection(peer, &self.endpoint, self.connections.clone(), msg_id).await
+            create_connection(
+                peer,
+                &self.endpoint,
+                self.connections.clone(),
+                &self.stats,
+                msg_id,
+            )
+            .await
         }
     }
 
@@ -0,0 +1,305 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Relaying of msgs for nodes which cannot accept incoming connections (e.g. behind a home router).
+//!
+//! Such a node connects out to a publicly reachable node acting as its relay, and registers
+//! with it over that connection. Other peers then connect to the relay when sending to it,
+//! and the relay forwards any msg destined to the registered node over its connection.
+//! Responses to msgs sent over bi-streams are forwarded back the same way.
+//!
+//! A registration is signed by the node's key, which its name is derived from, so a node can
+//! only have the msgs destined to itself relayed to it.
+
+use qp2p::{Connection, SendStream, UsrMsgBytes};
+use sn_interface::types::keys::ed25519::{self, Keypair, PublicKey, Signature, Verifier};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use dashmap::DashMap;
+use std::{
+    collections::BTreeSet,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use xor_name::XorName;
+
+/// Identifies a registration msg, which is never a valid `WireMsg` header.
+const REGISTRATION_TAG: &[u8] = b"sn_comms::relay::registration";
+
+const PUBLIC_KEY_LEN: usize = 32;
+const TIMESTAMP_LEN: usize = 8;
+const SIGNATURE_LEN: usize = 64;
+
+/// How often a relayed node renews its registration, which also keeps its connection
+/// to the relay from idling out.
+pub(crate) const REGISTRATION_RENEWAL_INTERVAL: Duration = Duration::from_secs(20);
+
+/// How far a registration's timestamp may be from our clock for it to be accepted,
+/// which bounds the time a registration could be replayed for.
+const REGISTRATION_VALIDITY: Duration = Duration::from_secs(2 * 20);
+
+/// The msg a node sends to its relay to register as reachable through it: its public key,
+/// the time of registration, and its signature over both.
+pub(crate) fn registration_msg(keypair: &Keypair) -> UsrMsgBytes {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut payload = BytesMut::with_capacity(PUBLIC_KEY_LEN + TIMESTAMP_LEN + SIGNATURE_LEN);
+    payload.put_slice(keypair.public.as_bytes());
+    payload.put_u64(timestamp);
+    let signature = ed25519::sign(&bytes_to_sign(&payload), keypair);
+    payload.put_slice(&signature.to_bytes());
+
+    (
+        Bytes::from_static(REGISTRATION_TAG),
+        Bytes::new(),
+        payload.freeze(),
+    )
+}
+
+/// Returns `None` if the msg is not a registration, otherwise the name of the node
+/// registering, derived from the key which signed the registration, if valid.
+pub(crate) fn parse_registration(bytes: &UsrMsgBytes) -> Option<Result<XorName, String>> {
+    let (header, _, payload) = bytes;
+    if header.as_ref() != REGISTRATION_TAG {
+        return None;
+    }
+
+    Some(verify_registration(payload, SystemTime::now()))
+}
+
+fn verify_registration(payload: &[u8], now: SystemTime) -> Result<XorName, String> {
+    if payload.len() != PUBLIC_KEY_LEN + TIMESTAMP_LEN + SIGNATURE_LEN {
+        return Err(format!("invalid length of {} bytes", payload.len()));
+    }
+    let (signed, signature) = payload.split_at(PUBLIC_KEY_LEN + TIMESTAMP_LEN);
+    let (public_key, timestamp) = signed.split_at(PUBLIC_KEY_LEN);
+
+    let public_key = PublicKey::from_bytes(public_key)
+        .map_err(|error| format!("invalid public key: {error}"))?;
+    let signature =
+        Signature::from_bytes(signature).map_err(|error| format!("invalid signature: {error}"))?;
+    public_key
+        .verify(&bytes_to_sign(signed), &signature)
+        .map_err(|_| "signature doesn't match the public key".to_string())?;
+
+    let mut secs = [0; TIMESTAMP_LEN];
+    secs.copy_from_slice(timestamp);
+    let registered_at = UNIX_EPOCH + Duration::from_secs(u64::from_be_bytes(secs));
+    let age = now
+        .duration_since(registered_at)
+        .or_else(|error| Ok::<_, String>(error.duration()))?;
+    if age > REGISTRATION_VALIDITY {
+        return Err(format!(
+            "registration made at {registered_at:?} has expired"
+        ));
+    }
+
+    Ok(ed25519::name(&public_key))
+}
+
+fn bytes_to_sign(signed: &[u8]) -> Vec<u8> {
+    [REGISTRATION_TAG, signed].concat()
+}
+
+/// Our registration with the relay we are reachable through.
+pub(crate) struct RelayRegistration {
+    pub(crate) relay_addr: SocketAddr,
+    pub(crate) keypair: Arc<Keypair>,
+}
+
+/// The nodes we relay msgs to, along with the connection each registered over.
+#[derive(Default)]
+pub(crate) struct RelayedPeers {
+    // our own name, which no one else can register under, while we relay
+    relaying_as: RwLock<Option<XorName>>,
+    connections: DashMap<XorName, Arc<Connection>>,
+}
+
+impl RelayedPeers {
+    pub(crate) fn enable(&self, our_name: XorName) {
+        if let Ok(mut relaying_as) = self.relaying_as.write() {
+            *relaying_as = Some(our_name);
+        }
+    }
+
+    /// Updates our own name, e.g. once relocated, if we are relaying.
+    pub(crate) fn update_our_name(&self, our_name: XorName) {
+        if let Ok(mut relaying_as) = self.relaying_as.write() {
+            if relaying_as.is_some() {
+                *relaying_as = Some(our_name);
+            }
+        }
+    }
+
+    /// Registers the node, unless we are not relaying or it's registering under our own name,
+    /// returning whether it was registered.
+    pub(crate) fn register(&self, name: XorName, conn: Arc<Connection>) -> bool {
+        match self.relaying_as.read().ok().and_then(|our_name| *our_name) {
+            None => {
+                debug!("Ignoring relay registration from {name:?}, as we are not relaying");
+                return false;
+            }
+            Some(our_name) if our_name == name => {
+                warn!(
+                    "Refusing relay registration over connection {} under our own name {name:?}",
+                    conn.id()
+                );
+                return false;
+            }
+            Some(_) => {}
+        }
+
+        // a node re-registering under a new name (e.g. once relocated) replaces its old one
+        self.connections.retain(|registered, registered_conn| {
+            *registered == name || registered_conn.id() != conn.id()
+        });
+
+        if let Some(previous) = self.connections.insert(name, conn.clone()) {
+            if previous.id() != conn.id() {
+                debug!(
+                    "Relayed node {name:?} registered over a new connection {}",
+                    conn.id()
+                );
+            }
+        } else {
+            info!("Relaying msgs to {name:?} over connection {}", conn.id());
+        }
+
+        true
+    }
+
+    /// Stops relaying to any node registered over the given connection, as it's been closed.
+    pub(crate) fn connection_closed(&self, conn_id: &str) {
+        self.connections.retain(|name, conn| {
+            let closed = conn.id() == conn_id;
+            if closed {
+                info!("No longer relaying msgs to {name:?}, its connection {conn_id} was closed");
+            }
+            !closed
+        });
+    }
+
+    pub(crate) fn connection(&self, name: &XorName) -> Option<Arc<Connection>> {
+        self.connections
+            .get(name)
+            .map(|entry| entry.value().clone())
+    }
+
+    pub(crate) fn names(&self) -> BTreeSet<XorName> {
+        self.connections.iter().map(|entry| *entry.key()).collect()
+    }
+}
+
+/// Forwards the msg to the relayed node, and if it came over a bi-stream,
+/// its response back to the sender.
+pub(crate) async fn forward(
+    conn: Arc<Connection>,
+    bytes: UsrMsgBytes,
+    response_stream: Option<SendStream>,
+) {
+    let conn_id = conn.id();
+
+    let mut response_stream = match response_stream {
+        Some(stream) => stream,
+        None => {
+            if let Err(error) = conn.send_with(bytes, 0).await {
+                warn!("Failed to relay msg over {conn_id}: {error:?}");
+            }
+            return;
+        }
+    };
+
+    let (mut send_stream, mut recv_stream) = match conn.open_bi().await {
+        Ok(bi_stream) => bi_stream,
+        Err(error) => {
+            warn!("Failed to open bi-stream to relay msg over {conn_id}: {error:?}");
+            return;
+        }
+    };
+
+    if let Err(error) = send_stream.send_user_msg(bytes).await {
+        warn!("Failed to relay msg over {conn_id}: {error:?}");
+        return;
+    }
+    let _ = send_stream.finish().await;
+
+    let response = match recv_stream.read().await {
+        Ok(response) => response,
+        Err(error) => {
+            warn!("Failed to receive the response to a relayed msg over {conn_id}: {error:?}");
+            return;
+        }
+    };
+
+    if let Err(error) = response_stream.send_user_msg(response).await {
+        warn!("Failed to relay the response received over {conn_id}: {error:?}");
+        return;
+    }
+    let _ = response_stream.finish().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use xor_name::Prefix;
+
+    fn keypair() -> Keypair {
+        ed25519::gen_keypair(&Prefix::default().range_inclusive(), 5)
+    }
+
+    #[test]
+    fn registration_msg_roundtrip() {
+        let keypair = keypair();
+        assert_eq!(
+            parse_registration(&registration_msg(&keypair)),
+            Some(Ok(ed25519::name(&keypair.public)))
+        );
+    }
+
+    #[test]
+    fn other_msgs_are_not_registrations() {
+        let (_, dst, payload) = registration_msg(&keypair());
+        let msg = (Bytes::from_static(b"header"), dst, payload);
+        assert_eq!(parse_registration(&msg), None);
+    }
+
+    #[test]
+    fn forged_registrations_are_rejected() {
+        let keypair = keypair();
+        let (header, dst, payload) = registration_msg(&keypair);
+
+        // registering under someone else's key
+        let mut forged = payload.to_vec();
+        forged[..PUBLIC_KEY_LEN].copy_from_slice(self::keypair().public.as_bytes());
+        let msg = (header.clone(), dst.clone(), Bytes::from(forged));
+        assert!(matches!(parse_registration(&msg), Some(Err(_))));
+
+        // or with a tampered timestamp
+        let mut tampered = payload.to_vec();
+        tampered[PUBLIC_KEY_LEN] ^= 1;
+        let msg = (header.clone(), dst.clone(), Bytes::from(tampered));
+        assert!(matches!(parse_registration(&msg), Some(Err(_))));
+
+        let truncated = (header, dst, Bytes::from_static(b"not a registration"));
+        assert!(matches!(parse_registration(&truncated), Some(Err(_))));
+    }
+
+    #[test]
+    fn stale_registrations_are_rejected() {
+        let (_, _, payload) = registration_msg(&keypair());
+        let later = SystemTime::now() + REGISTRATION_VALIDITY + Duration::from_secs(2);
+        assert!(verify_registration(&payload, later).is_err());
+        let earlier = SystemTime::now() - REGISTRATION_VALIDITY - Duration::from_secs(2);
+        assert!(verify_registration(&payload, earlier).is_err());
+    }
+}
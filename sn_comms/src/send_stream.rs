@@ -0,0 +1,74 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Result;
+
+#[cfg(feature = "test")]
+use super::in_memory::ResponseStream;
+
+use qp2p::UsrMsgBytes;
+
+/// The stream a msg came on, to send the response(s) to it on.
+#[derive(Debug)]
+pub struct SendStream(Inner);
+
+#[derive(Debug)]
+enum Inner {
+    Quic(qp2p::SendStream),
+    #[cfg(feature = "test")]
+    InMemory(ResponseStream),
+}
+
+impl SendStream {
+    #[cfg(feature = "test")]
+    pub(crate) fn in_memory(stream: ResponseStream) -> Self {
+        Self(Inner::InMemory(stream))
+    }
+
+    /// The identifier of the stream, for logging.
+    pub fn id(&self) -> String {
+        match &self.0 {
+            Inner::Quic(stream) => stream.id().to_string(),
+            #[cfg(feature = "test")]
+            Inner::InMemory(stream) => stream.id(),
+        }
+    }
+
+    /// Sets the priority of the stream relative to the others over the same connection.
+    pub fn set_priority(&self, priority: i32) {
+        match &self.0 {
+            Inner::Quic(stream) => stream.set_priority(priority),
+            #[cfg(feature = "test")]
+            Inner::InMemory(_) => {}
+        }
+    }
+
+    /// Sends the msg bytes over the stream.
+    pub async fn send_user_msg(&mut self, bytes: UsrMsgBytes) -> Result<()> {
+        match &mut self.0 {
+            Inner::Quic(stream) => Ok(stream.send_user_msg(bytes).await?),
+            #[cfg(feature = "test")]
+            Inner::InMemory(stream) => stream.send_user_msg(bytes),
+        }
+    }
+
+    /// Gracefully terminates the stream, once everything sent over it has been received.
+    pub async fn finish(&mut self) -> Result<()> {
+        match &mut self.0 {
+            Inner::Quic(stream) => Ok(stream.finish().await?),
+            #[cfg(feature = "test")]
+            Inner::InMemory(_) => Ok(()),
+        }
+    }
+}
+
+impl From<qp2p::SendStream> for SendStream {
+    fn from(stream: qp2p::SendStream) -> Self {
+        Self(Inner::Quic(stream))
+    }
+}
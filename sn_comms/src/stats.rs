@@ -0,0 +1,181 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use qp2p::UsrMsgBytes;
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Weight given to the previous smoothed rtt when a new rtt sample comes in,
+/// out of `RTT_SMOOTHING_DIVISOR` (as per TCP's smoothed rtt estimation, RFC 6298).
+const RTT_SMOOTHING_WEIGHT: u64 = 7;
+const RTT_SMOOTHING_DIVISOR: u64 = 8;
+
+/// Connection and bandwidth statistics for a peer, since its session was created.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PeerStats {
+    /// Bytes sent to the peer.
+    pub bytes_sent: u64,
+    /// Msgs sent to the peer.
+    pub msgs_sent: u64,
+    /// Bytes received from the peer, including responses over bi-streams.
+    pub bytes_received: u64,
+    /// Msgs received from the peer, including responses over bi-streams.
+    pub msgs_received: u64,
+    /// Msgs we failed to send to the peer, after all retries.
+    pub failed_sends: u64,
+    /// New connections made to the peer after a previous one was dropped.
+    pub reconnects: u64,
+    /// Connections to the peer currently open in its session.
+    pub open_connections: usize,
+    /// Round-trip time of the last request sent over a bi-stream, until its response came in.
+    pub last_rtt: Option<Duration>,
+    /// Smoothed round-trip time of the requests sent over bi-streams.
+    pub smoothed_rtt: Option<Duration>,
+}
+
+/// Counters kept by a `PeerSession`, updated concurrently by the session and its worker.
+#[derive(Debug, Default)]
+pub(crate) struct PeerStatsCounters {
+    bytes_sent: AtomicU64,
+    msgs_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    msgs_received: AtomicU64,
+    failed_sends: AtomicU64,
+    connections_opened: AtomicU64,
+    // rtts are kept in microseconds, 0 meaning there has been no sample yet
+    last_rtt: AtomicU64,
+    smoothed_rtt: AtomicU64,
+}
+
+impl PeerStatsCounters {
+    pub(crate) fn record_sent(&self, bytes_len: u64) {
+        let _ = self.bytes_sent.fetch_add(bytes_len, Ordering::Relaxed);
+        let _ = self.msgs_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_received(&self, bytes_len: u64) {
+        let _ = self.bytes_received.fetch_add(bytes_len, Ordering::Relaxed);
+        let _ = self.msgs_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_failed_send(&self) {
+        let _ = self.failed_sends.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_connection_opened(&self) {
+        let _ = self.connections_opened.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_rtt(&self, rtt: Duration) {
+        // never store 0, as that means no sample
+        let sample = u64::try_from(rtt.as_micros()).unwrap_or(u64::MAX).max(1);
+        self.last_rtt.store(sample, Ordering::Relaxed);
+
+        let _ = self
+            .smoothed_rtt
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |smoothed| {
+                if smoothed == 0 {
+                    return Some(sample);
+                }
+                let weighted = smoothed
+                    .saturating_mul(RTT_SMOOTHING_WEIGHT)
+                    .saturating_add(sample);
+                Some((weighted / RTT_SMOOTHING_DIVISOR).max(1))
+            });
+    }
+
+    pub(crate) fn snapshot(&self, open_connections: usize) -> PeerStats {
+        let rtt = |micros: u64| (micros > 0).then_some(Duration::from_micros(micros));
+
+        PeerStats {
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            msgs_sent: self.msgs_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            msgs_received: self.msgs_received.load(Ordering::Relaxed),
+            failed_sends: self.failed_sends.load(Ordering::Relaxed),
+            reconnects: self
+                .connections_opened
+                .load(Ordering::Relaxed)
+                .saturating_sub(1),
+            open_connections,
+            last_rtt: rtt(self.last_rtt.load(Ordering::Relaxed)),
+            smoothed_rtt: rtt(self.smoothed_rtt.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+pub(crate) fn msg_bytes_len(bytes: &UsrMsgBytes) -> u64 {
+    let (header, dst, payload) = bytes;
+    (header.len() + dst.len() + payload.len()) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+
+    #[test]
+    fn msg_bytes_len_adds_up_all_parts() {
+        let bytes = (
+            Bytes::from(vec![0; 3]),
+            Bytes::from(vec![0; 2]),
+            Bytes::from(vec![0; 10]),
+        );
+        assert_eq!(msg_bytes_len(&bytes), 15);
+    }
+
+    #[test]
+    fn counts_msgs_and_bytes() {
+        let counters = PeerStatsCounters::default();
+        counters.record_sent(10);
+        counters.record_sent(5);
+        counters.record_received(7);
+        counters.record_failed_send();
+
+        let stats = counters.snapshot(1);
+        assert_eq!(stats.bytes_sent, 15);
+        assert_eq!(stats.msgs_sent, 2);
+        assert_eq!(stats.bytes_received, 7);
+        assert_eq!(stats.msgs_received, 1);
+        assert_eq!(stats.failed_sends, 1);
+        assert_eq!(stats.open_connections, 1);
+    }
+
+    #[test]
+    fn first_connection_is_not_a_reconnect() {
+        let counters = PeerStatsCounters::default();
+        assert_eq!(counters.snapshot(0).reconnects, 0);
+
+        counters.record_connection_opened();
+        assert_eq!(counters.snapshot(1).reconnects, 0);
+
+        counters.record_connection_opened();
+        counters.record_connection_opened();
+        assert_eq!(counters.snapshot(1).reconnects, 2);
+    }
+
+    #[test]
+    fn rtt_is_smoothed() {
+        let counters = PeerStatsCounters::default();
+        assert_eq!(counters.snapshot(0).smoothed_rtt, None);
+
+        counters.record_rtt(Duration::from_millis(80));
+        let stats = counters.snapshot(0);
+        assert_eq!(stats.last_rtt, Some(Duration::from_millis(80)));
+        assert_eq!(stats.smoothed_rtt, Some(Duration::from_millis(80)));
+
+        counters.record_rtt(Duration::from_millis(160));
+        let stats = counters.snapshot(0);
+        assert_eq!(stats.last_rtt, Some(Duration::from_millis(160)));
+        assert_eq!(stats.smoothed_rtt, Some(Duration::from_millis(90)));
+    }
+}
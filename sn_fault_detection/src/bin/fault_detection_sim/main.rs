@@ -0,0 +1,207 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Replays recorded issue streams, or simulates synthetic node populations, through
+//! `FaultDetection`, reporting which nodes get flagged as faulty over time.
+
+mod replay;
+mod report;
+mod simulation;
+
+use report::Report;
+
+use sn_fault_detection::{FaultDetection, FaultDetectionConfig, IssueType};
+
+use clap::{AppSettings::ColoredHelp, Parser, Subcommand};
+use eyre::{eyre, Result};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use xor_name::XorName;
+
+#[derive(Parser, Debug)]
+/// Tune and evaluate the Safe Network node fault detection, without a live network
+#[clap(global_settings(&[ColoredHelp]), version)]
+struct CmdArgs {
+    #[clap(subcommand)]
+    cmd: SubCmds,
+    /// File with the fault detection config to use, in JSON format (as passed to sn_node)
+    #[clap(long, global = true)]
+    config: Option<PathBuf>,
+    /// How often, in seconds, the faulty nodes are checked for (as sn_node does periodically)
+    #[clap(long, global = true, default_value = "5")]
+    check_interval: u64,
+    /// Output the report in JSON format
+    #[clap(long, global = true)]
+    json: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum SubCmds {
+    /// Simulate a synthetic population of nodes, each with a failure profile
+    Simulate {
+        /// Number of honest nodes
+        #[clap(long, default_value = "30")]
+        honest: usize,
+        /// Number of flaky nodes, which drop connections in bursts
+        #[clap(long, default_value = "2")]
+        flaky: usize,
+        /// Number of slow nodes, which often fail to fulfil requests in time
+        #[clap(long, default_value = "2")]
+        slow: usize,
+        /// Number of byzantine nodes, which misbehave on purpose
+        #[clap(long, default_value = "1")]
+        byzantine: usize,
+        /// How many of the nodes are elders
+        #[clap(long, default_value = "7")]
+        elders: usize,
+        /// Duration of the simulation, in seconds
+        #[clap(long, default_value = "3600")]
+        duration: u64,
+        /// Seed for the simulation, a random one is used if not provided
+        #[clap(long)]
+        seed: Option<u64>,
+    },
+    /// Replay the issues tracked by a node, as found in its logs
+    Replay {
+        /// Path to a node's log file, or to the directory containing that node's log files
+        logs_path: PathBuf,
+        /// Nodes known to be faulty (as they appear in the logs, e.g. "a1b2c3(10100001).."),
+        /// used to calculate the false positive and false negative rates
+        #[clap(long)]
+        faulty: Vec<String>,
+    },
+}
+
+/// Something that happened to the tracker, at a time relative to the start of the run
+#[derive(Debug)]
+pub(crate) struct Event {
+    pub(crate) at: Duration,
+    pub(crate) node: XorName,
+    pub(crate) action: Action,
+}
+
+#[derive(Debug)]
+pub(crate) enum Action {
+    Track(IssueType),
+    Untrack(IssueType),
+}
+
+/// The nodes, and issues recorded against them, to be run through `FaultDetection`
+#[derive(Debug, Default)]
+pub(crate) struct Scenario {
+    /// Name to be used in the report for each node
+    pub(crate) labels: BTreeMap<XorName, String>,
+    pub(crate) elders: BTreeSet<XorName>,
+    /// The nodes known to be faulty, if known at all
+    pub(crate) faulty: Option<BTreeSet<XorName>>,
+    pub(crate) events: Vec<Event>,
+    pub(crate) duration: Duration,
+}
+
+fn main() -> Result<()> {
+    let args = CmdArgs::parse();
+
+    let config = match &args.config {
+        Some(path) => read_config(path)?,
+        None => FaultDetectionConfig::default(),
+    };
+    if args.check_interval == 0 {
+        return Err(eyre!("The check interval must be at least one second"));
+    }
+
+    let scenario = match args.cmd {
+        SubCmds::Simulate {
+            honest,
+            flaky,
+            slow,
+            byzantine,
+            elders,
+            duration,
+            seed,
+        } => {
+            let seed = seed.unwrap_or_else(rand::random);
+            if !args.json {
+                println!("Simulating with seed {seed}");
+            }
+            let population = simulation::Population {
+                honest,
+                flaky,
+                slow,
+                byzantine,
+                elders,
+            };
+            simulation::generate(&population, Duration::from_secs(duration), seed)?
+        }
+        SubCmds::Replay { logs_path, faulty } => replay::from_logs(&logs_path, &faulty)?,
+    };
+
+    let report = run(scenario, config, Duration::from_secs(args.check_interval));
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        report.print();
+    }
+
+    Ok(())
+}
+
+fn read_config(path: &Path) -> Result<FaultDetectionConfig> {
+    let content = fs::read(path)?;
+    serde_json::from_slice(&content)
+        .map_err(|err| eyre!("Could not parse config file {}: {err}", path.display()))
+}
+
+/// Feeds the scenario's events to `FaultDetection` over a virtual clock,
+/// checking for faulty nodes every `check_interval`.
+fn run(mut scenario: Scenario, config: FaultDetectionConfig, check_interval: Duration) -> Report {
+    let non_elders = scenario
+        .labels
+        .keys()
+        .filter(|node| !scenario.elders.contains(node))
+        .copied()
+        .collect();
+    let mut fault_detection =
+        FaultDetection::with_config(non_elders, scenario.elders.clone(), config);
+
+    scenario.events.sort_by_key(|event| event.at);
+    let mut events = scenario.events.into_iter().peekable();
+
+    let start = Instant::now();
+    let mut report = Report::new(scenario.labels, scenario.faulty);
+    let mut check_at = check_interval;
+
+    while check_at <= scenario.duration {
+        while let Some(event) = events.next_if(|event| event.at <= check_at) {
+            match event.action {
+                Action::Track(issue) => {
+                    fault_detection.track_issue_at(event.node, issue, start + event.at)
+                }
+                Action::Untrack(IssueType::Dkg) => fault_detection.dkg_ack_fulfilled(&event.node),
+                Action::Untrack(IssueType::ElderVoting) => {
+                    fault_detection.elder_vote_received(&event.node)
+                }
+                Action::Untrack(IssueType::AeProbeMsg) => {
+                    fault_detection.ae_update_msg_received(&event.node)
+                }
+                Action::Untrack(_) => {}
+            }
+        }
+
+        let faulty_nodes = fault_detection.get_faulty_nodes_at(start + check_at);
+        report.record_check(check_at, faulty_nodes);
+
+        check_at += check_interval;
+    }
+
+    report
+}
@@ -0,0 +1,470 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{Action, Event, Scenario};
+
+use sn_fault_detection::{IssueType, StorageFault};
+
+use chrono::DateTime;
+use eyre::{eyre, Result};
+use flate2::read::GzDecoder;
+use std::{
+    cmp::Reverse,
+    collections::BTreeSet,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use xor_name::XorName;
+
+const LOG_EXTENSION: &str = ".log";
+const GZ_EXTENSION: &str = "gz";
+
+const TRACK_ISSUE_PREFIX: &str = "Adding a new issue to ";
+const TRACK_ISSUE_SEPARATOR: &str = " the fault tracker: ";
+const DKG_FULFILLED: &str = "Pending dkg session removed for node: ";
+const ELDER_VOTE_FULFILLED: &str = "Pending elder_vote issue removed for node: ";
+const AE_PROBE_FULFILLED: &str = "Pending probe msg removed for node: ";
+const STARTING_WITH_ELDERS: &str = "Starting faults with elders: ";
+const STARTING_NON_ELDERS: &str = ", non_elder_nodes: ";
+const SETTING_ELDERS_PREFIX: &str = "Setting elder nodes:";
+const SETTING_ELDERS_SUFFIX: &str = " in FaultDetection tracker";
+
+/// Builds a scenario out of the fault detection log lines of a single node.
+///
+/// Nodes are identified by how they appear in the logs, `faulty` being a list of those.
+/// Rotated log files, compressed or not, are read oldest first.
+pub(crate) fn from_logs(path: &Path, faulty: &[String]) -> Result<Scenario> {
+    let mut files = vec![];
+    collect_log_files(path, &mut files)?;
+    if files.is_empty() {
+        return Err(eyre!("No log files found at {}", path.display()));
+    }
+    files.sort_by_cached_key(|file| rotation_order(file));
+
+    let mut scenario = Scenario {
+        faulty: if faulty.is_empty() {
+            None
+        } else {
+            Some(faulty.iter().map(|id| node_name(id)).collect())
+        },
+        ..Default::default()
+    };
+    for id in faulty {
+        let _ = scenario.labels.insert(node_name(id), id.clone());
+    }
+
+    for file in files {
+        let content = read_log_file(&file)?;
+        for (index, line) in content.lines().enumerate() {
+            parse_line(line, &mut scenario)
+                .map_err(|err| eyre!("{err}, at line {} of {}", index + 1, file.display()))?;
+        }
+    }
+
+    // events are timed relative to the earliest one found
+    let start = match scenario.events.iter().map(|event| event.at).min() {
+        Some(start) => start,
+        None => {
+            return Err(eyre!(
+                "No fault detection issues found in the logs at {}",
+                path.display()
+            ))
+        }
+    };
+    for event in &mut scenario.events {
+        event.at -= start;
+    }
+    scenario.duration = scenario
+        .events
+        .iter()
+        .map(|event| event.at)
+        .max()
+        .unwrap_or_default();
+
+    Ok(scenario)
+}
+
+fn collect_log_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_log_files(&path, files)?;
+        } else if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| name.contains(LOG_EXTENSION))
+        {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a log file, decompressing it if it was compressed on rotation.
+fn read_log_file(path: &Path) -> Result<String> {
+    let mut content = String::new();
+    if path.extension().map_or(false, |ext| ext == GZ_EXTENSION) {
+        let _ = GzDecoder::new(fs::File::open(path)?)
+            .read_to_string(&mut content)
+            .map_err(|err| eyre!("Could not decompress {}: {err}", path.display()))?;
+    } else {
+        let _ = fs::File::open(path)?.read_to_string(&mut content)?;
+    }
+    Ok(content)
+}
+
+/// Position of a log file within its rotation, the file currently written to being the newest.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Rotation {
+    /// Suffixed with a number, the greater the number the older the file.
+    Count(Reverse<usize>),
+    /// Suffixed with the time it was rotated at, e.g. "20230105T103201", as sn_node does,
+    /// followed by a number if several files were rotated within the same second.
+    Timestamp(String, usize),
+    /// The file being written to.
+    Current,
+}
+
+/// Sorts log files by the directory and base file name they're written to, then oldest first.
+fn rotation_order(path: &Path) -> (Option<PathBuf>, String, Rotation) {
+    let dir = path.parent().map(Path::to_path_buf);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = name
+        .strip_suffix(&format!(".{GZ_EXTENSION}"))
+        .unwrap_or(&name);
+    let (base, suffix) = match name.split_once(LOG_EXTENSION) {
+        Some((base, suffix)) => (base.to_string(), suffix.trim_start_matches('.')),
+        None => (name.to_string(), ""),
+    };
+
+    let rotation = if suffix.is_empty() {
+        Rotation::Current
+    } else if let Ok(count) = suffix.parse() {
+        Rotation::Count(Reverse(count))
+    } else {
+        match suffix.split_once('.') {
+            Some((timestamp, number)) => {
+                Rotation::Timestamp(timestamp.to_string(), number.parse().unwrap_or_default())
+            }
+            None => Rotation::Timestamp(suffix.to_string(), 0),
+        }
+    };
+
+    (dir, base, rotation)
+}
+
+fn parse_line(line: &str, scenario: &mut Scenario) -> Result<()> {
+    if let Some(rest) = find_after(line, STARTING_WITH_ELDERS) {
+        if let Some((elders, rest)) = rest.split_once(STARTING_NON_ELDERS) {
+            let elders = parse_set(elders, scenario);
+            scenario.elders = elders;
+            if let Some(non_elders) = rest.split_once("}") {
+                let _ = parse_set(&format!("{}}}", non_elders.0), scenario);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(rest) = find_after(line, SETTING_ELDERS_PREFIX) {
+        if let Some(elders) = rest.strip_suffix(SETTING_ELDERS_SUFFIX) {
+            scenario.elders = parse_set(elders, scenario);
+        }
+        return Ok(());
+    }
+
+    let action_and_node = if let Some(rest) = find_after(line, TRACK_ISSUE_PREFIX) {
+        rest.split_once(TRACK_ISSUE_SEPARATOR)
+            .and_then(|(id, issue)| Some((Action::Track(parse_issue(issue)?), id)))
+    } else if let Some(id) = find_after(line, DKG_FULFILLED) {
+        Some((Action::Untrack(IssueType::Dkg), id))
+    } else if let Some(id) = find_after(line, ELDER_VOTE_FULFILLED) {
+        Some((Action::Untrack(IssueType::ElderVoting), id))
+    } else if let Some(id) = find_after(line, AE_PROBE_FULFILLED) {
+        Some((Action::Untrack(IssueType::AeProbeMsg), id))
+    } else {
+        None
+    };
+
+    let (action, id) = match action_and_node {
+        Some(action_and_node) => action_and_node,
+        None => return Ok(()),
+    };
+    let timestamp = parse_timestamp(line)?;
+
+    let node = add_node(id.trim(), scenario);
+    scenario.events.push(Event {
+        at: timestamp,
+        node,
+        action,
+    });
+
+    Ok(())
+}
+
+fn find_after<'a>(line: &'a str, pattern: &str) -> Option<&'a str> {
+    line.find(pattern)
+        .map(|index| line[index + pattern.len()..].trim_end())
+}
+
+/// Parses a set of nodes as logged with `{:?}`, e.g. "{a1b2c3(10100001).., d4e5f6(11010100)..}"
+fn parse_set(set: &str, scenario: &mut Scenario) -> BTreeSet<XorName> {
+    set.trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(", ")
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| add_node(id, scenario))
+        .collect()
+}
+
+fn parse_issue(issue: &str) -> Option<IssueType> {
+    let issue = match issue.trim() {
+        "Communication" => IssueType::Communication,
+        "Dkg" => IssueType::Dkg,
+        "ElderVoting" => IssueType::ElderVoting,
+        "AeProbeMsg" => IssueType::AeProbeMsg,
+        "NetworkKnowledge" => IssueType::NetworkKnowledge,
+        "RequestOperation" => IssueType::RequestOperation,
+        "Storage(MissingData)" => IssueType::Storage(StorageFault::MissingData),
+        "Storage(CorruptData)" => IssueType::Storage(StorageFault::CorruptData),
+        "Storage(StaleReplica)" => IssueType::Storage(StorageFault::StaleReplica),
+        _ => return None,
+    };
+    Some(issue)
+}
+
+fn add_node(id: &str, scenario: &mut Scenario) -> XorName {
+    let name = node_name(id);
+    let _ = scenario
+        .labels
+        .entry(name)
+        .or_insert_with(|| id.to_string());
+    name
+}
+
+/// The logs only contain a shortened form of each node's name,
+/// so we derive a stable `XorName` for the tracker from it.
+fn node_name(id: &str) -> XorName {
+    XorName::from_content(id.as_bytes())
+}
+
+/// Parses the RFC3339 timestamp a log line starts with, e.g. "[2023-01-05T10:32:01.123456Z",
+/// into the time since the unix epoch.
+fn parse_timestamp(line: &str) -> Result<Duration> {
+    let timestamp = line
+        .trim_start()
+        .strip_prefix('[')
+        .and_then(|line| line.split_whitespace().next())
+        .ok_or_else(|| eyre!("Missing timestamp"))?;
+    let micros = DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|err| eyre!("Invalid timestamp {timestamp:?}: {err}"))?
+        .timestamp_micros();
+    let micros = u64::try_from(micros)
+        .map_err(|_| eyre!("Timestamp {timestamp:?} is before the unix epoch"))?;
+    Ok(Duration::from_micros(micros))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run;
+
+    use flate2::{write::GzEncoder, Compression};
+    use sn_fault_detection::FaultDetectionConfig;
+    use std::io::Write;
+
+    const OLDEST_LOG: &str = "\
+[2023-01-05T10:30:00.000000Z DEBUG sn_fault_detection] Starting faults with elders: {e1(000).., e2(001)..}, non_elder_nodes: {n1(010).., n2(011)..}, config: FaultDetectionConfig { .. }
+[2023-01-05T10:30:01.000000Z DEBUG sn_fault_detection] Adding a new issue to n1(010).. the fault tracker: Communication
+[2023-01-05T10:30:02.500000Z TRACE sn_fault_detection] Pending dkg session removed for node: e1(000)..
+";
+    const ROTATED_LOG: &str = "\
+[2023-01-05T11:31:01.000000+01:00 DEBUG sn_fault_detection] Adding a new issue to e2(001).. the fault tracker: Dkg
+[2023-01-05T10:31:02.000000Z DEBUG sn_node::node] Some unrelated log line
+";
+    const CURRENT_LOG: &str = "\
+[2023-01-05T10:32:00.000000Z INFO sn_fault_detection] Setting elder nodes:{e1(000).., n1(010)..} in FaultDetection tracker
+[2023-01-05T10:32:01.000000Z DEBUG sn_fault_detection] Adding a new issue to n2(011).. the fault tracker: AeProbeMsg
+[2023-01-05T10:32:02.000000Z TRACE sn_fault_detection] Pending probe msg removed for node: n2(011)..
+";
+
+    struct LogDir(PathBuf);
+
+    impl LogDir {
+        fn new() -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("fault_detection_sim_{}", rand::random::<u64>()));
+            fs::create_dir_all(&dir).expect("Could not create log dir");
+            Self(dir)
+        }
+
+        fn write(&self, name: &str, content: &str) {
+            fs::write(self.0.join(name), content).expect("Could not write log file");
+        }
+
+        fn write_compressed(&self, name: &str, content: &str) {
+            let file = fs::File::create(self.0.join(name)).expect("Could not create log file");
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            encoder
+                .write_all(content.as_bytes())
+                .expect("Could not compress log file");
+            let _ = encoder.finish().expect("Could not compress log file");
+        }
+    }
+
+    impl Drop for LogDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn events_of(scenario: &Scenario) -> Vec<(u128, &str, String)> {
+        scenario
+            .events
+            .iter()
+            .map(|event| {
+                (
+                    event.at.as_millis(),
+                    scenario.labels[&event.node].as_str(),
+                    format!("{:?}", event.action),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replayed_logs_are_read_in_rotation_order_and_decompressed() -> Result<()> {
+        let logs = LogDir::new();
+        logs.write("sn_node.log", CURRENT_LOG);
+        logs.write("sn_node.log.20230105T103101", ROTATED_LOG);
+        logs.write_compressed("sn_node.log.20230105T103001.gz", OLDEST_LOG);
+
+        let scenario = from_logs(&logs.0, &[])?;
+
+        assert_eq!(
+            events_of(&scenario),
+            vec![
+                (0, "n1(010)..", "Track(Communication)".to_string()),
+                (1_500, "e1(000)..", "Untrack(Dkg)".to_string()),
+                (60_000, "e2(001)..", "Track(Dkg)".to_string()),
+                (120_000, "n2(011)..", "Track(AeProbeMsg)".to_string()),
+                (121_000, "n2(011)..", "Untrack(AeProbeMsg)".to_string()),
+            ]
+        );
+        // the elders set last, in the current log file, are the ones kept
+        assert_eq!(
+            scenario.elders,
+            BTreeSet::from([node_name("e1(000).."), node_name("n1(010)..")])
+        );
+        assert_eq!(scenario.labels.len(), 4);
+        assert_eq!(scenario.duration, Duration::from_secs(121));
+
+        Ok(())
+    }
+
+    #[test]
+    fn log_files_are_ordered_oldest_first() {
+        let mut files = vec![
+            PathBuf::from("logs/sn_node.log"),
+            PathBuf::from("logs/sn_node.log.20230105T103101.1"),
+            PathBuf::from("logs/sn_node.log.20230105T103101"),
+            PathBuf::from("logs/sn_node.log.20230105T102901.gz"),
+            PathBuf::from("other/sn_node.log"),
+            PathBuf::from("other/sn_node.log.1"),
+            PathBuf::from("other/sn_node.log.2.gz"),
+        ];
+        files.sort_by_cached_key(|file| rotation_order(file));
+
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("logs/sn_node.log.20230105T102901.gz"),
+                PathBuf::from("logs/sn_node.log.20230105T103101"),
+                PathBuf::from("logs/sn_node.log.20230105T103101.1"),
+                PathBuf::from("logs/sn_node.log"),
+                PathBuf::from("other/sn_node.log.2.gz"),
+                PathBuf::from("other/sn_node.log.1"),
+                PathBuf::from("other/sn_node.log"),
+            ]
+        );
+    }
+
+    #[test]
+    fn timestamps_with_an_offset_are_parsed() -> Result<()> {
+        let utc = parse_timestamp("[2023-01-05T10:30:00.123456Z DEBUG sn_node] ..")?;
+        let offset = parse_timestamp("[2023-01-05T12:30:00.123456+02:00 DEBUG sn_node] ..")?;
+        assert_eq!(utc, offset);
+        assert_eq!(utc, Duration::from_micros(1_672_914_600_123_456));
+
+        assert!(parse_timestamp("[2023-01-05 10:30:00 DEBUG sn_node] ..").is_err());
+        assert!(parse_timestamp("2023-01-05T10:30:00Z DEBUG sn_node ..").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn issue_lines_without_a_valid_timestamp_fail_the_replay() {
+        let logs = LogDir::new();
+        logs.write(
+            "sn_node.log",
+            "[not a timestamp DEBUG sn_fault_detection] Adding a new issue to n1(010).. the fault tracker: Dkg\n",
+        );
+
+        let err = from_logs(&logs.0, &[]).expect_err("Replay should fail");
+        assert!(err.to_string().contains("at line 1 of"), "{err}");
+    }
+
+    #[test]
+    fn replayed_faulty_node_is_flagged() -> Result<()> {
+        let nodes = (0..20).map(|i| format!("n{i:02}({i:05b}).."));
+        let non_elders = nodes.clone().collect::<Vec<_>>().join(", ");
+        let mut log = format!(
+            "[2023-01-05T10:00:00Z DEBUG sn_fault_detection] Starting faults with elders: {{}}, non_elder_nodes: {{{non_elders}}}, config: ..\n"
+        );
+        for (i, node) in nodes.enumerate() {
+            // the last node keeps failing, the others only fail once
+            let issues = if i == 19 { 30 } else { 1 };
+            for issue in 0..issues {
+                log.push_str(&format!(
+                    "[2023-01-05T10:00:{issue:02}Z DEBUG sn_fault_detection] Adding a new issue to {node} the fault tracker: Communication\n"
+                ));
+            }
+        }
+        let logs = LogDir::new();
+        logs.write("sn_node.log", &log);
+
+        let faulty = vec!["n19(10011)..".to_string()];
+        let scenario = from_logs(&logs.0, &faulty)?;
+        let report = serde_json::to_value(run(
+            scenario,
+            FaultDetectionConfig::default(),
+            Duration::from_secs(5),
+        ))?;
+
+        let first_flagged = report["first_flagged"]
+            .as_object()
+            .ok_or_else(|| eyre!("Missing first flagged nodes"))?;
+        assert_eq!(
+            first_flagged.keys().collect::<Vec<_>>(),
+            vec!["n19(10011).."]
+        );
+        assert_eq!(report["false_positive_rate"], 0.0);
+        Ok(())
+    }
+}
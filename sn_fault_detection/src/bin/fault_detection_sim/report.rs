@@ -0,0 +1,158 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+use xor_name::XorName;
+
+/// The nodes flagged as faulty at a given check
+#[derive(Debug, Serialize)]
+pub(crate) struct Check {
+    /// Seconds since the start of the run
+    at_secs: u64,
+    /// Flagged nodes, from faultiest to least faulty
+    flagged: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Report {
+    /// Only the checks where the set of flagged nodes changed from the previous check
+    checks: Vec<Check>,
+    /// Seconds since the start of the run when each node was first flagged
+    first_flagged: BTreeMap<String, u64>,
+    total_checks: usize,
+    /// Ratio of checks in which non-faulty nodes were flagged, to all checks of non-faulty nodes
+    false_positive_rate: Option<f64>,
+    /// Ratio of checks in which faulty nodes were not flagged, to all checks of faulty nodes
+    false_negative_rate: Option<f64>,
+    #[serde(skip)]
+    labels: BTreeMap<XorName, String>,
+    #[serde(skip)]
+    faulty: Option<BTreeSet<XorName>>,
+    #[serde(skip)]
+    false_positives: usize,
+    #[serde(skip)]
+    false_negatives: usize,
+}
+
+impl Report {
+    pub(crate) fn new(
+        labels: BTreeMap<XorName, String>,
+        faulty: Option<BTreeSet<XorName>>,
+    ) -> Self {
+        Self {
+            checks: vec![],
+            first_flagged: BTreeMap::new(),
+            total_checks: 0,
+            false_positive_rate: None,
+            false_negative_rate: None,
+            labels,
+            faulty,
+            false_positives: 0,
+            false_negatives: 0,
+        }
+    }
+
+    pub(crate) fn record_check(&mut self, at: Duration, faulty_nodes: Vec<XorName>) {
+        self.total_checks += 1;
+        let at_secs = at.as_secs();
+
+        if let Some(faulty) = &self.faulty {
+            let flagged = faulty_nodes.iter().collect::<BTreeSet<_>>();
+            self.false_positives += flagged.iter().filter(|n| !faulty.contains(n)).count();
+            self.false_negatives += faulty.iter().filter(|n| !flagged.contains(n)).count();
+
+            let faulty_count = faulty.len();
+            let non_faulty_count = self.labels.len().saturating_sub(faulty_count);
+            self.false_positive_rate = Some(rate(
+                self.false_positives,
+                non_faulty_count,
+                self.total_checks,
+            ));
+            self.false_negative_rate =
+                Some(rate(self.false_negatives, faulty_count, self.total_checks));
+        }
+
+        let flagged = faulty_nodes
+            .iter()
+            .map(|node| self.label(node))
+            .collect::<Vec<_>>();
+
+        for label in &flagged {
+            let _ = self.first_flagged.entry(label.clone()).or_insert(at_secs);
+        }
+
+        let changed = match self.checks.last() {
+            Some(last) => last.flagged != flagged,
+            None => !flagged.is_empty(),
+        };
+        if changed {
+            self.checks.push(Check { at_secs, flagged });
+        }
+    }
+
+    pub(crate) fn print(&self) {
+        println!("Faulty nodes flagged over time (shown when changed):");
+        if self.checks.is_empty() {
+            println!("  no node was ever flagged");
+        }
+        for check in &self.checks {
+            let flagged = if check.flagged.is_empty() {
+                "-".to_string()
+            } else {
+                check.flagged.join(", ")
+            };
+            println!("  {:>7}s  {flagged}", check.at_secs);
+        }
+
+        println!();
+        println!("Nodes first flagged at:");
+        for (label, at_secs) in &self.first_flagged {
+            println!("  {label}: {at_secs}s");
+        }
+
+        if let Some(faulty) = &self.faulty {
+            let never_flagged = faulty
+                .iter()
+                .map(|node| self.label(node))
+                .filter(|label| !self.first_flagged.contains_key(label))
+                .collect::<Vec<_>>();
+            if !never_flagged.is_empty() {
+                println!("Faulty nodes never flagged: {}", never_flagged.join(", "));
+            }
+        }
+
+        println!();
+        println!("Checks run: {}", self.total_checks);
+        match (self.false_positive_rate, self.false_negative_rate) {
+            (Some(fp_rate), Some(fn_rate)) => {
+                println!("False positive rate: {:.4}", fp_rate);
+                println!("False negative rate: {:.4}", fn_rate);
+            }
+            _ => println!("False positive/negative rates unknown, as faulty nodes were not known"),
+        }
+    }
+
+    fn label(&self, node: &XorName) -> String {
+        self.labels
+            .get(node)
+            .cloned()
+            .unwrap_or_else(|| format!("{node:?}"))
+    }
+}
+
+fn rate(count: usize, nodes: usize, checks: usize) -> f64 {
+    let total = nodes * checks;
+    if total == 0 {
+        return 0.0;
+    }
+    count as f64 / total as f64
+}
@@ -0,0 +1,242 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{Action, Event, Scenario};
+
+use sn_fault_detection::{IssueType, StorageFault};
+
+use eyre::{eyre, Result};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    time::Duration,
+};
+use xor_name::XorName;
+
+/// How many nodes of each profile to simulate
+#[derive(Debug)]
+pub(crate) struct Population {
+    pub(crate) honest: usize,
+    pub(crate) flaky: usize,
+    pub(crate) slow: usize,
+    pub(crate) byzantine: usize,
+    pub(crate) elders: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Profile {
+    /// Well behaved, though it still hits the odd issue now and then
+    Honest,
+    /// Drops connections in bursts
+    Flaky,
+    /// Often fails to fulfil requests, or respond, in time
+    Slow,
+    /// Misbehaves on purpose, eg. not voting, serving wrong data or not holding it at all
+    Byzantine,
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Honest => "honest",
+            Self::Flaky => "flaky",
+            Self::Slow => "slow",
+            Self::Byzantine => "byzantine",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Probability, per second, of each issue being tracked against a node
+struct IssueRates {
+    communication: f64,
+    request_operation: f64,
+    network_knowledge: f64,
+    storage: f64,
+    // elder only issues
+    dkg: f64,
+    elder_voting: f64,
+    ae_probe: f64,
+    /// Probability of an elder only issue being fulfilled (ie untracked) afterwards
+    fulfilment: f64,
+}
+
+impl Profile {
+    /// Burst probabilities: chance of a burst starting each second, and how long it lasts
+    const FLAKY_BURST_START: f64 = 0.01;
+    const FLAKY_BURST_SECS: u64 = 20;
+
+    fn rates(&self, in_burst: bool) -> IssueRates {
+        let honest = IssueRates {
+            communication: 0.001,
+            request_operation: 0.002,
+            network_knowledge: 0.004,
+            storage: 0.0005,
+            dkg: 0.01,
+            elder_voting: 0.02,
+            ae_probe: 0.003,
+            fulfilment: 0.98,
+        };
+
+        match self {
+            Self::Honest => honest,
+            Self::Flaky if in_burst => IssueRates {
+                communication: 0.3,
+                request_operation: 0.1,
+                fulfilment: 0.5,
+                ..honest
+            },
+            Self::Flaky => honest,
+            Self::Slow => IssueRates {
+                communication: 0.005,
+                request_operation: 0.03,
+                ae_probe: 0.01,
+                fulfilment: 0.85,
+                ..honest
+            },
+            Self::Byzantine => IssueRates {
+                network_knowledge: 0.03,
+                storage: 0.01,
+                dkg: 0.02,
+                elder_voting: 0.03,
+                fulfilment: 0.2,
+                ..honest
+            },
+        }
+    }
+}
+
+struct SimNode {
+    name: XorName,
+    profile: Profile,
+    is_elder: bool,
+    burst_until: u64,
+}
+
+/// Generates the issues a population of nodes would run into over the given duration.
+pub(crate) fn generate(population: &Population, duration: Duration, seed: u64) -> Result<Scenario> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut profiles = std::iter::repeat(Profile::Honest)
+        .take(population.honest)
+        .chain(std::iter::repeat(Profile::Flaky).take(population.flaky))
+        .chain(std::iter::repeat(Profile::Slow).take(population.slow))
+        .chain(std::iter::repeat(Profile::Byzantine).take(population.byzantine))
+        .collect::<Vec<_>>();
+    if population.elders > profiles.len() {
+        return Err(eyre!(
+            "Cannot have {} elders in a population of {} nodes",
+            population.elders,
+            profiles.len()
+        ));
+    }
+    // elders are picked randomly amongst all profiles
+    profiles.shuffle(&mut rng);
+
+    let mut scenario = Scenario {
+        faulty: Some(BTreeSet::new()),
+        duration,
+        ..Default::default()
+    };
+    let mut profile_counts = BTreeMap::<Profile, usize>::new();
+    let mut nodes = vec![];
+    for (index, profile) in profiles.into_iter().enumerate() {
+        let name = XorName::random(&mut rng);
+        let count = profile_counts.entry(profile).or_default();
+        let is_elder = index < population.elders;
+        let label = if is_elder {
+            format!("{profile}-{count} (elder)")
+        } else {
+            format!("{profile}-{count}")
+        };
+        *count += 1;
+
+        let _ = scenario.labels.insert(name, label);
+        if is_elder {
+            let _ = scenario.elders.insert(name);
+        }
+        if profile != Profile::Honest {
+            if let Some(faulty) = scenario.faulty.as_mut() {
+                let _ = faulty.insert(name);
+            }
+        }
+
+        nodes.push(SimNode {
+            name,
+            profile,
+            is_elder,
+            burst_until: 0,
+        });
+    }
+
+    for second in 0..duration.as_secs() {
+        let at = Duration::from_secs(second);
+        for node in &mut nodes {
+            if node.profile == Profile::Flaky
+                && second >= node.burst_until
+                && rng.gen_bool(Profile::FLAKY_BURST_START)
+            {
+                node.burst_until = second + Profile::FLAKY_BURST_SECS;
+            }
+
+            let rates = node.profile.rates(second < node.burst_until);
+            let mut issues = vec![
+                (IssueType::Communication, rates.communication),
+                (IssueType::RequestOperation, rates.request_operation),
+                (IssueType::NetworkKnowledge, rates.network_knowledge),
+            ];
+            let storage_fault = if node.profile == Profile::Byzantine && rng.gen_bool(0.5) {
+                StorageFault::CorruptData
+            } else {
+                StorageFault::MissingData
+            };
+            issues.push((IssueType::Storage(storage_fault), rates.storage));
+
+            for (issue, rate) in issues {
+                if rng.gen_bool(rate) {
+                    scenario.events.push(Event {
+                        at,
+                        node: node.name,
+                        action: Action::Track(issue),
+                    });
+                }
+            }
+
+            if !node.is_elder {
+                continue;
+            }
+
+            let elder_issues = [
+                (IssueType::Dkg, rates.dkg),
+                (IssueType::ElderVoting, rates.elder_voting),
+                (IssueType::AeProbeMsg, rates.ae_probe),
+            ];
+            for (issue, rate) in elder_issues {
+                if !rng.gen_bool(rate) {
+                    continue;
+                }
+                scenario.events.push(Event {
+                    at,
+                    node: node.name,
+                    action: Action::Track(issue.clone()),
+                });
+                if rng.gen_bool(rates.fulfilment) {
+                    let delay = Duration::from_secs(rng.gen_range(1..=3));
+                    scenario.events.push(Event {
+                        at: at + delay,
+                        node: node.name,
+                        action: Action::Untrack(issue),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(scenario)
+}
@@ -0,0 +1,67 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const RECENT_ISSUE_DURATION: Duration = Duration::from_secs(60 * 10); // 10 minutes
+
+/// How many standard devs before we consider a node faulty
+/// https://en.wikipedia.org/wiki/68%E2%80%9395%E2%80%9399.7_rule
+const STD_DEVS_AWAY: usize = 3;
+
+const CONN_WEIGHTING: f32 = 1.5;
+const OP_WEIGHTING: f32 = 1.0;
+const KNOWLEDGE_WEIGHTING: f32 = 2.0;
+const DKG_WEIGHTING: f32 = 2.0; // there are quite a lot of DKG msgs that go out atm, so can't weight this too heavily
+const ELDER_VOTE_WEIGHTING: f32 = 2.5; // Not as severe as DKG votes missing, as these are not always required
+const AE_PROBE_WEIGHTING: f32 = 2.5;
+const STORAGE_WEIGHTING: f32 = 2.0; // a node may be missing data it's still being sent, so we allow for some of that
+
+/// Thresholds and weightings used by `FaultDetection` when scoring nodes.
+///
+/// Any field missing when deserialising falls back to its default value,
+/// so a config file only needs to contain the values being tuned.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaultDetectionConfig {
+    /// How long an issue is taken into account before it expires.
+    pub recent_issue_duration: Duration,
+    /// How many standard deviations above the mean a node's score must be to be considered faulty.
+    pub std_devs_away: usize,
+    /// Weighting applied to `IssueType::Communication` scores.
+    pub conn_weighting: f32,
+    /// Weighting applied to `IssueType::RequestOperation` scores.
+    pub op_weighting: f32,
+    /// Weighting applied to `IssueType::NetworkKnowledge` scores.
+    pub knowledge_weighting: f32,
+    /// Weighting applied to `IssueType::Dkg` scores.
+    pub dkg_weighting: f32,
+    /// Weighting applied to `IssueType::ElderVoting` scores.
+    pub elder_vote_weighting: f32,
+    /// Weighting applied to `IssueType::AeProbeMsg` scores.
+    pub ae_probe_weighting: f32,
+    /// Weighting applied to `IssueType::Storage` scores.
+    pub storage_weighting: f32,
+}
+
+impl Default for FaultDetectionConfig {
+    fn default() -> Self {
+        Self {
+            recent_issue_duration: RECENT_ISSUE_DURATION,
+            std_devs_away: STD_DEVS_AWAY,
+            conn_weighting: CONN_WEIGHTING,
+            op_weighting: OP_WEIGHTING,
+            knowledge_weighting: KNOWLEDGE_WEIGHTING,
+            dkg_weighting: DKG_WEIGHTING,
+            elder_vote_weighting: ELDER_VOTE_WEIGHTING,
+            ae_probe_weighting: AE_PROBE_WEIGHTING,
+            storage_weighting: STORAGE_WEIGHTING,
+        }
+    }
+}
@@ -9,23 +9,12 @@
 use crate::{get_mean_of, std_deviation, FaultDetection, NodeIdentifier};
 
 use itertools::Itertools;
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Instant,
+};
 use xor_name::XorName;
 
-use std::time::Duration;
-static RECENT_ISSUE_DURATION: Duration = Duration::from_secs(60 * 10); // 10 minutes
-
-/// How many standard devs before we consider a node faulty
-/// https://en.wikipedia.org/wiki/68%E2%80%9395%E2%80%9399.7_rule
-static STD_DEVS_AWAY: usize = 3;
-
-static CONN_WEIGHTING: f32 = 1.5;
-static OP_WEIGHTING: f32 = 1.0;
-static KNOWLEDGE_WEIGHTING: f32 = 2.0;
-static DKG_WEIGHTING: f32 = 2.0; // there are quite a lot of DKG msgs that go out atm, so can't weight this too heavily
-static ELDER_VOTE_WEIGHTING: f32 = 2.5; // Not as severe as DKG votes missing, as these are not always required
-static AE_PROBE_WEIGHTING: f32 = 2.5;
-
 #[derive(Clone, Debug)]
 /// Represents the different type of issues that can be recorded by the Fault Detection
 /// system.
@@ -44,6 +33,20 @@ pub enum IssueType {
     NetworkKnowledge,
     /// Represents a pending request operation issue to be tracked by Fault Detection.
     RequestOperation,
+    /// Represents a data storage issue, ie. a node not serving the data it's expected to hold,
+    /// to be tracked by Fault Detection.
+    Storage(StorageFault),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The different kinds of data storage faults a node can be found to have.
+pub enum StorageFault {
+    /// The node reported data as not found which it is expected to hold.
+    MissingData,
+    /// The node returned data which does not match its address.
+    CorruptData,
+    /// The node returned a replica of a register which is far behind other replicas.
+    StaleReplica,
 }
 
 #[derive(Debug)]
@@ -54,6 +57,7 @@ pub struct ScoreResults {
     pub knowledge_scores: BTreeMap<XorName, f32>,
     pub op_scores: BTreeMap<XorName, f32>,
     pub probe_scores: BTreeMap<XorName, f32>,
+    pub storage_scores: BTreeMap<XorName, f32>,
 }
 
 impl FaultDetection {
@@ -72,6 +76,7 @@ impl FaultDetection {
         let mut dkg_scores = BTreeMap::new();
         let mut elder_voting_scores = BTreeMap::new();
         let mut probe_scores = BTreeMap::new();
+        let mut storage_scores = BTreeMap::new();
 
         for node in nodes_in_question {
             let _ = dkg_scores.insert(
@@ -98,6 +103,14 @@ impl FaultDetection {
                 *node,
                 self.calculate_node_score_for_type(node, &IssueType::RequestOperation),
             );
+            // the kind of storage fault does not affect the score
+            let _ = storage_scores.insert(
+                *node,
+                self.calculate_node_score_for_type(
+                    node,
+                    &IssueType::Storage(StorageFault::MissingData),
+                ),
+            );
         }
 
         ScoreResults {
@@ -107,6 +120,7 @@ impl FaultDetection {
             knowledge_scores,
             op_scores,
             probe_scores,
+            storage_scores,
         }
     }
 
@@ -176,6 +190,13 @@ impl FaultDetection {
                     0
                 }
             }
+            IssueType::Storage(_) => {
+                if let Some(issues) = self.storage_issues.get(node) {
+                    issues.len()
+                } else {
+                    0
+                }
+            }
         }
     }
 
@@ -192,36 +213,42 @@ impl FaultDetection {
         let elder_voting_scores = scores.elder_voting_scores;
         let knowledge_scores = scores.knowledge_scores;
         let probe_scores = scores.probe_scores;
+        let storage_scores = scores.storage_scores;
 
         let mut pre_standardised_scores = BTreeMap::default();
         let mut scores_only = vec![];
         // now we loop to get the scores per xorname, so we can then avg etc
         for (name, score) in ops_scores {
-            let ops_score = score * OP_WEIGHTING;
+            let ops_score = score * self.config.op_weighting;
 
             let node_conn_score = *conn_scores.get(&name).unwrap_or(&1.0);
-            let node_conn_score = node_conn_score * CONN_WEIGHTING;
+            let node_conn_score = node_conn_score * self.config.conn_weighting;
 
             let node_dkg_score = *dkg_scores.get(&name).unwrap_or(&1.0);
-            let node_dkg_score = node_dkg_score * DKG_WEIGHTING;
+            let node_dkg_score = node_dkg_score * self.config.dkg_weighting;
 
             let node_elder_voting_score = *elder_voting_scores.get(&name).unwrap_or(&1.0);
-            let node_elder_voting_score = node_elder_voting_score * ELDER_VOTE_WEIGHTING;
+            let node_elder_voting_score =
+                node_elder_voting_score * self.config.elder_vote_weighting;
 
             let node_knowledge_score = *knowledge_scores.get(&name).unwrap_or(&1.0);
-            let node_knowledge_score = node_knowledge_score * KNOWLEDGE_WEIGHTING;
+            let node_knowledge_score = node_knowledge_score * self.config.knowledge_weighting;
 
             let node_probe_score = *probe_scores.get(&name).unwrap_or(&1.0);
-            let node_probe_score = node_probe_score * AE_PROBE_WEIGHTING;
+            let node_probe_score = node_probe_score * self.config.ae_probe_weighting;
+
+            let node_storage_score = *storage_scores.get(&name).unwrap_or(&1.0);
+            let node_storage_score = node_storage_score * self.config.storage_weighting;
 
             let final_score = ops_score
                 + node_conn_score
                 + node_knowledge_score
                 + node_elder_voting_score
                 + node_dkg_score
-                + node_probe_score;
+                + node_probe_score
+                + node_storage_score;
             debug!(
-                "Node {name} has a final score of {final_score} :: ElderVoting score({node_elder_voting_score}) Conns score({node_conn_score}), Dkg score({node_dkg_score}), Knowledge score({node_knowledge_score}), Ops score({score}), AeProbe score ({node_probe_score})"
+                "Node {name} has a final score of {final_score} :: ElderVoting score({node_elder_voting_score}) Conns score({node_conn_score}), Dkg score({node_dkg_score}), Knowledge score({node_knowledge_score}), Ops score({score}), AeProbe score ({node_probe_score}), Storage score ({node_storage_score})"
             );
 
             scores_only.push(final_score);
@@ -241,9 +268,10 @@ impl FaultDetection {
         // threshold needs to always be at least 1, and with the std dev always at least one
         // that should be fine.
         let at_least_1_as_std_dev = if std_dev < 1.0 { 1.0 } else { std_dev.ceil() };
-        let threshold = STD_DEVS_AWAY * at_least_1_as_std_dev as usize + mean as usize;
+        let std_devs_away = self.config.std_devs_away;
+        let threshold = std_devs_away * at_least_1_as_std_dev as usize + mean as usize;
         debug!(
-            "____Threshold is {STD_DEVS_AWAY:?} std devs away + mean, which is {:?}",
+            "____Threshold is {std_devs_away:?} std devs away + mean, which is {:?}",
             threshold
         );
 
@@ -258,24 +286,29 @@ impl FaultDetection {
         final_scores
     }
 
-    fn cleanup_time_sensistive_checks(&mut self) {
+    fn cleanup_time_sensistive_checks(&mut self, now: Instant) {
+        let recent_issue_duration = self.config.recent_issue_duration;
         for issues in &mut self.communication_issues.values_mut() {
-            issues.retain(|time| time.elapsed() < RECENT_ISSUE_DURATION);
+            issues.retain(|time| now.saturating_duration_since(*time) < recent_issue_duration);
         }
 
         for issues in &mut self.probe_issues.values_mut() {
-            issues.retain(|time| time.elapsed() < RECENT_ISSUE_DURATION);
+            issues.retain(|time| now.saturating_duration_since(*time) < recent_issue_duration);
         }
 
         for issues in &mut self.network_knowledge_issues.values_mut() {
-            issues.retain(|time| time.elapsed() < RECENT_ISSUE_DURATION);
+            issues.retain(|time| now.saturating_duration_since(*time) < recent_issue_duration);
         }
 
         for issues in &mut self.dkg_issues.values_mut() {
-            issues.retain(|time| time.elapsed() < RECENT_ISSUE_DURATION);
+            issues.retain(|time| now.saturating_duration_since(*time) < recent_issue_duration);
         }
         for issues in &mut self.unfulfilled_ops.values_mut() {
-            issues.retain(|time| time.elapsed() < RECENT_ISSUE_DURATION);
+            issues.retain(|time| now.saturating_duration_since(*time) < recent_issue_duration);
+        }
+
+        for issues in &mut self.storage_issues.values_mut() {
+            issues.retain(|time| now.saturating_duration_since(*time) < recent_issue_duration);
         }
     }
 
@@ -285,7 +318,14 @@ impl FaultDetection {
     ///
     /// The vec is ordered from fauliest to least faulty (returning faulty elders first)
     pub fn get_faulty_nodes(&mut self) -> Vec<XorName> {
-        self.cleanup_time_sensistive_checks();
+        self.get_faulty_nodes_at(Instant::now())
+    }
+
+    /// Get a list of nodes that are faulty, expiring issues as of the given time.
+    ///
+    /// See `get_faulty_nodes`; this allows replaying or simulating issues over a virtual clock.
+    pub fn get_faulty_nodes_at(&mut self, now: Instant) -> Vec<XorName> {
+        self.cleanup_time_sensistive_checks(now);
 
         info!("Non-elder fault calculations...");
         let final_non_elder_scores = self.get_weighted_scores(&self.non_elder_nodes);
@@ -320,7 +360,11 @@ impl FaultDetection {
 mod tests {
     use itertools::Itertools;
 
-    use crate::{detection::IssueType, tests::init_test_logger, FaultDetection};
+    use crate::{
+        detection::{IssueType, StorageFault},
+        tests::init_test_logger,
+        FaultDetection,
+    };
 
     use eyre::bail;
     use proptest::prelude::*;
@@ -360,6 +404,9 @@ mod tests {
         200 => Just(IssueType::ElderVoting), //
         0 => Just(IssueType::AeProbeMsg),
         180 => Just(IssueType::NetworkKnowledge),
+        40 => Just(IssueType::Storage(StorageFault::MissingData)),
+        10 => Just(IssueType::Storage(StorageFault::CorruptData)),
+        10 => Just(IssueType::Storage(StorageFault::StaleReplica)),
         ]
     }
 
@@ -505,6 +552,9 @@ mod tests {
                     IssueType::RequestOperation => {
                         assert_eq!(score_results.op_scores.len(), node_count);
                     },
+                    IssueType::Storage(_) => {
+                        assert_eq!(score_results.storage_scores.len(), node_count);
+                    },
                 }
             })
         }
@@ -551,6 +601,9 @@ mod tests {
                     IssueType::RequestOperation => {
                         score_results.op_scores
                     },
+                    IssueType::Storage(_) => {
+                        score_results.storage_scores
+                    },
                 };
 
 
@@ -851,6 +904,9 @@ mod tests {
                     IssueType::RequestOperation => {
                         score_results.op_scores
                     },
+                    IssueType::Storage(_) => {
+                        score_results.storage_scores
+                    },
                 };
                 for node in &nodes {
                     assert_eq!(*scores.get(node).unwrap(), 0.0);
@@ -896,6 +952,45 @@ mod ops_tests {
     }
 }
 
+#[cfg(test)]
+mod storage_tests {
+    use crate::{detection::StorageFault, tests::init_test_logger, FaultDetection, IssueType};
+    use std::collections::BTreeSet;
+    use xor_name::{rand::random as random_xorname, XorName};
+
+    #[tokio::test]
+    async fn storage_faults_lead_to_node_classified_as_faulty() {
+        init_test_logger();
+        let nodes = (0..20)
+            .map(|_| random_xorname())
+            .collect::<BTreeSet<XorName>>();
+        let mut fault_detection = FaultDetection::new(nodes.clone(), BTreeSet::new());
+        let nodes_vec = nodes.iter().cloned().collect::<Vec<XorName>>();
+
+        // a node missing some data every now and then is not faulty
+        fault_detection.track_issue(nodes_vec[0], IssueType::Storage(StorageFault::MissingData));
+        assert_eq!(
+            fault_detection.get_faulty_nodes().len(),
+            0,
+            "Node should not yet be faulty"
+        );
+
+        // but one which keeps losing or corrupting data is
+        for _ in 0..30 {
+            fault_detection
+                .track_issue(nodes_vec[0], IssueType::Storage(StorageFault::MissingData));
+            fault_detection
+                .track_issue(nodes_vec[0], IssueType::Storage(StorageFault::CorruptData));
+        }
+
+        assert_eq!(
+            fault_detection.get_faulty_nodes(),
+            vec![nodes_vec[0]],
+            "node should be found as faulty"
+        );
+    }
+}
+
 #[cfg(test)]
 mod comm_tests {
     use crate::{FaultDetection, IssueType};
@@ -45,9 +45,13 @@
 #[macro_use]
 extern crate tracing;
 
+mod config;
 mod detection;
+mod snapshot;
 
-pub use detection::IssueType;
+pub use config::FaultDetectionConfig;
+pub use detection::{IssueType, StorageFault};
+pub use snapshot::FaultDetectionSnapshot;
 
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
@@ -77,20 +81,33 @@ pub struct FaultDetection {
     /// The unfulfilled pending request operation issues logged against a node, along with an
     /// operation ID.
     pub unfulfilled_ops: TimedTracker,
+    /// The data storage issues logged against a node, along with a timestamp to expire after some time.
+    pub storage_issues: TimedTracker,
     /// All non elder nodes to be tracked
     non_elder_nodes: BTreeSet<XorName>,
     /// All elders in the section (we distinguish between elders and all other nodes as their roles mean
     /// elders may have relatively higher fault levels during normal operation)
     elders: BTreeSet<XorName>,
+    /// The thresholds and weightings used when scoring nodes
+    config: FaultDetectionConfig,
 }
 
 impl FaultDetection {
-    /// Set up a new tracker.
+    /// Set up a new tracker, using the default `FaultDetectionConfig`.
     pub fn new(
         non_elder_nodes: BTreeSet<NodeIdentifier>,
         elders: BTreeSet<NodeIdentifier>,
     ) -> Self {
-        debug!("Starting faults with elders: {elders:?}, non_elder_nodes: {non_elder_nodes:?}");
+        Self::with_config(non_elder_nodes, elders, FaultDetectionConfig::default())
+    }
+
+    /// Set up a new tracker which scores nodes using the provided config.
+    pub fn with_config(
+        non_elder_nodes: BTreeSet<NodeIdentifier>,
+        elders: BTreeSet<NodeIdentifier>,
+        config: FaultDetectionConfig,
+    ) -> Self {
+        debug!("Starting faults with elders: {elders:?}, non_elder_nodes: {non_elder_nodes:?}, config: {config:?}");
         Self {
             elders,
             communication_issues: BTreeMap::new(),
@@ -99,7 +116,9 @@ impl FaultDetection {
             probe_issues: BTreeMap::new(),
             network_knowledge_issues: BTreeMap::new(),
             unfulfilled_ops: BTreeMap::new(),
+            storage_issues: BTreeMap::new(),
             non_elder_nodes,
+            config,
         }
     }
 
@@ -113,32 +132,43 @@ impl FaultDetection {
     ///
     /// The `op_id` only applies when adding an operational issue.
     pub fn track_issue(&mut self, node_id: NodeIdentifier, issue_type: IssueType) {
+        self.track_issue_at(node_id, issue_type, Instant::now())
+    }
+
+    /// Adds an issue to the fault tracker, as having happened at the given time.
+    ///
+    /// This allows replaying or simulating issues over a virtual clock, see `get_faulty_nodes_at`.
+    pub fn track_issue_at(&mut self, node_id: NodeIdentifier, issue_type: IssueType, at: Instant) {
         debug!("Adding a new issue to {node_id:?} the fault tracker: {issue_type:?}");
 
         match issue_type {
             IssueType::Dkg => {
                 let queue = self.dkg_issues.entry(node_id).or_default();
-                queue.push_back(Instant::now());
+                queue.push_back(at);
             }
             IssueType::ElderVoting => {
                 let queue = self.elder_voting_issues.entry(node_id).or_default();
-                queue.push_back(Instant::now());
+                queue.push_back(at);
             }
             IssueType::AeProbeMsg => {
                 let queue = self.probe_issues.entry(node_id).or_default();
-                queue.push_back(Instant::now());
+                queue.push_back(at);
             }
             IssueType::Communication => {
                 let queue = self.communication_issues.entry(node_id).or_default();
-                queue.push_back(Instant::now());
+                queue.push_back(at);
             }
             IssueType::NetworkKnowledge => {
                 let queue = self.network_knowledge_issues.entry(node_id).or_default();
-                queue.push_back(Instant::now());
+                queue.push_back(at);
             }
             IssueType::RequestOperation => {
                 let queue = self.unfulfilled_ops.entry(node_id).or_default();
-                queue.push_back(Instant::now());
+                queue.push_back(at);
+            }
+            IssueType::Storage(_) => {
+                let queue = self.storage_issues.entry(node_id).or_default();
+                queue.push_back(at);
             }
         }
     }
@@ -246,6 +276,7 @@ impl FaultDetection {
             let _ = self.elder_voting_issues.remove(node);
             let _ = self.probe_issues.remove(node);
             let _ = self.unfulfilled_ops.remove(node);
+            let _ = self.storage_issues.remove(node);
         }
     }
 }
@@ -284,10 +315,10 @@ fn std_deviation(data: &[f32]) -> Option<f32> {
 
 #[cfg(test)]
 mod tests {
-    use super::{FaultDetection, IssueType};
+    use super::{FaultDetection, FaultDetectionConfig, FaultDetectionSnapshot, IssueType};
 
     use eyre::Error;
-    use std::{collections::BTreeSet, sync::Once};
+    use std::{collections::BTreeSet, sync::Once, time::Duration};
     use xor_name::{rand::random as random_xorname, XorName};
 
     type Result<T, E = Error> = std::result::Result<T, E>;
@@ -440,4 +471,74 @@ mod tests {
         assert_eq!(current_nodes.len(), 11);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn restored_snapshot_should_contain_the_tracked_issues() -> Result<()> {
+        let nodes = (0..10)
+            .map(|_| random_xorname())
+            .collect::<BTreeSet<XorName>>();
+        let nodes_vec = nodes.iter().cloned().collect::<Vec<XorName>>();
+        let mut fault_detection = FaultDetection::new(nodes.clone(), BTreeSet::new());
+
+        fault_detection.track_issue(nodes_vec[0], IssueType::Communication);
+        fault_detection.track_issue(nodes_vec[0], IssueType::Communication);
+        fault_detection.track_issue(nodes_vec[1], IssueType::Dkg);
+        fault_detection.track_issue(nodes_vec[2], IssueType::RequestOperation);
+
+        let serialized = bincode::serialize(&fault_detection.snapshot())?;
+        let snapshot: FaultDetectionSnapshot = bincode::deserialize(&serialized)?;
+
+        let mut restored = FaultDetection::new(nodes, BTreeSet::new());
+        restored.restore_snapshot(snapshot);
+
+        assert_eq!(restored.communication_issues.len(), 1);
+        assert_eq!(
+            restored
+                .communication_issues
+                .get(&nodes_vec[0])
+                .map(|issues| issues.len()),
+            Some(2)
+        );
+        assert_eq!(restored.dkg_issues.len(), 1);
+        assert_eq!(restored.unfulfilled_ops.len(), 1);
+        assert_eq!(restored.network_knowledge_issues.len(), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn restore_snapshot_should_skip_untracked_nodes_and_expired_issues() -> Result<()> {
+        let nodes = (0..10)
+            .map(|_| random_xorname())
+            .collect::<BTreeSet<XorName>>();
+        let nodes_vec = nodes.iter().cloned().collect::<Vec<XorName>>();
+        let mut fault_detection = FaultDetection::new(nodes, BTreeSet::new());
+
+        fault_detection.track_issue(nodes_vec[0], IssueType::Communication);
+        fault_detection.track_issue(nodes_vec[1], IssueType::NetworkKnowledge);
+        let snapshot = fault_detection.snapshot();
+
+        // the first node is no longer a member
+        let retained = nodes_vec[1..]
+            .iter()
+            .cloned()
+            .collect::<BTreeSet<XorName>>();
+        let mut restored = FaultDetection::new(retained.clone(), BTreeSet::new());
+        restored.restore_snapshot(snapshot.clone());
+
+        assert_eq!(restored.communication_issues.len(), 0);
+        assert_eq!(restored.network_knowledge_issues.len(), 1);
+
+        // with no time to live, every restored issue has expired
+        let config = FaultDetectionConfig {
+            recent_issue_duration: Duration::ZERO,
+            ..Default::default()
+        };
+        let mut restored = FaultDetection::with_config(retained, BTreeSet::new(), config);
+        restored.restore_snapshot(snapshot);
+
+        assert_eq!(restored.network_knowledge_issues.len(), 0);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,179 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{FaultDetection, NodeIdentifier, TimedTracker};
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// A tracker with each of its `Instant`s converted to the age of the issue when the snapshot
+/// was taken, so it can be persisted.
+type SnapshotTracker = BTreeMap<NodeIdentifier, Vec<Duration>>;
+
+/// A serializable copy of the issues tracked by `FaultDetection`.
+///
+/// `Instant`s cannot outlive the process which created them, so each issue is stored as its
+/// age relative to the wall-clock time the snapshot was taken at. Issues are restored with that
+/// same age, i.e. the time a node was down doesn't count towards expiring the issues it had
+/// tracked, as no issue could be tracked meanwhile either.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FaultDetectionSnapshot {
+    taken_at: SystemTime,
+    communication_issues: SnapshotTracker,
+    dkg_issues: SnapshotTracker,
+    elder_voting_issues: SnapshotTracker,
+    probe_issues: SnapshotTracker,
+    network_knowledge_issues: SnapshotTracker,
+    unfulfilled_ops: SnapshotTracker,
+    storage_issues: SnapshotTracker,
+}
+
+impl FaultDetection {
+    /// Take a snapshot of all the issues currently being tracked.
+    pub fn snapshot(&self) -> FaultDetectionSnapshot {
+        let now = Instant::now();
+
+        FaultDetectionSnapshot {
+            taken_at: SystemTime::now(),
+            communication_issues: to_snapshot(&self.communication_issues, now),
+            dkg_issues: to_snapshot(&self.dkg_issues, now),
+            elder_voting_issues: to_snapshot(&self.elder_voting_issues, now),
+            probe_issues: to_snapshot(&self.probe_issues, now),
+            network_knowledge_issues: to_snapshot(&self.network_knowledge_issues, now),
+            unfulfilled_ops: to_snapshot(&self.unfulfilled_ops, now),
+            storage_issues: to_snapshot(&self.storage_issues, now),
+        }
+    }
+
+    /// Restore the issues from a previously taken snapshot.
+    ///
+    /// Only issues against nodes currently being tracked, and which had not yet expired as per
+    /// the configured `recent_issue_duration` when the snapshot was taken, are restored.
+    /// Restored issues are added on top of any issues already tracked.
+    pub fn restore_snapshot(&mut self, snapshot: FaultDetectionSnapshot) {
+        let current_nodes = self.all_current_nodes();
+        let restore = Restore {
+            now: Instant::now(),
+            recent_issue_duration: self.config.recent_issue_duration,
+            current_nodes: &current_nodes,
+        };
+
+        debug!(
+            "Restoring FaultDetection snapshot taken at {:?} for nodes: {current_nodes:?}",
+            snapshot.taken_at
+        );
+
+        let communication_issues = restore.tracker(snapshot.communication_issues);
+        let dkg_issues = restore.tracker(snapshot.dkg_issues);
+        let elder_voting_issues = restore.tracker(snapshot.elder_voting_issues);
+        let probe_issues = restore.tracker(snapshot.probe_issues);
+        let network_knowledge_issues = restore.tracker(snapshot.network_knowledge_issues);
+        let unfulfilled_ops = restore.tracker(snapshot.unfulfilled_ops);
+        let storage_issues = restore.tracker(snapshot.storage_issues);
+
+        merge_into(&mut self.communication_issues, communication_issues);
+        merge_into(&mut self.dkg_issues, dkg_issues);
+        merge_into(&mut self.elder_voting_issues, elder_voting_issues);
+        merge_into(&mut self.probe_issues, probe_issues);
+        merge_into(&mut self.network_knowledge_issues, network_knowledge_issues);
+        merge_into(&mut self.unfulfilled_ops, unfulfilled_ops);
+        merge_into(&mut self.storage_issues, storage_issues);
+    }
+}
+
+struct Restore<'a> {
+    now: Instant,
+    recent_issue_duration: Duration,
+    current_nodes: &'a BTreeSet<NodeIdentifier>,
+}
+
+impl Restore<'_> {
+    fn tracker(&self, tracker: SnapshotTracker) -> TimedTracker {
+        tracker
+            .into_iter()
+            .filter(|(node, _)| self.current_nodes.contains(node))
+            .map(|(node, ages)| {
+                let issues = ages
+                    .into_iter()
+                    .filter(|age| *age < self.recent_issue_duration)
+                    .filter_map(|age| self.now.checked_sub(age))
+                    .collect::<VecDeque<_>>();
+                (node, issues)
+            })
+            .filter(|(_, issues)| !issues.is_empty())
+            .collect()
+    }
+}
+
+fn to_snapshot(tracker: &TimedTracker, now: Instant) -> SnapshotTracker {
+    tracker
+        .iter()
+        .map(|(node, instants)| {
+            let ages = instants
+                .iter()
+                .map(|instant| now.saturating_duration_since(*instant))
+                .collect();
+            (*node, ages)
+        })
+        .collect()
+}
+
+fn merge_into(tracker: &mut TimedTracker, restored: TimedTracker) {
+    for (node, issues) in restored {
+        let queue = tracker.entry(node).or_default();
+        queue.extend(issues);
+        queue.make_contiguous().sort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FaultDetection, IssueType};
+
+    use eyre::Error;
+    use std::{
+        collections::BTreeSet,
+        time::{Duration, SystemTime},
+    };
+    use xor_name::{rand::random as random_xorname, XorName};
+
+    type Result<T, E = Error> = std::result::Result<T, E>;
+
+    #[tokio::test]
+    async fn issues_are_restored_after_a_downtime_longer_than_their_expiry() -> Result<()> {
+        let nodes = (0..10)
+            .map(|_| random_xorname())
+            .collect::<BTreeSet<XorName>>();
+        let node = *nodes.iter().next().ok_or_else(|| eyre::eyre!("no nodes"))?;
+        let mut fault_detection = FaultDetection::new(nodes.clone(), BTreeSet::new());
+        fault_detection.track_issue(node, IssueType::Communication);
+
+        // as if the node went down for longer than the issues take to expire
+        let mut snapshot = fault_detection.snapshot();
+        snapshot.taken_at = SystemTime::now()
+            - fault_detection.config.recent_issue_duration
+            - Duration::from_secs(60);
+        let snapshot = bincode::deserialize(&bincode::serialize(&snapshot)?)?;
+
+        let mut restored = FaultDetection::new(nodes, BTreeSet::new());
+        restored.restore_snapshot(snapshot);
+
+        assert_eq!(
+            restored
+                .communication_issues
+                .get(&node)
+                .map(|issues| issues.len()),
+            Some(1)
+        );
+
+        Ok(())
+    }
+}
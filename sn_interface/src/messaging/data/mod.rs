@@ -12,6 +12,8 @@ mod cmd;
 mod errors;
 mod query;
 mod register;
+mod section_health;
+mod section_members;
 mod spentbook;
 
 pub use self::{
@@ -22,6 +24,8 @@ pub use self::{
         CreateRegister, EditRegister, RegisterCmd, RegisterQuery, SignedRegisterCreate,
         SignedRegisterEdit,
     },
+    section_health::SectionHealth,
+    section_members::SectionMembersProof,
     spentbook::{SpentbookCmd, SpentbookQuery},
 };
 
@@ -61,6 +65,16 @@ pub enum ClientMsg {
     /// reply.
     /// [`QueryResponse`]: Self::QueryResponse
     Query(DataQuery),
+    /// Query an elder for its view of its section's membership and its storage levels.
+    ///
+    /// Senders should eventually receive a [`SectionHealth`] response.
+    /// [`SectionHealth`]: ClientDataResponse::SectionHealth
+    SectionHealthQuery,
+    /// Query an elder for a proof of its section's current membership.
+    ///
+    /// Senders should eventually receive a [`SectionMembers`] response.
+    /// [`SectionMembers`]: ClientDataResponse::SectionMembers
+    SectionMembersQuery,
 }
 
 impl Display for ClientMsg {
@@ -68,6 +82,8 @@ impl Display for ClientMsg {
         match self {
             Self::Cmd(cmd) => write!(f, "ClientMsg::Cmd({cmd:?})"),
             Self::Query(query) => write!(f, "ClientMsg::Query({query:?})"),
+            Self::SectionHealthQuery => write!(f, "ClientMsg::SectionHealthQuery"),
+            Self::SectionMembersQuery => write!(f, "ClientMsg::SectionMembersQuery"),
         }
     }
 }
@@ -95,6 +111,29 @@ pub enum ClientDataResponse {
         /// [`Cmd`]: self::ClientMsg::Cmd
         correlation_id: MsgId,
     },
+    /// The response to a [`SectionHealthQuery`], with the elder's view of its section.
+    ///
+    /// [`SectionHealthQuery`]: self::ClientMsg::SectionHealthQuery
+    SectionHealth {
+        /// The elder's view of its section.
+        health: Box<SectionHealth>,
+        /// ID of the [`SectionHealthQuery`] message.
+        ///
+        /// [`SectionHealthQuery`]: self::ClientMsg::SectionHealthQuery
+        correlation_id: MsgId,
+    },
+    /// The response to a [`SectionMembersQuery`], with the section signed members of the
+    /// elder's section.
+    ///
+    /// [`SectionMembersQuery`]: self::ClientMsg::SectionMembersQuery
+    SectionMembers {
+        /// The proof of the members of the elder's section.
+        proof: Box<SectionMembersProof>,
+        /// ID of the [`SectionMembersQuery`] message.
+        ///
+        /// [`SectionMembersQuery`]: self::ClientMsg::SectionMembersQuery
+        correlation_id: MsgId,
+    },
     AntiEntropy {
         /// The update to our NetworkKnowledge containing the current `SectionAuthorityProvider`
         /// and the section chain truncated from the triggering msg's dst section_key or genesis_key
@@ -117,6 +156,17 @@ impl Display for ClientDataResponse {
             Self::CmdResponse { response, .. } => {
                 write!(f, "ClientDataResponse::CmdResponse({response:?})")
             }
+            Self::SectionHealth { health, .. } => {
+                write!(f, "ClientDataResponse::SectionHealth({health:?})")
+            }
+            Self::SectionMembers { proof, .. } => {
+                write!(
+                    f,
+                    "ClientDataResponse::SectionMembers({:?}, {} members)",
+                    proof.signed_sap.prefix(),
+                    proof.members.len()
+                )
+            }
             Self::AntiEntropy { .. } => {
                 write!(f, "ClientDataResponse::AntiEntropy")
             }
@@ -0,0 +1,45 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::network_knowledge::NodeState;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use xor_name::Prefix;
+
+/// An elder's view of its section, sent in response to a [`ClientMsg::SectionHealthQuery`].
+///
+/// This is unsigned, and reflects what a single elder knows, so it is meant to be compared
+/// with the responses of the other elders of the section rather than be trusted on its own.
+///
+/// [`ClientMsg::SectionHealthQuery`]: super::ClientMsg::SectionHealthQuery
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SectionHealth {
+    /// Prefix of the elder's section.
+    pub prefix: Prefix,
+    /// Current section key, as known by the elder.
+    pub section_key: bls::PublicKey,
+    /// Current members of the section, as known by the elder.
+    pub members: BTreeSet<NodeState>,
+    /// Number of bytes stored by the elder.
+    pub used_space: usize,
+    /// Minimum storage capacity the elder has allocated, in bytes.
+    pub min_capacity: usize,
+    /// Maximum storage capacity the elder has allocated, in bytes.
+    pub max_capacity: usize,
+}
+
+impl SectionHealth {
+    /// Percentage of the elder's max capacity used.
+    pub fn used_space_percent(&self) -> f64 {
+        if self.max_capacity == 0 {
+            return 0.0;
+        }
+        100.0 * self.used_space as f64 / self.max_capacity as f64
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    messaging::system::SectionSigned,
+    network_knowledge::{
+        MembershipState, NodeState, SectionAuthUtils, SectionAuthorityProvider, SectionTreeUpdate,
+        SectionsDAG,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// The current members of a section, each with the section signature which agreed on its
+/// membership, sent in response to a [`ClientMsg::SectionMembersQuery`].
+///
+/// Unlike [`SectionHealth`], this can be verified on its own: the SAP and the proof chain
+/// prove the section keys, and the members' signatures are checked against them.
+///
+/// [`ClientMsg::SectionMembersQuery`]: super::ClientMsg::SectionMembersQuery
+/// [`SectionHealth`]: super::SectionHealth
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SectionMembersProof {
+    /// Current SAP of the section, as known by the elder.
+    pub signed_sap: SectionSigned<SectionAuthorityProvider>,
+    /// Chain of the section's keys, from the genesis key, or the latest checkpoint, to the
+    /// section key of the SAP.
+    pub proof_chain: SectionsDAG,
+    /// Current members of the section, as known by the elder.
+    pub members: BTreeSet<SectionSigned<NodeState>>,
+}
+
+impl SectionMembersProof {
+    /// The update to a `SectionTree` carried by this proof.
+    pub fn section_tree_update(&self) -> SectionTreeUpdate {
+        SectionTreeUpdate::new(self.signed_sap.clone(), self.proof_chain.clone())
+    }
+
+    /// Verifies the SAP and all the members were signed by keys of the given `SectionsDAG`,
+    /// and that all the members match the prefix of the SAP.
+    ///
+    /// The `SectionsDAG` is expected to be one we trust, already merged with `proof_chain`.
+    pub fn verify(&self, sections_dag: &SectionsDAG) -> bool {
+        let prefix = self.signed_sap.prefix();
+        self.signed_sap.verify(sections_dag)
+            && self.signed_sap.sig.public_key == self.signed_sap.section_key()
+            && self.members.iter().all(|member| {
+                prefix.matches(&member.name())
+                    && member.state() == MembershipState::Joined
+                    && member.verify(sections_dag)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestKeys, TestSapBuilder};
+    use eyre::{eyre, Result};
+    use xor_name::Prefix;
+
+    // Proof of the members of a single section network, signed by its genesis key.
+    fn proof() -> (SectionMembersProof, bls::SecretKey) {
+        let (sap, sk_set, ..) = TestSapBuilder::new(Prefix::default()).build();
+        let sk = sk_set.secret_key();
+        let members = sap
+            .members()
+            .map(|node_state| TestKeys::get_section_signed(&sk, node_state.clone()))
+            .collect();
+        let proof = SectionMembersProof {
+            proof_chain: SectionsDAG::new(sap.section_key()),
+            signed_sap: TestKeys::get_section_signed(&sk, sap),
+            members,
+        };
+
+        (proof, sk)
+    }
+
+    #[test]
+    fn proof_verifies_against_its_proof_chain() {
+        let (proof, _) = proof();
+        assert!(proof.verify(&proof.proof_chain));
+    }
+
+    #[test]
+    fn proof_with_a_member_signed_by_an_unknown_key_fails_verification() -> Result<()> {
+        let (mut proof, _) = proof();
+        let member = proof
+            .members
+            .pop_first()
+            .ok_or_else(|| eyre!("The SAP should have members"))?;
+        let unknown_sk = bls::SecretKey::random();
+        let _ = proof
+            .members
+            .insert(TestKeys::get_section_signed(&unknown_sk, member.value));
+
+        assert!(!proof.verify(&proof.proof_chain));
+        Ok(())
+    }
+
+    #[test]
+    fn proof_with_a_member_which_left_fails_verification() -> Result<()> {
+        let (mut proof, sk) = proof();
+        let member = proof
+            .members
+            .pop_first()
+            .ok_or_else(|| eyre!("The SAP should have members"))?;
+        let left = NodeState::left(*member.peer(), member.previous_name());
+        let _ = proof
+            .members
+            .insert(TestKeys::get_section_signed(&sk, left));
+
+        assert!(!proof.verify(&proof.proof_chain));
+        Ok(())
+    }
+}
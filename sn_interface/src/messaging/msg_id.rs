@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use hex_fmt::HexFmt;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -22,7 +23,7 @@ pub struct MsgId(#[debug(with = "Self::fmt_bytes")] [u8; MESSAGE_ID_LEN]);
 impl MsgId {
     /// Generates a new `MsgId` with random content.
     pub fn new() -> Self {
-        Self(rand::random())
+        Self(crate::rng::thread_rng().gen())
     }
 
     fn fmt_bytes(bytes: &[u8; MESSAGE_ID_LEN], f: &mut fmt::Formatter) -> fmt::Result {
@@ -20,7 +20,8 @@ use custom_debug::Debug as CustomDebug;
 use std::io::Write;
 // Current version of the messaging protocol.
 // At this point this implementation supports only this version.
-const MESSAGING_PROTO_VERSION: u16 = 1u16;
+// Version 2: peers carry the address of the relay they are reachable through, if any.
+const MESSAGING_PROTO_VERSION: u16 = 2u16;
 
 // Header to be serialisied at the front of the wire message.
 // This header contains the information needed to deserialize the payload.
@@ -0,0 +1,75 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use bls::PublicKey as BlsPublicKey;
+use serde::{Deserialize, Serialize};
+use xor_name::XorName;
+
+/// Compact summary of a node's knowledge of its own section.
+///
+/// Peers compare digests to find out whether they're in sync, and only fetch
+/// the parts they're missing when they aren't.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KnowledgeDigest {
+    /// Latest section key known, which identifies the section chain up to it.
+    pub section_key: BlsPublicKey,
+    /// Hash of the names of the current members of the section.
+    pub members_hash: XorName,
+    /// Number of current members of the section.
+    pub members_count: usize,
+}
+
+impl KnowledgeDigest {
+    /// Creates the digest of the section key and the names of the current members.
+    pub fn new<'a>(
+        section_key: BlsPublicKey,
+        members: impl IntoIterator<Item = &'a XorName>,
+    ) -> Self {
+        let mut names: Vec<_> = members.into_iter().collect();
+        // the hash must not depend on the order the members are provided in
+        names.sort();
+        names.dedup();
+        let parts: Vec<&[u8]> = names.iter().map(|name| &name.0[..]).collect();
+
+        Self {
+            section_key,
+            members_hash: XorName::from_content_parts(&parts),
+            members_count: names.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_does_not_depend_on_members_order() {
+        let key = bls::SecretKey::random().public_key();
+        let names: Vec<XorName> = (0..5).map(|_| xor_name::rand::random()).collect();
+
+        let digest = KnowledgeDigest::new(key, &names);
+        let reversed = KnowledgeDigest::new(key, names.iter().rev());
+
+        assert_eq!(digest, reversed);
+        assert_eq!(digest.members_count, 5);
+    }
+
+    #[test]
+    fn digest_differs_on_members_or_key() {
+        let key = bls::SecretKey::random().public_key();
+        let mut names: Vec<XorName> = (0..5).map(|_| xor_name::rand::random()).collect();
+        let digest = KnowledgeDigest::new(key, &names);
+
+        let other_key = bls::SecretKey::random().public_key();
+        assert_ne!(digest, KnowledgeDigest::new(other_key, &names));
+
+        let _ = names.pop();
+        assert_ne!(digest, KnowledgeDigest::new(key, &names));
+    }
+}
@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod ae_digest;
 mod dkg;
 mod join;
 mod node_msgs;
@@ -18,6 +19,7 @@ use crate::messaging::AuthorityProof;
 use crate::network_knowledge::{NodeState, RelocationProof, SapCandidate, SectionTreeUpdate};
 use crate::SectionAuthorityProvider;
 
+pub use ae_digest::KnowledgeDigest;
 pub use dkg::DkgSessionId;
 pub use join::{JoinRejectReason, JoinRequest, JoinResponse};
 pub use node_msgs::{NodeDataCmd, NodeDataQuery, NodeEvent, NodeQueryResponse};
@@ -32,6 +34,7 @@ use sn_consensus::{Generation, SignedVote};
 use sn_sdkg::DkgSignedVote;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{self, Display, Formatter};
+use std::net::SocketAddr;
 use xor_name::XorName;
 
 /// List of peers of a section
@@ -85,6 +88,18 @@ pub enum NodeMsg {
     /// Sends the current section key of target section which we know
     /// This expects a response, even if we're up to date.
     AntiEntropyProbe(BlsPublicKey),
+    /// Digest of our knowledge of our section, sent to the other members of it upon changes,
+    /// instead of a full AE update. Members not in sync respond with an `AntiEntropyFetch`.
+    AntiEntropyDigest(KnowledgeDigest),
+    /// Fetch the parts of the section knowledge we're missing, as found out from an
+    /// `AntiEntropyDigest`. It's responded to with an `AntiEntropy` update whose proof chain
+    /// starts at our section key, and carrying only the members whose state differs from ours.
+    AntiEntropyFetch {
+        /// Our current section key.
+        section_key: BlsPublicKey,
+        /// Names of the current members of our section, as we know them.
+        members: BTreeSet<XorName>,
+    },
     /// Send from a section to the node to be immediately relocated.
     Relocate(SectionSigned<NodeState>),
     /// Membership Votes, in order they should be processed in.
@@ -92,7 +107,12 @@ pub enum NodeMsg {
     /// Membership Anti-Entropy request
     MembershipAE(Generation),
     /// Try to join a section in the network.
-    TryJoin(Option<RelocationProof>),
+    TryJoin {
+        /// Proof of the joining node having been relocated, if it was.
+        relocation: Option<RelocationProof>,
+        /// The relay the joining node is reachable through, if it cannot accept incoming connections.
+        relay: Option<SocketAddr>,
+    },
     /// Response to a join request.
     JoinResponse(JoinResponse),
     /// Sent to the new elder candidates to start the DKG process, along with a sig of the DkgSessionId
@@ -170,10 +190,15 @@ pub enum NodeMsg {
 impl NodeMsg {
     pub fn is_join(&self) -> bool {
         // we could also differentiate, say if it's a relocation
-        matches!(self, NodeMsg::TryJoin(_))
+        matches!(self, NodeMsg::TryJoin { .. })
     }
     pub fn is_ae(&self) -> bool {
-        matches!(self, NodeMsg::AntiEntropy { .. })
+        matches!(
+            self,
+            NodeMsg::AntiEntropy { .. }
+                | NodeMsg::AntiEntropyDigest(_)
+                | NodeMsg::AntiEntropyFetch { .. }
+        )
     }
 }
 
@@ -217,10 +242,12 @@ impl NodeMsg {
         match self {
             Self::AntiEntropy { .. } => State::AntiEntropy,
             Self::AntiEntropyProbe { .. } => State::AntiEntropy,
+            Self::AntiEntropyDigest(_) => State::AntiEntropy,
+            Self::AntiEntropyFetch { .. } => State::AntiEntropy,
             Self::Relocate(_) => State::Relocate,
             Self::MembershipAE(_) => State::Membership,
             Self::MembershipVotes(_) => State::Membership,
-            Self::TryJoin(_) => State::Join,
+            Self::TryJoin { .. } => State::Join,
             Self::JoinResponse(_) => State::Join,
             Self::DkgStart { .. } => State::Dkg,
             Self::DkgEphemeralPubKey { .. } => State::Dkg,
@@ -244,10 +271,12 @@ impl Display for NodeMsg {
         match self {
             Self::AntiEntropy { .. } => write!(f, "NodeMsg::AntiEntropy"),
             Self::AntiEntropyProbe { .. } => write!(f, "NodeMsg::AntiEntropyProbe"),
+            Self::AntiEntropyDigest { .. } => write!(f, "NodeMsg::AntiEntropyDigest"),
+            Self::AntiEntropyFetch { .. } => write!(f, "NodeMsg::AntiEntropyFetch"),
             Self::Relocate { .. } => write!(f, "NodeMsg::Relocate"),
             Self::MembershipVotes { .. } => write!(f, "NodeMsg::MembershipVotes"),
             Self::MembershipAE { .. } => write!(f, "NodeMsg::MembershipAE"),
-            Self::TryJoin(_) => write!(f, "NodeMsg::TryJoin"),
+            Self::TryJoin { .. } => write!(f, "NodeMsg::TryJoin"),
             Self::JoinResponse { .. } => write!(f, "NodeMsg::JoinResponse"),
             Self::DkgStart { .. } => write!(f, "NodeMsg::DkgStart"),
             Self::DkgEphemeralPubKey { .. } => write!(f, "NodeMsg::DkgEphemeralPubKey"),
@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use super::NetworkParams;
 use thiserror::Error;
 
 /// The type returned by the `sn_routing` message handling methods.
@@ -45,6 +46,17 @@ pub enum Error {
     UntrustedSectionAuthProvider(String),
     #[error("The genesis key of the provided SectionTree is invalid: {0:?}")]
     InvalidGenesisKey(bls::PublicKey),
+    #[error("The network contacts cannot be trusted: {0}")]
+    InvalidNetworkContacts(String),
+    #[error("Invalid sections checkpoint: {0}")]
+    InvalidCheckpoint(String),
+    #[error("Invalid network params: {0}")]
+    InvalidNetworkParams(String),
+    #[error("Network params {found:?} don't match the network's {expected:?}")]
+    NetworkParamsMismatch {
+        expected: Box<NetworkParams>,
+        found: Box<NetworkParams>,
+    },
     #[error("A relocating node provided invalid relocation proof.")]
     InvalidRelocationProof,
     #[error("A signature share is invalid.")]
@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 mod errors;
+mod network_params;
 mod node_info;
 mod section_peers;
 mod section_tree;
@@ -22,17 +23,23 @@ pub use section_tree::test_utils as test_utils_st;
 
 pub use self::{
     errors::{Error, Result},
+    network_params::{NetworkParams, DEFAULT_DATA_COPY_COUNT, DEFAULT_ELDER_COUNT, MIN_ADULT_AGE},
     node_info::MyNodeInfo,
     node_state::{MembershipState, NodeState, RelocationDst, RelocationInfo, RelocationProof},
     section_authority_provider::{SapCandidate, SectionAuthUtils, SectionAuthorityProvider},
     section_keys::{SectionKeyShare, SectionKeysProvider},
-    section_tree::{SectionTree, SectionTreeUpdate},
-    sections_dag::SectionsDAG,
+    section_tree::{
+        NetworkContacts, NetworkContactsHeader, SectionTree, SectionTreeUpdate,
+        MIN_NETWORK_CONTACTS_FORMAT_VERSION, NETWORK_CONTACTS_FORMAT_VERSION,
+    },
+    sections_dag::{SectionsCheckpoint, SectionsDAG},
 };
 
 use crate::{
     messaging::{
-        system::{NodeMsg, SectionPeers as SectionPeersMsg, SectionSig, SectionSigned},
+        system::{
+            KnowledgeDigest, NodeMsg, SectionPeers as SectionPeersMsg, SectionSig, SectionSigned,
+        },
         Dst,
     },
     types::Peer,
@@ -50,41 +57,6 @@ use xor_name::{Prefix, XorName};
 /// validate it.
 pub const GENESIS_DBC_SK: &str = "0c5152498fc5b2f9ed691ef875f2c16f1f950910391f7ba1df63e9f0ce4b2780";
 
-/// The minimum age a node becomes an adult node.
-pub const MIN_ADULT_AGE: u8 = 5;
-
-const SN_ELDER_COUNT: &str = "SN_ELDER_COUNT";
-/// Number of elders per section.
-pub const DEFAULT_ELDER_COUNT: usize = 7;
-
-/// Get the expected elder count for our network.
-/// Defaults to `DEFAULT_ELDER_COUNT`, but can be overridden by the env var `SN_ELDER_COUNT`.
-pub fn elder_count() -> usize {
-    // if we have an env var for this, lets override
-    match std::env::var(SN_ELDER_COUNT) {
-        Ok(count) => {
-            match count.parse() {
-                Ok(count) => {
-                    warn!("ELDER_COUNT count set from env var SN_ELDER_COUNT: {SN_ELDER_COUNT:?}={count}");
-                    count
-                }
-                Err(error) => {
-                    warn!("There was an error parsing {SN_ELDER_COUNT:?} env var. DEFAULT_ELDER_COUNT={DEFAULT_ELDER_COUNT} will be used: {error:?}");
-                    DEFAULT_ELDER_COUNT
-                }
-            }
-        }
-        Err(_) => DEFAULT_ELDER_COUNT,
-    }
-}
-
-/// Recommended section size.
-/// The section will keep adding nodes when requested by the upper layers, until it can split.
-/// A split happens if both post-split sections would have at least this number of nodes.
-pub fn recommended_section_size() -> usize {
-    2 * elder_count()
-}
-
 /// `SuperMajority` of a given group (i.e. > 2/3)
 #[inline]
 pub const fn supermajority(group_size: usize) -> usize {
@@ -115,13 +87,16 @@ pub fn partition_by_prefix(
     Some((zero, one))
 }
 
+/// A section keeps adding nodes until it can split, which happens once both post-split
+/// sections would have at least the `recommended_section_size` number of nodes.
 pub fn section_has_room_for_node(
     joining_node: XorName,
     prefix: &Prefix,
     members: impl IntoIterator<Item = XorName>,
+    recommended_section_size: usize,
 ) -> bool {
     // We multiply by two to allow a buffer for when nodes are joining sequentially.
-    let split_section_size_cap = recommended_section_size() * 2;
+    let split_section_size_cap = recommended_section_size * 2;
 
     match partition_by_prefix(prefix, members) {
         Some((zeros, ones)) => {
@@ -167,17 +142,23 @@ impl NetworkKnowledge {
     }
 
     /// Creates `NetworkKnowledge` for the first node in the network
+    /// The network params are fixed in its genesis SAP.
     pub fn first_node(
         peer: Peer,
         genesis_sk_set: bls::SecretKeySet,
+        network_params: NetworkParams,
     ) -> Result<(Self, SectionKeyShare)> {
         let public_key_set = genesis_sk_set.public_keys();
         let secret_key_index = 0u8;
         let secret_key_share = genesis_sk_set.secret_key_share(secret_key_index as u64);
 
         let section_tree = {
-            let genesis_signed_sap =
-                create_first_section_authority_provider(&public_key_set, &secret_key_share, peer)?;
+            let genesis_signed_sap = create_first_section_authority_provider(
+                &public_key_set,
+                &secret_key_share,
+                peer,
+                network_params,
+            )?;
             SectionTree::new(genesis_signed_sap)?
         };
         let mut network_knowledge = Self::new(Prefix::default(), section_tree)?;
@@ -304,6 +285,11 @@ impl NetworkKnowledge {
         &mut self.section_tree
     }
 
+    /// Return the params of the network, as fixed in its genesis SAP
+    pub fn network_params(&self) -> NetworkParams {
+        self.signed_sap.network_params()
+    }
+
     /// Return current section key
     pub fn section_key(&self) -> bls::PublicKey {
         self.signed_sap.section_key()
@@ -548,6 +534,18 @@ impl NetworkKnowledge {
     pub fn anti_entropy_probe(&self) -> NodeMsg {
         NodeMsg::AntiEntropyProbe(self.section_key())
     }
+
+    /// Returns the digest of our section key and current members, to be compared
+    /// with other members' to find out if either of us is missing an update.
+    pub fn knowledge_digest(&self) -> KnowledgeDigest {
+        let members: Vec<_> = self
+            .section_peers
+            .members()
+            .iter()
+            .map(|member| member.name())
+            .collect();
+        KnowledgeDigest::new(self.section_key(), &members)
+    }
 }
 
 // Create `SectionAuthorityProvider` for the first node.
@@ -555,6 +553,7 @@ fn create_first_section_authority_provider(
     pk_set: &bls::PublicKeySet,
     sk_share: &bls::SecretKeyShare,
     peer: Peer,
+    network_params: NetworkParams,
 ) -> Result<SectionSigned<SectionAuthorityProvider>> {
     let section_auth = SectionAuthorityProvider::new(
         iter::once(peer),
@@ -562,6 +561,7 @@ fn create_first_section_authority_provider(
         [NodeState::joined(peer, None)],
         pk_set.clone(),
         0,
+        network_params,
     );
     let sig = create_first_sig(pk_set, sk_share, &section_auth)?;
     Ok(SectionSigned::new(section_auth, sig))
@@ -586,7 +586,7 @@ fn create_first_sig<T: Serialize>(
 
 #[cfg(test)]
 mod tests {
-    use super::{supermajority, NetworkKnowledge};
+    use super::{supermajority, NetworkKnowledge, NetworkParams};
     use crate::{
         test_utils::{gen_addr, prefix, TestKeys, TestSapBuilder, TestSectionTree},
         types::Peer,
@@ -628,7 +628,8 @@ mod tests {
         let mut rng = thread_rng();
         let sk_gen = SecretKeySet::random(0, &mut rng);
         let peer = Peer::new(XorName::random(&mut rng), gen_addr());
-        let (mut knowledge, _) = NetworkKnowledge::first_node(peer, sk_gen.clone())?;
+        let (mut knowledge, _) =
+            NetworkKnowledge::first_node(peer, sk_gen.clone(), NetworkParams::default())?;
 
         // section 1
         let (sap1, sk_1, ..) = TestSapBuilder::new(prefix("1")).elder_count(0).build();
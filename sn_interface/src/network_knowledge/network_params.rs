@@ -0,0 +1,212 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{Error, Result};
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The minimum age a node becomes an adult node.
+pub const MIN_ADULT_AGE: u8 = 5;
+
+/// Number of elders per section.
+pub const DEFAULT_ELDER_COUNT: usize = 7;
+
+/// Number of copies of a chunk
+pub const DEFAULT_DATA_COPY_COUNT: usize = 4;
+
+const SN_ELDER_COUNT: &str = "SN_ELDER_COUNT";
+const SN_DATA_COPY_COUNT: &str = "SN_DATA_COPY_COUNT";
+
+/// Parameters shaping the network's sections, its data replication and its nodes' timings.
+///
+/// They are fixed by the genesis node in the genesis SAP, and carried over unchanged into every
+/// SAP after it, so any node or client can verify them against the section chain.
+/// SAPs with params which differ from the ones we know are refused.
+///
+/// Any value not present when deserialising falls back to its default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkParams {
+    /// Number of elders per section.
+    pub elder_count: usize,
+    /// A section splits once both halves would have at least this number of members.
+    pub recommended_section_size: usize,
+    /// The age nodes join the network with.
+    pub min_adult_age: u8,
+    /// Number of adults holding a copy of each chunk.
+    pub data_copy_count: usize,
+    /// Maximum number of nodes relocated on a single churn event.
+    pub max_relocations_per_churn: usize,
+    /// Interval between the AE probes elders send to other sections, in milliseconds.
+    pub probe_interval_ms: u64,
+    /// Time after which a relocating node retries joining its destination, in milliseconds.
+    pub relocation_timeout_ms: u64,
+    /// Interval between elders' checks for missing membership votes, in milliseconds.
+    pub missing_vote_interval_ms: u64,
+    /// Interval between elders' checks for missing DKG msgs, in milliseconds.
+    pub missing_dkg_msg_interval_ms: u64,
+}
+
+impl Default for NetworkParams {
+    fn default() -> Self {
+        Self {
+            elder_count: DEFAULT_ELDER_COUNT,
+            recommended_section_size: 2 * DEFAULT_ELDER_COUNT,
+            min_adult_age: MIN_ADULT_AGE,
+            data_copy_count: DEFAULT_DATA_COPY_COUNT,
+            max_relocations_per_churn: DEFAULT_ELDER_COUNT / 2,
+            probe_interval_ms: 300_000,
+            relocation_timeout_ms: 60_000,
+            missing_vote_interval_ms: 5_000,
+            missing_dkg_msg_interval_ms: 5_000,
+        }
+    }
+}
+
+impl NetworkParams {
+    /// Overrides the elder count and data copy count with the env vars `SN_ELDER_COUNT` and
+    /// `SN_DATA_COPY_COUNT`, when set.
+    /// The section size and relocation cap derived from the elder count follow an overridden one.
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Some(elder_count) = count_from_env(SN_ELDER_COUNT) {
+            self.elder_count = elder_count;
+            self.recommended_section_size = 2 * elder_count;
+            self.max_relocations_per_churn = elder_count / 2;
+        }
+        if let Some(data_copy_count) = count_from_env(SN_DATA_COPY_COUNT) {
+            self.data_copy_count = data_copy_count;
+        }
+        self
+    }
+
+    /// Checks the params can make up a working network.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| Err(Error::InvalidNetworkParams(reason.to_string()));
+
+        if self.elder_count == 0 {
+            return invalid("elder_count must be at least 1");
+        }
+        if self.recommended_section_size < self.elder_count {
+            return invalid("recommended_section_size must be at least elder_count");
+        }
+        if self.data_copy_count == 0 {
+            return invalid("data_copy_count must be at least 1");
+        }
+        if self.min_adult_age == 0 {
+            return invalid("min_adult_age must be at least 1");
+        }
+        if [
+            self.probe_interval_ms,
+            self.relocation_timeout_ms,
+            self.missing_vote_interval_ms,
+            self.missing_dkg_msg_interval_ms,
+        ]
+        .contains(&0)
+        {
+            return invalid("intervals and timeouts must be longer than 0ms");
+        }
+
+        Ok(())
+    }
+
+    /// Interval between the AE probes elders send to other sections.
+    pub fn probe_interval(&self) -> Duration {
+        Duration::from_millis(self.probe_interval_ms)
+    }
+
+    /// Time after which a relocating node retries joining its destination.
+    pub fn relocation_timeout(&self) -> Duration {
+        Duration::from_millis(self.relocation_timeout_ms)
+    }
+
+    /// Interval between elders' checks for missing membership votes.
+    pub fn missing_vote_interval(&self) -> Duration {
+        Duration::from_millis(self.missing_vote_interval_ms)
+    }
+
+    /// Interval between elders' checks for missing DKG msgs.
+    pub fn missing_dkg_msg_interval(&self) -> Duration {
+        Duration::from_millis(self.missing_dkg_msg_interval_ms)
+    }
+}
+
+fn count_from_env(var: &str) -> Option<usize> {
+    let count = std::env::var(var).ok()?;
+    match count.parse() {
+        Ok(count) => {
+            warn!("Count set from env var {var:?}={count}");
+            Some(count)
+        }
+        Err(error) => {
+            warn!("There was an error parsing {var:?} env var, it will be ignored: {error:?}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_values_fall_back_to_defaults() -> eyre::Result<()> {
+        let params: NetworkParams =
+            serde_json::from_str(r#"{ "elder_count": 3, "recommended_section_size": 6 }"#)?;
+
+        assert_eq!(params.elder_count, 3);
+        assert_eq!(params.recommended_section_size, 6);
+        assert_eq!(params.min_adult_age, MIN_ADULT_AGE);
+        assert_eq!(
+            params.probe_interval_ms,
+            NetworkParams::default().probe_interval_ms
+        );
+        params.validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn env_overrides_are_only_applied_when_asked_for() {
+        std::env::set_var(SN_ELDER_COUNT, "3");
+        std::env::set_var(SN_DATA_COPY_COUNT, "2");
+
+        let defaults = NetworkParams::default();
+        assert_eq!(defaults.elder_count, DEFAULT_ELDER_COUNT);
+        assert_eq!(defaults.data_copy_count, DEFAULT_DATA_COPY_COUNT);
+
+        let params = defaults.with_env_overrides();
+        std::env::remove_var(SN_ELDER_COUNT);
+        std::env::remove_var(SN_DATA_COPY_COUNT);
+
+        assert_eq!(params.elder_count, 3);
+        assert_eq!(params.recommended_section_size, 6);
+        assert_eq!(params.max_relocations_per_churn, 1);
+        assert_eq!(params.data_copy_count, 2);
+        assert_eq!(params.min_adult_age, defaults.min_adult_age);
+    }
+
+    #[test]
+    fn invalid_params_are_rejected() {
+        let params = NetworkParams {
+            elder_count: 7,
+            recommended_section_size: 5,
+            ..NetworkParams::default()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(Error::InvalidNetworkParams(_))
+        ));
+
+        let params = NetworkParams {
+            probe_interval_ms: 0,
+            ..NetworkParams::default()
+        };
+        assert!(params.validate().is_err());
+    }
+}
@@ -23,6 +23,8 @@ pub struct MyNodeInfo {
     #[debug(skip)]
     pub keypair: Arc<Keypair>,
     pub addr: SocketAddr,
+    /// The relay we are reachable through, if we cannot accept incoming connections.
+    pub relay: Option<SocketAddr>,
 }
 
 impl MyNodeInfo {
@@ -30,11 +32,15 @@ impl MyNodeInfo {
         Self {
             keypair: Arc::new(keypair),
             addr,
+            relay: None,
         }
     }
 
     pub fn peer(&self) -> Peer {
-        Peer::new(self.name(), self.addr)
+        match self.relay {
+            Some(relay) => Peer::new_relayed(self.name(), self.addr, relay),
+            None => Peer::new(self.name(), self.addr),
+        }
     }
 
     pub fn name(&self) -> XorName {
@@ -97,6 +97,7 @@ impl NodeState {
         prefix: &Prefix,
         members: &BTreeMap<XorName, Self>,
         archived: &BTreeSet<XorName>,
+        recommended_section_size: usize,
     ) -> Result<()> {
         let name = self.name();
         info!("Validating node state for {name} - {:?}", self.state);
@@ -111,7 +112,12 @@ impl NodeState {
                 if members.contains_key(&name) {
                     info!("Rejecting join from existing member {name}");
                     Err(Error::ExistingMemberConflict)
-                } else if !section_has_room_for_node(name, prefix, members.keys().copied()) {
+                } else if !section_has_room_for_node(
+                    name,
+                    prefix,
+                    members.keys().copied(),
+                    recommended_section_size,
+                ) {
                     info!("Rejecting join since we are at capacity");
                     Err(Error::TryJoinLater)
                 } else if let Some(existing_node) = members
@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::NodeState;
+use super::{NetworkParams, NodeState};
 use crate::{
     messaging::system::{DkgSessionId, SectionSig, SectionSigned},
     network_knowledge::SectionsDAG,
@@ -67,6 +67,8 @@ pub struct SectionAuthorityProvider {
     members: BTreeSet<NodeState>,
     /// The membership generation this SAP was instantiated on
     membership_gen: Generation,
+    /// The params of the network, as fixed in the genesis SAP
+    network_params: NetworkParams,
 }
 
 /// `SectionAuthorityProvider` candidates for handover consensus to vote on
@@ -141,13 +143,15 @@ impl Display for SectionAuthorityProvider {
 }
 
 impl SectionAuthorityProvider {
-    /// Creates a new `SectionAuthorityProvider` with the given members, prefix and public keyset.
+    /// Creates a new `SectionAuthorityProvider` with the given members, prefix and public keyset,
+    /// carrying the params of the network it's part of.
     pub fn new<E, M>(
         elders: E,
         prefix: Prefix,
         members: M,
         pk_set: PublicKeySet,
         membership_gen: Generation,
+        network_params: NetworkParams,
     ) -> Self
     where
         E: IntoIterator<Item = Peer>,
@@ -159,16 +163,22 @@ impl SectionAuthorityProvider {
             elders: elders.into_iter().collect(),
             members: members.into_iter().collect(),
             membership_gen,
+            network_params,
         }
     }
 
-    pub fn from_dkg_session(session_id: &DkgSessionId, pk_set: PublicKeySet) -> Self {
+    pub fn from_dkg_session(
+        session_id: &DkgSessionId,
+        pk_set: PublicKeySet,
+        network_params: NetworkParams,
+    ) -> Self {
         Self::new(
             session_id.elder_peers(),
             session_id.prefix,
             session_id.bootstrap_members.clone(),
             pk_set,
             session_id.membership_gen,
+            network_params,
         )
     }
 
@@ -189,6 +199,17 @@ impl SectionAuthorityProvider {
         self.membership_gen
     }
 
+    pub fn network_params(&self) -> NetworkParams {
+        self.network_params
+    }
+
+    /// Replaces the network params this SAP carries.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn with_network_params(mut self, network_params: NetworkParams) -> Self {
+        self.network_params = network_params;
+        self
+    }
+
     /// A convenience function since we often use SAP elders as recipients.
     pub fn elders_vec(&self) -> Vec<Peer> {
         self.elders.iter().cloned().collect()
@@ -237,8 +258,9 @@ impl SectionAuthorityProvider {
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils {
     use crate::{
-        elder_count,
-        network_knowledge::{supermajority, MyNodeInfo, NodeState, SectionAuthorityProvider},
+        network_knowledge::{
+            supermajority, MyNodeInfo, NetworkParams, NodeState, SectionAuthorityProvider,
+        },
         test_utils::gen_sorted_nodes,
     };
     use rand::{thread_rng, RngCore};
@@ -269,15 +291,17 @@ pub mod test_utils {
         sk_set: Option<bls::SecretKeySet>,
         sk_threshold_size: Option<usize>,
         elder_age_pattern: Option<Vec<u8>>,
+        network_params: NetworkParams,
     }
 
     impl TestSapBuilder {
         /// Set the `Prefix` of the SAP. Also initiates the SAP builder by providing default values
         /// to the configs.
         pub fn new(prefix: Prefix) -> Self {
+            let network_params = NetworkParams::default();
             Self {
                 prefix,
-                elder_count: elder_count(),
+                elder_count: network_params.elder_count,
                 adult_count: 0,
                 membership_gen: 0,
                 sk_threshold_size: None,
@@ -285,6 +309,7 @@ pub mod test_utils {
                 adult_nodes: None,
                 sk_set: None,
                 elder_age_pattern: None,
+                network_params,
             }
         }
 
@@ -352,6 +377,12 @@ pub mod test_utils {
             self
         }
 
+        /// Set the network params the SAP carries. The elder count isn't changed by them.
+        pub fn network_params(mut self, network_params: NetworkParams) -> Self {
+            self.network_params = network_params;
+            self
+        }
+
         /// Build the final SAP with the provided rng. Also returns the `SecretKeySet` used by the SAP along with the
         /// set of elder, adult nodes.
         ///
@@ -400,6 +431,7 @@ pub mod test_utils {
                 members,
                 sk_set.public_keys(),
                 self.membership_gen as u64,
+                self.network_params,
             );
             (sap, sk_set, elder_nodes, adult_nodes)
         }
@@ -0,0 +1,496 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! The file format network contacts are shared and persisted with.
+//!
+//! A contacts file starts with a human readable header, followed by an empty line and the
+//! serialised `SectionTree`:
+//!
+//! ```text
+//! safe-network-contacts v1
+//! genesis-key: <hex>
+//! created-at: <seconds since the UNIX epoch>
+//! section-count: <number of sections>
+//! publisher-key: <hex>
+//! signature: <hex>
+//!
+//! <SectionTree as json>
+//! ```
+//!
+//! The `publisher-key` and `signature` lines are optional. When present, the signature covers
+//! the rest of the header along with the exact bytes of the serialised `SectionTree`, so the
+//! file can be verified to come from a publisher we trust before we trust its genesis key.
+//! Files consisting of only the serialised `SectionTree`, as written by older versions, are
+//! still accepted, though they can't be signed.
+//!
+//! Later versions of the format may only add header lines, which older versions skip, so files
+//! of any version from `MIN_NETWORK_CONTACTS_FORMAT_VERSION` onwards can be read.
+
+use super::SectionTree;
+use crate::network_knowledge::{Error, Result};
+
+use bls::{PublicKey as BlsPublicKey, SecretKey as BlsSecretKey, Signature as BlsSignature};
+use serde::Serialize;
+use std::{
+    fmt::{self, Display, Formatter},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The current version of the network contacts file format.
+pub const NETWORK_CONTACTS_FORMAT_VERSION: u16 = 1;
+/// The oldest version of the network contacts file format which can still be read.
+pub const MIN_NETWORK_CONTACTS_FORMAT_VERSION: u16 = 1;
+
+const MAGIC: &str = "safe-network-contacts";
+const GENESIS_KEY: &str = "genesis-key";
+const CREATED_AT: &str = "created-at";
+const SECTION_COUNT: &str = "section-count";
+const PUBLISHER_KEY: &str = "publisher-key";
+const SIGNATURE: &str = "signature";
+
+/// The human readable header of a network contacts file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetworkContactsHeader {
+    /// Version of the format the file was written with.
+    pub version: u16,
+    /// Genesis key of the network the contacts are for.
+    pub genesis_key: BlsPublicKey,
+    /// When the file was created, in seconds since the UNIX epoch.
+    /// Unknown for files in the legacy format.
+    pub created_at: Option<u64>,
+    /// Number of sections in the contacts.
+    pub section_count: usize,
+}
+
+impl Display for NetworkContactsHeader {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let created_at = self
+            .created_at
+            .map_or_else(|| "unknown".to_string(), |secs| secs.to_string());
+        write!(
+            f,
+            "Network contacts v{} for genesis key {}, created at {created_at}, with {} section(s)",
+            self.version,
+            self.genesis_key.to_hex(),
+            self.section_count
+        )
+    }
+}
+
+/// Network contacts along with the header and optional publisher signature they are shared with.
+#[derive(Debug, Clone)]
+pub struct NetworkContacts {
+    header: NetworkContactsHeader,
+    publisher: Option<(BlsPublicKey, BlsSignature)>,
+    section_tree: SectionTree,
+    // the exact bytes the section tree was read from, or serialised to, which the signature covers
+    serialised_tree: Vec<u8>,
+}
+
+impl NetworkContacts {
+    /// Wraps the section tree as unsigned network contacts, created now.
+    pub fn new(section_tree: SectionTree) -> Result<Self> {
+        let serialised_tree =
+            serde_json::to_vec(&section_tree).map_err(|e| Error::Serialisation(e.to_string()))?;
+        Ok(Self {
+            header: header_of(&section_tree, now()),
+            publisher: None,
+            section_tree,
+            serialised_tree,
+        })
+    }
+
+    /// Signs the contacts as published by the owner of `publisher_sk`,
+    /// replacing any previous signature.
+    pub fn sign(&mut self, publisher_sk: &BlsSecretKey) -> Result<()> {
+        let publisher = publisher_sk.public_key();
+        let signature = publisher_sk.sign(self.signed_bytes(&publisher)?);
+        self.publisher = Some((publisher, signature));
+        Ok(())
+    }
+
+    /// Verifies the contacts can be trusted: the header has to match the contacts,
+    /// and every section key in the `SectionsDAG` has to be signed by its parent key.
+    ///
+    /// Signed contacts can only be trusted if they are signed by the `trusted_publisher`, as
+    /// anyone can sign a file with their own key, thus they are rejected if it's not provided.
+    /// Contacts pruned to checkpoints have to be signed.
+    pub fn verify(&self, trusted_publisher: Option<&BlsPublicKey>) -> Result<()> {
+        if self.header.version < MIN_NETWORK_CONTACTS_FORMAT_VERSION {
+            return Err(Error::InvalidNetworkContacts(format!(
+                "format version {} is no longer supported, the oldest supported is {MIN_NETWORK_CONTACTS_FORMAT_VERSION}",
+                self.header.version
+            )));
+        }
+
+        // the version is the one the file was written with, which may be newer than ours
+        let expected_header = NetworkContactsHeader {
+            version: self.header.version,
+            ..header_of(&self.section_tree, self.header.created_at)
+        };
+        if self.header != expected_header {
+            return Err(Error::InvalidNetworkContacts(format!(
+                "the header doesn't match the contacts. Header: {}, contacts: {}",
+                self.header, expected_header
+            )));
+        }
+
+        match (&self.publisher, trusted_publisher) {
+            (Some((publisher, signature)), Some(trusted)) => {
+                if !publisher.verify(signature, self.signed_bytes(publisher)?) {
+                    return Err(Error::InvalidNetworkContacts(format!(
+                        "invalid signature by publisher {}",
+                        publisher.to_hex()
+                    )));
+                }
+                if trusted != publisher {
+                    return Err(Error::InvalidNetworkContacts(format!(
+                        "signed by publisher {} instead of the trusted publisher {}",
+                        publisher.to_hex(),
+                        trusted.to_hex()
+                    )));
+                }
+            }
+            (Some((publisher, _)), None) => {
+                return Err(Error::InvalidNetworkContacts(format!(
+                    "signed by publisher {}, though no trusted publisher key was provided to \
+                    verify it against. A signature by an unknown key proves nothing",
+                    publisher.to_hex()
+                )))
+            }
+            (None, Some(trusted)) => {
+                return Err(Error::InvalidNetworkContacts(format!(
+                    "not signed, while expected to be signed by publisher {}",
+                    trusted.to_hex()
+                )))
+            }
+            (None, None) => {}
+        }
+
+        let sections_dag = self.section_tree.get_sections_dag();
+        if self.publisher.is_none() && sections_dag.checkpoints().next().is_some() {
+            return Err(Error::InvalidNetworkContacts(
+                "pruned contacts have to be signed by their publisher, as their checkpoints are not \
+                proven by the genesis key"
+                    .to_string(),
+            ));
+        }
+
+        if !sections_dag.self_verify() {
+            return Err(Error::InvalidNetworkContacts(
+                "the SectionsDAG contains keys which are not signed by their parent".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The header of the contacts.
+    pub fn header(&self) -> &NetworkContactsHeader {
+        &self.header
+    }
+
+    /// The key of the publisher which signed the contacts, if signed.
+    pub fn publisher(&self) -> Option<&BlsPublicKey> {
+        self.publisher.as_ref().map(|(publisher, _)| publisher)
+    }
+
+    /// The contacts.
+    pub fn section_tree(&self) -> &SectionTree {
+        &self.section_tree
+    }
+
+    /// Consumes the file, returning the contacts.
+    pub fn into_section_tree(self) -> SectionTree {
+        self.section_tree
+    }
+
+    /// Serialises the contacts into the contacts file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        file_bytes(&self.header, self.publisher.as_ref(), &self.serialised_tree)
+    }
+
+    /// Reads contacts in either the contacts file format or the legacy format from disk.
+    ///
+    /// This does not verify the contacts, see [`NetworkContacts::verify`].
+    pub async fn from_disk(path: &Path) -> Result<Self> {
+        Self::from_bytes(&super::read_file(path).await?)
+    }
+
+    /// Writes the contacts to disk in the contacts file format.
+    pub async fn write_to_disk(&self, path: &Path) -> Result<()> {
+        super::write_file(path, &self.to_bytes()).await
+    }
+
+    /// Deserialises contacts from the contacts file format, or from the legacy format which
+    /// consists of only the serialised `SectionTree`.
+    ///
+    /// This does not verify the contacts, see [`NetworkContacts::verify`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if !bytes.starts_with(MAGIC.as_bytes()) {
+            let section_tree = serde_json::from_slice::<SectionTree>(bytes)
+                .map_err(|err| Error::Deserialisation(err.to_string()))?;
+            return Ok(Self {
+                header: header_of(&section_tree, None),
+                publisher: None,
+                section_tree,
+                serialised_tree: bytes.to_vec(),
+            });
+        }
+
+        let header_end = bytes
+            .windows(2)
+            .position(|window| window == b"\n\n")
+            .ok_or_else(|| malformed("missing the empty line ending the header"))?;
+        let header = std::str::from_utf8(&bytes[..header_end])
+            .map_err(|_| malformed("the header is not valid UTF-8"))?;
+        let serialised_tree = bytes[header_end + 2..].to_vec();
+
+        let mut lines = header.lines();
+        let version = lines
+            .next()
+            .and_then(|line| line.strip_prefix(MAGIC))
+            .and_then(|version| version.trim().strip_prefix('v'))
+            .and_then(|version| version.parse::<u16>().ok())
+            .ok_or_else(|| malformed("invalid format version"))?;
+        if version < MIN_NETWORK_CONTACTS_FORMAT_VERSION {
+            return Err(malformed(&format!(
+                "unsupported format version {version}, the oldest supported is {MIN_NETWORK_CONTACTS_FORMAT_VERSION}"
+            )));
+        }
+
+        let mut genesis_key = None;
+        let mut created_at = None;
+        let mut section_count = None;
+        let mut publisher_key = None;
+        let mut signature = None;
+        for line in lines {
+            let (field, value) = line
+                .split_once(':')
+                .ok_or_else(|| malformed(&format!("invalid header line '{line}'")))?;
+            let value = value.trim();
+            match field.trim() {
+                GENESIS_KEY => genesis_key = Some(parse_public_key(GENESIS_KEY, value)?),
+                CREATED_AT => created_at = Some(parse_number(CREATED_AT, value)?),
+                SECTION_COUNT => section_count = Some(parse_number(SECTION_COUNT, value)?),
+                PUBLISHER_KEY => publisher_key = Some(parse_public_key(PUBLISHER_KEY, value)?),
+                SIGNATURE => signature = Some(parse_signature(value)?),
+                // unknown fields are skipped, to allow for additions in later versions
+                _ => {}
+            }
+        }
+
+        let publisher = match (publisher_key, signature) {
+            (Some(publisher), Some(signature)) => Some((publisher, signature)),
+            (None, None) => None,
+            _ => {
+                return Err(malformed(&format!(
+                    "{PUBLISHER_KEY} and {SIGNATURE} must be provided together"
+                )))
+            }
+        };
+
+        let section_tree = serde_json::from_slice::<SectionTree>(&serialised_tree)
+            .map_err(|err| Error::Deserialisation(err.to_string()))?;
+
+        Ok(Self {
+            header: NetworkContactsHeader {
+                version,
+                genesis_key: genesis_key
+                    .ok_or_else(|| malformed(&format!("missing {GENESIS_KEY}")))?,
+                created_at,
+                section_count: section_count
+                    .ok_or_else(|| malformed(&format!("missing {SECTION_COUNT}")))?,
+            },
+            publisher,
+            section_tree,
+            serialised_tree,
+        })
+    }
+
+    // The header (bar the signature), including the publisher's own key, and the serialised
+    // contacts, as signed by the publisher.
+    fn signed_bytes(&self, publisher: &BlsPublicKey) -> Result<Vec<u8>> {
+        bincode::serialize(&(&self.header, publisher, &self.serialised_tree))
+            .map_err(|e| Error::Serialisation(e.to_string()))
+    }
+}
+
+/// The section tree serialised as unsigned contacts, created now.
+pub(super) fn unsigned_bytes(section_tree: &SectionTree) -> Result<Vec<u8>> {
+    let serialised_tree =
+        serde_json::to_vec(section_tree).map_err(|e| Error::Serialisation(e.to_string()))?;
+    let header = header_of(section_tree, now());
+    Ok(file_bytes(&header, None, &serialised_tree))
+}
+
+fn file_bytes(
+    header: &NetworkContactsHeader,
+    publisher: Option<&(BlsPublicKey, BlsSignature)>,
+    serialised_tree: &[u8],
+) -> Vec<u8> {
+    let mut text = format!("{MAGIC} v{}\n", header.version);
+    text.push_str(&format!("{GENESIS_KEY}: {}\n", header.genesis_key.to_hex()));
+    if let Some(created_at) = header.created_at {
+        text.push_str(&format!("{CREATED_AT}: {created_at}\n"));
+    }
+    text.push_str(&format!("{SECTION_COUNT}: {}\n", header.section_count));
+    if let Some((publisher, signature)) = publisher {
+        text.push_str(&format!("{PUBLISHER_KEY}: {}\n", publisher.to_hex()));
+        text.push_str(&format!(
+            "{SIGNATURE}: {}\n",
+            hex::encode(signature.to_bytes())
+        ));
+    }
+    text.push('\n');
+
+    let mut bytes = text.into_bytes();
+    bytes.extend_from_slice(serialised_tree);
+    bytes
+}
+
+fn now() -> Option<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .ok()
+}
+
+fn header_of(section_tree: &SectionTree, created_at: Option<u64>) -> NetworkContactsHeader {
+    NetworkContactsHeader {
+        version: NETWORK_CONTACTS_FORMAT_VERSION,
+        genesis_key: *section_tree.genesis_key(),
+        created_at,
+        section_count: section_tree.all().count(),
+    }
+}
+
+fn parse_public_key(field: &str, value: &str) -> Result<BlsPublicKey> {
+    BlsPublicKey::from_hex(value).map_err(|_| malformed(&format!("invalid {field}")))
+}
+
+fn parse_signature(value: &str) -> Result<BlsSignature> {
+    let bytes = hex::decode(value)
+        .ok()
+        .and_then(|bytes| <[u8; bls::SIG_SIZE]>::try_from(bytes).ok())
+        .ok_or_else(|| malformed(&format!("invalid {SIGNATURE}")))?;
+    BlsSignature::from_bytes(bytes).map_err(|_| malformed(&format!("invalid {SIGNATURE}")))
+}
+
+fn parse_number<T: std::str::FromStr>(field: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| malformed(&format!("invalid {field}")))
+}
+
+fn malformed(reason: &str) -> Error {
+    Error::Deserialisation(format!("Malformed network contacts file: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestSectionTree;
+    use eyre::Result;
+
+    #[test]
+    fn roundtrip_signed_contacts() -> Result<()> {
+        let (tree, _) = TestSectionTree::random_tree();
+        let publisher_sk = BlsSecretKey::random();
+        let mut contacts = NetworkContacts::new(tree)?;
+        contacts.sign(&publisher_sk)?;
+
+        let bytes = contacts.to_bytes();
+        let read = NetworkContacts::from_bytes(&bytes)?;
+        assert_eq!(read.header(), contacts.header());
+        assert_eq!(read.publisher(), Some(&publisher_sk.public_key()));
+        read.verify(Some(&publisher_sk.public_key()))?;
+
+        // the header is human readable
+        let header = String::from_utf8_lossy(&bytes);
+        assert!(header.starts_with("safe-network-contacts v1\n"));
+        assert!(header.contains(&format!(
+            "genesis-key: {}",
+            read.section_tree().genesis_key().to_hex()
+        )));
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_contacts_are_rejected() -> Result<()> {
+        let (tree, _) = TestSectionTree::random_tree();
+        let publisher_sk = BlsSecretKey::random();
+        let mut contacts = NetworkContacts::new(tree)?;
+        contacts.sign(&publisher_sk)?;
+
+        // claim a different creation time than the one signed
+        let bytes = String::from_utf8(contacts.to_bytes())?;
+        let created_at = contacts.header().created_at.unwrap_or_default();
+        let tampered = bytes.replacen(
+            &format!("created-at: {created_at}"),
+            &format!("created-at: {}", created_at + 1),
+            1,
+        );
+        let read = NetworkContacts::from_bytes(tampered.as_bytes())?;
+        assert!(matches!(
+            read.verify(None),
+            Err(Error::InvalidNetworkContacts(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn untrusted_publisher_is_rejected() -> Result<()> {
+        let (tree, _) = TestSectionTree::random_tree();
+        let mut contacts = NetworkContacts::new(tree)?;
+
+        let trusted = BlsSecretKey::random().public_key();
+        assert!(contacts.verify(Some(&trusted)).is_err());
+
+        // a signature can't be trusted without knowing who the publisher is meant to be
+        contacts.sign(&BlsSecretKey::random())?;
+        assert!(contacts.verify(None).is_err());
+        assert!(contacts.verify(Some(&trusted)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn newer_format_versions_are_accepted() -> Result<()> {
+        let (tree, _) = TestSectionTree::random_tree();
+        let contacts = NetworkContacts::new(tree)?;
+
+        // a later version with a header line we don't know about
+        let bytes = String::from_utf8(contacts.to_bytes())?;
+        let newer = bytes.replacen(
+            "safe-network-contacts v1\n",
+            &format!(
+                "safe-network-contacts v{}\nsome-new-field: some value\n",
+                NETWORK_CONTACTS_FORMAT_VERSION + 1
+            ),
+            1,
+        );
+        let read = NetworkContacts::from_bytes(newer.as_bytes())?;
+        assert_eq!(read.header().version, NETWORK_CONTACTS_FORMAT_VERSION + 1);
+        read.verify(None)?;
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_contacts_are_accepted() -> Result<()> {
+        let (tree, _) = TestSectionTree::random_tree();
+        let legacy = serde_json::to_vec(&tree)?;
+
+        let read = NetworkContacts::from_bytes(&legacy)?;
+        assert_eq!(read.header().created_at, None);
+        assert_eq!(read.publisher(), None);
+        assert_eq!(read.section_tree().genesis_key(), tree.genesis_key());
+        read.verify(None)?;
+        Ok(())
+    }
+}
@@ -15,13 +15,20 @@
 //! covered and is automatically removed.
 //!
 
+mod contacts_file;
 mod stats;
 
+pub use self::contacts_file::{
+    NetworkContacts, NetworkContactsHeader, MIN_NETWORK_CONTACTS_FORMAT_VERSION,
+    NETWORK_CONTACTS_FORMAT_VERSION,
+};
+
 use self::stats::NetworkStats;
 
 use crate::messaging::system::SectionSigned;
 use crate::network_knowledge::{
-    Error, Result, SectionAuthUtils, SectionAuthorityProvider, SectionsDAG,
+    Error, NetworkParams, Result, SectionAuthUtils, SectionAuthorityProvider, SectionsCheckpoint,
+    SectionsDAG,
 };
 
 use bls::PublicKey as BlsPublicKey;
@@ -37,6 +44,10 @@ use tempfile::NamedTempFile;
 use tokio::{fs, io::AsyncReadExt};
 use xor_name::{Prefix, XorName};
 
+/// How many of the latest keys of each section chain are kept in the `SectionsDAG`, the older
+/// ones being pruned to a checkpoint, see [`SectionsDAG::checkpoints_for_depth`].
+const SECTIONS_DAG_CHECKPOINT_DEPTH: usize = 256;
+
 /// Container for storing information about other sections in the network.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SectionTree {
@@ -60,34 +71,17 @@ impl SectionTree {
         })
     }
 
-    /// Create a new SectionTree deserialised from bytes
+    /// Create a new SectionTree deserialised from bytes, in either the network contacts file
+    /// format or the legacy format.
+    ///
+    /// The contacts are not verified, see [`NetworkContacts::verify`] for that.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        serde_json::from_slice(bytes).map_err(|err| Error::Deserialisation(err.to_string()))
+        Ok(NetworkContacts::from_bytes(bytes)?.into_section_tree())
     }
 
     /// Create a new SectionTree deserialised from a file
     pub async fn from_disk(path: &Path) -> Result<Self> {
-        let mut section_tree_file = fs::File::open(path).await.map_err(|err| {
-            Error::FileHandling(format!(
-                "Error opening SectionTree file from {}: {:?}",
-                path.display(),
-                err
-            ))
-        })?;
-
-        let mut section_tree_content = vec![];
-        let _ = section_tree_file
-            .read_to_end(&mut section_tree_content)
-            .await
-            .map_err(|err| {
-                Error::FileHandling(format!(
-                    "Error reading SectionTree from {}: {:?}",
-                    path.display(),
-                    err
-                ))
-            })?;
-
-        Self::from_bytes(&section_tree_content)
+        Self::from_bytes(&read_file(path).await?)
     }
 
     /// Returns the genesis key of the Network
@@ -95,6 +89,15 @@ impl SectionTree {
         self.sections_dag.genesis_key()
     }
 
+    /// Returns the params of the Network, as carried by all of its SAPs
+    pub fn network_params(&self) -> NetworkParams {
+        self.sections
+            .values()
+            .next()
+            .map(|sap| sap.network_params())
+            .unwrap_or_default()
+    }
+
     pub fn get_sections_dag(&self) -> &SectionsDAG {
         &self.sections_dag
     }
@@ -216,6 +219,8 @@ impl SectionTree {
     ) -> Result<bool> {
         let signed_sap = section_tree_update.signed_sap;
         let proof_chain = section_tree_update.proof_chain;
+        // the checkpoints the sender pruned the proof chain to, which we can prune to as well
+        let checkpoints = proof_chain.signed_checkpoints();
 
         if self.sections_dag.has_key(&signed_sap.value.section_key()) {
             info!(
@@ -275,6 +280,14 @@ impl SectionTree {
                 signed_sap.value
             )));
         }
+        // All SAPs carry the params fixed in the genesis SAP
+        let expected = self.network_params();
+        if signed_sap.network_params() != expected {
+            return Err(Error::NetworkParamsMismatch {
+                expected: Box::new(expected),
+                found: Box::new(signed_sap.network_params()),
+            });
+        }
         // SAP's key should be the last key of the proof chain
         if proof_chain.last_key()? != signed_sap.section_key() {
             return Err(Error::UntrustedProofChain(format!(
@@ -292,6 +305,7 @@ impl SectionTree {
             // where we have outdated SAP (aware of prefix)/ not aware of the prefix, we have the
             // proof chain's genesis key in our sections_dag.
             self.sections_dag.merge(proof_chain)?;
+            self.prune_to_verified_checkpoints(checkpoints);
             for (prefix, section_key) in &self.sections {
                 debug!("Known prefix, section_key after update: {prefix:?} = {section_key:?}");
             }
@@ -303,58 +317,77 @@ impl SectionTree {
         }
     }
 
+    /// Prunes the keys older than the checkpoints from our `SectionsDAG`, which shrinks both the
+    /// network contacts and the proof chains of the updates we generate, see
+    /// [`SectionsDAG::prune`]. Returns `Error::InvalidCheckpoint` if the key of any of the SAPs
+    /// we know would be pruned.
+    pub fn prune_to_checkpoints<I>(&mut self, checkpoints: I) -> Result<()>
+    where
+        I: IntoIterator<Item = SectionsCheckpoint>,
+    {
+        let mut sections_dag = self.sections_dag.clone();
+        sections_dag.prune(checkpoints)?;
+
+        if let Some(sap) = self
+            .sections
+            .values()
+            .find(|sap| !sections_dag.has_key(&sap.section_key()))
+        {
+            return Err(Error::InvalidCheckpoint(format!(
+                "it's newer than the key of the SAP we know for {:?}",
+                sap.prefix()
+            )));
+        }
+
+        self.sections_dag = sections_dag;
+        Ok(())
+    }
+
+    // Prunes our `SectionsDAG` to the checkpoints which can be verified with it, along with the
+    // checkpoints for the section chains which have grown too long. Pruning is skipped if it
+    // would prune the key of any of the SAPs we know, as they may be lagging behind.
+    fn prune_to_verified_checkpoints(&mut self, checkpoints: Vec<SectionsCheckpoint>) {
+        let mut checkpoints: Vec<_> = checkpoints
+            .into_iter()
+            .filter(|checkpoint| {
+                !self
+                    .sections_dag
+                    .checkpoints()
+                    .any(|key| key == &checkpoint.key)
+                    && self.sections_dag.verify_checkpoint(checkpoint)
+            })
+            .collect();
+        match self
+            .sections_dag
+            .checkpoints_for_depth(SECTIONS_DAG_CHECKPOINT_DEPTH)
+        {
+            Ok(ours) => checkpoints.extend(ours),
+            Err(error) => warn!("Failed to checkpoint our SectionsDAG: {error:?}"),
+        }
+
+        if checkpoints.is_empty() {
+            return;
+        }
+        match self.prune_to_checkpoints(checkpoints) {
+            Ok(()) => debug!(
+                "Pruned our SectionsDAG to checkpoints: {:?}",
+                self.sections_dag.checkpoints().collect::<Vec<_>>()
+            ),
+            Err(error) => debug!("Not pruning our SectionsDAG: {error:?}"),
+        }
+    }
+
     /// For testing purpose, we may need to populate a `section_tree` without a proof chain.
     #[cfg(any(test, feature = "test-utils"))]
     pub fn insert_without_chain(&mut self, sap: SectionSigned<SectionAuthorityProvider>) -> bool {
         self.insert(sap)
     }
 
-    /// Serialise and write it to disk on the provided file path
+    /// Serialise it as unsigned network contacts and write it to disk on the provided file path
     pub async fn write_to_disk(&self, path: &Path) -> Result<()> {
         trace!("Writing section tree to disk at {}", path.display());
-        let parent_path = if let Some(parent_path) = path.parent() {
-            fs::create_dir_all(parent_path).await.map_err(|err| {
-                Error::DirectoryHandling(format!(
-                    "Could not create '{}' parent directory path: {}",
-                    path.display(),
-                    err,
-                ))
-            })?;
-            parent_path
-        } else {
-            Path::new(".")
-        };
-
-        let mut temp_file = NamedTempFile::new_in(parent_path).map_err(|e| {
-            Error::FileHandling(format!(
-                "Error creating tempfile at {}: {:?}",
-                parent_path.display(),
-                e
-            ))
-        })?;
-
-        let serialized =
-            serde_json::to_vec(self).map_err(|e| Error::Serialisation(e.to_string()))?;
-
-        temp_file.write_all(serialized.as_slice()).map_err(|e| {
-            Error::FileHandling(format!(
-                "Error writing tempfile at {}: {:?}",
-                temp_file.path().display(),
-                e
-            ))
-        })?;
-
-        fs::rename(temp_file.path(), &path).await.map_err(|e| {
-            Error::FileHandling(format!(
-                "Error renaming tempfile from {} to {}: {:?}",
-                temp_file.path().display(),
-                path.display(),
-                e
-            ))
-        })?;
-
+        write_file(path, &contacts_file::unsigned_bytes(self)?).await?;
         trace!("Wrote SectionTree to disk: {}", path.display());
-
         Ok(())
     }
 
@@ -467,6 +500,68 @@ impl SectionTreeUpdate {
     }
 }
 
+async fn read_file(path: &Path) -> Result<Vec<u8>> {
+    let mut file = fs::File::open(path).await.map_err(|err| {
+        Error::FileHandling(format!(
+            "Error opening SectionTree file from {}: {:?}",
+            path.display(),
+            err
+        ))
+    })?;
+
+    let mut content = vec![];
+    let _ = file.read_to_end(&mut content).await.map_err(|err| {
+        Error::FileHandling(format!(
+            "Error reading SectionTree from {}: {:?}",
+            path.display(),
+            err
+        ))
+    })?;
+
+    Ok(content)
+}
+
+// Writes to a tempfile which is then renamed, so the file is never left partially written.
+async fn write_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    let parent_path = if let Some(parent_path) = path.parent() {
+        fs::create_dir_all(parent_path).await.map_err(|err| {
+            Error::DirectoryHandling(format!(
+                "Could not create '{}' parent directory path: {}",
+                path.display(),
+                err,
+            ))
+        })?;
+        parent_path
+    } else {
+        Path::new(".")
+    };
+
+    let mut temp_file = NamedTempFile::new_in(parent_path).map_err(|e| {
+        Error::FileHandling(format!(
+            "Error creating tempfile at {}: {:?}",
+            parent_path.display(),
+            e
+        ))
+    })?;
+
+    temp_file.write_all(bytes).map_err(|e| {
+        Error::FileHandling(format!(
+            "Error writing tempfile at {}: {:?}",
+            temp_file.path().display(),
+            e
+        ))
+    })?;
+
+    fs::rename(temp_file.path(), &path).await.map_err(|e| {
+        Error::FileHandling(format!(
+            "Error renaming tempfile from {} to {}: {:?}",
+            temp_file.path().display(),
+            path.display(),
+            e
+        ))
+    })
+}
+
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils {
 
@@ -831,6 +926,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn sap_with_different_network_params_should_result_in_error_during_update() -> Result<()> {
+        let (mut tree, genesis_sk) = TestSectionTree::random_tree();
+
+        let (sap0, sk0) = random_signed_sap(prefix("0"));
+        let params = NetworkParams {
+            elder_count: sap0.network_params().elder_count + 1,
+            ..sap0.network_params()
+        };
+        let sap0 = TestKeys::get_section_signed(&sk0, sap0.value.with_network_params(params));
+        let tree_update =
+            TestSectionTree::get_section_tree_update(&sap0, tree.get_sections_dag(), &genesis_sk);
+        assert!(matches!(
+            tree.update_the_section_tree(tree_update),
+            Err(Error::NetworkParamsMismatch { .. })
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn outdated_sap_result_in_no_update() -> Result<()> {
         let (mut tree, genesis_sk) = TestSectionTree::random_tree();
@@ -857,6 +972,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn checkpoints_of_the_proof_chain_are_adopted_during_update() -> Result<()> {
+        let (mut tree, genesis_sk) = TestSectionTree::random_tree();
+
+        // gen -> sap0 -> sap1
+        let (sap0, sk0) = random_signed_sap(prefix("0"));
+        let tree_update =
+            TestSectionTree::get_section_tree_update(&sap0, tree.get_sections_dag(), &genesis_sk);
+        assert!(tree.update_the_section_tree(tree_update)?);
+        let (sap1, sk1) = random_signed_sap(prefix("0"));
+        let tree_update =
+            TestSectionTree::get_section_tree_update(&sap1, tree.get_sections_dag(), &sk0);
+        assert!(tree.update_the_section_tree(tree_update)?);
+
+        // the sender has pruned its DAG to sap1, which was signed by sap0
+        let mut pruned = tree.get_sections_dag().clone();
+        pruned.prune([pruned.checkpoint(&sap1.section_key())?])?;
+
+        let (sap2, _) = random_signed_sap(prefix("0"));
+        let tree_update = TestSectionTree::get_section_tree_update(&sap2, &pruned, &sk1);
+        assert!(tree.update_the_section_tree(tree_update)?);
+
+        assert_eq!(tree.get(&prefix("0")), Some(sap2.value.clone()));
+        assert_lists(
+            tree.get_sections_dag().checkpoints().copied(),
+            [sap1.section_key()],
+        );
+        assert!(!tree.get_sections_dag().has_key(&sap0.section_key()));
+
+        Ok(())
+    }
+
     // Proptest which updates the `SectionTree` using randomized length/order of proof_chain. Error cases, no update cases
     // are ignored, i.e., each update results in a new SAP being added. At the end of each update verify that the
     // leaves of `SectionTree::sections_dag` are the keys of all the `SectionTree::sections` (SAPs). After all the
@@ -49,19 +49,54 @@ impl Sha3Hash for SectionInfo {
     }
 }
 
+/// A section key the keys older than which can be dropped from a `SectionsDAG`, along with the
+/// proof it was agreed by a supermajority of the elders of the section, i.e. the signature over
+/// it by its parent section key.
+///
+/// A `SectionsDAG` only prunes to checkpoints signed by the parent of the checkpoint key in
+/// that same DAG, see [`SectionsDAG::prune`]. As the checkpoint key is then no longer proven
+/// by the genesis key, proofs going through it are only trusted by those who already know the
+/// checkpoint key.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SectionsCheckpoint {
+    /// Genesis key of the network.
+    pub genesis_key: bls::PublicKey,
+    /// The checkpoint section key.
+    pub key: bls::PublicKey,
+    /// The parent section key which signed the checkpoint key.
+    pub signer: bls::PublicKey,
+    /// Signature by the `signer` over the checkpoint key.
+    pub sig: bls::Signature,
+}
+
+impl SectionsCheckpoint {
+    /// Returns whether the checkpoint key is signed by the `signer`.
+    pub fn verify(&self) -> bool {
+        bincode::serialize(&self.key)
+            .map(|bytes| self.signer.verify(&self.sig, bytes))
+            .unwrap_or(false)
+    }
+}
+
 /// A Merkle DAG of BLS keys where every key is signed by its parent key, except the genesis one.
+/// Once pruned, checkpoint keys are children of the genesis key, signed by their pruned parent.
 #[derive(Clone, PartialEq, Eq)]
 pub struct SectionsDAG {
     genesis_key: bls::PublicKey,
     dag: MerkleReg<SectionInfo>,
     dag_root: BTreeSet<bls::PublicKey>,
     hashes: BTreeMap<bls::PublicKey, Hash>,
+    // keys whose ancestors were pruned, along with the pruned parent key which signed them
+    checkpoints: BTreeMap<bls::PublicKey, bls::PublicKey>,
 }
 
 #[derive(Deserialize, Serialize)]
 struct Intermediate {
     genesis_key: bls::PublicKey,
     sections: Vec<(bls::PublicKey, SectionInfo)>,
+    // (checkpoint key, pruned parent key which signed it)
+    #[serde(default)]
+    checkpoints: Vec<(bls::PublicKey, bls::PublicKey)>,
 }
 
 impl Serialize for SectionsDAG {
@@ -99,6 +134,7 @@ impl Serialize for SectionsDAG {
         let inter = Intermediate {
             genesis_key: self.genesis_key,
             sections,
+            checkpoints: self.checkpoints.clone().into_iter().collect(),
         };
         inter.serialize(serializer)
     }
@@ -111,6 +147,7 @@ impl<'de> Deserialize<'de> for SectionsDAG {
     {
         let inter: Intermediate = Deserialize::deserialize(deserializer)?;
         let mut dag = SectionsDAG::new(inter.genesis_key);
+        dag.checkpoints = inter.checkpoints.into_iter().collect();
         for (parent, info) in inter.sections {
             dag.verify_and_insert(&parent, info.key, info.sig)
                 .map_err(D::Error::custom)?;
@@ -155,6 +192,7 @@ impl SectionsDAG {
             dag: MerkleReg::new(),
             dag_root: BTreeSet::new(),
             hashes: BTreeMap::new(),
+            checkpoints: BTreeMap::new(),
         }
     }
 
@@ -254,6 +292,12 @@ impl SectionsDAG {
         let mut parent = *from;
         for node in crdt_ops.into_iter().rev() {
             let key = node.value.key;
+            // the branch can only go through a checkpoint of ours if anchored at genesis
+            if parent == self.genesis_key {
+                if let Some(signer) = self.checkpoints.get(&key) {
+                    let _ = dag.checkpoints.insert(key, *signer);
+                }
+            }
             dag.insert_trusted_key(&parent, node.value.key, node.value.sig)?;
             parent = key;
         }
@@ -299,12 +343,21 @@ impl SectionsDAG {
     /// Returns `Error::InvalidSignature` if the provided DAG fails signature verification
     /// Returns `Error::KeyNotFound` if the genesis_key of either of the DAGs is not present in the
     /// other
+    /// Returns `Error::UntrustedProofChain` if the provided DAG goes through a checkpoint we don't
+    /// know of, as it's not proven by the genesis key.
+    ///
+    /// The keys older than our checkpoints are not inserted, unless needed by other branches.
     pub fn merge(&mut self, mut other: Self) -> Result<()> {
         if !other.self_verify() {
             return Err(Error::UntrustedProofChain(format!(
                 "Proof chain failed self verification: {other:?}",
             )));
         }
+        if let Some(checkpoint) = other.checkpoints.keys().find(|key| !self.has_key(key)) {
+            return Err(Error::UntrustedProofChain(format!(
+                "Proof chain goes through an unknown checkpoint {checkpoint:?}: {other:?}",
+            )));
+        }
         // find which DAG is the parent
         if !self.has_key(other.genesis_key()) {
             if other.has_key(self.genesis_key()) {
@@ -313,7 +366,8 @@ impl SectionsDAG {
                 return Err(Error::KeyNotFound(self.genesis_key));
             }
         }
-        // insert all the nodes from `other`
+        // insert all the nodes from `other`, bar the ones we already have or have pruned
+        let pruned = other.prunable_keys(&self.checkpoints.keys().copied().collect())?;
         let mut need_to_visit: Vec<(bls::PublicKey, Node<SectionInfo>)> = Vec::new();
         for key in &other.dag_root {
             need_to_visit.push((*other.genesis_key(), other.get_node(key)?));
@@ -325,6 +379,10 @@ impl SectionsDAG {
                     .into_iter()
                     .map(|child_node| (current_node.value.key, child_node)),
             );
+            let key = current_node.value.key;
+            if self.has_key(&key) || pruned.contains(&key) {
+                continue;
+            }
             self.insert_node(&parent_key, current_node)?;
         }
 
@@ -396,12 +454,149 @@ impl SectionsDAG {
         })
     }
 
-    /// Returns `true` if the `genesis_key` is present in the list of `trusted_keys`
+    /// Returns `true` if the `genesis_key` is present in the list of `trusted_keys`, which for a
+    /// proof chain anchored at a checkpoint is the checkpoint key.
+    /// Any checkpoint the DAG goes through also has to be trusted, as it's not proven by the
+    /// `genesis_key`.
     pub fn check_trust<I>(&self, trusted_keys: I) -> bool
     where
         I: IntoIterator<Item = bls::PublicKey>,
     {
-        trusted_keys.into_iter().any(|k| k == self.genesis_key)
+        let trusted_keys: BTreeSet<_> = trusted_keys.into_iter().collect();
+        trusted_keys.contains(&self.genesis_key)
+            && self
+                .checkpoints
+                .keys()
+                .all(|key| trusted_keys.contains(key))
+    }
+
+    /// Returns the checkpoint keys the DAG was pruned to, see [`SectionsDAG::prune`].
+    pub fn checkpoints(&self) -> impl Iterator<Item = &bls::PublicKey> {
+        self.checkpoints.keys()
+    }
+
+    /// Returns the checkpoints the DAG was pruned to, along with their signatures, so others
+    /// knowing the checkpoint keys can prune their own DAG to them.
+    pub fn signed_checkpoints(&self) -> Vec<SectionsCheckpoint> {
+        self.checkpoints
+            .keys()
+            .filter_map(|key| self.checkpoint(key).ok())
+            .collect()
+    }
+
+    /// Returns the checkpoint for `key`, i.e. the signature over it by its parent key.
+    /// Returns `Error::InvalidCheckpoint` for the genesis key, and `Error::KeyNotFound` if the key
+    /// is not in the DAG.
+    pub fn checkpoint(&self, key: &bls::PublicKey) -> Result<SectionsCheckpoint> {
+        let signer = self
+            .signer_of(key)?
+            .ok_or_else(|| Error::InvalidCheckpoint("the genesis key".to_string()))?;
+        Ok(SectionsCheckpoint {
+            genesis_key: self.genesis_key,
+            key: *key,
+            signer,
+            sig: self.get_node(key)?.value.sig,
+        })
+    }
+
+    /// Returns whether we can prune to the checkpoint: its key is in the DAG, and it's signed by
+    /// the key which signed the checkpoint key in the DAG.
+    pub fn verify_checkpoint(&self, checkpoint: &SectionsCheckpoint) -> bool {
+        checkpoint.genesis_key == self.genesis_key
+            && matches!(self.signer_of(&checkpoint.key), Ok(Some(signer)) if signer == checkpoint.signer)
+            && checkpoint.verify()
+    }
+
+    /// The checkpoints to prune the DAG to so that only the last `depth` keys of each branch
+    /// are kept. A branch is only checkpointed once it's twice as long, so the DAG is pruned
+    /// every `depth` new keys rather than on each of them.
+    pub fn checkpoints_for_depth(&self, depth: usize) -> Result<Vec<SectionsCheckpoint>> {
+        let mut checkpoints = BTreeMap::new();
+        if depth == 0 {
+            return Ok(vec![]);
+        }
+
+        for leaf in self.leaf_keys() {
+            let mut branch = vec![leaf];
+            let mut key = leaf;
+            while let Some(parent) = self.get_parent_key(&key)? {
+                if parent == self.genesis_key {
+                    break;
+                }
+                branch.push(parent);
+                key = parent;
+            }
+
+            if branch.len() > 2 * depth {
+                let key = branch[depth];
+                let _ = checkpoints.insert(key, self.checkpoint(&key)?);
+            }
+        }
+
+        Ok(checkpoints.into_values().collect())
+    }
+
+    /// Prunes the keys older than the provided checkpoints, i.e. their ancestors bar the genesis
+    /// key, unless still needed by branches which don't go through any of the checkpoints.
+    /// The checkpoint keys then become children of the genesis key, still signed by their
+    /// pruned parent key.
+    ///
+    /// Returns `Error::InvalidCheckpoint` if any of the checkpoints fails
+    /// [`SectionsDAG::verify_checkpoint`].
+    pub fn prune<I>(&mut self, checkpoints: I) -> Result<()>
+    where
+        I: IntoIterator<Item = SectionsCheckpoint>,
+    {
+        let mut checkpoint_sigs = BTreeMap::new();
+        for checkpoint in checkpoints {
+            if !self.verify_checkpoint(&checkpoint) {
+                return Err(Error::InvalidCheckpoint(format!("{checkpoint:?}")));
+            }
+            let _ = checkpoint_sigs.insert(checkpoint.key, (checkpoint.signer, checkpoint.sig));
+        }
+
+        let pruned = self.prunable_keys(&checkpoint_sigs.keys().copied().collect())?;
+        if pruned.is_empty() {
+            return Ok(());
+        }
+
+        let mut pruned_dag = Self::new(self.genesis_key);
+        let mut need_to_visit: Vec<(bls::PublicKey, Node<SectionInfo>)> = Vec::new();
+        for key in &self.dag_root {
+            need_to_visit.push((self.genesis_key, self.get_node(key)?));
+        }
+        // parents are visited before their children
+        while let Some((parent_key, current_node)) = need_to_visit.pop() {
+            need_to_visit.extend(
+                self.child_nodes(current_node.hash())
+                    .into_iter()
+                    .map(|child_node| (current_node.value.key, child_node)),
+            );
+            let key = current_node.value.key;
+            if pruned.contains(&key) {
+                continue;
+            }
+
+            if pruned.contains(&parent_key) {
+                // can only be a checkpoint, as all the other children of pruned keys are pruned
+                let (signer, sig) = checkpoint_sigs
+                    .get(&key)
+                    .cloned()
+                    .ok_or(Error::KeyNotFound(key))?;
+                let _ = pruned_dag.checkpoints.insert(key, signer);
+                pruned_dag.insert_trusted_key(&self.genesis_key, key, sig)?;
+            } else {
+                if parent_key == self.genesis_key {
+                    if let Some(signer) = self.checkpoints.get(&key) {
+                        let _ = pruned_dag.checkpoints.insert(key, *signer);
+                    }
+                }
+                pruned_dag.insert_trusted_key(&parent_key, key, current_node.value.sig)?;
+            }
+        }
+
+        *self = pruned_dag;
+        Ok(())
     }
 
     /// Returns the parent of the provided key. None is returned if we're provided the `genesis_key`
@@ -494,6 +689,53 @@ impl SectionsDAG {
         }
     }
 
+    // The keys which can be pruned given the checkpoints: their ancestors, bar the genesis key and
+    // the keys on the way to any leaf which doesn't go through one of the checkpoints.
+    fn prunable_keys(
+        &self,
+        checkpoints: &BTreeSet<bls::PublicKey>,
+    ) -> Result<BTreeSet<bls::PublicKey>> {
+        let mut prunable = BTreeSet::new();
+        for checkpoint in checkpoints.iter().filter(|key| self.has_key(key)) {
+            prunable.extend(self.get_ancestors(checkpoint)?);
+        }
+        let _ = prunable.remove(&self.genesis_key);
+        if prunable.is_empty() {
+            return Ok(prunable);
+        }
+
+        for leaf in self.leaf_keys() {
+            let mut key = leaf;
+            let mut branch = Vec::new();
+            let through_checkpoint = loop {
+                if checkpoints.contains(&key) {
+                    break true;
+                }
+                branch.push(key);
+                match self.get_parent_key(&key)? {
+                    Some(parent) => key = parent,
+                    None => break false,
+                }
+            };
+            if !through_checkpoint {
+                for key in branch {
+                    let _ = prunable.remove(&key);
+                }
+            }
+        }
+
+        Ok(prunable)
+    }
+
+    // The key which signed `key`: its parent key, or its pruned parent key if a checkpoint.
+    // None for the genesis key.
+    fn signer_of(&self, key: &bls::PublicKey) -> Result<Option<bls::PublicKey>> {
+        if let Some(signer) = self.checkpoints.get(key) {
+            return Ok(Some(*signer));
+        }
+        self.get_parent_key(key)
+    }
+
     fn get_hash(&self, key: &bls::PublicKey) -> Result<Hash> {
         let hash = *self.hashes.get(key).ok_or(Error::KeyNotFound(*key))?;
         Ok(hash)
@@ -505,15 +747,19 @@ impl SectionsDAG {
         section_key: &bls::PublicKey,
         sig: &bls::Signature,
     ) -> bool {
+        let signer = match self.checkpoints.get(section_key) {
+            Some(signer) if parent_key == &self.genesis_key => signer,
+            _ => parent_key,
+        };
         bincode::serialize(section_key)
-            .map(|bytes| parent_key.verify(sig, bytes))
+            .map(|bytes| signer.verify(sig, bytes))
             .unwrap_or(false)
     }
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use super::{Error, SectionInfo, SectionsDAG};
+    use super::{Error, SectionInfo, SectionsCheckpoint, SectionsDAG};
     use crate::{
         messaging::system::SectionSigned,
         test_utils::{assert_lists, prefix, TestKeys, TestSapBuilder},
@@ -1115,6 +1361,194 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[test]
+    fn prune_to_checkpoints() -> Result<()> {
+        //  gen -> pk_a1 -> pk_a2 -> pk_a3
+        //                       |
+        //                       +-> pk_b
+        let (sk_gen, pk_gen) = gen_keypair();
+        let (sk_a1, info_a1) = gen_signed_keypair(&sk_gen);
+        let (sk_a2, info_a2) = gen_signed_keypair(&sk_a1);
+        let (_, info_a3) = gen_signed_keypair(&sk_a2);
+        let (_, info_b) = gen_signed_keypair(&sk_a2);
+
+        let mut dag = SectionsDAG::new(pk_gen);
+        dag.verify_and_insert(&pk_gen, info_a1.key, info_a1.sig)?;
+        dag.verify_and_insert(&info_a1.key, info_a2.key, info_a2.sig)?;
+        dag.verify_and_insert(&info_a2.key, info_a3.key, info_a3.sig)?;
+        dag.verify_and_insert(&info_a2.key, info_b.key, info_b.sig)?;
+
+        // a2 is still needed by b, so nothing is pruned
+        let mut partially_checkpointed = dag.clone();
+        partially_checkpointed.prune([dag.checkpoint(&info_a3.key)?])?;
+        assert_eq!(partially_checkpointed, dag);
+
+        let mut pruned = dag.clone();
+        pruned.prune([dag.checkpoint(&info_a3.key)?, dag.checkpoint(&info_b.key)?])?;
+        assert_lists(pruned.keys(), [pk_gen, info_a3.key, info_b.key]);
+        assert_lists(pruned.checkpoints().copied(), [info_a3.key, info_b.key]);
+        assert_eq!(pruned.get_parent_key(&info_b.key)?, Some(pk_gen));
+        assert!(pruned.self_verify());
+
+        // the checkpoints are still signed by their pruned parent, thus can be shared
+        assert_lists(
+            pruned.signed_checkpoints(),
+            [dag.checkpoint(&info_a3.key)?, dag.checkpoint(&info_b.key)?],
+        );
+        let mut pruned_by_others = dag.clone();
+        pruned_by_others.prune(pruned.signed_checkpoints())?;
+        assert_eq!(pruned_by_others, pruned);
+
+        // proofs going through a checkpoint are only trusted by those who know the checkpoint
+        let proof = pruned.partial_dag(&pk_gen, &info_a3.key)?;
+        assert!(proof.self_verify());
+        assert!(!proof.check_trust([pk_gen]));
+        assert!(proof.check_trust(dag.keys()));
+        // while proofs anchored at the checkpoint only need the checkpoint
+        let proof = pruned.partial_dag(&info_a3.key, &info_a3.key)?;
+        assert!(proof.check_trust([info_a3.key]));
+
+        let pruned_string = serde_json::to_string(&pruned)?;
+        assert_eq!(pruned, serde_json::from_str::<SectionsDAG>(&pruned_string)?);
+        let pruned_bytes = bincode::serialize(&pruned)?;
+        assert_eq!(pruned, bincode::deserialize::<SectionsDAG>(&pruned_bytes)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn checkpoints_for_depth_keep_the_last_keys_of_each_branch() -> Result<()> {
+        let (mut parent_sk, pk_gen) = gen_keypair();
+        let mut dag = SectionsDAG::new(pk_gen);
+        let mut parent_key = pk_gen;
+        let mut branch = vec![];
+        for _ in 0..6 {
+            let (sk, info) = gen_signed_keypair(&parent_sk);
+            dag.verify_and_insert(&parent_key, info.key, info.sig)?;
+            branch.push(info.key);
+            parent_key = info.key;
+            parent_sk = sk;
+        }
+
+        // not yet twice as long as the depth
+        assert!(dag.checkpoints_for_depth(3)?.is_empty());
+
+        let checkpoints = dag.checkpoints_for_depth(2)?;
+        assert_eq!(checkpoints, vec![dag.checkpoint(&branch[3])?]);
+        dag.prune(checkpoints)?;
+        assert_lists(dag.keys(), [pk_gen, branch[3], branch[4], branch[5]]);
+
+        // and it's not pruned again until it's grown enough
+        assert!(dag.checkpoints_for_depth(2)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_into_pruned_dag() -> Result<()> {
+        //  gen -> pk_a1 -> pk_a2 -> pk_a3
+        let (sk_gen, pk_gen) = gen_keypair();
+        let (sk_a1, info_a1) = gen_signed_keypair(&sk_gen);
+        let (sk_a2, info_a2) = gen_signed_keypair(&sk_a1);
+        let (_, info_a3) = gen_signed_keypair(&sk_a2);
+
+        let mut dag = SectionsDAG::new(pk_gen);
+        dag.verify_and_insert(&pk_gen, info_a1.key, info_a1.sig.clone())?;
+        dag.verify_and_insert(&info_a1.key, info_a2.key, info_a2.sig.clone())?;
+        let mut pruned = dag.clone();
+        pruned.prune([dag.checkpoint(&info_a2.key)?])?;
+        assert_lists(pruned.keys(), [pk_gen, info_a2.key]);
+
+        // the keys older than our checkpoint are not inserted back
+        dag.verify_and_insert(&info_a2.key, info_a3.key, info_a3.sig)?;
+        pruned.merge(dag.partial_dag(&pk_gen, &info_a3.key)?)?;
+        assert_lists(pruned.keys(), [pk_gen, info_a2.key, info_a3.key]);
+        assert!(pruned.self_verify());
+
+        // and an unpruned DAG can merge the proof chains of a pruned one
+        let mut unpruned = SectionsDAG::new(pk_gen);
+        unpruned.verify_and_insert(&pk_gen, info_a1.key, info_a1.sig)?;
+        unpruned.verify_and_insert(&info_a1.key, info_a2.key, info_a2.sig)?;
+        unpruned.merge(pruned.partial_dag(&pk_gen, &info_a3.key)?)?;
+        assert_eq!(unpruned, dag);
+
+        Ok(())
+    }
+
+    #[test]
+    fn forged_checkpoints_are_rejected() -> Result<()> {
+        let (sk_gen, pk_gen) = gen_keypair();
+        let (sk_a1, info_a1) = gen_signed_keypair(&sk_gen);
+        let (_, info_a2) = gen_signed_keypair(&sk_a1);
+        let mut dag = SectionsDAG::new(pk_gen);
+        dag.verify_and_insert(&pk_gen, info_a1.key, info_a1.sig)?;
+        dag.verify_and_insert(&info_a1.key, info_a2.key, info_a2.sig)?;
+        let checkpoint = dag.checkpoint(&info_a2.key)?;
+        assert!(dag.verify_checkpoint(&checkpoint));
+
+        // self-signed
+        let (other_sk, other_pk) = gen_keypair();
+        let (sk_a2, _) = gen_keypair();
+        let self_signed = SectionsCheckpoint {
+            signer: info_a2.key,
+            sig: sk_a2.sign(bincode::serialize(&info_a2.key)?),
+            ..checkpoint.clone()
+        };
+        assert!(matches!(
+            dag.clone().prune([self_signed]),
+            Err(Error::InvalidCheckpoint(_))
+        ));
+
+        // signed by a key which is not the parent of the checkpoint key
+        let forged = SectionsCheckpoint {
+            signer: other_pk,
+            sig: other_sk.sign(bincode::serialize(&info_a2.key)?),
+            ..checkpoint.clone()
+        };
+        assert!(forged.verify());
+        assert!(matches!(
+            dag.clone().prune([forged]),
+            Err(Error::InvalidCheckpoint(_))
+        ));
+
+        // not signed by the parent key
+        let forged = SectionsCheckpoint {
+            sig: other_sk.sign(bincode::serialize(&info_a2.key)?),
+            ..checkpoint.clone()
+        };
+        assert!(matches!(
+            dag.clone().prune([forged]),
+            Err(Error::InvalidCheckpoint(_))
+        ));
+
+        // a key which is not in the DAG
+        let unknown = SectionsCheckpoint {
+            key: other_pk,
+            signer: info_a1.key,
+            sig: sk_a1.sign(bincode::serialize(&other_pk)?),
+            ..checkpoint
+        };
+        assert!(matches!(
+            dag.clone().prune([unknown]),
+            Err(Error::InvalidCheckpoint(_))
+        ));
+
+        // a DAG claiming a checkpoint we don't know of
+        let (sk_fake_parent, pk_fake_parent) = gen_keypair();
+        let (_, info_fake) = gen_signed_keypair(&sk_fake_parent);
+        let mut fake = SectionsDAG::new(pk_gen);
+        let _ = fake.checkpoints.insert(info_fake.key, pk_fake_parent);
+        fake.verify_and_insert(&pk_gen, info_fake.key, info_fake.sig)?;
+        assert!(fake.self_verify());
+        assert!(!fake.check_trust(dag.keys()));
+        assert!(matches!(
+            dag.merge(fake),
+            Err(Error::UntrustedProofChain(_))
+        ));
+
+        Ok(())
+    }
+
     // Test helpers
     fn gen_keypair() -> (bls::SecretKey, bls::PublicKey) {
         let sk_set = bls::SecretKeySet::random(0, &mut thread_rng());
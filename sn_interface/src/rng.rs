@@ -0,0 +1,114 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! The source of randomness of the network logic.
+//!
+//! Keys, msg ids and any other random choice made by the nodes should be drawn from
+//! [`thread_rng`], which is the OS' RNG unless a test seeded the current thread with
+//! `seed_thread_rng`, so a whole network run on a single thread can be replayed from a seed.
+
+use rand::{rngs::OsRng, rngs::StdRng, CryptoRng, RngCore};
+use std::{cell::RefCell, marker::PhantomData};
+
+thread_local! {
+    static SEEDED_RNG: RefCell<Option<StdRng>> = RefCell::new(None);
+}
+
+/// Handle to the current thread's RNG, see [`thread_rng`].
+///
+/// It implements the RNG traits of both rand 0.8 and rand 0.7, the latter still being required
+/// by `ed25519-dalek`. It is not `Send`, as it refers to the thread it was obtained on.
+#[derive(Clone, Debug)]
+pub struct ThreadRng(PhantomData<*const ()>);
+
+/// Returns the RNG of the current thread, seeded or not.
+pub fn thread_rng() -> ThreadRng {
+    ThreadRng(PhantomData)
+}
+
+/// Whether the current thread's RNG has been seeded, i.e. randomness must not be drawn from
+/// other threads for a run to be replayable.
+pub fn is_seeded() -> bool {
+    SEEDED_RNG.with(|rng| rng.borrow().is_some())
+}
+
+/// Seeds the current thread's RNG, or restores the OS' one if `seed` is `None`.
+#[cfg(any(test, feature = "test-utils"))]
+pub fn seed_thread_rng(seed: Option<u64>) {
+    use rand::SeedableRng;
+    SEEDED_RNG.with(|rng| *rng.borrow_mut() = seed.map(StdRng::seed_from_u64));
+}
+
+impl ThreadRng {
+    fn with<R>(&mut self, f: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+        SEEDED_RNG.with(|rng| match rng.borrow_mut().as_mut() {
+            Some(rng) => f(rng),
+            None => f(&mut OsRng),
+        })
+    }
+}
+
+impl RngCore for ThreadRng {
+    fn next_u32(&mut self) -> u32 {
+        self.with(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.with(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.with(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.with(|rng| rng.try_fill_bytes(dest))
+    }
+}
+
+impl CryptoRng for ThreadRng {}
+
+impl rand_07::RngCore for ThreadRng {
+    fn next_u32(&mut self) -> u32 {
+        RngCore::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        RngCore::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RngCore::fill_bytes(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_07::Error> {
+        RngCore::fill_bytes(self, dest);
+        Ok(())
+    }
+}
+
+impl rand_07::CryptoRng for ThreadRng {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn seeded_thread_rng_replays() {
+        seed_thread_rng(Some(7));
+        assert!(is_seeded());
+        let first: [u64; 4] = thread_rng().gen();
+        seed_thread_rng(Some(7));
+        let second: [u64; 4] = thread_rng().gen();
+        seed_thread_rng(None);
+
+        assert_eq!(first, second);
+        assert!(!is_seeded());
+    }
+}
@@ -33,7 +33,7 @@ pub fn name(public_key: &PublicKey) -> XorName {
 /// Construct a random `XorName` whose last byte represents the targeted age.
 pub fn gen_name_with_age(age: u8) -> XorName {
     loop {
-        let name: XorName = xor_name::rand::random();
+        let name = XorName::random(&mut crate::rng::thread_rng());
         if age == name[XOR_NAME_LEN - 1] {
             return name;
         }
@@ -42,15 +42,19 @@ pub fn gen_name_with_age(age: u8) -> XorName {
 
 /// Construct a `Keypair` whose name is in the interval [start, end] (both endpoints inclusive).
 /// And the last byte equals to the targeted age.
+/// Keys come from `rng::thread_rng`, on the current thread only if it's been seeded.
 pub fn gen_keypair(range: &RangeInclusive<XorName>, age: u8) -> Keypair {
     use rayon::prelude::*;
+    if crate::rng::is_seeded() {
+        return gen_keypair_single_thread(range, age);
+    }
     let parallelism = std::thread::available_parallelism()
         .map(|s| s.get())
         .unwrap_or(1);
     let result = (0..parallelism)
         .par_bridge()
         .map(|_| loop {
-            let keypair = Keypair::generate(&mut rand_07::thread_rng());
+            let keypair = Keypair::generate(&mut crate::rng::thread_rng());
             let new_name = XorName::from(crate::types::PublicKey::Ed25519(keypair.public));
             if range.contains(&new_name) && age == new_name[XOR_NAME_LEN - 1] {
                 return Some(keypair);
@@ -65,7 +69,7 @@ pub fn gen_keypair(range: &RangeInclusive<XorName>, age: u8) -> Keypair {
 }
 
 fn gen_keypair_single_thread(range: &RangeInclusive<XorName>, age: u8) -> Keypair {
-    let mut rng = rand_07::thread_rng();
+    let mut rng = crate::rng::thread_rng();
     loop {
         let keypair = Keypair::generate(&mut rng);
         let new_name = XorName::from(crate::types::PublicKey::Ed25519(keypair.public));
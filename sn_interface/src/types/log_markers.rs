@@ -15,6 +15,7 @@ use strum_macros::{Display as StrumDisplay, EnumIter, EnumString};
 pub enum LogMarker {
     // Stats
     TotalNetworkElders,
+    PeerStats,
     // Split
     StillElderAfterSplit,
     SplitSuccess,
@@ -47,6 +48,7 @@ pub enum LogMarker {
     // Data
     DataStoreReceivedAtElder,
     DataQueryReceviedAtElder,
+    StorageFaultDetected,
     // Chunks
     StoringChunk,
     StoredNewChunk,
@@ -90,6 +92,10 @@ pub enum LogMarker {
     AeSendRedirect,
     AeSendRetryAsOutdated,
     AeSendRetryDstPkFail,
+    AeSendDigest,
+    AeSendFetch,
+    AeBudgetExceeded,
+    AeStats,
     // Sending messages
     SendToNodes,
     SendResourceProofChallenge,
@@ -106,6 +112,7 @@ pub enum LogMarker {
     ReceiveCompleted,
     ConnectionReused,
     // Relocation
+    RelocationDecision,
     RelocateStart,
     RelocateEnd,
 }
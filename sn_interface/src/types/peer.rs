@@ -15,15 +15,36 @@ use std::{
 use xor_name::{XorName, XOR_NAME_LEN};
 
 /// A Peer with name, derived from its `PublicKey`, and an address.
+///
+/// A peer which cannot accept incoming connections (e.g. behind a home router) is reachable
+/// through a relay instead, a publicly reachable node which forwards its msgs to it.
 #[derive(Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Peer {
     name: XorName,
     addr: SocketAddr,
+    // Msgs are bincode encoded, thus adding this field changed their wire format, which is why
+    // the messaging protocol version was bumped. The default is only of use to the self-describing
+    // formats, e.g. reading the peers of network contacts written before relays were supported.
+    #[serde(default)]
+    relay: Option<SocketAddr>,
 }
 
 impl Peer {
     pub fn new(name: XorName, addr: SocketAddr) -> Self {
-        Self { name, addr }
+        Self {
+            name,
+            addr,
+            relay: None,
+        }
+    }
+
+    /// A peer only reachable through the relay at the given address.
+    pub fn new_relayed(name: XorName, addr: SocketAddr, relay: SocketAddr) -> Self {
+        Self {
+            name,
+            addr,
+            relay: Some(relay),
+        }
     }
 
     pub fn name(&self) -> XorName {
@@ -34,6 +55,21 @@ impl Peer {
         self.addr
     }
 
+    /// The address of the relay this peer is reachable through, if any.
+    pub fn relay(&self) -> Option<SocketAddr> {
+        self.relay
+    }
+
+    pub fn is_relayed(&self) -> bool {
+        self.relay.is_some()
+    }
+
+    /// The address to connect to in order to reach this peer,
+    /// i.e. its relay's address if it's relayed, or its own otherwise.
+    pub fn connect_addr(&self) -> SocketAddr {
+        self.relay.unwrap_or(self.addr)
+    }
+
     /// Returns the age.
     pub fn age(&self) -> u8 {
         self.name[XOR_NAME_LEN - 1]
@@ -47,13 +83,18 @@ impl Peer {
         Peer {
             addr,
             name: XorName::from(super::PublicKey::from(public_key)),
+            relay: None,
         }
     }
 }
 
 impl Display for Peer {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{} at {}", self.name(), self.addr(),)
+        write!(f, "{} at {}", self.name(), self.addr(),)?;
+        if let Some(relay) = self.relay() {
+            write!(f, " via {relay}")?;
+        }
+        Ok(())
     }
 }
 
@@ -63,6 +104,7 @@ impl Hash for Peer {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.name().hash(state);
         self.addr().hash(state);
+        self.relay().hash(state);
     }
 }
 
@@ -71,18 +113,19 @@ impl Ord for Peer {
         self.name()
             .cmp(&other.name())
             .then_with(|| self.addr().cmp(&other.addr()))
+            .then_with(|| self.relay().cmp(&other.relay()))
     }
 }
 
 impl PartialEq for Peer {
     fn eq(&self, other: &Self) -> bool {
-        self.name() == other.name() && self.addr() == other.addr()
+        self.name() == other.name() && self.addr() == other.addr() && self.relay() == other.relay()
     }
 }
 
 impl PartialEq<&Self> for Peer {
     fn eq(&self, other: &&Self) -> bool {
-        self.name() == other.name() && self.addr() == other.addr()
+        self == *other
     }
 }
 
@@ -91,3 +134,28 @@ impl PartialOrd for Peer {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn relayed_peer_is_reached_through_its_relay() {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 12000));
+        let relay = SocketAddr::from((Ipv4Addr::LOCALHOST, 12001));
+        let name = xor_name::rand::random();
+
+        let peer = Peer::new(name, addr);
+        assert!(!peer.is_relayed());
+        assert_eq!(peer.connect_addr(), addr);
+
+        let relayed = Peer::new_relayed(name, addr, relay);
+        assert!(relayed.is_relayed());
+        assert_eq!(relayed.addr(), addr);
+        assert_eq!(relayed.connect_addr(), relay);
+        assert_ne!(peer, relayed);
+        assert_eq!(relayed.to_string(), format!("{name} at {addr} via {relay}"));
+    }
+}
@@ -8,6 +8,7 @@
 
 use sn_interface::{
     messaging::data::{CreateRegister, EditRegister, SignedRegisterCreate, SignedRegisterEdit},
+    network_knowledge::NetworkParams,
     test_utils::TestKeys,
     types::{
         register::{Policy, Register, User},
@@ -367,7 +368,11 @@ fn get_new_data_store() -> Result<DataStorage> {
 
     let config = Config::default();
 
-    let used_space = UsedSpace::new(config.min_capacity(), config.max_capacity());
+    let used_space = UsedSpace::new(
+        config.min_capacity(),
+        config.max_capacity(),
+        NetworkParams::default().recommended_section_size,
+    );
     let store = DataStorage::new(&storage_dir, used_space)?;
 
     Ok(store)
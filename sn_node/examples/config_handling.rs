@@ -80,6 +80,19 @@ async fn main() -> Result<()> {
         config.clear_data,
         file_config.clear_data || command_line_args.clear_data
     );
+    assert_eq!(
+        config.persist_node_state,
+        file_config.persist_node_state || command_line_args.persist_node_state
+    );
+
+    if command_line_args.node_state_key_file.is_some() {
+        assert_eq!(
+            command_line_args.node_state_key_file,
+            config.node_state_key_file
+        );
+    } else {
+        assert_eq!(file_config.node_state_key_file, config.node_state_key_file);
+    }
 
     if command_line_args.local_addr.is_some() {
         assert_eq!(command_line_args.local_addr, config.local_addr);
@@ -87,6 +100,30 @@ async fn main() -> Result<()> {
         assert_eq!(file_config.local_addr, config.local_addr);
     }
 
+    if command_line_args.relay.is_some() {
+        assert_eq!(command_line_args.relay, config.relay);
+    } else {
+        assert_eq!(file_config.relay, config.relay);
+    }
+
+    if command_line_args.network_params_file.is_some() {
+        assert_eq!(
+            command_line_args.network_params_file,
+            config.network_params_file
+        );
+    } else {
+        assert_eq!(file_config.network_params_file, config.network_params_file);
+    }
+
+    if command_line_args.relocation_policy.is_some() {
+        assert_eq!(
+            command_line_args.relocation_policy,
+            config.relocation_policy
+        );
+    } else {
+        assert_eq!(file_config.relocation_policy, config.relocation_policy);
+    }
+
     if command_line_args.first {
         assert!(config.first);
     }
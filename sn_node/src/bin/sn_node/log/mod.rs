@@ -139,6 +139,13 @@ pub fn init_node_logging(config: &Config) -> Result<Option<WorkerGuard>> {
     #[cfg(feature = "tokio-console")]
     layers.layers.push(console_subscriber::spawn().boxed());
 
+    #[cfg(feature = "chaos")]
+    {
+        use sn_node::node::chaos::{ChaosProfile, CrashPointsLayer};
+        let profile = ChaosProfile::load(config.chaos_profile())?;
+        layers.layers.push(CrashPointsLayer::new(&profile).boxed());
+    }
+
     tracing_subscriber::registry().with(layers.layers).init();
 
     Ok(layers.guard)
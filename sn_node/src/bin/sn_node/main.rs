@@ -132,21 +132,18 @@ fn create_runtime_and_node(config: &Config) -> Result<()> {
                 let join_future = async {
                     // Simulate failed node starts, and ensure that
                     #[cfg(feature = "chaos")]
+                    if !config.is_first()
+                        && sn_node::node::chaos::ChaosProfile::load(config.chaos_profile())?
+                            .crash_at_startup()
                     {
-                        use rand::Rng;
-                        let mut rng = rand::thread_rng();
-                        let x: f64 = rng.gen_range(0.0..1.0);
-
-                        if !config.is_first() && x > 0.6 {
-                            println!(
-                               "\n =========== [Chaos] (PID: {our_pid}): Startup chaos crash w/ x of: {x}. ============== \n",
-                           );
-
-                            // tiny sleep so testnet doesn't detect a faulty node and exit
-                            tokio::time::sleep(Duration::from_secs(1)).await;
-                            warn!("[Chaos] (PID: {our_pid}): ChaoticStartupCrash");
-                            return Err(NodeError::ChaoticStartupCrash);
-                        }
+                        println!(
+                            "\n =========== [Chaos] (PID: {our_pid}): Startup chaos crash. ============== \n",
+                        );
+
+                        // tiny sleep so testnet doesn't detect a faulty node and exit
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        warn!("[Chaos] (PID: {our_pid}): ChaoticStartupCrash");
+                        return Err(NodeError::ChaoticStartupCrash);
                     }
 
                     // this keeps node running
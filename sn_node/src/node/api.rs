@@ -18,7 +18,9 @@ use sn_comms::Comm;
 use sn_interface::{
     dbcs::gen_genesis_dbc,
     messaging::system::SectionSigned,
-    network_knowledge::{NetworkKnowledge, SectionKeyShare, SectionsDAG, GENESIS_DBC_SK},
+    network_knowledge::{
+        NetworkKnowledge, NetworkParams, SectionKeyShare, SectionsDAG, GENESIS_DBC_SK,
+    },
     types::{log_markers::LogMarker, Peer},
     SectionAuthorityProvider,
 };
@@ -29,12 +31,14 @@ use std::{path::PathBuf, sync::Arc};
 use tokio::sync::mpsc;
 
 impl MyNode {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn first_node(
         comm: Comm,
         keypair: Keypair,
         used_space: UsedSpace,
         root_storage_dir: PathBuf,
         genesis_sk_set: bls::SecretKeySet,
+        network_params: NetworkParams,
         fault_cmds_sender: mpsc::Sender<FaultsCmd>,
     ) -> Result<(Self, Dbc)> {
         let peer = Peer::from(comm.socket_addr(), keypair.public);
@@ -43,7 +47,7 @@ impl MyNode {
             gen_genesis_dbc(&genesis_sk_set, &bls::SecretKey::from_hex(GENESIS_DBC_SK)?)?;
 
         let (network_knowledge, section_key_share) =
-            NetworkKnowledge::first_node(peer, genesis_sk_set)?;
+            NetworkKnowledge::first_node(peer, genesis_sk_set, network_params)?;
 
         let node = Self::new(
             comm,
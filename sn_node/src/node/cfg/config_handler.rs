@@ -6,13 +6,19 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::node::{Error, Result};
+use crate::node::{
+    relocation::{builtin_relocation_policy, OldestFirst, RelocationPolicy},
+    Error, Result,
+};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
+use sn_fault_detection::FaultDetectionConfig;
+use sn_interface::network_knowledge::NetworkParams;
 use std::{
     io::{self},
     net::SocketAddr,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 use tokio::{
     fs::{self, File},
@@ -25,6 +31,8 @@ pub(crate) const DEFAULT_MAX_CAPACITY: usize = 2 * DEFAULT_MIN_CAPACITY;
 
 const CONFIG_FILE: &str = "node.config";
 const DEFAULT_ROOT_DIR_NAME: &str = "root_dir";
+// Env var with the hex encoded key the section key share is encrypted with at rest
+const NODE_STATE_KEY_ENV: &str = "SN_NODE_STATE_KEY";
 
 /// Node configuration
 #[derive(Default, Clone, Debug, Serialize, Deserialize, clap::StructOpt)]
@@ -105,6 +113,64 @@ pub struct Config {
     /// e.g. Digital Ocean droplets.
     #[clap(long)]
     pub skip_auto_port_forwarding: bool,
+    /// Address of a publicly reachable node to relay our msgs, for when this node cannot accept
+    /// incoming connections (e.g. when behind a home router without port forwarding).
+    ///
+    /// The relay node must have been started with `--relay-for-others`.
+    #[clap(long)]
+    pub relay: Option<SocketAddr>,
+    /// Relay msgs for the nodes which register with us, as they cannot accept incoming
+    /// connections. Only to be set on publicly reachable nodes.
+    #[clap(long)]
+    pub relay_for_others: bool,
+    /// File with the thresholds and weightings to be used by fault detection, in JSON format.
+    ///
+    /// Any value not present in the file falls back to its default.
+    #[clap(long)]
+    pub fault_detection_config_file: Option<PathBuf>,
+    /// Snapshot the issues tracked by fault detection to the root dir, and restore them when
+    /// the node is restarted with the same root dir.
+    #[clap(long)]
+    pub persist_fault_detection: bool,
+    /// Snapshot the node's state (network knowledge, membership and section key share) to the
+    /// root dir, and resume from it when the node is restarted with the same root dir and address,
+    /// instead of joining the network again as a new node.
+    ///
+    /// The section key share is stored encrypted with the key from `--node-state-key-file`, or
+    /// from the `SN_NODE_STATE_KEY` env var, one of which is required.
+    #[clap(long)]
+    pub persist_node_state: bool,
+    /// File with the hex encoded BLS secret key the section key share is encrypted with when
+    /// persisting the node's state. It must not be within the root dir, as the snapshot and
+    /// keypair are stored there.
+    #[clap(long)]
+    pub node_state_key_file: Option<PathBuf>,
+    /// File with the params of the network (elder count, section size, data copy count,
+    /// intervals, etc.), in JSON format.
+    ///
+    /// The genesis node fixes them for the whole network, any value not present in the file falls
+    /// back to its default. Without a file, the genesis node uses the defaults, with the elder and
+    /// data copy counts taken from the `SN_ELDER_COUNT` and `SN_DATA_COPY_COUNT` env vars if set.
+    /// Joining nodes refuse to join a network with params other than the
+    /// ones in the file, or adopt the network's params if no file is provided.
+    #[clap(long)]
+    pub network_params_file: Option<PathBuf>,
+    /// Name of the policy deciding which members to relocate on churn, while this node is an
+    /// elder. All the elders of a section need to use the same one.
+    ///
+    /// Only `oldest-first`, the default, is built in. Other policies can be set on the `Config`
+    /// of nodes started as a library, taking precedence over this one.
+    #[clap(long)]
+    pub relocation_policy: Option<String>,
+    // Policy set on the config when starting the node as a library.
+    #[clap(skip)]
+    #[serde(skip)]
+    custom_relocation_policy: Option<Arc<dyn RelocationPolicy>>,
+    /// File describing the faults to inject into the node, in JSON format.
+    /// If not set, it's read from the `SN_CHAOS_PROFILE` file, or the `SN_CHAOS` env var.
+    #[cfg(feature = "chaos")]
+    #[clap(long)]
+    pub chaos_profile: Option<PathBuf>,
 }
 
 impl Config {
@@ -139,6 +205,48 @@ impl Config {
             ));
         }
 
+        if self.first && self.relay.is_some() {
+            return Err(Error::Configuration(
+                "The --first and --relay arguments are mutually exclusive, as the genesis node \
+                becomes an elder, and elders must be directly reachable."
+                    .to_string(),
+            ));
+        }
+
+        if self.first && self.persist_node_state {
+            return Err(Error::Configuration(
+                "The --first and --persist-node-state arguments are mutually exclusive, as the \
+                genesis node always starts a new network."
+                    .to_string(),
+            ));
+        }
+
+        if self.persist_node_state
+            && self.node_state_key_file.is_none()
+            && std::env::var(NODE_STATE_KEY_ENV).is_err()
+        {
+            return Err(Error::Configuration(format!(
+                "The --persist-node-state argument requires the key to encrypt the section key \
+                share with, from either --node-state-key-file or the {NODE_STATE_KEY_ENV} env var."
+            )));
+        }
+
+        if let Some(key_file) = &self.node_state_key_file {
+            let root_dir = self.root_dir()?;
+            let canonical =
+                |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            if canonical(key_file).starts_with(canonical(&root_dir)) {
+                return Err(Error::Configuration(
+                    "The --node-state-key-file must not be within the root dir, where the node \
+                    state snapshot is stored."
+                        .to_string(),
+                ));
+            }
+        }
+
+        // fail early on an unknown relocation policy name
+        let _policy = self.relocation_policy()?;
+
         Ok(())
     }
 
@@ -184,6 +292,42 @@ impl Config {
         if config.local_addr.is_some() {
             self.local_addr = config.local_addr;
         }
+
+        if config.relay.is_some() {
+            self.relay = config.relay;
+        }
+
+        self.relay_for_others = config.relay_for_others || self.relay_for_others;
+
+        if config.fault_detection_config_file.is_some() {
+            self.fault_detection_config_file = config.fault_detection_config_file;
+        }
+
+        self.persist_fault_detection =
+            config.persist_fault_detection || self.persist_fault_detection;
+
+        self.persist_node_state = config.persist_node_state || self.persist_node_state;
+
+        if config.node_state_key_file.is_some() {
+            self.node_state_key_file = config.node_state_key_file;
+        }
+
+        if config.network_params_file.is_some() {
+            self.network_params_file = config.network_params_file;
+        }
+
+        if config.relocation_policy.is_some() {
+            self.relocation_policy = config.relocation_policy;
+        }
+
+        if config.custom_relocation_policy.is_some() {
+            self.custom_relocation_policy = config.custom_relocation_policy;
+        }
+
+        #[cfg(feature = "chaos")]
+        if config.chaos_profile.is_some() {
+            self.chaos_profile = config.chaos_profile;
+        }
     }
 
     /// The address to be credited when this node farms `SafeCoin`.
@@ -248,6 +392,131 @@ impl Config {
             .unwrap_or_else(|| SocketAddr::from((std::net::Ipv4Addr::UNSPECIFIED, 0)))
     }
 
+    /// Address of the node to relay our msgs, if we cannot accept incoming connections.
+    pub fn relay(&self) -> Option<SocketAddr> {
+        self.relay
+    }
+
+    /// Whether we relay msgs for nodes which cannot accept incoming connections.
+    pub fn relay_for_others(&self) -> bool {
+        self.relay_for_others
+    }
+
+    /// Thresholds and weightings to be used by fault detection.
+    /// Reads them from the configured file if any, otherwise the defaults are returned.
+    pub async fn fault_detection_config(&self) -> Result<FaultDetectionConfig> {
+        let path = match &self.fault_detection_config_file {
+            Some(path) => path,
+            None => return Ok(FaultDetectionConfig::default()),
+        };
+
+        let content = fs::read(path).await?;
+        debug!("Reading fault detection config from {}", path.display());
+
+        serde_json::from_slice(&content).map_err(|err| {
+            warn!(
+                "Could not parse content of fault detection config file '{:?}': {:?}",
+                path, err
+            );
+            err.into()
+        })
+    }
+
+    /// Whether the issues tracked by fault detection are persisted across restarts.
+    pub fn persist_fault_detection(&self) -> bool {
+        self.persist_fault_detection
+    }
+
+    /// The params of the network, read from the configured file if any.
+    pub async fn network_params(&self) -> Result<Option<NetworkParams>> {
+        let path = match &self.network_params_file {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        let content = fs::read(path).await?;
+        debug!("Reading network params from {}", path.display());
+
+        let params: NetworkParams = serde_json::from_slice(&content).map_err(|err| {
+            warn!(
+                "Could not parse content of network params file '{:?}': {:?}",
+                path, err
+            );
+            Error::from(err)
+        })?;
+        params.validate()?;
+
+        Ok(Some(params))
+    }
+
+    /// The params to start a new network with, as the genesis node.
+    /// Read from the configured file if any, else the defaults overridden by the env vars
+    /// `SN_ELDER_COUNT` and `SN_DATA_COPY_COUNT` are used.
+    pub async fn genesis_network_params(&self) -> Result<NetworkParams> {
+        match self.network_params().await? {
+            Some(params) => Ok(params),
+            None => {
+                let params = NetworkParams::default().with_env_overrides();
+                params.validate()?;
+                Ok(params)
+            }
+        }
+    }
+
+    /// Sets the relocation policy of the node, taking precedence over the one named by
+    /// `relocation_policy`.
+    pub fn set_relocation_policy(&mut self, policy: Arc<dyn RelocationPolicy>) {
+        self.custom_relocation_policy = Some(policy);
+    }
+
+    /// The relocation policy of the node: the one set on the config if any, else the built in one
+    /// named by `relocation_policy`, else [`OldestFirst`].
+    pub fn relocation_policy(&self) -> Result<Arc<dyn RelocationPolicy>> {
+        if let Some(policy) = &self.custom_relocation_policy {
+            return Ok(policy.clone());
+        }
+        match &self.relocation_policy {
+            Some(name) => builtin_relocation_policy(name)
+                .ok_or_else(|| Error::Configuration(format!("Unknown relocation policy '{name}'"))),
+            None => Ok(Arc::new(OldestFirst)),
+        }
+    }
+
+    /// Whether the node's state is persisted, to resume from it upon restarts.
+    pub fn persist_node_state(&self) -> bool {
+        self.persist_node_state
+    }
+
+    /// The key to encrypt the section key share with when persisting the node's state, read from
+    /// the configured file if any, else from the `SN_NODE_STATE_KEY` env var.
+    pub async fn node_state_key(&self) -> Result<bls::SecretKey> {
+        let hex = match &self.node_state_key_file {
+            Some(path) => {
+                debug!("Reading node state key from {}", path.display());
+                String::from_utf8(fs::read(path).await?).map_err(|_| {
+                    Error::Configuration(format!(
+                        "The node state key file '{}' is not hex encoded",
+                        path.display()
+                    ))
+                })?
+            }
+            None => std::env::var(NODE_STATE_KEY_ENV).map_err(|_| {
+                Error::Configuration(format!(
+                    "No node state key file is configured, nor is the {NODE_STATE_KEY_ENV} env \
+                    var set"
+                ))
+            })?,
+        };
+
+        Ok(bls::SecretKey::from_hex(hex.trim())?)
+    }
+
+    /// Path to the file describing the faults to inject, if any.
+    #[cfg(feature = "chaos")]
+    pub fn chaos_profile(&self) -> Option<&std::path::Path> {
+        self.chaos_profile.as_deref()
+    }
+
     /// Get the completions option
     pub fn completions(&self) -> &Option<String> {
         &self.completions
@@ -357,7 +626,7 @@ fn smoke() -> Result<()> {
     // NOTE: IF this value is being changed due to a change in the config,
     // the change in config also be handled in Config::merge()
     // and in examples/config_handling.rs
-    let expected_size = 46;
+    let expected_size = 54;
 
     assert_eq!(bincode::serialize(&Config::default())?.len(), expected_size);
     Ok(())
@@ -0,0 +1,627 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Fault injection, to exercise the network's recovery from failures.
+//!
+//! The faults to inject into a node are described by its [`ChaosProfile`], read as JSON from the
+//! file passed with `--chaos-profile`, or else from the file at `SN_CHAOS_PROFILE`, or else inline
+//! from `SN_CHAOS`. Without any of those, nodes only crash at startup now and then, as they always
+//! have with the `chaos` feature.
+//!
+//! Every fault injected is logged with a `[Chaos]` prefix.
+
+use crate::node::{Error, Result};
+
+use sn_interface::{
+    messaging::{system::DkgSessionId, MsgId},
+    types::{log_markers::LogMarker, ChunkAddress, Peer},
+};
+
+use bytes::Bytes;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    future::Future,
+    path::Path,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use tokio::{sync::oneshot, time::Instant};
+use tracing::{field::Field, Event, Subscriber};
+use tracing_subscriber::{field::Visit, layer::Context, Layer};
+use xor_name::XorName;
+
+/// Env var holding the path to a chaos profile file.
+pub const CHAOS_PROFILE_ENV: &str = "SN_CHAOS_PROFILE";
+/// Env var holding a chaos profile, as JSON.
+pub const CHAOS_ENV: &str = "SN_CHAOS";
+/// Exit code of a node crashed at one of the profile's crash points.
+pub const CHAOS_CRASH_EXIT_CODE: i32 = 13;
+
+// Probability of crashing at startup when no profile is provided.
+const DEFAULT_STARTUP_CRASH_PROBABILITY: f64 = 0.4;
+// How long a reordered msg may take to be sent, before the next one is let go regardless.
+const REORDERED_SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Faults to inject into a node.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosProfile {
+    /// Probability for a node, other than the genesis one, to crash right after starting.
+    pub startup_crash_probability: f64,
+    /// Faults affecting the `NodeMsg`s sent by the node.
+    pub msg_faults: Vec<MsgFault>,
+    /// Probability for a chunk to be corrupted when stored by the node.
+    pub chunk_corruption_probability: f64,
+    /// Stalling of the node when taking part in DKG sessions.
+    pub dkg_stall: Option<DkgStall>,
+    /// Points where the node crashes, each being a `LogMarker` logged by the node.
+    pub crash_points: Vec<CrashPoint>,
+}
+
+/// A fault affecting the msgs of a variant sent by the node, each copy sent to a peer being
+/// affected independently.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MsgFault {
+    /// Msg variant, as displayed in the logs, e.g. `AntiEntropy`, `NodeMsg::DkgVotes` or, for
+    /// responses, `NodeDataResponse::QueryResponse` or `ClientDataResponse::CmdResponse`.
+    pub msg: String,
+    /// Probability for each msg of the variant to be affected.
+    pub probability: f64,
+    /// What happens to an affected msg.
+    pub fault: MsgFaultKind,
+}
+
+/// What happens to a msg affected by a [`MsgFault`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MsgFaultKind {
+    /// The msg is not sent.
+    Drop,
+    /// The msg is sent after the given delay.
+    Delay {
+        /// Delay in ms
+        millis: u64,
+    },
+    /// The msg is sent twice.
+    Duplicate,
+    /// The msg is held back along with the other msgs being reordered, which are all sent one
+    /// after the other in a random order once the first of them has been held for the given
+    /// time, letting the msgs sent in the meantime overtake them.
+    Reorder {
+        /// How long msgs are held back for, at most, in ms
+        max_millis: u64,
+    },
+}
+
+/// Stalling of the node when taking part in DKG sessions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DkgStall {
+    /// Probability for the node to stall in each DKG session it takes part in.
+    pub probability: f64,
+    /// For how long the node doesn't handle any msg of the session once stalled, in ms.
+    pub millis: u64,
+}
+
+/// A point where the node crashes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CrashPoint {
+    /// Name of the `LogMarker` at which the node crashes, e.g. `PromotedToElder`.
+    pub marker: String,
+    /// Probability of crashing each time the marker is logged.
+    pub probability: f64,
+}
+
+impl ChaosProfile {
+    /// Reads the profile from the file, or else from the env, falling back to the default
+    /// profile, which only crashes nodes at startup.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let env_path = std::env::var(CHAOS_PROFILE_ENV).ok();
+        let profile = if let Some(path) = path.or_else(|| env_path.as_deref().map(Path::new)) {
+            debug!("[Chaos] Reading chaos profile from {}", path.display());
+            let content = std::fs::read(path)?;
+            Self::from_json(&content)?
+        } else if let Ok(json) = std::env::var(CHAOS_ENV) {
+            debug!("[Chaos] Reading chaos profile from {CHAOS_ENV}");
+            Self::from_json(json.as_bytes())?
+        } else {
+            Self::default_profile()
+        };
+
+        info!("[Chaos] Injecting faults as per {profile:?}");
+        Ok(profile)
+    }
+
+    /// Deserialises and validates a profile.
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        let profile: Self = serde_json::from_slice(json)
+            .map_err(|err| Error::Configuration(format!("Invalid chaos profile: {err}")))?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    fn default_profile() -> Self {
+        Self {
+            startup_crash_probability: DEFAULT_STARTUP_CRASH_PROBABILITY,
+            ..Self::default()
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let probabilities = [
+            self.startup_crash_probability,
+            self.chunk_corruption_probability,
+        ]
+        .into_iter()
+        .chain(self.msg_faults.iter().map(|fault| fault.probability))
+        .chain(self.dkg_stall.iter().map(|stall| stall.probability))
+        .chain(self.crash_points.iter().map(|point| point.probability));
+        for probability in probabilities {
+            if !(0.0..=1.0).contains(&probability) {
+                return Err(Error::Configuration(format!(
+                    "Invalid chaos profile: probability {probability} is not within [0, 1]"
+                )));
+            }
+        }
+
+        for point in &self.crash_points {
+            if LogMarker::from_str(&point.marker).is_err() {
+                return Err(Error::Configuration(format!(
+                    "Invalid chaos profile: unknown LogMarker '{}'",
+                    point.marker
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    // The first fault for the msg variant which kicks in, if any.
+    fn msg_fault(&self, msg_variant: &str) -> Option<&MsgFaultKind> {
+        let mut rng = sn_interface::rng::thread_rng();
+        self.msg_faults
+            .iter()
+            .filter(|fault| {
+                msg_variant == fault.msg || msg_variant == format!("NodeMsg::{}", fault.msg)
+            })
+            .find(|fault| rng.gen_bool(fault.probability))
+            .map(|fault| &fault.fault)
+    }
+
+    /// Whether the node is to crash right after starting.
+    pub fn crash_at_startup(&self) -> bool {
+        let probability = self.startup_crash_probability;
+        let crash = sn_interface::rng::thread_rng().gen_bool(probability);
+        if crash {
+            warn!("[Chaos] Crashing at startup, with probability {probability}");
+        }
+        crash
+    }
+}
+
+/// The faults being injected into a node, as per its profile.
+///
+/// Cloned handles share the state of the faults, such as the msgs held back for reordering.
+#[derive(Clone, Default)]
+pub struct Chaos(Arc<ChaosState>);
+
+#[derive(Default)]
+struct ChaosState {
+    profile: ChaosProfile,
+    // DKG sessions we are stalling in, and until when
+    stalled_dkg_sessions: Mutex<BTreeMap<XorName, Instant>>,
+    // Msgs held back for reordering, each waiting for its turn to be sent
+    reordered_msgs: Mutex<Vec<oneshot::Sender<SendTurn>>>,
+}
+
+impl fmt::Debug for Chaos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Chaos").field(&self.0.profile).finish()
+    }
+}
+
+/// What is to happen to a msg about to be sent, as per the faults of the profile.
+pub(crate) enum MsgFate {
+    /// Sent right away, as usual.
+    Sent,
+    /// Not sent.
+    Dropped,
+    /// Sent twice.
+    Duplicated,
+    /// Held back, to be sent once the wait is over.
+    Held(HeldMsg),
+}
+
+/// A msg held back by a delay or reordering fault.
+pub(crate) enum HeldMsg {
+    Delayed(Duration),
+    Reordered(oneshot::Receiver<SendTurn>),
+}
+
+/// The turn of a reordered msg to be sent, letting the next one go when dropped.
+pub(crate) struct SendTurn {
+    _sent: oneshot::Sender<()>,
+}
+
+impl HeldMsg {
+    /// Waits for the msg to be sent, returning its turn, if reordered, to drop once sent.
+    pub(crate) async fn wait(self) -> Option<SendTurn> {
+        match self {
+            Self::Delayed(delay) => {
+                tokio::time::sleep(delay).await;
+                None
+            }
+            Self::Reordered(turn) => turn.await.ok(),
+        }
+    }
+}
+
+impl Chaos {
+    /// Injects the faults of the profile.
+    pub fn new(profile: ChaosProfile) -> Self {
+        Self(Arc::new(ChaosState {
+            profile,
+            ..ChaosState::default()
+        }))
+    }
+
+    /// The profile of the faults being injected.
+    pub fn profile(&self) -> &ChaosProfile {
+        &self.0.profile
+    }
+
+    /// Decides what is to happen to the msg about to be sent, as per the profile.
+    pub(crate) fn msg_fate(&self, msg_variant: &str, msg_id: MsgId, peer: &Peer) -> MsgFate {
+        match self.0.profile.msg_fault(msg_variant) {
+            None => MsgFate::Sent,
+            Some(MsgFaultKind::Drop) => {
+                warn!("[Chaos] Dropping {msg_variant} {msg_id:?} to {peer}");
+                MsgFate::Dropped
+            }
+            Some(MsgFaultKind::Duplicate) => {
+                warn!("[Chaos] Duplicating {msg_variant} {msg_id:?} to {peer}");
+                MsgFate::Duplicated
+            }
+            Some(MsgFaultKind::Delay { millis }) => {
+                warn!("[Chaos] Delaying {msg_variant} {msg_id:?} to {peer} by {millis}ms");
+                MsgFate::Held(HeldMsg::Delayed(Duration::from_millis(*millis)))
+            }
+            Some(MsgFaultKind::Reorder { max_millis }) => {
+                warn!("[Chaos] Reordering {msg_variant} {msg_id:?} to {peer}");
+                MsgFate::Held(self.hold_for_reordering(Duration::from_millis(*max_millis)))
+            }
+        }
+    }
+
+    /// Sends the msg with `send`, as per its fate: not at all, twice, or later on, in a task of
+    /// its own if held back. Returns the outcomes of the sends made right away, the ones of the
+    /// later sends being passed to `on_late_outcome`.
+    pub(crate) async fn send<S, F, T, L>(
+        &self,
+        msg_variant: &str,
+        msg_id: MsgId,
+        peer: Peer,
+        send: S,
+        on_late_outcome: L,
+    ) -> Vec<T>
+    where
+        S: Fn() -> F + Send + 'static,
+        F: Future<Output = T> + Send,
+        T: Send + 'static,
+        L: FnOnce(T) + Send + 'static,
+    {
+        match self.msg_fate(msg_variant, msg_id, &peer) {
+            MsgFate::Sent => vec![send().await],
+            MsgFate::Dropped => vec![],
+            MsgFate::Duplicated => vec![send().await, send().await],
+            MsgFate::Held(held) => {
+                let _handle = tokio::spawn(async move {
+                    let _turn = held.wait().await;
+                    on_late_outcome(send().await);
+                });
+                vec![]
+            }
+        }
+    }
+
+    /// Sends the msg with `send` as per its fate, waiting for it to be sent, for when its outcome
+    /// is to be awaited anyway. Returns the outcomes of the sends made.
+    pub(crate) async fn send_and_await<S, F, T>(
+        &self,
+        msg_variant: &str,
+        msg_id: MsgId,
+        peer: &Peer,
+        send: S,
+    ) -> Vec<T>
+    where
+        S: Fn() -> F,
+        F: Future<Output = T>,
+    {
+        match self.msg_fate(msg_variant, msg_id, peer) {
+            MsgFate::Sent => vec![send().await],
+            MsgFate::Dropped => vec![],
+            MsgFate::Duplicated => vec![send().await, send().await],
+            MsgFate::Held(held) => {
+                // the next reordered msg can go as soon as this one is on its way
+                drop(held.wait().await);
+                vec![send().await]
+            }
+        }
+    }
+
+    // Holds back a msg until its turn comes, the held msgs being released one after the other
+    // in a random order once the first of them has been held for `max_wait`.
+    fn hold_for_reordering(&self, max_wait: Duration) -> HeldMsg {
+        let (turn_sender, turn) = oneshot::channel();
+        let mut held = lock(&self.0.reordered_msgs);
+        held.push(turn_sender);
+        if held.len() == 1 {
+            let chaos = self.clone();
+            let _handle = tokio::spawn(async move {
+                tokio::time::sleep(max_wait).await;
+                chaos.release_reordered_msgs().await;
+            });
+        }
+        HeldMsg::Reordered(turn)
+    }
+
+    async fn release_reordered_msgs(&self) {
+        let mut held = std::mem::take(&mut *lock(&self.0.reordered_msgs));
+        held.shuffle(&mut sn_interface::rng::thread_rng());
+        warn!("[Chaos] Sending {} reordered msgs", held.len());
+        for turn_sender in held {
+            let (sent_sender, sent) = oneshot::channel();
+            if turn_sender.send(SendTurn { _sent: sent_sender }).is_ok() {
+                // we get an error once the turn is dropped, i.e. once the msg is sent
+                let _ = tokio::time::timeout(REORDERED_SEND_TIMEOUT, sent).await;
+            }
+        }
+    }
+
+    /// Returns the content to store for the chunk, corrupted as per the profile.
+    pub(crate) fn corrupt_chunk(&self, address: &ChunkAddress, value: &Bytes) -> Bytes {
+        let probability = self.0.profile.chunk_corruption_probability;
+        let mut rng = sn_interface::rng::thread_rng();
+        if value.is_empty() || !rng.gen_bool(probability) {
+            return value.clone();
+        }
+
+        let mut corrupted = value.to_vec();
+        let index = rng.gen_range(0..corrupted.len());
+        corrupted[index] = !corrupted[index];
+        warn!("[Chaos] Corrupting byte #{index} of chunk {address:?} being stored");
+
+        Bytes::from(corrupted)
+    }
+
+    /// Waits until we are no longer stalled in the DKG session, deciding whether we stall in it
+    /// the first time one of its msgs is handled.
+    pub(crate) async fn stall_dkg_participant(&self, session_id: &DkgSessionId) {
+        let stall = match &self.0.profile.dkg_stall {
+            Some(stall) => stall,
+            None => return,
+        };
+
+        let session = XorName(session_id.hash());
+        let until = {
+            let mut stalled_sessions = lock(&self.0.stalled_dkg_sessions);
+            let now = Instant::now();
+            stalled_sessions.retain(|_, until| *until > now);
+            *stalled_sessions.entry(session).or_insert_with(|| {
+                if sn_interface::rng::thread_rng().gen_bool(stall.probability) {
+                    warn!(
+                        "[Chaos] Stalling in DKG session s{} for {}ms",
+                        session_id.sh(),
+                        stall.millis
+                    );
+                    now + Duration::from_millis(stall.millis)
+                } else {
+                    now
+                }
+            })
+        };
+
+        tokio::time::sleep_until(until).await;
+    }
+}
+
+// Locks the mutex, even if poisoned, as the faults state stays consistent regardless.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Tracing layer crashing the node, as per its profile, when one of its crash points is logged.
+#[derive(Debug)]
+pub struct CrashPointsLayer {
+    crash_points: Vec<CrashPoint>,
+}
+
+impl CrashPointsLayer {
+    /// Layer for the crash points of the profile.
+    pub fn new(profile: &ChaosProfile) -> Self {
+        Self {
+            crash_points: profile.crash_points.clone(),
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for CrashPointsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if self.crash_points.is_empty() {
+            return;
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let words: Vec<_> = visitor.0.split(|c: char| !c.is_alphanumeric()).collect();
+
+        for point in &self.crash_points {
+            if words.contains(&point.marker.as_str())
+                && sn_interface::rng::thread_rng().gen_bool(point.probability)
+            {
+                // not logged through tracing, as that would get back here
+                println!(
+                    "\n =========== [Chaos] (PID: {}): Crashing at {}. ============== \n",
+                    std::process::id(),
+                    point.marker
+                );
+                std::process::exit(CHAOS_CRASH_EXIT_CODE);
+            }
+        }
+    }
+}
+
+// Captures the formatted msg of an event.
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_is_read_from_json() -> Result<()> {
+        let json = r#"{
+            "msg_faults": [
+                { "msg": "AntiEntropy", "probability": 0.5, "fault": { "kind": "drop" } },
+                { "msg": "NodeMsg::DkgVotes", "probability": 1.0, "fault": { "kind": "delay", "millis": 200 } }
+            ],
+            "chunk_corruption_probability": 0.1,
+            "dkg_stall": { "probability": 0.2, "millis": 5000 },
+            "crash_points": [{ "marker": "PromotedToElder", "probability": 1.0 }]
+        }"#;
+
+        let profile = ChaosProfile::from_json(json.as_bytes())?;
+
+        assert_eq!(profile.startup_crash_probability, 0.0);
+        assert_eq!(profile.msg_faults.len(), 2);
+        assert_eq!(profile.msg_faults[0].fault, MsgFaultKind::Drop);
+        assert_eq!(
+            profile.dkg_stall,
+            Some(DkgStall {
+                probability: 0.2,
+                millis: 5000
+            })
+        );
+        assert_eq!(profile.crash_points[0].marker, "PromotedToElder");
+        Ok(())
+    }
+
+    #[test]
+    fn profile_with_unknown_marker_or_invalid_probability_is_rejected() {
+        let unknown_marker =
+            r#"{ "crash_points": [{ "marker": "NotAMarker", "probability": 1.0 }] }"#;
+        assert!(ChaosProfile::from_json(unknown_marker.as_bytes()).is_err());
+
+        let invalid_probability = r#"{ "chunk_corruption_probability": 1.5 }"#;
+        assert!(ChaosProfile::from_json(invalid_probability.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn msg_faults_match_variants_with_or_without_prefix() {
+        let fault = |msg: &str| MsgFault {
+            msg: msg.to_string(),
+            probability: 1.0,
+            fault: MsgFaultKind::Duplicate,
+        };
+        let profile = ChaosProfile {
+            msg_faults: vec![fault("AntiEntropy"), fault("NodeMsg::DkgVotes")],
+            ..ChaosProfile::default()
+        };
+
+        assert!(profile.msg_fault("NodeMsg::AntiEntropy").is_some());
+        assert!(profile.msg_fault("NodeMsg::DkgVotes").is_some());
+        assert!(profile.msg_fault("NodeMsg::AntiEntropyProbe").is_none());
+    }
+
+    fn chaos_with(fault: MsgFaultKind) -> Chaos {
+        Chaos::new(ChaosProfile {
+            msg_faults: vec![MsgFault {
+                msg: "AntiEntropy".to_string(),
+                probability: 1.0,
+                fault,
+            }],
+            ..ChaosProfile::default()
+        })
+    }
+
+    // Sends the msgs through chaos, recording the order they are sent in.
+    async fn send_all(chaos: &Chaos, msgs: &[(&str, usize)], sent: &Arc<Mutex<Vec<usize>>>) {
+        let peer = Peer::new(
+            XorName::random(&mut rand::thread_rng()),
+            ([127, 0, 0, 1], 0).into(),
+        );
+        for (variant, index) in msgs {
+            let (sent, index) = (sent.clone(), *index);
+            let _outcomes = chaos
+                .send(
+                    variant,
+                    MsgId::new(),
+                    peer,
+                    move || {
+                        let sent = sent.clone();
+                        async move { lock(&sent).push(index) }
+                    },
+                    |_| {},
+                )
+                .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn delayed_msgs_are_sent_later_without_holding_up_others() {
+        let chaos = chaos_with(MsgFaultKind::Delay { millis: 100 });
+        let sent = Arc::new(Mutex::new(vec![]));
+
+        send_all(
+            &chaos,
+            &[("NodeMsg::AntiEntropy", 0), ("NodeMsg::DkgAE", 1)],
+            &sent,
+        )
+        .await;
+        assert_eq!(*lock(&sent), vec![1]);
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(*lock(&sent), vec![1, 0]);
+    }
+
+    #[tokio::test]
+    async fn reordered_msgs_are_held_and_sent_in_a_random_order() {
+        let chaos = chaos_with(MsgFaultKind::Reorder { max_millis: 100 });
+        let sent = Arc::new(Mutex::new(vec![]));
+        let reordered: Vec<_> = (0..20)
+            .map(|index| ("NodeMsg::AntiEntropy", index))
+            .collect();
+
+        send_all(&chaos, &reordered, &sent).await;
+        send_all(&chaos, &[("NodeMsg::DkgAE", 20)], &sent).await;
+        assert_eq!(*lock(&sent), vec![20]);
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let sent = lock(&sent).clone();
+        assert_eq!(sent.len(), 21);
+        let mut reordered_sent = sent[1..].to_vec();
+        assert_ne!(reordered_sent, (0..20).collect::<Vec<_>>());
+        reordered_sent.sort_unstable();
+        assert_eq!(reordered_sent, (0..20).collect::<Vec<_>>());
+    }
+}
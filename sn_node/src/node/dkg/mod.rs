@@ -19,11 +19,13 @@ use sn_interface::{
     },
 };
 
-use bls::{PublicKey as BlsPublicKey, PublicKeySet, SecretKey as BlsSecretKey, SecretKeyShare};
+use bls::{
+    rand::Rng, PublicKey as BlsPublicKey, PublicKeySet, SecretKey as BlsSecretKey, SecretKeyShare,
+};
 use sn_sdkg::{DkgSignedVote, DkgState, NodeId, VoteResponse};
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
-use std::time::Instant;
+use tokio::time::Instant;
 use xor_name::XorName;
 
 /// A mapping of DKG participant XorName to their ephemeral bls public key along
@@ -57,7 +59,7 @@ fn create_dkg_state(
     secret_key: BlsSecretKey,
     ephemeral_bls_pks: DkgPubKeys,
 ) -> Result<DkgState> {
-    let mut rng = bls::rand::rngs::OsRng;
+    let mut rng = sn_interface::rng::thread_rng();
     let threshold = threshold(session_id.elders.len());
     let mut public_keys: BTreeMap<NodeId, BlsPublicKey> = BTreeMap::new();
     for (xorname, (ephemeral_pk, _)) in ephemeral_bls_pks.iter() {
@@ -126,7 +128,7 @@ impl DkgVoter {
         }
 
         // gen new key
-        let new_secret_key: BlsSecretKey = bls::rand::random();
+        let new_secret_key: BlsSecretKey = sn_interface::rng::thread_rng().gen();
         let new_pub_key = new_secret_key.public_key();
         let serialized_key = bincode::serialize(&new_pub_key)?;
         let key_sig = types::keys::ed25519::sign(&serialized_key, keypair);
@@ -328,7 +330,7 @@ impl DkgVoter {
         session_id: &DkgSessionId,
         vote: DkgSignedVote,
     ) -> Result<Vec<VoteResponse>> {
-        let rng = bls::rand::rngs::OsRng;
+        let rng = sn_interface::rng::thread_rng();
         match self.dkg_states.get_mut(&session_id.hash()) {
             Some(state) => Ok(state.handle_signed_vote(vote, rng)?),
             None => Err(Error::NoDkgStateForSession(session_id.clone())),
@@ -157,6 +157,9 @@ pub enum Error {
     /// Configuration error.
     #[error("Configuration error: {0}")]
     Configuration(String),
+    /// The node state snapshot found at the root dir cannot be restored.
+    #[error("Cannot restore node state snapshot: {0}")]
+    InvalidNodeStateSnapshot(String),
     /// Invalid node authority for a query response.
     #[error("Invalid node authority received for a QueryResponse message")]
     InvalidQueryResponseAuthority,
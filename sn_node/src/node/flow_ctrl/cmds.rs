@@ -8,7 +8,7 @@
 
 use crate::node::{core::NodeContext, messaging::Peers, SectionStateVote, XorName};
 
-use qp2p::SendStream;
+use sn_comms::SendStream;
 use sn_consensus::Decision;
 use sn_fault_detection::IssueType;
 use sn_interface::{
@@ -7,12 +7,32 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::node::flow_ctrl::FlowCtrl;
-use crate::node::STANDARD_CHANNEL_SIZE;
-use sn_fault_detection::{FaultDetection, IssueType};
-use std::collections::BTreeSet;
-use tokio::sync::mpsc::{self, Receiver, Sender};
+use crate::node::{Result, STANDARD_CHANNEL_SIZE};
+use sn_fault_detection::{FaultDetection, FaultDetectionConfig, FaultDetectionSnapshot, IssueType};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{
+    fs,
+    sync::mpsc::{self, Receiver, Sender},
+};
 use xor_name::XorName;
 
+// File name where to snapshot the tracked issues (stored at this node's set root storage dir)
+pub(crate) const FAULT_DETECTION_FILE_NAME: &str = "fault_detection";
+// How often the tracked issues are snapshotted, besides on shutdown
+const FAULT_DETECTION_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How the `FaultDetection` tracker is to be set up
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FaultDetectionSettings {
+    pub(crate) config: FaultDetectionConfig,
+    /// Where to snapshot the tracked issues to, and restore them from on start, if anywhere
+    pub(crate) snapshot_path: Option<PathBuf>,
+}
+
 pub(crate) struct FaultChannels {
     pub(crate) cmds_sender: Sender<FaultsCmd>,
     pub(crate) faulty_nodes_receiver: Receiver<Vec<XorName>>,
@@ -31,12 +51,37 @@ impl FlowCtrl {
     /// Spawns a tokio task that listens for the `FaultsCmd` and processes them
     pub(crate) fn start_fault_detection(
         mut tracker: FaultDetection,
+        snapshot_path: Option<PathBuf>,
         mut fault_cmds_from_node: Receiver<FaultsCmd>,
     ) -> Receiver<Vec<XorName>> {
         let (fault_nodes_sender, faulty_nodes_receiver) = mpsc::channel(STANDARD_CHANNEL_SIZE);
 
         let _handle = tokio::task::spawn(async move {
-            while let Some(cmd) = fault_cmds_from_node.recv().await {
+            if let Some(path) = &snapshot_path {
+                match read_snapshot(path).await {
+                    Ok(Some(snapshot)) => tracker.restore_snapshot(snapshot),
+                    Ok(None) => debug!("No FaultDetection snapshot found at {path:?}"),
+                    Err(error) => {
+                        warn!("Could not read FaultDetection snapshot from {path:?}: {error:?}")
+                    }
+                }
+            }
+
+            let mut snapshot_interval = tokio::time::interval(FAULT_DETECTION_SNAPSHOT_INTERVAL);
+            loop {
+                let cmd = tokio::select! {
+                    cmd = fault_cmds_from_node.recv() => cmd,
+                    _ = snapshot_interval.tick(), if snapshot_path.is_some() => {
+                        store_snapshot(&tracker, snapshot_path.as_deref()).await;
+                        continue;
+                    }
+                };
+
+                // the node has been dropped, i.e. it's shutting down
+                let Some(cmd) = cmd else {
+                    break;
+                };
+
                 match cmd {
                     FaultsCmd::AddNode(node) => tracker.add_new_node(node),
                     FaultsCmd::UpdateNodes(adults, elders) => {
@@ -63,6 +108,8 @@ impl FlowCtrl {
                     }
                 }
             }
+
+            store_snapshot(&tracker, snapshot_path.as_deref()).await;
         });
 
         faulty_nodes_receiver
@@ -90,3 +137,28 @@ impl FlowCtrl {
         }
     }
 }
+
+async fn store_snapshot(tracker: &FaultDetection, snapshot_path: Option<&Path>) {
+    if let Some(path) = snapshot_path {
+        if let Err(error) = write_snapshot(&tracker.snapshot(), path).await {
+            warn!("Could not write FaultDetection snapshot to {path:?}: {error:?}");
+        }
+    }
+}
+
+async fn read_snapshot(path: &Path) -> Result<Option<FaultDetectionSnapshot>> {
+    match fs::read(path).await {
+        Ok(content) => Ok(Some(bincode::deserialize(&content)?)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Writes to a temporary file first, so a crash mid-write doesn't leave a corrupted snapshot.
+async fn write_snapshot(snapshot: &FaultDetectionSnapshot, path: &Path) -> Result<()> {
+    let serialized = bincode::serialize(snapshot)?;
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serialized).await?;
+    fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
@@ -22,20 +22,25 @@ use periodic_checks::PeriodicChecksTimestamps;
 use crate::node::{
     flow_ctrl::{
         cmds::Cmd,
-        fault_detection::{FaultChannels, FaultsCmd},
+        fault_detection::{FaultChannels, FaultDetectionSettings, FaultsCmd},
     },
     messaging::Peers,
     MyNode, Result, STANDARD_CHANNEL_SIZE,
 };
 
-use sn_comms::MsgFromPeer;
+use sn_comms::{MsgFromPeer, PeerStats};
 use sn_fault_detection::FaultDetection;
 use sn_interface::{
     messaging::system::{JoinRejectReason, NodeDataCmd, NodeMsg},
+    network_knowledge::NetworkParams,
     types::{log_markers::LogMarker, DataAddress, Peer},
 };
 
-use std::{collections::BTreeSet, net::SocketAddr, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::SocketAddr,
+    sync::Arc,
+};
 use tokio::sync::{mpsc, RwLock};
 use xor_name::XorName;
 
@@ -69,6 +74,10 @@ pub(crate) struct FlowCtrl {
     cmd_sender_channel: mpsc::Sender<(Cmd, Vec<usize>)>,
     fault_channels: FaultChannels,
     timestamps: PeriodicChecksTimestamps,
+    /// The params of the network, which set the periodic checks' intervals
+    network_params: NetworkParams,
+    /// Peer comms stats as of the last periodic check
+    last_peer_stats: BTreeMap<Peer, PeerStats>,
 }
 
 impl FlowCtrl {
@@ -79,6 +88,7 @@ impl FlowCtrl {
         mut incoming_msg_events: mpsc::Receiver<MsgFromPeer>,
         data_replication_receiver: mpsc::Receiver<(Vec<DataAddress>, Peer)>,
         fault_cmds_channels: (mpsc::Sender<FaultsCmd>, mpsc::Receiver<FaultsCmd>),
+        fault_detection_settings: FaultDetectionSettings,
     ) -> (
         mpsc::Sender<(Cmd, Vec<usize>)>,
         mpsc::Receiver<RejoinReason>,
@@ -91,34 +101,13 @@ impl FlowCtrl {
 
         let node_identifier = node_context.info.name();
 
-        let all_members = node_context
-            .network_knowledge
-            .adults()
-            .iter()
-            .map(|peer| peer.name())
-            .collect::<BTreeSet<XorName>>();
-        let elders = node_context
-            .network_knowledge
-            .elders()
-            .iter()
-            .map(|peer| peer.name())
-            .collect::<BTreeSet<XorName>>();
-        let fault_channels = {
-            let tracker = FaultDetection::new(all_members, elders);
-            // start FaultDetection in a new thread
-            let faulty_nodes_receiver = Self::start_fault_detection(tracker, fault_cmds_channels.1);
-            FaultChannels {
-                cmds_sender: fault_cmds_channels.0,
-                faulty_nodes_receiver,
-            }
-        };
-
-        let flow_ctrl = Self {
-            node: cmd_ctrl.node(),
-            cmd_sender_channel: cmd_sender_channel.clone(),
-            fault_channels,
-            timestamps: PeriodicChecksTimestamps::now(),
-        };
+        let flow_ctrl = Self::new(
+            cmd_ctrl.node(),
+            cmd_sender_channel.clone(),
+            fault_cmds_channels,
+            fault_detection_settings,
+        )
+        .await;
 
         let _handle = tokio::task::spawn(flow_ctrl.process_messages_and_periodic_checks());
 
@@ -171,6 +160,52 @@ impl FlowCtrl {
         (cmd_sender_channel, rejoin_network_rx)
     }
 
+    /// Constructs a FlowCtrl instance, starting the node's `FaultDetection` on a new thread,
+    /// with its periodic checks sending the cmds they result in onto `cmd_sender_channel`
+    pub(crate) async fn new(
+        node: Arc<RwLock<MyNode>>,
+        cmd_sender_channel: mpsc::Sender<(Cmd, Vec<usize>)>,
+        fault_cmds_channels: (mpsc::Sender<FaultsCmd>, mpsc::Receiver<FaultsCmd>),
+        fault_detection_settings: FaultDetectionSettings,
+    ) -> Self {
+        let node_context = node.read().await.context();
+        let all_members = node_context
+            .network_knowledge
+            .adults()
+            .iter()
+            .map(|peer| peer.name())
+            .collect::<BTreeSet<XorName>>();
+        let elders = node_context
+            .network_knowledge
+            .elders()
+            .iter()
+            .map(|peer| peer.name())
+            .collect::<BTreeSet<XorName>>();
+        let fault_channels = {
+            let tracker =
+                FaultDetection::with_config(all_members, elders, fault_detection_settings.config);
+            // start FaultDetection in a new thread
+            let faulty_nodes_receiver = Self::start_fault_detection(
+                tracker,
+                fault_detection_settings.snapshot_path,
+                fault_cmds_channels.1,
+            );
+            FaultChannels {
+                cmds_sender: fault_cmds_channels.0,
+                faulty_nodes_receiver,
+            }
+        };
+
+        Self {
+            node,
+            cmd_sender_channel,
+            fault_channels,
+            timestamps: PeriodicChecksTimestamps::now(),
+            network_params: node_context.network_knowledge.network_params(),
+            last_peer_stats: BTreeMap::new(),
+        }
+    }
+
     /// Listens on data_replication_receiver on a new thread, sorts and batches data, generating SendMsg Cmds
     async fn send_out_data_for_replication(
         node_arc: Arc<RwLock<MyNode>>,
@@ -13,17 +13,30 @@ use crate::node::{
     MyNode,
 };
 
-use sn_interface::{messaging::system::NodeMsg, types::log_markers::LogMarker};
+use sn_comms::PeerStats;
+use sn_fault_detection::IssueType;
+use sn_interface::{
+    messaging::system::NodeMsg,
+    network_knowledge::NetworkParams,
+    types::{log_markers::LogMarker, Peer},
+};
 
-use std::{collections::BTreeSet, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{sync::RwLock, time::Instant};
 
-const PROBE_INTERVAL: Duration = Duration::from_secs(300);
-const RELOCATION_TIMEOUT_SECS: Duration = Duration::from_secs(60);
-const MISSING_VOTE_INTERVAL: Duration = Duration::from_secs(5);
-const MISSING_DKG_MSG_INTERVAL: Duration = Duration::from_secs(5);
 // const SECTION_PROBE_INTERVAL: Duration = Duration::from_secs(300);
 const FAULT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const PEER_STATS_INTERVAL: Duration = Duration::from_secs(60);
+/// More reconnects than this to a peer within a `PEER_STATS_INTERVAL` is tracked as a comms issue
+const MAX_RECONNECTS_PER_INTERVAL: u64 = 3;
+/// A peer's smoothed rtt this many times over the median of all peers is tracked as a comms issue
+const SLOW_RTT_FACTOR: u32 = 4;
+/// ...as long as it's over this, so we don't track issues over what are fast responses anyway
+const MIN_SLOW_RTT: Duration = Duration::from_secs(1);
 // 30 adult nodes checked per minute., so each node should be queried 10x in 10 mins
 // Which should hopefully trigger fault if we're not getting responses back
 // const ADULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(2);
@@ -38,6 +51,7 @@ pub(super) struct PeriodicChecksTimestamps {
     last_dkg_msg_check: Instant,
     last_fault_check: Instant,
     last_relocation_retry_check: Instant,
+    last_peer_stats_check: Instant,
 }
 
 impl PeriodicChecksTimestamps {
@@ -51,28 +65,35 @@ impl PeriodicChecksTimestamps {
             last_dkg_msg_check: Instant::now(),
             last_fault_check: Instant::now(),
             last_relocation_retry_check: Instant::now(),
+            last_peer_stats_check: Instant::now(),
         }
     }
 
     /// Check if any of the periodic checks have elapsed
-    pub(crate) fn something_expired(&self) -> bool {
-        self.last_vote_check.elapsed() > MISSING_VOTE_INTERVAL
-            || self.last_probe.elapsed() > PROBE_INTERVAL
-            || self.last_dkg_msg_check.elapsed() > MISSING_DKG_MSG_INTERVAL
+    pub(crate) fn something_expired(&self, params: &NetworkParams) -> bool {
+        self.last_vote_check.elapsed() > params.missing_vote_interval()
+            || self.last_probe.elapsed() > params.probe_interval()
+            || self.last_dkg_msg_check.elapsed() > params.missing_dkg_msg_interval()
             || self.last_fault_check.elapsed() > FAULT_CHECK_INTERVAL
-            || self.last_relocation_retry_check.elapsed() > RELOCATION_TIMEOUT_SECS
+            || self.last_relocation_retry_check.elapsed() > params.relocation_timeout()
+            || self.last_peer_stats_check.elapsed() > PEER_STATS_INTERVAL
     }
 }
 
 impl FlowCtrl {
     /// Generate and fire commands for all types of periodic checks
     pub(super) async fn perform_periodic_checks(&mut self) {
-        if !self.timestamps.something_expired() {
+        if !self.timestamps.something_expired(&self.network_params) {
             return;
         }
 
         let context = self.node.read().await.context();
 
+        if self.timestamps.last_peer_stats_check.elapsed() > PEER_STATS_INTERVAL {
+            self.timestamps.last_peer_stats_check = Instant::now();
+            self.check_peer_stats(&context);
+        }
+
         if !context.is_elder {
             // self.enqueue_cmds_for_adult_periodic_checks(context).await;
 
@@ -106,7 +127,7 @@ impl FlowCtrl {
         let now = Instant::now();
         let mut cmds = vec![];
 
-        if self.timestamps.last_probe.elapsed() > PROBE_INTERVAL {
+        if self.timestamps.last_probe.elapsed() > self.network_params.probe_interval() {
             self.timestamps.last_probe = now;
             if let Some(cmd) = Self::probe_the_network(context) {
                 cmds.push(cmd);
@@ -140,7 +161,7 @@ impl FlowCtrl {
         //     }
         // }
 
-        if self.timestamps.last_vote_check.elapsed() > MISSING_VOTE_INTERVAL {
+        if self.timestamps.last_vote_check.elapsed() > self.network_params.missing_vote_interval() {
             self.timestamps.last_vote_check = now;
             let read_locked_node = self.node.read().await;
 
@@ -154,7 +175,9 @@ impl FlowCtrl {
             debug!(" ----> vote periodics done");
         }
 
-        if self.timestamps.last_dkg_msg_check.elapsed() > MISSING_DKG_MSG_INTERVAL {
+        if self.timestamps.last_dkg_msg_check.elapsed()
+            > self.network_params.missing_dkg_msg_interval()
+        {
             debug!(" ----> dkg msg periodics start");
             self.timestamps.last_dkg_msg_check = now;
             Self::check_for_missed_dkg_messages(self.node.clone(), self.cmd_sender_channel.clone())
@@ -170,11 +193,16 @@ impl FlowCtrl {
         // This check keeps relocation retrying if it times out.
         if let Some(proof) = &context.relocation_proof {
             if !context.network_knowledge.is_section_member(&context.name) {
-                if self.timestamps.last_relocation_retry_check.elapsed() > RELOCATION_TIMEOUT_SECS {
+                if self.timestamps.last_relocation_retry_check.elapsed()
+                    > self.network_params.relocation_timeout()
+                {
                     self.timestamps.last_relocation_retry_check = Instant::now();
                     cmds.push(MyNode::send_msg_to_our_elders_await_responses(
                         context.clone(),
-                        NodeMsg::TryJoin(Some(proof.clone())),
+                        NodeMsg::TryJoin {
+                            relocation: Some(proof.clone()),
+                            relay: context.info.relay,
+                        },
                     ));
                 }
             } else {
@@ -292,7 +320,7 @@ impl FlowCtrl {
 
             if let Some(time) = last_received_vote_time {
                 // we want to resend the prev vote
-                if time.elapsed() >= MISSING_VOTE_INTERVAL {
+                if time.elapsed() >= self.network_params.missing_vote_interval() {
                     debug!("Vote consensus appears stalled...");
                     if let Some(cmd) =
                         MyNode::membership_gossip_votes(context, membership_context).await
@@ -325,7 +353,11 @@ impl FlowCtrl {
             let last_received_dkg_message = dkg_voter.last_received_dkg_message();
 
             if let Some(time) = last_received_dkg_message {
-                if time.elapsed() >= MISSING_DKG_MSG_INTERVAL {
+                let interval = node
+                    .network_knowledge
+                    .network_params()
+                    .missing_dkg_msg_interval();
+                if time.elapsed() >= interval {
                     let cmds = node.dkg_gossip_msgs();
                     if !cmds.is_empty() {
                         debug!("Dkg msg resending cmd, as Dkg voting appears stalled...");
@@ -341,6 +373,60 @@ impl FlowCtrl {
         });
     }
 
+    /// Logs the comms and AE stats of each peer, and as elders, tracks comms issues for
+    /// section members which reconnected too often or are way slower than the rest.
+    /// Failed sends are not considered here, as those are tracked as they happen.
+    fn check_peer_stats(&mut self, context: &NodeContext) {
+        let peer_stats = context.comm.peer_stats();
+        for (peer, stats) in &peer_stats {
+            debug!("{}: {peer:?}: {stats:?}", LogMarker::PeerStats);
+        }
+        for (peer, stats) in context.ae_budget.stats() {
+            debug!("{}: {peer:?}: {stats:?}", LogMarker::AeStats);
+        }
+
+        if context.is_elder {
+            for peer in Self::peers_with_comms_issues(&self.last_peer_stats, &peer_stats) {
+                if context.network_knowledge.is_section_member(&peer.name()) {
+                    debug!("Tracking comms issue for {peer:?} as per its stats");
+                    context.track_node_issue(peer.name(), IssueType::Communication);
+                }
+            }
+        }
+
+        self.last_peer_stats = peer_stats;
+    }
+
+    fn peers_with_comms_issues(
+        previous: &BTreeMap<Peer, PeerStats>,
+        current: &BTreeMap<Peer, PeerStats>,
+    ) -> BTreeSet<Peer> {
+        let mut peers = BTreeSet::new();
+
+        for (peer, stats) in current {
+            let previous_reconnects = previous.get(peer).map_or(0, |stats| stats.reconnects);
+            if stats.reconnects.saturating_sub(previous_reconnects) > MAX_RECONNECTS_PER_INTERVAL {
+                let _prev = peers.insert(*peer);
+            }
+        }
+
+        let mut rtts = current
+            .values()
+            .filter_map(|stats| stats.smoothed_rtt)
+            .collect::<Vec<_>>();
+        rtts.sort();
+        if let Some(median) = rtts.get(rtts.len() / 2) {
+            let slow_rtt = (*median * SLOW_RTT_FACTOR).max(MIN_SLOW_RTT);
+            for (peer, stats) in current {
+                if stats.smoothed_rtt.map_or(false, |rtt| rtt > slow_rtt) {
+                    let _prev = peers.insert(*peer);
+                }
+            }
+        }
+
+        peers
+    }
+
     async fn vote_out_faulty_nodes(&mut self) -> Vec<Cmd> {
         info!("Voting out faulty nodes");
         let mut cmds = vec![];
@@ -371,3 +457,63 @@ impl FlowCtrl {
 
 //     Ok(AuthorityProof::verify(auth, payload)?)
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    fn peer(port: u16) -> Peer {
+        Peer::new(
+            xor_name::rand::random(),
+            SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        )
+    }
+
+    fn stats(reconnects: u64, rtt_millis: u64) -> PeerStats {
+        PeerStats {
+            reconnects,
+            smoothed_rtt: Some(Duration::from_millis(rtt_millis)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn peers_reconnecting_too_often_have_comms_issues() {
+        let (steady, churning) = (peer(1), peer(2));
+        let previous = BTreeMap::from([(steady, stats(10, 50)), (churning, stats(1, 50))]);
+        let current = BTreeMap::from([
+            (steady, stats(10 + MAX_RECONNECTS_PER_INTERVAL, 50)),
+            (churning, stats(2 + MAX_RECONNECTS_PER_INTERVAL, 50)),
+        ]);
+
+        let peers = FlowCtrl::peers_with_comms_issues(&previous, &current);
+        assert_eq!(peers, BTreeSet::from([churning]));
+    }
+
+    #[test]
+    fn peers_way_slower_than_the_median_have_comms_issues() {
+        let mut current = (0..10)
+            .map(|port| (peer(port), stats(0, 300)))
+            .collect::<BTreeMap<_, _>>();
+        let (slow, way_slow) = (peer(10), peer(11));
+        let _prev = current.insert(slow, stats(0, 1100));
+        let _prev = current.insert(way_slow, stats(0, 1300));
+
+        let peers = FlowCtrl::peers_with_comms_issues(&BTreeMap::new(), &current);
+        assert_eq!(peers, BTreeSet::from([way_slow]));
+    }
+
+    #[test]
+    fn fast_peers_have_no_comms_issues() {
+        let mut current = (0..10)
+            .map(|port| (peer(port), stats(0, 10)))
+            .collect::<BTreeMap<_, _>>();
+        // over 4x the median, but still a fast response
+        let _prev = current.insert(peer(10), stats(0, 900));
+
+        let peers = FlowCtrl::peers_with_comms_issues(&BTreeMap::new(), &current);
+        assert!(peers.is_empty());
+    }
+}
@@ -152,7 +152,7 @@ impl<'a> ProcessAndInspectCmds<'a> {
     }
 
     pub(crate) async fn next(&mut self) -> crate::node::error::Result<Option<&Cmd>> {
-        let mut next_index = self.index_inspected + 1;
+        let mut next_index = self.index_inspected.wrapping_add(1);
         if next_index < self.pending_cmds.len() {
             let cmd = self.pending_cmds.get(next_index);
             assert!(cmd.is_some());
@@ -161,7 +161,7 @@ impl<'a> ProcessAndInspectCmds<'a> {
         }
 
         while let Some(cmd) = self.pending_cmds.pop_front() {
-            next_index -= 1;
+            next_index = next_index.wrapping_sub(1);
             if !matches!(
                 cmd,
                 Cmd::SendMsg { .. }
@@ -10,23 +10,27 @@
 pub(crate) mod cmd_utils;
 pub(crate) mod dbc_utils;
 pub(crate) mod network_builder;
+pub(crate) mod simulation;
 
 use crate::node::{
     flow_ctrl::{
         dispatcher::Dispatcher,
-        tests::network_builder::{TestNetwork, TestNetworkBuilder},
+        tests::network_builder::{
+            elder_count, test_network_params, TestNetwork, TestNetworkBuilder,
+        },
     },
     messaging::Peers,
-    relocation_check, ChurnId, Cmd, Error, SectionStateVote,
+    relocation_check, ChurnId, Cmd, Error, MyNode, SectionStateVote,
 };
 use cmd_utils::{handle_online_cmd, ProcessAndInspectCmds};
+use simulation::{sim_network_params, sim_seed, Simulation};
 
 use sn_comms::MsgFromPeer;
 use sn_consensus::Decision;
 use sn_dbc::Hash;
 use sn_interface::{
     dbcs::gen_genesis_dbc,
-    elder_count, init_logger,
+    init_logger,
     messaging::{
         data::{
             ClientDataResponse, ClientMsg, CmdResponse, DataCmd, Error as MessagingDataError,
@@ -36,21 +40,22 @@ use sn_interface::{
         Dst, MsgType, WireMsg,
     },
     network_knowledge::{
-        recommended_section_size, supermajority, Error as NetworkKnowledgeError, MembershipState,
-        MyNodeInfo, NodeState, RelocationDst, RelocationInfo, RelocationProof, SectionKeysProvider,
-        SectionTreeUpdate, SectionsDAG, MIN_ADULT_AGE,
+        supermajority, Error as NetworkKnowledgeError, MembershipState, MyNodeInfo,
+        NetworkKnowledge, NodeState, RelocationDst, RelocationInfo, RelocationProof,
+        SectionKeysProvider, SectionTreeUpdate, SectionsDAG, MIN_ADULT_AGE,
     },
     test_utils::*,
     types::{keys::ed25519, PublicKey},
 };
 
 use assert_matches::assert_matches;
-use eyre::{bail, eyre, Result};
+use eyre::{bail, eyre, ContextCompat, Result};
 use rand::{rngs::StdRng, thread_rng, SeedableRng};
 use std::{
     collections::{BTreeSet, HashSet},
     iter,
     sync::Arc,
+    time::Duration,
 };
 use tokio::sync::RwLock;
 use xor_name::{Prefix, XorName};
@@ -95,7 +100,10 @@ async fn membership_churn_starts_on_join_request_from_relocated_node() -> Result
             name: XorName::from(PublicKey::Bls(section_key)),
             section_key,
         },
-        NodeMsg::TryJoin(Some(proof)),
+        NodeMsg::TryJoin {
+            relocation: Some(proof),
+            relay: None,
+        },
     )?;
 
     let elder_dispatcher = env.get_dispatchers(prefix, 1, 0, None).remove(0);
@@ -535,7 +543,7 @@ async fn relocation_decision_is_sent() -> Result<()> {
     init_logger();
 
     let prefix: Prefix = prefix("0");
-    let adults = recommended_section_size() - elder_count();
+    let adults = test_network_params().recommended_section_size - elder_count();
     let env = TestNetworkBuilder::new(thread_rng())
         .sap(prefix, elder_count(), adults, None, None)
         .build();
@@ -675,7 +683,7 @@ async fn handle_elders_update() -> Result<()> {
         &dispatcher,
     );
 
-    let mut update_actual_recipients = HashSet::new();
+    let mut digest_actual_recipients = HashSet::new();
     while let Some(cmd) = cmds.next().await? {
         let (msg, recipients) = match cmd {
             Cmd::SendMsg {
@@ -686,29 +694,18 @@ async fn handle_elders_update() -> Result<()> {
             _ => continue,
         };
 
-        let section_tree_update = match msg {
-            NodeMsg::AntiEntropy {
-                section_tree_update,
-                kind: AntiEntropyKind::Update { .. },
-                ..
-            } => section_tree_update.clone(),
+        let digest = match msg {
+            NodeMsg::AntiEntropyDigest(digest) => digest,
             _ => continue,
         };
 
-        assert_eq!(
-            section_tree_update.proof_chain.last_key()?,
-            sk_set1.public_keys().public_key()
-        );
-        // Merging the section contained in the message with the original section succeeds.
-        assert!(section0
-            .clone()
-            .update_knowledge_if_valid(section_tree_update, None, &info.name())
-            .is_ok());
+        assert_eq!(digest.section_key, sk_set1.public_keys().public_key());
+        assert_ne!(*digest, section0.knowledge_digest());
 
-        update_actual_recipients.extend(recipients);
+        digest_actual_recipients.extend(recipients);
     }
 
-    let update_expected_recipients: HashSet<_> = env
+    let digest_expected_recipients: HashSet<_> = env
         .get_peers(prefix, elder_count(), 1, Some(0))
         .into_iter()
         .filter(|peer| *peer != info.peer())
@@ -716,7 +713,18 @@ async fn handle_elders_update() -> Result<()> {
         .chain(iter::once(demoted_peer.0.peer()))
         .collect();
 
-    assert_eq!(update_actual_recipients, update_expected_recipients);
+    assert_eq!(digest_actual_recipients, digest_expected_recipients);
+
+    // The update fetched by a member with the original section knowledge is valid for it.
+    let section_tree_update = fetch_ae_update(&dispatcher, &section0).await?;
+    assert_eq!(
+        section_tree_update.proof_chain.last_key()?,
+        sk_set1.public_keys().public_key()
+    );
+    assert!(section0
+        .clone()
+        .update_knowledge_if_valid(section_tree_update, None, &info.name())
+        .is_ok());
 
     assert_lists(
         dispatcher.node().read().await.network_knowledge().elders(),
@@ -814,7 +822,7 @@ async fn handle_demote_during_split() -> Result<()> {
     };
     let mut cmds = ProcessAndInspectCmds::new(cmd, &dispatcher);
 
-    let mut update_recipients = BTreeSet::new();
+    let mut digest_recipients = BTreeSet::new();
     while let Some(cmd) = cmds.next().await? {
         let (msg, recipients) = match cmd {
             Cmd::SendMsg {
@@ -823,20 +831,57 @@ async fn handle_demote_during_split() -> Result<()> {
             _ => continue,
         };
 
-        if let NodeMsg::AntiEntropy {
-            kind: AntiEntropyKind::Update { .. },
-            ..
-        } = msg
-        {
-            update_recipients.extend(recipients.into_iter().map(|r| r.name()))
+        if let NodeMsg::AntiEntropyDigest(_) = msg {
+            digest_recipients.extend(recipients.into_iter().map(|r| r.name()))
         }
     }
 
     // our node's whole section
-    assert_eq!(update_recipients.len(), elder_count());
+    assert_eq!(digest_recipients.len(), elder_count());
+
+    // The update fetched by a member with the pre-split section knowledge is valid for it.
+    let pre_split_section = env.get_network_knowledge(Prefix::default(), None);
+    let section_tree_update = fetch_ae_update(&dispatcher, &pre_split_section).await?;
+    assert!(pre_split_section
+        .clone()
+        .update_knowledge_if_valid(section_tree_update, None, &node_name)
+        .is_ok());
     Ok(())
 }
 
+// Returns the `SectionTreeUpdate` the node responds with to an AE-Fetch
+// from a member with the given knowledge of the section.
+async fn fetch_ae_update(
+    dispatcher: &Dispatcher,
+    knowledge: &NetworkKnowledge,
+) -> Result<SectionTreeUpdate> {
+    let context = dispatcher.node().read().await.context();
+    let members = knowledge
+        .section_members()
+        .iter()
+        .map(|member| member.name())
+        .collect();
+    let fetcher = knowledge
+        .elders()
+        .into_iter()
+        .find(|peer| *peer != context.info.peer())
+        .context("No other elder to fetch the update")?;
+
+    let cmd = MyNode::handle_ae_fetch(&context, knowledge.section_key(), members, fetcher)
+        .context("No response to the AE-Fetch")?;
+    match cmd {
+        Cmd::SendMsg {
+            msg:
+                NodeMsg::AntiEntropy {
+                    section_tree_update,
+                    kind: AntiEntropyKind::Update { .. },
+                },
+            ..
+        } => Ok(section_tree_update),
+        cmd => bail!("Unexpected response to the AE-Fetch: {cmd:?}"),
+    }
+}
+
 #[tokio::test]
 async fn spentbook_spend_client_message_should_replicate_to_adults_and_send_ack() -> Result<()> {
     init_logger();
@@ -956,6 +1001,82 @@ async fn spentbook_spend_transaction_with_no_inputs_should_return_spentbook_erro
     bail!("We expected an error to be returned");
 }
 
+#[tokio::test]
+async fn section_health_query_is_answered_with_our_view_of_the_section() -> Result<()> {
+    init_logger();
+    let prefix = prefix("1");
+
+    let mut env = TestNetworkBuilder::new(thread_rng())
+        .sap(prefix, elder_count(), 6, None, Some(0))
+        .build();
+    let dispatcher = env.get_dispatchers(prefix, 1, 0, None).remove(0);
+    let section = env.get_network_knowledge(prefix, None);
+
+    let comm_rx = env.take_comm_rx(dispatcher.node().read().await.info().public_key());
+    let mut cmds = ProcessAndInspectCmds::new_from_client_msg(
+        ClientMsg::SectionHealthQuery,
+        &dispatcher,
+        comm_rx,
+    )
+    .await?;
+
+    while let Some(cmd) = cmds.next().await? {
+        if let Cmd::SendClientResponse {
+            msg: ClientDataResponse::SectionHealth { health, .. },
+            ..
+        } = cmd
+        {
+            assert_eq!(health.prefix, prefix);
+            assert_eq!(health.section_key, section.section_key());
+            assert_eq!(health.members, section.section_members());
+            assert_eq!(health.members.len(), elder_count() + 6);
+            return Ok(());
+        }
+    }
+
+    bail!("We expected a SectionHealth response to be sent back");
+}
+
+#[tokio::test]
+async fn section_members_query_is_answered_with_a_verifiable_proof() -> Result<()> {
+    init_logger();
+    let prefix = prefix("1");
+
+    let mut env = TestNetworkBuilder::new(thread_rng())
+        .sap(prefix, elder_count(), 6, None, Some(0))
+        .build();
+    let dispatcher = env.get_dispatchers(prefix, 1, 0, None).remove(0);
+    let section = env.get_network_knowledge(prefix, None);
+
+    let comm_rx = env.take_comm_rx(dispatcher.node().read().await.info().public_key());
+    let mut cmds = ProcessAndInspectCmds::new_from_client_msg(
+        ClientMsg::SectionMembersQuery,
+        &dispatcher,
+        comm_rx,
+    )
+    .await?;
+
+    while let Some(cmd) = cmds.next().await? {
+        if let Cmd::SendClientResponse {
+            msg: ClientDataResponse::SectionMembers { proof, .. },
+            ..
+        } = cmd
+        {
+            assert_eq!(proof.signed_sap.prefix(), prefix);
+            assert_eq!(proof.members, section.section_signed_members());
+            assert_eq!(proof.members.len(), elder_count() + 6);
+
+            // a client only knowing the genesis key can verify it
+            let mut dag = SectionsDAG::new(*section.genesis_key());
+            dag.merge(proof.proof_chain.clone())?;
+            assert!(proof.verify(&dag));
+            return Ok(());
+        }
+    }
+
+    bail!("We expected a SectionMembers response to be sent back");
+}
+
 /// This could potentially be the start of a case for the updated proof chain and SAP being sent
 /// with the spend request, but I don't know exactly what the conditions are for getting the
 /// network knowledge to update correctly.
@@ -1082,6 +1203,97 @@ async fn spentbook_spend_with_updated_network_knowledge_should_update_the_node()
     Ok(())
 }
 
+#[tokio::test(start_paused = true)]
+async fn simulated_network_splits_with_invariants_holding() -> Result<()> {
+    init_logger();
+    let mut sim = Simulation::new(sim_seed(1), sim_network_params()).await?;
+
+    sim.schedule_churn(16, 0, Duration::from_secs(60));
+    sim.run_for(Duration::from_secs(90)).await?;
+    let _ = sim.put_chunk(1024).await?;
+
+    sim.schedule_churn(16, 0, Duration::from_secs(60));
+    sim.run_for(Duration::from_secs(180)).await?;
+    let _ = sim.put_chunk(1024).await?;
+    sim.run_for(Duration::from_secs(30)).await?;
+
+    let sections = sim.sections().await;
+    assert!(
+        sections.len() >= 2,
+        "seed {}: the network did not split, its sections are {sections:?}",
+        sim.seed()
+    );
+
+    let members_by_elder = sim.members_by_elder().await;
+    let members: usize = members_by_elder
+        .values()
+        .map(|members| members.len())
+        .sum::<usize>()
+        / sim_network_params().elder_count;
+    assert!(
+        members > sim.live_nodes() / 2,
+        "seed {}: only {members} of the {} nodes are members",
+        sim.seed(),
+        sim.live_nodes()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn simulated_network_survives_churn_with_relocations_and_handovers() -> Result<()> {
+    init_logger();
+    let mut sim = Simulation::new(sim_seed(2), sim_network_params()).await?;
+
+    // relocations only take place once the first section has split
+    sim.schedule_churn(32, 0, Duration::from_secs(120));
+    sim.run_for(Duration::from_secs(180)).await?;
+    let _ = sim.put_chunk(1024).await?;
+    let section_keys = sim.section_keys().await;
+
+    // one node leaving at a time, as a section can't vote a second one out in the meantime
+    for _ in 0..4 {
+        sim.schedule_churn(2, 1, Duration::from_secs(30));
+        sim.run_for(Duration::from_secs(60)).await?;
+    }
+    sim.schedule_elder_leave(Duration::ZERO);
+    sim.run_for(Duration::from_secs(60)).await?;
+    let _ = sim.put_chunk(1024).await?;
+    sim.run_for(Duration::from_secs(30)).await?;
+
+    assert!(
+        sim.relocated_members().await >= 1,
+        "seed {}: no member was relocated",
+        sim.seed()
+    );
+    assert!(
+        sim.section_keys().await > section_keys,
+        "seed {}: no handover took place during churn",
+        sim.seed()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn simulated_churn_is_replayable_from_its_seed() -> Result<()> {
+    init_logger();
+    let seed = sim_seed(3);
+
+    let mut runs = vec![];
+    for _ in 0..2 {
+        let mut sim = Simulation::new(seed, sim_network_params()).await?;
+        sim.schedule_churn(10, 0, Duration::from_secs(30));
+        sim.run_for(Duration::from_secs(60)).await?;
+        sim.schedule_churn(2, 1, Duration::from_secs(30));
+        sim.run_for(Duration::from_secs(60)).await?;
+        runs.push((sim.churn_log().to_vec(), sim.trace_digest()));
+    }
+
+    assert_eq!(runs[0], runs[1], "seed {seed}");
+    Ok(())
+}
+
 fn get_single_sig(proposal: &SectionStateVote) -> Vec<u8> {
     bincode::serialize(proposal).expect("Failed to serialize")
 }
@@ -7,12 +7,11 @@ use crate::{
 
 use sn_comms::{Comm, MsgFromPeer};
 use sn_interface::{
-    elder_count,
     messaging::system::SectionSigned,
     network_knowledge::{
-        supermajority, MyNodeInfo, NetworkKnowledge, NodeState, SectionAuthorityProvider,
-        SectionKeyShare, SectionKeysProvider, SectionTree, SectionTreeUpdate, SectionsDAG,
-        MIN_ADULT_AGE,
+        supermajority, MyNodeInfo, NetworkKnowledge, NetworkParams, NodeState,
+        SectionAuthorityProvider, SectionKeyShare, SectionKeysProvider, SectionTree,
+        SectionTreeUpdate, SectionsDAG, MIN_ADULT_AGE,
     },
     test_utils::*,
     types::{keys::ed25519::gen_keypair, Peer, PublicKey},
@@ -41,6 +40,17 @@ pub(crate) const ELDER_AGE_PATTERN: &[u8] = &[50, 45, 40, 35, 30, 25, 20];
 // the Rx channel for each node
 pub(crate) type TestCommRx = BTreeMap<PublicKey, Option<Receiver<MsgFromPeer>>>;
 
+/// The params of the networks built by `TestNetworkBuilder`. As with a genesis node's config,
+/// the defaults can be overridden with the `SN_ELDER_COUNT` and `SN_DATA_COPY_COUNT` env vars.
+pub(crate) fn test_network_params() -> NetworkParams {
+    NetworkParams::default().with_env_overrides()
+}
+
+/// The elder count of the networks built by `TestNetworkBuilder`.
+pub(crate) fn elder_count() -> usize {
+    test_network_params().elder_count
+}
+
 #[derive(Clone, Debug)]
 enum TestMemberType {
     Elder,
@@ -58,6 +68,7 @@ pub(crate) struct TestNetworkBuilder<R: RngCore> {
     receivers: TestCommRx,
     rng: R,
     n_churns_each_section: usize,
+    network_params: NetworkParams,
 }
 
 impl<R: RngCore> TestNetworkBuilder<R> {
@@ -68,6 +79,7 @@ impl<R: RngCore> TestNetworkBuilder<R> {
             rng,
             receivers: BTreeMap::new(),
             n_churns_each_section: 1,
+            network_params: test_network_params(),
         }
     }
 
@@ -154,8 +166,8 @@ impl<R: RngCore> TestNetworkBuilder<R> {
             let _ = self.receivers.insert(node.public_key(), Some(rx));
             nodes.push((node, comm, memb_type));
         }
-        self.sections
-            .push((sap.clone(), nodes, secret_key_set.clone()));
+        let sap = sap.clone().with_network_params(self.network_params);
+        self.sections.push((sap, nodes, secret_key_set.clone()));
         self
     }
 
@@ -203,6 +215,7 @@ impl<R: RngCore> TestNetworkBuilder<R> {
             members_iter,
             sk_set.public_keys(),
             0,
+            self.network_params,
         );
 
         let nodes = members
@@ -462,8 +475,14 @@ impl<R: RngCore> TestNetworkBuilder<R> {
             .chain(elders_for_sap.clone())
             .map(|peer| NodeState::joined(peer, None));
         let sk_set = gen_sk_set(&mut self.rng, elder_count, sk_threshold_size);
-        let sap =
-            SectionAuthorityProvider::new(elders_for_sap, prefix, members, sk_set.public_keys(), 0);
+        let sap = SectionAuthorityProvider::new(
+            elders_for_sap,
+            prefix,
+            members,
+            sk_set.public_keys(),
+            0,
+            self.network_params,
+        );
 
         let nodes = elders
             .into_iter()
@@ -826,7 +845,11 @@ impl TestNetwork {
             info.keypair.clone(),
             network_knowledge.clone(),
             None,
-            UsedSpace::new(min_capacity, max_capacity),
+            UsedSpace::new(
+                min_capacity,
+                max_capacity,
+                network_knowledge.network_params().recommended_section_size,
+            ),
             root_storage_dir,
             mpsc::channel(10).0,
         )
@@ -854,7 +877,11 @@ impl TestNetwork {
             info.keypair.clone(),
             network_knowledge.clone(),
             sk_share.clone(),
-            UsedSpace::new(min_capacity, max_capacity),
+            UsedSpace::new(
+                min_capacity,
+                max_capacity,
+                network_knowledge.network_params().recommended_section_size,
+            ),
             root_storage_dir,
             mpsc::channel(10).0,
         )
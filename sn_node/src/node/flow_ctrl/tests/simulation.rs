@@ -0,0 +1,861 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Deterministic discrete-event simulation of a whole network.
+//!
+//! Real `MyNode`s are driven through their `Dispatcher` and the periodic checks of their
+//! `FlowCtrl`, over the in-memory transport of `sn_comms`: every packet a node sends, be it a msg,
+//! a request or a response on a stream, becomes a delivery event on a virtual clock, with a
+//! latency. The tokio clock is paused and only advanced from one event to the next, so the
+//! timeouts and intervals of the nodes run on the virtual clock too.
+//!
+//! All randomness, the scenario's (churn, latencies, data) as well as the nodes' own (keypairs,
+//! msg ids, DKG, section keys...), is drawn from the thread RNG seeded with the seed of the
+//! simulation, so a run which breaks an invariant can be replayed by setting `SN_SIM_SEED` to the
+//! seed reported in the failure.
+//!
+//! Simulations must thus run on a current-thread runtime with a paused clock, i.e. in
+//! `#[tokio::test(start_paused = true)]` tests.
+
+use crate::{
+    node::{
+        cfg::create_test_capacity_and_root_storage,
+        flow_ctrl::{
+            dispatcher::Dispatcher,
+            fault_detection::{FaultDetectionSettings, FaultsCmd},
+            FlowCtrl,
+        },
+        messaging::Peers,
+        Cmd, Error, MyNode, STANDARD_CHANNEL_SIZE,
+    },
+    UsedSpace,
+};
+
+use sn_comms::{in_memory::Packet, Comm};
+use sn_interface::{
+    messaging::system::{NodeDataCmd, NodeMsg},
+    network_knowledge::{NetworkKnowledge, NetworkParams, SectionAuthUtils, SectionTree},
+    rng::{seed_thread_rng, thread_rng},
+    types::{keys::ed25519, Chunk, DataAddress, Peer, ReplicatedData},
+};
+
+use bytes::Bytes;
+use eyre::{bail, eyre, Result};
+use itertools::Itertools;
+use rand::{Rng, RngCore};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, VecDeque},
+    env,
+    hash::{Hash, Hasher},
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{
+    mpsc::{self, Receiver, UnboundedReceiver, UnboundedSender},
+    RwLock,
+};
+use xor_name::{Prefix, XorName};
+
+/// Env var which overrides the seed of a simulation, to replay a failing run.
+pub(crate) const SEED_ENV_VAR: &str = "SN_SIM_SEED";
+
+// Age of the genesis node, as set by the node starter.
+const GENESIS_AGE: u8 = 255;
+// Bounds of the latency of every packet delivered, in ms.
+const MIN_LATENCY_MS: u64 = 1;
+const MAX_LATENCY_MS: u64 = 50;
+// How often the periodic checks of each node are run. Their own intervals are all in seconds.
+const PERIODIC_CHECKS_INTERVAL: Duration = Duration::from_secs(1);
+// How long a joining node waits before sending its join request again, and how many times.
+const JOIN_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_JOIN_ATTEMPTS: usize = 10;
+// How many times the runtime is yielded to, for the tasks the nodes spawned to make progress,
+// before checking what they output.
+const SETTLE_YIELDS: usize = 4;
+// Upper bound on the events handled in a single run, so a livelock fails rather than hangs.
+const MAX_EVENTS_PER_RUN: usize = 2_000_000;
+
+type NodeId = usize;
+
+/// Returns the seed set in `SN_SIM_SEED`, if any, or the given default one otherwise.
+pub(crate) fn sim_seed(default: u64) -> u64 {
+    env::var(SEED_ENV_VAR)
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Network params small enough for sections to split, and for nodes to be relocated, with a
+/// few dozen nodes.
+pub(crate) fn sim_network_params() -> NetworkParams {
+    NetworkParams {
+        elder_count: 4,
+        recommended_section_size: 6,
+        min_adult_age: 1,
+        data_copy_count: 3,
+        max_relocations_per_churn: 2,
+        probe_interval_ms: 30_000,
+        relocation_timeout_ms: 20_000,
+        missing_vote_interval_ms: 5_000,
+        missing_dkg_msg_interval_ms: 5_000,
+    }
+}
+
+// A node of the simulation, along with the receivers `FlowCtrl` would otherwise drain.
+struct SimNode {
+    node: Arc<RwLock<MyNode>>,
+    dispatcher: Arc<Dispatcher>,
+    flow_ctrl: FlowCtrl,
+    // cmds enqueued by the periodic checks, and the tasks they spawn
+    cmds_rx: Receiver<(Cmd, Vec<usize>)>,
+    replication_rx: Receiver<(Vec<DataAddress>, Peer)>,
+    // the name it joined with, which it keeps unless relocated
+    joined_as: XorName,
+    join_attempts: usize,
+}
+
+#[derive(Debug)]
+enum Event {
+    Deliver(Packet),
+    PeriodicChecks(NodeId),
+    Join,
+    Leave { elder: bool },
+    RetryJoin(NodeId),
+}
+
+pub(crate) struct Simulation {
+    seed: u64,
+    now: Duration,
+    next_seq: u64,
+    events: BTreeMap<(Duration, u64), Event>,
+    next_node_id: NodeId,
+    nodes: BTreeMap<NodeId, SimNode>,
+    node_ids: BTreeMap<SocketAddr, NodeId>,
+    genesis_id: NodeId,
+    genesis_key: bls::PublicKey,
+    // the params the genesis node started the network with
+    network_params: NetworkParams,
+    // the network contacts new nodes bootstrap from, i.e. the genesis section tree
+    network_contacts: SectionTree,
+    // the in-memory network all the nodes send their packets on
+    network: UnboundedSender<Packet>,
+    packets: UnboundedReceiver<Packet>,
+    // cmds output by the cmds processed on tasks, as they await responses
+    spawned_tx: UnboundedSender<(NodeId, Vec<Cmd>)>,
+    spawned_rx: UnboundedReceiver<(NodeId, Vec<Cmd>)>,
+    // cmds to be processed once the one being processed is done with
+    pending: VecDeque<(NodeId, Cmd)>,
+    chunks: BTreeSet<DataAddress>,
+    churn_log: Vec<String>,
+    // digest of every packet delivered and every churn event, in order
+    trace: DefaultHasher,
+}
+
+impl Simulation {
+    /// Starts a network with the given params, made of a genesis node only, seeding the thread
+    /// RNG with the seed.
+    pub(crate) async fn new(seed: u64, network_params: NetworkParams) -> Result<Self> {
+        info!("Starting network simulation with seed {seed} ({SEED_ENV_VAR}={seed} to replay)");
+        seed_thread_rng(Some(seed));
+
+        let (network, packets) = mpsc::unbounded_channel();
+        let (spawned_tx, spawned_rx) = mpsc::unbounded_channel();
+
+        let keypair = ed25519::gen_keypair(&Prefix::default().range_inclusive(), GENESIS_AGE);
+        let genesis_sk_set = bls::SecretKeySet::random(0, &mut thread_rng());
+        let (fault_cmds_channels, used_space, root_storage_dir) = node_resources(&network_params)?;
+        let (node, _genesis_dbc) = MyNode::first_node(
+            in_memory_comm(0, &network),
+            keypair,
+            used_space,
+            root_storage_dir,
+            genesis_sk_set,
+            network_params,
+            fault_cmds_channels.0.clone(),
+        )?;
+
+        let mut sim = Self {
+            seed,
+            now: Duration::ZERO,
+            next_seq: 0,
+            events: BTreeMap::new(),
+            next_node_id: 0,
+            nodes: BTreeMap::new(),
+            node_ids: BTreeMap::new(),
+            genesis_id: 0,
+            genesis_key: *node.network_knowledge().genesis_key(),
+            network_params,
+            network_contacts: node.network_knowledge().section_tree().clone(),
+            network,
+            packets,
+            spawned_tx,
+            spawned_rx,
+            pending: VecDeque::new(),
+            chunks: BTreeSet::new(),
+            churn_log: vec![],
+            trace: DefaultHasher::new(),
+        };
+        sim.genesis_id = sim.add_node(node, fault_cmds_channels).await;
+
+        Ok(sim)
+    }
+
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Joins and leaves which took place so far, with the virtual time they happened at.
+    pub(crate) fn churn_log(&self) -> &[String] {
+        &self.churn_log
+    }
+
+    /// Digest of every packet delivered and every churn event so far, to tell runs apart.
+    pub(crate) fn trace_digest(&self) -> u64 {
+        self.trace.finish()
+    }
+
+    /// Number of nodes currently running.
+    pub(crate) fn live_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Prefixes of the sections the elders of which are running.
+    pub(crate) async fn sections(&self) -> BTreeSet<Prefix> {
+        let mut prefixes = BTreeSet::new();
+        for sim_node in self.nodes.values() {
+            let node = sim_node.node.read().await;
+            if node.is_elder() {
+                let _ = prefixes.insert(node.network_knowledge().prefix());
+            }
+        }
+        prefixes
+    }
+
+    /// Number of running members which have been relocated since they joined.
+    pub(crate) async fn relocated_members(&self) -> usize {
+        let mut relocated = 0;
+        for sim_node in self.nodes.values() {
+            let node = sim_node.node.read().await;
+            let name = node.name();
+            if name != sim_node.joined_as && node.network_knowledge().is_section_member(&name) {
+                relocated += 1;
+            }
+        }
+        relocated
+    }
+
+    /// Number of section keys in the largest `SectionsDAG` held by a node, i.e. the number of
+    /// elder handovers and splits the network went through, plus the genesis key.
+    pub(crate) async fn section_keys(&self) -> usize {
+        let mut keys = 0;
+        for sim_node in self.nodes.values() {
+            let node = sim_node.node.read().await;
+            let dag = node.network_knowledge().section_tree().get_sections_dag();
+            keys = keys.max(dag.keys().count());
+        }
+        keys
+    }
+
+    /// Members of their section as known by each of the elders.
+    pub(crate) async fn members_by_elder(&self) -> BTreeMap<XorName, BTreeSet<Peer>> {
+        let mut members = BTreeMap::new();
+        for sim_node in self.nodes.values() {
+            let node = sim_node.node.read().await;
+            if node.is_elder() {
+                let _ = members.insert(node.name(), node.network_knowledge().members());
+            }
+        }
+        members
+    }
+
+    /// Schedules `joins` nodes joining and `leaves` nodes leaving, at random times within
+    /// `window` from now.
+    pub(crate) fn schedule_churn(&mut self, joins: usize, leaves: usize, window: Duration) {
+        let window_ms = window.as_millis().max(1) as u64;
+        for _ in 0..joins {
+            let at = Duration::from_millis(thread_rng().gen_range(0..window_ms));
+            self.schedule(at, Event::Join);
+        }
+        for _ in 0..leaves {
+            let at = Duration::from_millis(thread_rng().gen_range(0..window_ms));
+            self.schedule(at, Event::Leave { elder: false });
+        }
+    }
+
+    /// Schedules an elder leaving after `after` from now, for its section to hand over.
+    pub(crate) fn schedule_elder_leave(&mut self, after: Duration) {
+        self.schedule(after, Event::Leave { elder: true });
+    }
+
+    /// Stores a chunk of random bytes, by having an elder of the section it belongs to send it
+    /// to the members which should hold it as per its knowledge of the section.
+    pub(crate) async fn put_chunk(&mut self, len: usize) -> Result<DataAddress> {
+        let mut bytes = vec![0; len];
+        thread_rng().fill_bytes(&mut bytes);
+        let chunk = Chunk::new(Bytes::from(bytes));
+        let data = ReplicatedData::Chunk(chunk.clone());
+        let address = data.address();
+
+        let (elder_id, context) = {
+            let mut elder = None;
+            for (id, sim_node) in &self.nodes {
+                let node = sim_node.node.read().await;
+                if node.is_elder() && node.network_knowledge().prefix().matches(chunk.name()) {
+                    elder = Some((*id, node.context()));
+                    break;
+                }
+            }
+            elder.ok_or_else(|| eyre!("{}: no elder to store the chunk", self.failure()))?
+        };
+
+        let holders: BTreeSet<_> = context
+            .network_knowledge
+            .members()
+            .into_iter()
+            .sorted_by(|lhs, rhs| chunk.name().cmp_distance(&lhs.name(), &rhs.name()))
+            .take(self.network_params.data_copy_count)
+            .collect();
+        debug!("Simulation storing {address:?} from node #{elder_id} at {holders:?}");
+
+        let msg = NodeMsg::NodeDataCmd(NodeDataCmd::ReplicateDataBatch(vec![data]));
+        let cmd = Cmd::send_msg(msg, Peers::Multiple(holders), context);
+        self.process(elder_id, cmd).await;
+        self.settle().await;
+        let _ = self.chunks.insert(address);
+
+        Ok(address)
+    }
+
+    /// Handles the events due within `duration` from now, moving the clock forward to each of
+    /// them, then checks the invariants hold.
+    pub(crate) async fn run_for(&mut self, duration: Duration) -> Result<()> {
+        let until = self.now + duration;
+        let mut handled = 0;
+        while let Some(entry) = self.events.first_entry() {
+            let (at, _) = *entry.key();
+            if at > until {
+                break;
+            }
+            let event = entry.remove();
+
+            handled += 1;
+            if handled > MAX_EVENTS_PER_RUN {
+                bail!(
+                    "{}: still busy after handling {MAX_EVENTS_PER_RUN} events",
+                    self.failure()
+                );
+            }
+
+            self.advance_to(at).await;
+            self.handle_event(event).await?;
+            self.settle().await;
+        }
+        self.advance_to(until).await;
+        self.settle().await;
+        let members: usize = self
+            .members_by_elder()
+            .await
+            .values()
+            .map(BTreeSet::len)
+            .sum::<usize>()
+            / self.network_params.elder_count;
+        info!(
+            "Simulation with seed {} at {:?}, after {handled} events: {} nodes running, about \
+            {members} of them members of {:?}",
+            self.seed,
+            self.now,
+            self.nodes.len(),
+            self.sections().await
+        );
+
+        self.check_invariants().await
+    }
+
+    /// Checks that:
+    /// - every node's `SectionsDAG` is valid, rooted at the genesis key, and proves every SAP
+    ///   it knows of, including its own section's,
+    /// - the elders of each section agree on the section key, and on its members,
+    /// - every chunk stored is held by `data_copy_count` members of its section, or by all of
+    ///   them if fewer.
+    pub(crate) async fn check_invariants(&self) -> Result<()> {
+        let mut violations = vec![];
+        // section prefix -> (section key, members) held by each of its elders
+        let mut elder_views = BTreeMap::<_, Vec<_>>::new();
+
+        for (id, sim_node) in &self.nodes {
+            let node = sim_node.node.read().await;
+            let knowledge = node.network_knowledge();
+            let dag = knowledge.section_tree().get_sections_dag();
+
+            if knowledge.genesis_key() != &self.genesis_key {
+                violations.push(format!("node #{id} has another genesis key"));
+            }
+            if !dag.self_verify() {
+                violations.push(format!("node #{id} has an invalid SectionsDAG"));
+            }
+            for prefix in knowledge.prefixes() {
+                let verified = knowledge
+                    .section_tree()
+                    .get_signed(prefix)
+                    .map_or(false, |sap| sap.verify(dag));
+                if !verified {
+                    violations.push(format!(
+                        "node #{id} holds a SAP for {prefix:?} not proven by its SectionsDAG"
+                    ));
+                }
+            }
+            if !knowledge.signed_sap().verify(dag) {
+                violations.push(format!(
+                    "node #{id}'s own SAP is not proven by its SectionsDAG"
+                ));
+            }
+
+            if node.is_elder() {
+                elder_views.entry(knowledge.prefix()).or_default().push((
+                    *id,
+                    knowledge.section_key(),
+                    knowledge.members(),
+                ));
+            }
+        }
+
+        for (prefix, views) in &elder_views {
+            if !views.iter().map(|(_, key, _)| key).all_equal() {
+                violations.push(format!(
+                    "elders of {prefix:?} disagree on the section key: {:?}",
+                    views
+                        .iter()
+                        .map(|(id, key, _)| (id, key))
+                        .collect::<Vec<_>>()
+                ));
+            }
+            if !views.iter().map(|(_, _, members)| members).all_equal() {
+                violations.push(format!(
+                    "elders of {prefix:?} disagree on the section members"
+                ));
+            }
+        }
+
+        for address in &self.chunks {
+            let Some(members) = elder_views
+                .iter()
+                .find(|(prefix, _)| prefix.matches(address.name()))
+                .and_then(|(_, views)| views.first())
+                .map(|(_, _, members)| members.len())
+            else {
+                violations.push(format!("no elder of the section of {address:?} is running"));
+                continue;
+            };
+            let min_copies = self.network_params.data_copy_count.min(members);
+
+            let mut copies = 0;
+            for sim_node in self.nodes.values() {
+                let data_storage = sim_node.node.read().await.data_storage.clone();
+                if data_storage.get_from_local_store(address).await.is_ok() {
+                    copies += 1;
+                }
+            }
+            if copies < min_copies {
+                violations.push(format!(
+                    "{address:?} is held by {copies} nodes, instead of {min_copies}"
+                ));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            bail!(
+                "{}, invariants violated at {:?}:\n{}",
+                self.failure(),
+                self.now,
+                violations.join("\n")
+            )
+        }
+    }
+
+    async fn handle_event(&mut self, event: Event) -> Result<()> {
+        match event {
+            Event::Deliver(packet) => {
+                let (Some(&from), Some(&to)) = (
+                    self.node_ids.get(&packet.src),
+                    self.node_ids.get(&packet.dst),
+                ) else {
+                    trace!("Simulation dropping {packet:?}, as its sender or recipient left");
+                    return Ok(());
+                };
+                (self.now, from, to, packet.msg_id, packet.is_response()).hash(&mut self.trace);
+
+                match packet.deliver() {
+                    Ok(Some(msg)) => match FlowCtrl::handle_new_msg_event(msg) {
+                        Ok(cmd) => self.process(to, cmd).await,
+                        Err(error) => debug!("Node #{to} could not handle a msg: {error:?}"),
+                    },
+                    // a response, handed to the task awaiting it
+                    Ok(None) => {}
+                    Err(error) => debug!("Could not deliver a packet to #{to}: {error:?}"),
+                }
+            }
+            Event::PeriodicChecks(id) => {
+                let Some(sim_node) = self.nodes.get_mut(&id) else {
+                    return Ok(());
+                };
+                Box::pin(sim_node.flow_ctrl.perform_periodic_checks()).await;
+                self.schedule(PERIODIC_CHECKS_INTERVAL, Event::PeriodicChecks(id));
+            }
+            Event::Join => {
+                let id = self.start_joining_node().await?;
+                self.schedule(Duration::ZERO, Event::RetryJoin(id));
+            }
+            Event::RetryJoin(id) => {
+                let Some(sim_node) = self.nodes.get_mut(&id) else {
+                    return Ok(());
+                };
+                let is_member = {
+                    let node = sim_node.node.read().await;
+                    node.network_knowledge().is_section_member(&node.name())
+                };
+                if is_member || sim_node.join_attempts >= MAX_JOIN_ATTEMPTS {
+                    return Ok(());
+                }
+                sim_node.join_attempts += 1;
+                let name = sim_node.node.read().await.name();
+
+                // Past the first section, elders only let a node in once their storage grows
+                // a level, so we stand in for that, letting in the joiner.
+                let mut elders = vec![];
+                for (elder_id, sim_node) in &self.nodes {
+                    let node = sim_node.node.read().await;
+                    if node.is_elder() && node.network_knowledge().prefix().matches(&name) {
+                        elders.push(*elder_id);
+                    }
+                }
+                for elder_id in elders {
+                    self.process(elder_id, Cmd::SetJoinsAllowed(true)).await;
+                }
+
+                self.process(id, Cmd::TryJoinNetwork).await;
+                self.schedule(JOIN_RETRY_INTERVAL, Event::RetryJoin(id));
+            }
+            Event::Leave { elder } => {
+                let mut candidates = vec![];
+                for (id, sim_node) in &self.nodes {
+                    if *id != self.genesis_id && (!elder || sim_node.node.read().await.is_elder()) {
+                        candidates.push(*id);
+                    }
+                }
+                if candidates.is_empty() {
+                    self.log_churn(format!("{:?}: no node to leave", self.now));
+                    return Ok(());
+                }
+                let id = candidates[thread_rng().gen_range(0..candidates.len())];
+                self.remove_node(id).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Starts a new node bootstrapping from the network contacts, brought up to date with what
+    // the elders know first, as the contacts handed out to new nodes would be.
+    async fn start_joining_node(&mut self) -> Result<NodeId> {
+        for sim_node in self.nodes.values() {
+            let node = sim_node.node.read().await;
+            if !node.is_elder() {
+                continue;
+            }
+            let section_tree = node.network_knowledge().section_tree();
+            for prefix in section_tree.prefixes() {
+                let update = section_tree.generate_section_tree_update(prefix)?;
+                let _ = self.network_contacts.update_the_section_tree(update)?;
+            }
+        }
+
+        let keypair = ed25519::gen_keypair(
+            &Prefix::default().range_inclusive(),
+            self.network_params.min_adult_age,
+        );
+        let name = ed25519::name(&keypair.public);
+        let sap = self.network_contacts.get_signed_by_name(&name)?;
+        let network_knowledge = NetworkKnowledge::new(sap.prefix(), self.network_contacts.clone())?;
+
+        let (fault_cmds_channels, used_space, root_storage_dir) =
+            node_resources(&self.network_params)?;
+        let node = MyNode::new(
+            in_memory_comm(self.next_node_id, &self.network),
+            Arc::new(keypair),
+            network_knowledge,
+            None,
+            used_space,
+            root_storage_dir,
+            fault_cmds_channels.0.clone(),
+        )?;
+
+        let id = self.add_node(node, fault_cmds_channels).await;
+        self.log_churn(format!("{:?}: node #{id} ({name}) joins", self.now));
+
+        Ok(id)
+    }
+
+    // The node stops, i.e. it's dropped along with any packet from or to it, and the elders of
+    // its section are told it's gone, as their fault detection would eventually have done. The
+    // node may be an elder, so this leads to handovers.
+    async fn remove_node(&mut self, id: NodeId) {
+        let Some(sim_node) = self.nodes.remove(&id) else {
+            return;
+        };
+        let (name, addr) = {
+            let node = sim_node.node.read().await;
+            (node.name(), node.addr)
+        };
+        let _ = self.node_ids.remove(&addr);
+        self.log_churn(format!("{:?}: node #{id} ({name}) leaves", self.now));
+
+        let mut elders = vec![];
+        for (elder_id, sim_node) in &self.nodes {
+            let node = sim_node.node.read().await;
+            if node.is_elder() && node.network_knowledge().prefix().matches(&name) {
+                elders.push(*elder_id);
+            }
+        }
+        for elder_id in elders {
+            let cmd = Cmd::ProposeVoteNodesOffline(BTreeSet::from([name]));
+            self.pending.push_back((elder_id, cmd));
+        }
+    }
+
+    async fn add_node(
+        &mut self,
+        node: MyNode,
+        fault_cmds_channels: (mpsc::Sender<FaultsCmd>, Receiver<FaultsCmd>),
+    ) -> NodeId {
+        let id = self.next_node_id;
+        self.next_node_id += 1;
+
+        let _ = self.node_ids.insert(node.addr, id);
+        let joined_as = node.name();
+        let node = Arc::new(RwLock::new(node));
+        let (dispatcher, replication_rx) = Dispatcher::new(node.clone());
+        let (cmd_sender_channel, cmds_rx) = mpsc::channel(STANDARD_CHANNEL_SIZE);
+        let flow_ctrl = FlowCtrl::new(
+            node.clone(),
+            cmd_sender_channel,
+            fault_cmds_channels,
+            FaultDetectionSettings::default(),
+        )
+        .await;
+        let _ = self.nodes.insert(
+            id,
+            SimNode {
+                node,
+                dispatcher: Arc::new(dispatcher),
+                flow_ctrl,
+                cmds_rx,
+                replication_rx,
+                joined_as,
+                join_attempts: 0,
+            },
+        );
+        self.schedule(PERIODIC_CHECKS_INTERVAL, Event::PeriodicChecks(id));
+
+        id
+    }
+
+    // Processes the cmd, and all the cmds resulting from it, on the node, one after the other.
+    // Cmds awaiting responses are processed in the background instead, for the responses to be
+    // delivered meanwhile.
+    async fn process(&mut self, id: NodeId, cmd: Cmd) {
+        let mut cmds = VecDeque::from([cmd]);
+        while let Some(cmd) = cmds.pop_front() {
+            let Some(sim_node) = self.nodes.get(&id) else {
+                return;
+            };
+            let dispatcher = sim_node.dispatcher.clone();
+
+            if matches!(
+                cmd,
+                Cmd::SendMsgEnqueueAnyResponse { .. }
+                    | Cmd::SendMsgAwaitResponseAndRespondToClient { .. }
+            ) {
+                let spawned_tx = self.spawned_tx.clone();
+                let _handle = tokio::spawn(async move {
+                    let cmds = match dispatcher.process_cmd(cmd).await {
+                        Ok(cmds) => cmds,
+                        Err(error) => {
+                            debug!("Node #{id} failed to process a cmd: {error:?}");
+                            vec![]
+                        }
+                    };
+                    let _result = spawned_tx.send((id, cmds));
+                });
+                continue;
+            }
+
+            let cmd_string = cmd.to_string();
+            // on a task of its own, as `FlowCtrl` does, rather than nested in the simulation's
+            // future, as the stack that would take overflows the test thread's
+            let processing = tokio::spawn(async move { dispatcher.process_cmd(cmd).await });
+            let result = match processing.await {
+                Ok(result) => result,
+                Err(error) => std::panic::resume_unwind(error.into_panic()),
+            };
+            match result {
+                Ok(new_cmds) => cmds.extend(new_cmds),
+                Err(Error::RejoinRequired(reason)) => {
+                    // it would restart from scratch, which is left out of the simulation
+                    self.log_churn(format!(
+                        "{:?}: node #{id} stops, as it must rejoin: {reason:?}",
+                        self.now
+                    ));
+                    self.remove_node(id).await;
+                    return;
+                }
+                Err(error) => {
+                    debug!("Node #{id} failed to process {cmd_string}: {error:?}");
+                }
+            }
+
+            cmds.extend(self.send_data_for_replication(id).await);
+        }
+    }
+
+    // Lets the tasks the nodes spawned make progress, and processes what they output, until
+    // they're all idle or waiting on the clock or the network.
+    async fn settle(&mut self) {
+        loop {
+            for _ in 0..SETTLE_YIELDS {
+                tokio::task::yield_now().await;
+            }
+            let mut progressed = false;
+
+            while let Ok((id, cmds)) = self.spawned_rx.try_recv() {
+                progressed = true;
+                self.pending.extend(cmds.into_iter().map(|cmd| (id, cmd)));
+            }
+            while let Some((id, cmd)) = self.pending.pop_front() {
+                progressed = true;
+                self.process(id, cmd).await;
+            }
+
+            let ids: Vec<_> = self.nodes.keys().copied().collect();
+            for id in ids {
+                while let Some(Ok((cmd, _))) =
+                    self.nodes.get_mut(&id).map(|node| node.cmds_rx.try_recv())
+                {
+                    progressed = true;
+                    self.process(id, cmd).await;
+                }
+            }
+
+            while let Ok(packet) = self.packets.try_recv() {
+                progressed = true;
+                let latency =
+                    Duration::from_millis(thread_rng().gen_range(MIN_LATENCY_MS..=MAX_LATENCY_MS));
+                self.schedule(latency, Event::Deliver(packet));
+            }
+
+            if !progressed {
+                break;
+            }
+        }
+    }
+
+    // Does what `FlowCtrl` does with the data the node enqueued for replication, returning the
+    // cmds to send it.
+    async fn send_data_for_replication(&mut self, id: NodeId) -> Vec<Cmd> {
+        let Some(sim_node) = self.nodes.get_mut(&id) else {
+            return vec![];
+        };
+        let mut batches = vec![];
+        while let Ok(batch) = sim_node.replication_rx.try_recv() {
+            batches.push(batch);
+        }
+        if batches.is_empty() {
+            return vec![];
+        }
+
+        let context = sim_node.node.read().await.context();
+        let mut cmds = vec![];
+        for (data_addresses, peer) in batches {
+            let mut data_batch = vec![];
+            for address in &data_addresses {
+                match context.data_storage.get_from_local_store(address).await {
+                    Ok(data) => data_batch.push(data),
+                    Err(error) => {
+                        error!("Node #{id} could not get {address:?} for replication: {error:?}")
+                    }
+                }
+            }
+            let msg = NodeMsg::NodeDataCmd(NodeDataCmd::ReplicateDataBatch(data_batch));
+            cmds.push(Cmd::send_msg(msg, Peers::Single(peer), context.clone()));
+        }
+        cmds
+    }
+
+    async fn advance_to(&mut self, at: Duration) {
+        if at > self.now {
+            tokio::time::advance(at - self.now).await;
+            self.now = at;
+        }
+    }
+
+    // Events scheduled for the same time are handled in the order they were scheduled.
+    fn schedule(&mut self, after: Duration, event: Event) {
+        let _ = self.events.insert((self.now + after, self.next_seq), event);
+        self.next_seq += 1;
+    }
+
+    fn log_churn(&mut self, entry: String) {
+        entry.hash(&mut self.trace);
+        self.churn_log.push(entry);
+    }
+
+    fn failure(&self) -> String {
+        format!(
+            "Simulation with seed {} failed (replay with {SEED_ENV_VAR}={})",
+            self.seed, self.seed
+        )
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        // the test thread may be reused
+        seed_thread_rng(None);
+    }
+}
+
+// Everything a node needs besides its `Comm`, as the node starter would set it up.
+#[allow(clippy::type_complexity)]
+fn node_resources(
+    network_params: &NetworkParams,
+) -> Result<(
+    (mpsc::Sender<FaultsCmd>, Receiver<FaultsCmd>),
+    UsedSpace,
+    PathBuf,
+)> {
+    let (min_capacity, max_capacity, root_storage_dir) = create_test_capacity_and_root_storage()?;
+    let used_space = UsedSpace::new(
+        min_capacity,
+        max_capacity,
+        network_params.recommended_section_size,
+    );
+    Ok((
+        mpsc::channel(STANDARD_CHANNEL_SIZE),
+        used_space,
+        root_storage_dir,
+    ))
+}
+
+// A `Comm` over the in-memory network, at the virtual address of the node.
+fn in_memory_comm(id: NodeId, network: &UnboundedSender<Packet>) -> Comm {
+    let addr = SocketAddr::from(([10, 0, (id / 256) as u8, (id % 256) as u8], 12000));
+    Comm::new_in_memory(addr, network.clone())
+}
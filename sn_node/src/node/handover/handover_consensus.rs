@@ -17,9 +17,19 @@ use sn_interface::network_knowledge::SapCandidate;
 
 use bls::{PublicKeySet, SecretKeyShare};
 use core::fmt::Debug;
+use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::BTreeMap};
 use tracing::info;
 
+/// The decided state of a `Handover`, from which it can be resumed after a restart.
+/// Votes of the ongoing round are not included, they are caught up on through handover AE.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct HandoverSnapshot {
+    gen: Generation,
+    failed_rounds: BTreeMap<Generation, Decision<SapCandidate>>,
+    decision: Option<Decision<SapCandidate>>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Handover {
     pub(crate) consensus: Consensus<SapCandidate>,
@@ -42,6 +52,42 @@ impl Handover {
         }
     }
 
+    pub(crate) fn snapshot(&self) -> HandoverSnapshot {
+        HandoverSnapshot {
+            gen: self.gen,
+            failed_rounds: self
+                .failed_consensus_rounds
+                .iter()
+                .map(|(gen, (_, decision))| (*gen, decision.clone()))
+                .collect(),
+            decision: self.consensus.decision.clone(),
+        }
+    }
+
+    /// Resumes the decided state from a snapshot taken with the same section key set.
+    pub(crate) fn restore(&mut self, snapshot: HandoverSnapshot) {
+        self.failed_consensus_rounds = snapshot
+            .failed_rounds
+            .into_iter()
+            .map(|(gen, decision)| {
+                let mut consensus = self.new_consensus();
+                consensus.decision = Some(decision.clone());
+                (gen, (consensus, decision))
+            })
+            .collect();
+        self.consensus = self.new_consensus();
+        self.consensus.decision = snapshot.decision;
+        self.gen = snapshot.gen;
+    }
+
+    fn new_consensus(&self) -> Consensus<SapCandidate> {
+        Consensus::<SapCandidate>::from(
+            self.consensus.secret_key.clone(),
+            self.consensus.elders.clone(),
+            self.consensus.n_elders,
+        )
+    }
+
     pub(crate) fn propose(&mut self, proposal: SapCandidate) -> Result<SignedVote<SapCandidate>> {
         let vote = Vote {
             gen: self.gen,
@@ -92,11 +138,7 @@ impl Handover {
     pub(crate) fn handle_empty_set_decision(&mut self) {
         if let Some(decision) = &self.consensus.decision {
             if decision.proposals.is_empty() {
-                let new_consensus = Consensus::<SapCandidate>::from(
-                    self.consensus.secret_key.clone(),
-                    self.consensus.elders.clone(),
-                    self.consensus.n_elders,
-                );
+                let new_consensus = self.new_consensus();
                 let old_decision = decision.clone();
                 let old_consensus = std::mem::replace(&mut self.consensus, new_consensus);
                 let _none = self
@@ -218,4 +260,46 @@ mod tests {
             assert_eq!(state.consensus.decision, None);
         });
     }
+
+    #[test]
+    fn snapshot_restores_decided_rounds() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let elders_sk = SecretKeySet::random(0, &mut rng);
+        let new_handover = || {
+            Handover::from(
+                (0, elders_sk.secret_key_share(0)),
+                elders_sk.public_keys(),
+                1,
+            )
+        };
+        let empty_decision = Decision {
+            votes: BTreeSet::new(),
+            proposals: BTreeMap::new(),
+            faults: BTreeSet::new(),
+        };
+
+        // a failed round at gen 0, and a decided round at gen 1
+        let mut handover = new_handover();
+        handover.consensus.decision = Some(empty_decision.clone());
+        handover.handle_empty_set_decision();
+        handover.consensus.decision = Some(empty_decision.clone());
+
+        let mut restored = new_handover();
+        restored.restore(handover.snapshot());
+
+        assert_eq!(restored.generation(), 1);
+        assert_eq!(restored.consensus.decision, Some(empty_decision.clone()));
+        assert_eq!(
+            Vec::from_iter(restored.failed_consensus_rounds.keys().copied()),
+            vec![0]
+        );
+        assert_eq!(
+            restored
+                .failed_consensus_rounds
+                .get(&0)
+                .map(|(c, d)| (&c.decision, d)),
+            Some((&Some(empty_decision.clone()), &empty_decision))
+        );
+        assert!(restored.consensus.votes.is_empty());
+    }
 }
@@ -7,19 +7,19 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 use bls::{PublicKeySet, SecretKeyShare};
 use core::fmt::Debug;
+use serde::{Deserialize, Serialize};
 use sn_consensus::{
     Ballot, Consensus, Decision, Generation, NodeId, SignedVote, Vote, VoteResponse,
 };
 use sn_interface::{
     messaging::system::DkgSessionId,
     network_knowledge::{
-        partition_by_prefix, recommended_section_size, MembershipState, NodeState,
-        SectionAuthorityProvider,
+        partition_by_prefix, MembershipState, NodeState, SectionAuthorityProvider,
     },
 };
 use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
-use std::time::Instant;
 use thiserror::Error;
+use tokio::time::Instant;
 use xor_name::{Prefix, XorName};
 
 #[derive(Debug, Error)]
@@ -39,11 +39,12 @@ pub(crate) type Result<T> = std::result::Result<T, Error>;
 fn get_split_info(
     prefix: Prefix,
     members: &BTreeMap<XorName, NodeState>,
+    recommended_section_size: usize,
 ) -> Option<(BTreeSet<NodeState>, BTreeSet<NodeState>)> {
     let (zero, one) = partition_by_prefix(&prefix, members.keys().copied())?;
 
     // make sure the sections contain enough entries
-    let split_threshold = recommended_section_size();
+    let split_threshold = recommended_section_size;
     if zero.len() < split_threshold || one.len() < split_threshold {
         return None;
     }
@@ -64,7 +65,11 @@ pub(crate) fn try_split_dkg(
 ) -> Option<(DkgSessionId, DkgSessionId)> {
     let prefix = sap.prefix();
 
-    let (zero, one) = get_split_info(prefix, members)?;
+    let (zero, one) = get_split_info(
+        prefix,
+        members,
+        sap.network_params().recommended_section_size,
+    )?;
 
     // get elders for section ...0
     let zero_prefix = prefix.pushed(false);
@@ -129,11 +134,22 @@ pub(crate) fn elder_candidates(
 
     candidates
         .into_iter()
+        // elders are contacted directly by clients and other sections, so they can't be relayed
+        .filter(|node| !node.peer().is_relayed())
         .sorted_by(|lhs, rhs| cmp_elder_candidates(lhs, rhs, current_elders))
-        .take(sn_interface::elder_count())
+        .take(current_elders.network_params().elder_count)
         .collect()
 }
 
+/// The decided state of a `Membership`, from which it can be resumed after a restart.
+/// Votes of the ongoing round are not included, they are caught up on through membership AE.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MembershipSnapshot {
+    bootstrap_members: BTreeSet<NodeState>,
+    gen: Generation,
+    decisions: BTreeMap<Generation, Decision<NodeState>>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Membership {
     consensus: Consensus<NodeState>,
@@ -142,6 +158,8 @@ pub(crate) struct Membership {
     history: BTreeMap<Generation, (Decision<NodeState>, Consensus<NodeState>)>,
     // last membership vote timestamp
     last_received_vote_time: Option<Instant>,
+    // the size a section needs to reach before splitting, bounding the joins we accept
+    recommended_section_size: usize,
 }
 
 impl Membership {
@@ -151,6 +169,7 @@ impl Membership {
         elders: PublicKeySet,
         n_elders: usize,
         bootstrap_members: BTreeSet<NodeState>,
+        recommended_section_size: usize,
     ) -> Self {
         trace!("Membership - Creating new membership instance");
         Membership {
@@ -159,6 +178,7 @@ impl Membership {
             gen: 0,
             history: BTreeMap::default(),
             last_received_vote_time: None,
+            recommended_section_size,
         }
     }
 
@@ -166,6 +186,40 @@ impl Membership {
         self.consensus.elders.clone()
     }
 
+    pub(crate) fn snapshot(&self) -> MembershipSnapshot {
+        MembershipSnapshot {
+            bootstrap_members: self.bootstrap_members.clone(),
+            gen: self.gen,
+            decisions: self
+                .history
+                .iter()
+                .map(|(gen, (decision, _))| (*gen, decision.clone()))
+                .collect(),
+        }
+    }
+
+    /// Resumes the decided state from a snapshot taken with the same section key set.
+    pub(crate) fn restore(&mut self, snapshot: MembershipSnapshot) {
+        let history = snapshot
+            .decisions
+            .into_iter()
+            .map(|(gen, decision)| {
+                let mut consensus = Consensus::from(
+                    self.consensus.secret_key.clone(),
+                    self.consensus.elders.clone(),
+                    self.consensus.n_elders,
+                );
+                consensus.decision = Some(decision.clone());
+                (gen, (decision, consensus))
+            })
+            .collect();
+
+        self.bootstrap_members = snapshot.bootstrap_members;
+        self.gen = snapshot.gen;
+        self.history = history;
+        self.last_received_vote_time = None;
+    }
+
     pub(crate) fn last_received_vote_time(&self) -> Option<Instant> {
         self.last_received_vote_time
     }
@@ -444,7 +498,12 @@ impl Membership {
         let archived_members = self.archived_members();
 
         for proposal in signed_vote.proposals() {
-            if let Err(err) = proposal.validate(prefix, &members, &archived_members) {
+            if let Err(err) = proposal.validate(
+                prefix,
+                &members,
+                &archived_members,
+                self.recommended_section_size,
+            ) {
                 warn!("Failed to validate {proposal:?} with error {:?}", err);
                 warn!(
                     "Members at generation {} are: {:?}",
@@ -459,3 +518,76 @@ impl Membership {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sn_interface::test_utils::gen_peer;
+
+    use bls::SecretKeySet;
+    use eyre::{eyre, Result};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn restore_resumes_decided_generations() -> Result<()> {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let elders_sk = SecretKeySet::random(0, &mut rng);
+        let new_membership = || {
+            Membership::from(
+                (0, elders_sk.secret_key_share(0)),
+                elders_sk.public_keys(),
+                1,
+                BTreeSet::new(),
+                100,
+            )
+        };
+
+        let mut membership = new_membership();
+        let joined = Vec::from_iter((0..3).map(|_| NodeState::joined(gen_peer(5), None)));
+        for node_state in &joined {
+            let _decision = decide(&mut membership, node_state.clone())?;
+        }
+        let left = NodeState::left(*joined[0].peer(), None);
+        let _decision = decide(&mut membership, left)?;
+
+        let snapshot = membership.snapshot();
+        let mut restored = new_membership();
+        restored.restore(snapshot.clone());
+
+        assert_eq!(restored.generation(), 4);
+        assert_eq!(
+            restored.current_section_members(),
+            membership.current_section_members()
+        );
+        assert_eq!(restored.archived_members(), membership.archived_members());
+        assert_eq!(
+            bincode::serialize(&restored.snapshot())?,
+            bincode::serialize(&snapshot)?
+        );
+        // the decided generations can still be served to others through AE
+        assert_eq!(restored.anti_entropy(1)?.len(), 4);
+
+        // and voting carries on from the next generation
+        let _decision = decide(&mut restored, NodeState::joined(gen_peer(5), None))?;
+        assert_eq!(restored.generation(), 5);
+        assert_eq!(restored.current_section_members().len(), 3);
+
+        Ok(())
+    }
+
+    // Drives the vote on a single proposal to a decision, as the only elder.
+    fn decide(membership: &mut Membership, node_state: NodeState) -> Result<Decision<NodeState>> {
+        let prefix = Prefix::default();
+        let mut vote = membership.propose(node_state, &prefix)?;
+        loop {
+            match membership.handle_signed_vote(vote, &prefix)? {
+                (_, Some(decision)) => return Ok(decision),
+                (VoteResponse::Broadcast(next_vote), None) => vote = next_vote,
+                (VoteResponse::WaitingForMoreVotes, None) => {
+                    return Err(eyre!("no decision reached"))
+                }
+            }
+        }
+    }
+}
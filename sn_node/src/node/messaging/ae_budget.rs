@@ -0,0 +1,179 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use sn_interface::{
+    messaging::{system::NodeMsg, WireMsg},
+    types::{log_markers::LogMarker, Peer},
+};
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Window over which the AE msgs sent to a peer are counted against its budget.
+pub(crate) const AE_BUDGET_WINDOW: Duration = Duration::from_secs(10);
+/// Max number of unsolicited AE msgs we send to a single peer within an `AE_BUDGET_WINDOW`.
+pub(crate) const AE_MSGS_PER_WINDOW: u64 = 30;
+/// Peers we haven't sent AE msgs to for this many windows are no longer tracked.
+const IDLE_WINDOWS_BEFORE_PRUNING: u32 = 30;
+
+/// Anti-Entropy statistics for a peer, since we first sent it an AE msg.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct AeStats {
+    /// Bytes of the AE msgs sent to the peer.
+    pub(crate) bytes_sent: u64,
+    /// AE msgs sent to the peer.
+    pub(crate) msgs_sent: u64,
+    /// Unsolicited AE msgs not sent to the peer as its budget was spent.
+    pub(crate) msgs_dropped: u64,
+}
+
+#[derive(Debug)]
+struct PeerBudget {
+    window_start: Instant,
+    msgs_in_window: u64,
+    stats: AeStats,
+}
+
+/// Limits the number of unsolicited Anti-Entropy msgs sent to each peer, i.e. the AE updates
+/// it triggers by probing us, so a peer which keeps probing us doesn't get flooded with full
+/// AE updates. Responses to stale msgs, AE-Digests and AE-Fetches are never held back, as the
+/// peer would otherwise be left without the knowledge it needs, but they count against the budget.
+/// Also keeps track of the AE traffic sent to each peer.
+///
+/// Clones share the same budgets.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AeBudget {
+    peers: Arc<Mutex<BTreeMap<Peer, PeerBudget>>>,
+}
+
+impl AeBudget {
+    /// Size of the AE msg as accounted for in the peers' stats, i.e. the length of its wire payload.
+    pub(crate) fn msg_size(msg: &NodeMsg) -> u64 {
+        WireMsg::serialize_msg_payload(msg)
+            .map(|payload| payload.len() as u64)
+            .unwrap_or_default()
+    }
+
+    /// Spends budget of the peer to send it an unsolicited AE msg of the given size, returning
+    /// false if its budget for the current window is already spent, in which case the msg is
+    /// not to be sent.
+    pub(crate) fn try_spend(&self, peer: &Peer, msg_size: u64) -> bool {
+        self.spend_at(peer, msg_size, Instant::now(), true)
+    }
+
+    /// Spends budget of the peer for an AE msg of the given size which is sent to it regardless.
+    pub(crate) fn record(&self, peer: &Peer, msg_size: u64) {
+        let _ = self.spend_at(peer, msg_size, Instant::now(), false);
+    }
+
+    fn spend_at(&self, peer: &Peer, msg_size: u64, now: Instant, enforce: bool) -> bool {
+        let mut peers = match self.peers.lock() {
+            Ok(peers) => peers,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let budget = peers.entry(*peer).or_insert_with(|| PeerBudget {
+            window_start: now,
+            msgs_in_window: 0,
+            stats: AeStats::default(),
+        });
+
+        if now.duration_since(budget.window_start) >= AE_BUDGET_WINDOW {
+            budget.window_start = now;
+            budget.msgs_in_window = 0;
+        }
+
+        if enforce && budget.msgs_in_window >= AE_MSGS_PER_WINDOW {
+            budget.stats.msgs_dropped += 1;
+            debug!(
+                "{}: not sending AE msg to {peer:?}, {} msgs were already sent to it within {AE_BUDGET_WINDOW:?}",
+                LogMarker::AeBudgetExceeded,
+                budget.msgs_in_window
+            );
+            return false;
+        }
+
+        budget.msgs_in_window += 1;
+        budget.stats.msgs_sent += 1;
+        budget.stats.bytes_sent += msg_size;
+        true
+    }
+
+    /// Returns the AE stats of each peer, pruning the peers we haven't sent AE msgs to lately.
+    pub(crate) fn stats(&self) -> BTreeMap<Peer, AeStats> {
+        self.stats_at(Instant::now())
+    }
+
+    fn stats_at(&self, now: Instant) -> BTreeMap<Peer, AeStats> {
+        let mut peers = match self.peers.lock() {
+            Ok(peers) => peers,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        peers.retain(|_, budget| {
+            now.duration_since(budget.window_start) < AE_BUDGET_WINDOW * IDLE_WINDOWS_BEFORE_PRUNING
+        });
+
+        peers
+            .iter()
+            .map(|(peer, budget)| (*peer, budget.stats))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sn_interface::test_utils::gen_addr;
+
+    use eyre::{ContextCompat, Result};
+
+    #[test]
+    fn budget_is_spent_within_a_window_and_restored_after_it() -> Result<()> {
+        let budget = AeBudget::default();
+        let peer = Peer::new(xor_name::rand::random(), gen_addr());
+        let other_peer = Peer::new(xor_name::rand::random(), gen_addr());
+        let start = Instant::now();
+
+        for _ in 0..AE_MSGS_PER_WINDOW {
+            assert!(budget.spend_at(&peer, 100, start, true));
+        }
+        assert!(!budget.spend_at(&peer, 100, start, true));
+        // responses are still sent, though accounted for
+        assert!(budget.spend_at(&peer, 100, start, false));
+        // other peers have budgets of their own
+        assert!(budget.spend_at(&other_peer, 100, start, true));
+
+        assert!(budget.spend_at(&peer, 100, start + AE_BUDGET_WINDOW, true));
+
+        let stats = budget.stats_at(start + AE_BUDGET_WINDOW);
+        let peer_stats = stats.get(&peer).context("peer should be tracked")?;
+        assert_eq!(peer_stats.msgs_sent, AE_MSGS_PER_WINDOW + 2);
+        assert_eq!(peer_stats.bytes_sent, 100 * (AE_MSGS_PER_WINDOW + 2));
+        assert_eq!(peer_stats.msgs_dropped, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn idle_peers_are_pruned() {
+        let budget = AeBudget::default();
+        let peer = Peer::new(xor_name::rand::random(), gen_addr());
+        let start = Instant::now();
+
+        assert!(budget.spend_at(&peer, 100, start, true));
+        assert!(budget.stats_at(start).contains_key(&peer));
+
+        let later = start + AE_BUDGET_WINDOW * IDLE_WINDOWS_BEFORE_PRUNING;
+        assert!(budget.stats_at(later).is_empty());
+    }
+}
@@ -9,7 +9,7 @@
 use crate::node::{
     core::NodeContext,
     flow_ctrl::{cmds::Cmd, RejoinReason},
-    messaging::Peers,
+    messaging::{AeBudget, Peers},
     Error, MyNode, Result,
 };
 
@@ -17,7 +17,7 @@ use sn_fault_detection::IssueType;
 use sn_interface::{
     messaging::{
         data::ClientDataResponse,
-        system::{AntiEntropyKind, NodeMsg},
+        system::{AntiEntropyKind, KnowledgeDigest, NodeMsg, SectionPeers},
         MsgId, MsgKind, MsgType, WireMsg,
     },
     network_knowledge::{NetworkKnowledge, SectionTreeUpdate},
@@ -26,14 +26,16 @@ use sn_interface::{
 
 use bls::PublicKey as BlsPublicKey;
 use itertools::Itertools;
-use qp2p::{SendStream, UsrMsgBytes};
+use qp2p::UsrMsgBytes;
+use sn_comms::SendStream;
 use std::{collections::BTreeSet, sync::Arc};
 use tokio::sync::RwLock;
 use xor_name::XorName;
 
 impl MyNode {
-    /// Send `AntiEntropy` update message to all nodes in our own section.
-    pub(crate) fn send_ae_update_to_our_section(&self) -> Result<Option<Cmd>> {
+    /// Send a digest of our knowledge of our section to all the other members of it,
+    /// so those which aren't in sync with us fetch only what they're missing.
+    pub(crate) fn send_ae_digest_to_our_section(&self) -> Option<Cmd> {
         let our_name = self.info().name();
         let context = &self.context();
         let recipients: BTreeSet<_> = self
@@ -45,34 +47,26 @@ impl MyNode {
             .collect();
 
         if recipients.is_empty() {
-            warn!("No peers of our section found in our network knowledge to send AE-Update");
-            return Ok(None);
+            warn!("No peers of our section found in our network knowledge to send AE-Digest");
+            return None;
         }
 
-        let leaf = self.section_chain().last_key()?;
-        // The previous PK which is likely what adults know
-        match self.section_chain().get_parent_key(&leaf) {
-            Ok(prev_pk) => {
-                let prev_pk = prev_pk.unwrap_or(*self.section_chain().genesis_key());
-                Ok(Some(MyNode::send_ae_update_to_nodes(
-                    context,
-                    Peers::Multiple(recipients),
-                    prev_pk,
-                )))
-            }
-            Err(_) => {
-                error!("SectionsDAG fields went out of sync");
-                Ok(None)
-            }
-        }
+        let digest = self.network_knowledge.knowledge_digest();
+        debug!(
+            "{}: {digest:?} to {} members",
+            LogMarker::AeSendDigest,
+            recipients.len()
+        );
+
+        MyNode::send_ae_msg(context, NodeMsg::AntiEntropyDigest(digest), recipients)
     }
 
     /// Send `AntiEntropy` update message to the specified nodes.
     pub(crate) fn send_ae_update_to_nodes(
         context: &NodeContext,
-        recipients: Peers,
+        recipients: BTreeSet<Peer>,
         section_pk: BlsPublicKey,
-    ) -> Cmd {
+    ) -> Option<Cmd> {
         let members = context.network_knowledge.section_signed_members();
 
         let ae_msg = NodeMsg::AntiEntropy {
@@ -80,7 +74,140 @@ impl MyNode {
             kind: AntiEntropyKind::Update { members },
         };
 
-        Cmd::send_msg(ae_msg, recipients, context.clone())
+        MyNode::send_ae_msg(context, ae_msg, recipients)
+    }
+
+    /// Respond to an AE-Probe with an `AntiEntropy` update, unless the prober already
+    /// spent its AE budget, as probes are unsolicited and could otherwise flood us.
+    pub(crate) fn respond_to_ae_probe(
+        context: &NodeContext,
+        prober: Peer,
+        section_pk: BlsPublicKey,
+    ) -> Option<Cmd> {
+        let ae_msg = NodeMsg::AntiEntropy {
+            section_tree_update: MyNode::generate_ae_section_tree_update(context, Some(section_pk)),
+            kind: AntiEntropyKind::Update {
+                members: context.network_knowledge.section_signed_members(),
+            },
+        };
+
+        if !context
+            .ae_budget
+            .try_spend(&prober, AeBudget::msg_size(&ae_msg))
+        {
+            return None;
+        }
+
+        Some(Cmd::send_msg(
+            ae_msg,
+            Peers::Single(prober),
+            context.clone(),
+        ))
+    }
+
+    /// Compare the digest of a member's knowledge of our section with ours, and if we're
+    /// not in sync, fetch from it what we're missing.
+    pub(crate) fn handle_ae_digest(
+        context: &NodeContext,
+        digest: KnowledgeDigest,
+        sender: Peer,
+    ) -> Option<Cmd> {
+        if !context.network_knowledge.prefix().matches(&sender.name()) {
+            warn!("Dropping AE-Digest from {sender:?}, which is not of our section");
+            return None;
+        }
+
+        let our_digest = context.network_knowledge.knowledge_digest();
+        if digest == our_digest {
+            trace!("Our knowledge of our section is in sync with {sender:?}'s");
+            return None;
+        }
+
+        if digest.section_key != our_digest.section_key
+            && context.network_knowledge.has_chain_key(&digest.section_key)
+        {
+            // they're behind us, they'll fetch the update once they get our digest
+            trace!("Ignoring AE-Digest from {sender:?} with an older section key");
+            return None;
+        }
+
+        let members = context
+            .network_knowledge
+            .section_members()
+            .iter()
+            .map(|member| member.name())
+            .collect();
+        debug!(
+            "{}: from {sender:?}, whose digest {digest:?} differs from ours {our_digest:?}",
+            LogMarker::AeSendFetch
+        );
+
+        MyNode::send_ae_msg(
+            context,
+            NodeMsg::AntiEntropyFetch {
+                section_key: our_digest.section_key,
+                members,
+            },
+            BTreeSet::from([sender]),
+        )
+    }
+
+    /// Respond to an AE-Fetch with the proof chain from the sender's section key,
+    /// and only the members whose state it is missing: those it doesn't know of,
+    /// and the latest state of those it knows of which are no longer members.
+    pub(crate) fn handle_ae_fetch(
+        context: &NodeContext,
+        section_key: BlsPublicKey,
+        members: BTreeSet<XorName>,
+        sender: Peer,
+    ) -> Option<Cmd> {
+        let our_members = context.network_knowledge.section_signed_members();
+        let our_names: BTreeSet<_> = our_members.iter().map(|member| member.name()).collect();
+
+        let mut members_diff: SectionPeers = our_members
+            .into_iter()
+            .filter(|member| !members.contains(&member.name()))
+            .collect();
+        members_diff.extend(
+            members
+                .difference(&our_names)
+                .filter_map(|name| context.network_knowledge.is_either_member_or_archived(name)),
+        );
+
+        if section_key == context.network_knowledge.section_key() && members_diff.is_empty() {
+            trace!("{sender:?} fetched no differences with our knowledge");
+            return None;
+        }
+
+        let ae_msg = NodeMsg::AntiEntropy {
+            section_tree_update: MyNode::generate_ae_section_tree_update(
+                context,
+                Some(section_key),
+            ),
+            kind: AntiEntropyKind::Update {
+                members: members_diff,
+            },
+        };
+
+        MyNode::send_ae_msg(context, ae_msg, BTreeSet::from([sender]))
+    }
+
+    // Sends the AE msg to the recipients, accounting for it in their AE budgets.
+    fn send_ae_msg(context: &NodeContext, msg: NodeMsg, recipients: BTreeSet<Peer>) -> Option<Cmd> {
+        if recipients.is_empty() {
+            return None;
+        }
+
+        let msg_size = AeBudget::msg_size(&msg);
+        for peer in &recipients {
+            context.ae_budget.record(peer, msg_size);
+        }
+
+        Some(Cmd::send_msg(
+            msg,
+            Peers::Multiple(recipients),
+            context.clone(),
+        ))
     }
 
     #[instrument(skip_all)]
@@ -112,11 +239,13 @@ impl MyNode {
             let previous_section_key = prev_context.network_knowledge.section_key();
 
             // Send AE update to sibling section's new Elders
-            Ok(vec![MyNode::send_ae_update_to_nodes(
+            Ok(MyNode::send_ae_update_to_nodes(
                 prev_context,
-                Peers::Multiple(promoted_sibling_elders),
+                promoted_sibling_elders,
                 previous_section_key,
-            )])
+            )
+            .into_iter()
+            .collect())
         } else {
             error!("Failed to get sibling SAP during split.");
             Ok(vec![])
@@ -332,15 +461,19 @@ impl MyNode {
                     name: origin.name(),
                     issue: sn_fault_detection::IssueType::NetworkKnowledge,
                 };
+                let msg = NodeMsg::AntiEntropy {
+                    section_tree_update,
+                    kind,
+                };
+                // stale msgs are always answered, else the sender couldn't ever catch up with us
+                context.ae_budget.record(&origin, AeBudget::msg_size(&msg));
+
                 if let Some(stream) = send_stream {
                     debug!("Sending AE response over send_stream for {msg_id:?}");
                     Ok(vec![
                         track_node_cmd,
                         Cmd::SendNodeMsgResponse {
-                            msg: NodeMsg::AntiEntropy {
-                                section_tree_update,
-                                kind,
-                            },
+                            msg,
                             msg_id,
                             send_stream: stream,
                             recipient: origin,
@@ -351,14 +484,7 @@ impl MyNode {
                     debug!("Attempting to send AE response over fresh conn for {msg_id:?}");
                     Ok(vec![
                         track_node_cmd,
-                        Cmd::send_msg(
-                            NodeMsg::AntiEntropy {
-                                section_tree_update,
-                                kind,
-                            },
-                            Peers::Single(origin),
-                            context.clone(),
-                        ),
+                        Cmd::send_msg(msg, Peers::Single(origin), context.clone()),
                     ])
                 }
             }
@@ -493,9 +619,11 @@ fn generate_ae_section_tree_update(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::node::{flow_ctrl::tests::network_builder::TestNetworkBuilder, MIN_ADULT_AGE};
+    use crate::node::{
+        flow_ctrl::tests::network_builder::{elder_count, TestNetworkBuilder},
+        MIN_ADULT_AGE,
+    };
     use sn_interface::{
-        elder_count,
         messaging::{Dst, MsgId, MsgKind},
         network_knowledge::MyNodeInfo,
         test_utils::{gen_addr, prefix},
@@ -503,7 +631,7 @@ mod tests {
     };
 
     use bls::SecretKey;
-    use eyre::{ContextCompat, Result};
+    use eyre::{bail, ContextCompat, Result};
     use xor_name::Prefix;
 
     #[tokio::test]
@@ -536,6 +664,99 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn ae_digest_out_of_sync_triggers_fetch() -> Result<()> {
+        let our_prefix = prefix("0");
+        let env = TestNetworkBuilder::new(rand::thread_rng())
+            .sap(our_prefix, elder_count(), 2, None, None)
+            .build();
+        let node = env.get_nodes(our_prefix, 1, 0, None).remove(0);
+        let sender = env
+            .get_nodes(our_prefix, 0, 1, None)
+            .remove(0)
+            .info()
+            .peer();
+        let context = node.context();
+
+        let digest = context.network_knowledge.knowledge_digest();
+        assert!(MyNode::handle_ae_digest(&context, digest, sender).is_none());
+
+        let names: Vec<_> = context
+            .network_knowledge
+            .section_members()
+            .iter()
+            .map(|member| member.name())
+            .collect();
+        let other_digest = KnowledgeDigest::new(digest.section_key, names.iter().skip(1));
+        let cmd = MyNode::handle_ae_digest(&context, other_digest, sender)
+            .context("AE-Fetch expected")?;
+
+        match cmd {
+            Cmd::SendMsg {
+                msg:
+                    NodeMsg::AntiEntropyFetch {
+                        section_key,
+                        members,
+                    },
+                ..
+            } => {
+                assert_eq!(section_key, digest.section_key);
+                assert_eq!(members.len(), names.len());
+            }
+            _ => bail!("Unexpected cmd: {cmd:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ae_fetch_is_responded_with_the_differing_members_only() -> Result<()> {
+        let our_prefix = prefix("0");
+        let env = TestNetworkBuilder::new(rand::thread_rng())
+            .sap(our_prefix, elder_count(), 2, None, None)
+            .build();
+        let node = env.get_nodes(our_prefix, 1, 0, None).remove(0);
+        let sender = env
+            .get_nodes(our_prefix, 0, 1, None)
+            .remove(0)
+            .info()
+            .peer();
+        let context = node.context();
+        let section_key = context.network_knowledge.section_key();
+
+        let mut members: BTreeSet<_> = context
+            .network_knowledge
+            .section_members()
+            .iter()
+            .map(|member| member.name())
+            .collect();
+        // nothing to respond with when the sender is in sync
+        assert!(MyNode::handle_ae_fetch(&context, section_key, members.clone(), sender).is_none());
+
+        let missing = members.pop_first().context("section should have members")?;
+        // a name we don't know of has no state for us to provide
+        let _ = members.insert(xor_name::rand::random());
+        let cmd = MyNode::handle_ae_fetch(&context, section_key, members, sender)
+            .context("AE-Update expected")?;
+
+        match cmd {
+            Cmd::SendMsg {
+                msg:
+                    NodeMsg::AntiEntropy {
+                        kind: AntiEntropyKind::Update { members },
+                        ..
+                    },
+                ..
+            } => {
+                let names: Vec<_> = members.iter().map(|member| member.name()).collect();
+                assert_eq!(names, vec![missing]);
+            }
+            _ => bail!("Unexpected cmd: {cmd:?}"),
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn ae_redirect_to_other_section() -> Result<()> {
         // create an env with 3 churns in prefix0. And a single churn in prefix1
@@ -10,7 +10,7 @@ use crate::node::{core::NodeContext, flow_ctrl::cmds::Cmd, Error, MyNode, Result
 
 use bytes::BufMut;
 
-use qp2p::SendStream;
+use sn_comms::SendStream;
 use sn_dbc::{
     get_public_commitments_from_transaction, Commitment, KeyImage, RingCtTransaction, SpentProof,
     SpentProofShare,
@@ -18,8 +18,8 @@ use sn_dbc::{
 use sn_interface::{
     messaging::{
         data::{
-            ClientDataResponse, ClientMsg, DataCmd, DataQueryVariant, EditRegister,
-            SignedRegisterEdit, SpentbookCmd,
+            ClientDataResponse, ClientMsg, DataCmd, DataQueryVariant, EditRegister, SectionHealth,
+            SectionMembersProof, SignedRegisterEdit, SpentbookCmd,
         },
         system::{NodeDataResponse, OperationId},
         AuthorityProof, ClientAuth, MsgId,
@@ -83,6 +83,67 @@ impl MyNode {
         }
     }
 
+    /// Sends our view of our section's membership, and our storage levels, back to the client
+    pub(crate) fn send_section_health_response(
+        context: NodeContext,
+        correlation_id: MsgId,
+        source_client: Peer,
+        send_stream: SendStream,
+    ) -> Cmd {
+        let used_space = context.data_storage.used_space();
+        let health = SectionHealth {
+            prefix: context.network_knowledge.prefix(),
+            section_key: context.network_knowledge.section_key(),
+            members: context.network_knowledge.section_members(),
+            used_space: used_space.used(),
+            min_capacity: used_space.min_capacity(),
+            max_capacity: used_space.max_capacity(),
+        };
+
+        debug!("{correlation_id:?} sending section health back to client");
+        let msg = ClientDataResponse::SectionHealth {
+            health: Box::new(health),
+            correlation_id,
+        };
+
+        Cmd::SendClientResponse {
+            msg,
+            correlation_id,
+            send_stream,
+            context,
+            source_client,
+        }
+    }
+
+    /// Sends the section signed members of our section, along with our SAP and section chain
+    /// to prove them, back to the client
+    pub(crate) fn send_section_members_response(
+        context: NodeContext,
+        correlation_id: MsgId,
+        source_client: Peer,
+        send_stream: SendStream,
+    ) -> Cmd {
+        let proof = SectionMembersProof {
+            signed_sap: context.network_knowledge.signed_sap(),
+            proof_chain: context.network_knowledge.section_chain(),
+            members: context.network_knowledge.section_signed_members(),
+        };
+
+        debug!("{correlation_id:?} sending section members proof back to client");
+        let msg = ClientDataResponse::SectionMembers {
+            proof: Box::new(proof),
+            correlation_id,
+        };
+
+        Cmd::SendClientResponse {
+            msg,
+            correlation_id,
+            send_stream,
+            context,
+            source_client,
+        }
+    }
+
     /// Handle data query
     #[allow(clippy::too_many_arguments)]
     pub(crate) async fn handle_data_query_where_stored(
@@ -147,6 +208,22 @@ impl MyNode {
                     send_stream,
                 )
             }
+            ClientMsg::SectionHealthQuery => {
+                return Ok(vec![MyNode::send_section_health_response(
+                    context,
+                    msg_id,
+                    origin,
+                    send_stream,
+                )])
+            }
+            ClientMsg::SectionMembersQuery => {
+                return Ok(vec![MyNode::send_section_members_response(
+                    context,
+                    msg_id,
+                    origin,
+                    send_stream,
+                )])
+            }
         };
 
         // extract the data from the request
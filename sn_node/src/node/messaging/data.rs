@@ -13,7 +13,6 @@ use crate::node::{
 use crate::storage::{Error as StorageError, StorageLevel};
 
 use sn_interface::{
-    data_copy_count,
     messaging::{
         data::{DataCmd, DataQuery},
         system::{NodeDataCmd, NodeDataQuery, NodeEvent, NodeMsg, OperationId},
@@ -22,7 +21,7 @@ use sn_interface::{
     types::{log_markers::LogMarker, Keypair, Peer, PublicKey, ReplicatedData},
 };
 
-use qp2p::SendStream;
+use sn_comms::SendStream;
 use xor_name::XorName;
 
 use bytes::Bytes;
@@ -44,11 +43,12 @@ impl MyNode {
         let targets = Self::target_data_holders(&context, data_name);
 
         // make sure the expected replication factor is achieved
-        if data_copy_count() > targets.len() {
+        let data_copy_count = context.network_knowledge.network_params().data_copy_count;
+        if data_copy_count > targets.len() {
             error!("InsufficientNodeCount for storing data reliably for {msg_id:?}");
             let error = Error::InsufficientNodeCount {
                 prefix: context.network_knowledge.prefix(),
-                expected: data_copy_count() as u8,
+                expected: data_copy_count as u8,
                 found: targets.len() as u8,
             };
 
@@ -187,7 +187,7 @@ impl MyNode {
         let candidates = members
             .into_iter()
             .sorted_by(|lhs, rhs| target.cmp_distance(&lhs.name(), &rhs.name()))
-            .take(data_copy_count())
+            .take(context.network_knowledge.network_params().data_copy_count)
             .collect::<BTreeSet<_>>();
 
         trace!("Target holders of {:?} are : {:?}", target, candidates,);
@@ -19,7 +19,7 @@ use sn_interface::{
         system::{DkgSessionId, NodeMsg, SectionSigShare},
         AuthorityProof, MsgId, SectionSig,
     },
-    network_knowledge::{SectionAuthorityProvider, SectionKeyShare},
+    network_knowledge::{NetworkParams, SectionAuthorityProvider, SectionKeyShare},
     types::{self, log_markers::LogMarker, Peer},
 };
 
@@ -43,8 +43,10 @@ fn acknowledge_dkg_outcome(
     participant_index: usize,
     pub_key_set: PublicKeySet,
     sec_key_share: SecretKeyShare,
+    network_params: NetworkParams,
 ) -> Cmd {
-    let section_auth = SectionAuthorityProvider::from_dkg_session(session_id, pub_key_set.clone());
+    let section_auth =
+        SectionAuthorityProvider::from_dkg_session(session_id, pub_key_set.clone(), network_params);
     let outcome = SectionKeyShare {
         public_key_set: pub_key_set,
         index: participant_index,
@@ -433,7 +435,11 @@ impl MyNode {
                     new_pubs.public_key(),
                 );
                 cmds.push(acknowledge_dkg_outcome(
-                    session_id, our_id, new_pubs, new_sec,
+                    session_id,
+                    our_id,
+                    new_pubs,
+                    new_sec,
+                    self.network_knowledge.network_params(),
                 ))
             }
         }
@@ -684,7 +690,13 @@ impl MyNode {
                     "Gossiping DKG outcome for s{} as we didn't notice SAP change",
                     session_id.sh()
                 );
-                let cmd = acknowledge_dkg_outcome(session_id, our_id.into(), new_pubs, new_sec);
+                let cmd = acknowledge_dkg_outcome(
+                    session_id,
+                    our_id.into(),
+                    new_pubs,
+                    new_sec,
+                    self.network_knowledge.network_params(),
+                );
                 vec![cmd]
             }
             Ok(None) => {
@@ -1072,7 +1084,7 @@ mod tests {
                                             proposal: SectionStateVote::JoinsAllowed(..),
                                             ..
                                         } => (),
-                                        NodeMsg::AntiEntropy { .. } => (),
+                                        NodeMsg::AntiEntropyDigest(_) => (),
                                         msg => panic!("Unexpected msg {msg}"),
                                     }
                                 }
@@ -1376,7 +1388,7 @@ mod tests {
 
             match msg {
                 NodeMsg::RequestHandover { .. } => (),
-                NodeMsg::AntiEntropy { .. } => (),
+                NodeMsg::AntiEntropyDigest(_) => (),
                 msg => panic!("Unexpected msg {msg}"),
             }
         }
@@ -164,6 +164,7 @@ impl MyNode {
                         LogMarker::HandoverConsensusTermination,
                         sap_candidates
                     );
+                    self.persist_state();
                     match self.broadcast_handover_completed(sap_candidates) {
                         Ok(c) => cmds.extend(c),
                         Err(err) => error!("Error broadcasting handover complete: {err:?}"),
@@ -452,6 +453,7 @@ impl MyNode {
         match &self.handover_voting {
             Some(handover_state) => {
                 let had_consensus_value = handover_state.consensus_value().is_some();
+                let prev_gen = handover_state.generation();
                 let mut state = handover_state.clone();
                 let mut cmds = MyNode::handle_vote(context, &mut state, signed_vote, peer)?;
 
@@ -475,7 +477,14 @@ impl MyNode {
                         }
                     }
                 }
+                let decided = !had_consensus_value && state.consensus_value().is_some();
+                let gen_changed = state.generation() != prev_gen;
                 self.handover_voting = Some(state);
+
+                // resume from the decided rounds, rather than voting on them anew, if restarted
+                if decided || gen_changed {
+                    self.persist_state();
+                }
                 Ok(cmds)
             }
             None => {
@@ -19,9 +19,10 @@ impl MyNode {
         if context.network_knowledge.is_section_member(&context.name) {
             None
         } else {
+            let relay = context.info.relay;
             Some(MyNode::send_msg_to_our_elders_await_responses(
                 context,
-                NodeMsg::TryJoin(relocation),
+                NodeMsg::TryJoin { relocation, relay },
             ))
         }
     }
@@ -33,7 +34,8 @@ mod tests {
     use crate::node::{
         flow_ctrl::{
             dispatcher::Dispatcher,
-            tests::network_builder::{TestNetwork, TestNetworkBuilder},
+            fault_detection::FaultDetectionSettings,
+            tests::network_builder::{elder_count, TestNetwork, TestNetworkBuilder},
             CmdCtrl, FlowCtrl, RejoinReason,
         },
         messaging::Peers,
@@ -42,7 +44,7 @@ mod tests {
 
     use sn_comms::MsgFromPeer;
     use sn_interface::{
-        elder_count, init_logger,
+        init_logger,
         messaging::system::{JoinRejectReason, JoinResponse},
         network_knowledge::{MembershipState, NetworkKnowledge},
     };
@@ -412,6 +414,7 @@ mod tests {
             incoming_msg_receiver,
             data_replication_receiver,
             mpsc::channel(10),
+            FaultDetectionSettings::default(),
         )
         .await;
 
@@ -12,7 +12,7 @@ use crate::node::{
 
 use sn_interface::{
     messaging::system::{JoinRejectReason, JoinResponse, NodeMsg},
-    network_knowledge::{NodeState, RelocationProof, MIN_ADULT_AGE},
+    network_knowledge::{NodeState, RelocationProof},
     types::{log_markers::LogMarker, Peer},
 };
 
@@ -67,7 +67,7 @@ impl MyNode {
             Some(proof.previous_name())
         } else {
             // New node ->
-            if !MyNode::is_infant_node(&peer) {
+            if !MyNode::is_infant_node(context, &peer) {
                 debug!("Unreachable path; {peer} age is invalid: {}. This should be a hard coded value in join logic. Dropping the msg.", peer.age());
                 return Ok(None);
             }
@@ -98,9 +98,9 @@ impl MyNode {
         Ok(node.propose_membership_change(node_state))
     }
 
-    pub(crate) fn is_infant_node(peer: &Peer) -> bool {
-        // Age should be MIN_ADULT_AGE for joining infant.
-        peer.age() == MIN_ADULT_AGE
+    pub(crate) fn is_infant_node(context: &NodeContext, peer: &Peer) -> bool {
+        // Age should be the network's min adult age for joining infant.
+        peer.age() == context.network_knowledge.network_params().min_adult_age
     }
 
     pub(crate) fn verify_relocated_age(peer: &Peer, proof: &RelocationProof) -> Result<()> {
@@ -220,7 +220,7 @@ impl MyNode {
 
         cmds.extend(self.trigger_dkg()?);
 
-        cmds.extend(self.send_ae_update_to_our_section()?);
+        cmds.extend(self.send_ae_digest_to_our_section());
 
         self.fault_detection_retain_only(
             self.network_knowledge
@@ -264,8 +264,18 @@ impl MyNode {
         self.log_section_stats();
         self.log_network_stats();
 
-        // updates comm with new members and removes connections that are not from our members
-        self.comm.set_comm_targets(self.network_knowledge.members());
+        // updates comm with new members and removes connections that are not from our members,
+        // keeping the nodes we relocate until then, so they get the msg telling them so
+        let mut targets = self.network_knowledge.members();
+        targets.extend(
+            leaving_nodes
+                .iter()
+                .filter(|(n, _)| n.is_relocated())
+                .map(|(n, _)| *n.peer()),
+        );
+        self.comm.set_comm_targets(targets);
+
+        self.persist_state();
 
         // lets check that we have the correct data now we're changing membership
         cmds.push(MyNode::ask_for_any_new_data_from_whole_section(&self.context()).await);
@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod ae_budget;
 mod anti_entropy;
 mod client_msgs;
 mod data;
@@ -17,15 +18,19 @@ mod membership;
 pub(crate) mod node_msgs;
 mod promotion;
 mod relocation;
+mod replica_checks;
 mod section_state;
 mod serialize;
 mod signature;
 mod streams;
 mod update_section;
 
+pub(crate) use ae_budget::AeBudget;
+pub(crate) use replica_checks::ReplicaChecks;
+
 use crate::node::{flow_ctrl::cmds::Cmd, Error, MyNode, Result};
 
-use qp2p::SendStream;
+use sn_comms::SendStream;
 use sn_interface::{
     messaging::{MsgType, WireMsg},
     types::Peer,
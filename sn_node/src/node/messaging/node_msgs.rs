@@ -13,7 +13,7 @@ use crate::{
     storage::{Error as StorageError, StorageLevel},
 };
 
-use sn_comms::Error as CommsError;
+use sn_comms::{Error as CommsError, SendStream};
 use sn_interface::{
     messaging::{
         data::CmdResponse,
@@ -24,7 +24,8 @@ use sn_interface::{
     types::{log_markers::LogMarker, Keypair, Peer, PublicKey, ReplicatedData},
 };
 
-use qp2p::{SendStream, UsrMsgBytes};
+use qp2p::UsrMsgBytes;
+use rand::Rng;
 use sn_fault_detection::IssueType;
 use xor_name::XorName;
 
@@ -41,6 +42,8 @@ impl MyNode {
         context: NodeContext,
     ) -> Result<Vec<Cmd>> {
         trace!("Sending msg: {msg_id:?}");
+        #[cfg(feature = "chaos")]
+        let msg_variant = msg.to_string();
         let peer_msgs = into_msg_bytes(
             &context.network_knowledge,
             context.name,
@@ -50,11 +53,46 @@ impl MyNode {
         )?;
 
         let comm = context.comm.clone();
+        #[cfg(not(feature = "chaos"))]
         let tasks = peer_msgs
             .into_iter()
             .map(|(peer, msg)| comm.send_out_bytes(peer, msg_id, msg));
+        #[cfg(not(feature = "chaos"))]
         let results = futures::future::join_all(tasks).await;
 
+        #[cfg(feature = "chaos")]
+        let tasks = peer_msgs.into_iter().map(|(peer, msg)| {
+            let comm = comm.clone();
+            let late_context = context.clone();
+            context.chaos.send(
+                &msg_variant,
+                msg_id,
+                peer,
+                move || {
+                    let comm = comm.clone();
+                    let msg = msg.clone();
+                    async move { comm.send_out_bytes(peer, msg_id, msg).await }
+                },
+                move |result| {
+                    if let Err(error) = result {
+                        error!("Late send of {msg_id:?} to {peer} failed: {error:?}");
+                        if late_context
+                            .network_knowledge
+                            .is_section_member(&peer.name())
+                        {
+                            late_context.track_node_issue(peer.name(), IssueType::Communication);
+                        }
+                    }
+                },
+            )
+        });
+        #[cfg(feature = "chaos")]
+        let results: Vec<_> = futures::future::join_all(tasks)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
         // Any failed sends are tracked via Cmd::HandlePeerFailedSend, which will track issues for any peers
         // in the section (otherwise ignoring failed send to out of section nodes or clients)
         let cmds = results
@@ -191,9 +229,14 @@ impl MyNode {
         trace!("{:?}: {msg_id:?}", LogMarker::NodeMsgToBeHandled);
 
         match msg {
-            NodeMsg::TryJoin(relocation) => {
+            NodeMsg::TryJoin { relocation, relay } => {
                 trace!("Handling msg {:?}: TryJoin from {}", msg_id, sender);
-                MyNode::handle_join(node, &context, sender, relocation)
+                // a node which cannot accept incoming connections is to be reached via its relay
+                let joining_peer = match relay {
+                    Some(relay) => Peer::new_relayed(sender.name(), sender.addr(), relay),
+                    None => sender,
+                };
+                MyNode::handle_join(node, &context, joining_peer, relocation)
                     .await
                     .map(|c| c.into_iter().collect())
             }
@@ -214,7 +257,7 @@ impl MyNode {
                     .await
             }
             // Respond to a probe msg
-            // We always respond to probe msgs if we're an elder as health checks use this to see if a node is alive
+            // We respond to probe msgs if we're an elder, within the prober's AE budget, as health checks use this to see if a node is alive
             // and repsonsive, as well as being a method of keeping nodes up to date.
             NodeMsg::AntiEntropyProbe(section_key) => {
                 debug!("Aeprobe in");
@@ -228,13 +271,26 @@ impl MyNode {
                 }
 
                 trace!("Received Probe message from {}: {:?}", sender, msg_id);
-                cmds.push(MyNode::send_ae_update_to_nodes(
-                    &context,
-                    Peers::Single(sender),
-                    section_key,
-                ));
+                cmds.extend(MyNode::respond_to_ae_probe(&context, sender, section_key));
                 Ok(cmds)
             }
+            NodeMsg::AntiEntropyDigest(digest) => {
+                trace!("Handling msg: AE-Digest from {sender}: {msg_id:?}");
+                Ok(MyNode::handle_ae_digest(&context, digest, sender)
+                    .into_iter()
+                    .collect())
+            }
+            NodeMsg::AntiEntropyFetch {
+                section_key,
+                members,
+            } => {
+                trace!("Handling msg: AE-Fetch from {sender}: {msg_id:?}");
+                Ok(
+                    MyNode::handle_ae_fetch(&context, section_key, members, sender)
+                        .into_iter()
+                        .collect(),
+                )
+            }
             // The approval or rejection of a join (approval both for new network joiner as well as
             // existing node relocated to the section) will be received here.
             NodeMsg::JoinResponse(join_response) => {
@@ -353,6 +409,9 @@ impl MyNode {
                     sender
                 );
 
+                #[cfg(feature = "chaos")]
+                context.chaos.stall_dkg_participant(&session_id).await;
+
                 let mut node = node.write().await;
                 debug!("[NODE WRITE]: DKGstart write gottt...");
                 node.untrack_node_issue(sender.name(), IssueType::Dkg);
@@ -370,6 +429,10 @@ impl MyNode {
                     session_id.sh(),
                     sender
                 );
+
+                #[cfg(feature = "chaos")]
+                context.chaos.stall_dkg_participant(&session_id).await;
+
                 let mut node = node.write().await;
                 debug!("[NODE WRITE]: DKG Ephemeral write gottt...");
                 node.handle_dkg_ephemeral_pubkey(&session_id, section_auth, pub_key, sig, sender)
@@ -386,6 +449,10 @@ impl MyNode {
                     sender,
                     votes
                 );
+
+                #[cfg(feature = "chaos")]
+                context.chaos.stall_dkg_participant(&session_id).await;
+
                 let mut node = node.write().await;
                 debug!("[NODE WRITE]: DKG Votes write gottt...");
 
@@ -532,9 +599,10 @@ pub(crate) fn into_msg_bytes(
     };
 
     // we first generate the XorName
+    let mut rng = sn_interface::rng::thread_rng();
     let dst = Dst {
-        name: xor_name::rand::random(),
-        section_key: bls::SecretKey::random().public_key(),
+        name: XorName::random(&mut rng),
+        section_key: rng.gen::<bls::SecretKey>().public_key(),
     };
 
     let mut initial_wire_msg = WireMsg::new_msg(msg_id, payload, kind, dst);
@@ -8,12 +8,11 @@
 
 use crate::node::{
     flow_ctrl::cmds::Cmd,
-    relocation::{find_nodes_to_relocate, ChurnId},
+    relocation::{log_decision, validate_decision, ChurnId, RelocationDecision},
     MyNode, Result,
 };
 
 use sn_interface::{
-    elder_count,
     messaging::system::SectionSigned,
     network_knowledge::{node_state::RelocationInfo, MembershipState, NodeState, RelocationProof},
     types::{keys::ed25519, log_markers::LogMarker},
@@ -29,33 +28,36 @@ impl MyNode {
         churn_id: ChurnId,
         excluded: BTreeSet<XorName>,
     ) -> Result<Vec<Cmd>> {
-        // Do not carry out relocations in the first section
-        // TODO: consider avoiding relocations in first 16 sections instead.
-        if self.network_knowledge.prefix().is_empty() {
-            return Ok(vec![]);
-        }
-        debug!("Try to find relocate peers, excluded {excluded:?}");
-        // Do not carry out relocation when there is not enough elder nodes.
-        if self.network_knowledge.section_auth().elder_count() < elder_count() {
-            debug!(
-                "Not enough elders current {:?} vs. expected {:?}",
+        let policy = self.relocation_policy.clone();
+        let decision = if self.network_knowledge.prefix().is_empty() {
+            // Do not carry out relocations in the first section
+            // TODO: consider avoiding relocations in first 16 sections instead.
+            RelocationDecision::none("no relocations in the first section")
+        } else if self.network_knowledge.section_auth().elder_count()
+            < self.network_knowledge.network_params().elder_count
+        {
+            // Do not carry out relocation when there is not enough elder nodes.
+            RelocationDecision::none(format!(
+                "not enough elders, {} instead of {}",
                 self.network_knowledge.section_auth().elder_count(),
-                elder_count()
-            );
-            return Ok(vec![]);
-        }
+                self.network_knowledge.network_params().elder_count
+            ))
+        } else {
+            debug!("Try to find relocate peers, excluded {excluded:?}");
+            let decision = policy.decide(&self.network_knowledge, &churn_id, &excluded);
+            validate_decision(decision, &self.network_knowledge, &excluded)
+        };
+        log_decision(policy.name(), &self.network_knowledge, &churn_id, &decision);
 
         let mut cmds = vec![];
-        for (node_state, relocation_dst) in
-            find_nodes_to_relocate(&self.network_knowledge, &churn_id, excluded)
-        {
+        for relocation in decision.relocations {
             debug!(
                 "Relocating {:?} to {} (on churn of {churn_id})",
-                node_state.peer(),
-                relocation_dst.name(),
+                relocation.node.peer(),
+                relocation.dst.name(),
             );
 
-            cmds.extend(self.propose_membership_change(node_state.relocate(relocation_dst)));
+            cmds.extend(self.propose_membership_change(relocation.node.relocate(relocation.dst)));
         }
 
         Ok(cmds)
@@ -111,6 +113,8 @@ impl MyNode {
 
         // we switch to the new section
         self.switch_section(dst_sap, new_keypair)?;
+        // and make sure our relay, if any, forwards msgs for our new name
+        self.comm.update_our_keypair(self.keypair.clone());
 
         info!(
             "Relocation of us as {}: switched section to {new_prefix:?} with new name {new_name}. Now trying to join..",
@@ -120,6 +124,7 @@ impl MyNode {
         let proof = RelocationProof::new(info, node_sig, original_info.keypair.public);
         // we cache the proof so that we can retry if the join times out
         self.relocation_proof = Some(proof.clone());
+        self.persist_state();
 
         Ok(MyNode::try_join_section(self.context(), Some(proof)))
     }
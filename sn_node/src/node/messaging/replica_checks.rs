@@ -0,0 +1,268 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use sn_fault_detection::StorageFault;
+use sn_interface::messaging::data::{DataQueryVariant, QueryResponse};
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+use xor_name::XorName;
+
+/// For how long the responses of the replicas to a query are compared with each other.
+/// Client replica checks query all the replicas at once, so their responses arrive within
+/// a short period of time.
+pub(crate) const REPLICA_CHECK_WINDOW: Duration = Duration::from_secs(30);
+/// How many entries a replica of a register can be missing, compared to another replica,
+/// before it's considered a stale replica. Some lag is expected as register edits are
+/// applied on each replica independently.
+pub(crate) const STALE_REGISTER_REPLICA_THRESHOLD: u64 = 5;
+
+/// The responses received from the replicas to a query within a `REPLICA_CHECK_WINDOW`.
+#[derive(Debug)]
+struct ReplicaResponses {
+    first_seen: Instant,
+    holding: BTreeSet<XorName>,
+    missing: BTreeSet<XorName>,
+    register_sizes: BTreeMap<XorName, u64>,
+    stale: BTreeSet<XorName>,
+}
+
+impl ReplicaResponses {
+    fn new(now: Instant) -> Self {
+        Self {
+            first_seen: now,
+            holding: BTreeSet::new(),
+            missing: BTreeSet::new(),
+            register_sizes: BTreeMap::new(),
+            stale: BTreeSet::new(),
+        }
+    }
+}
+
+/// Compares the responses of the replicas holding a piece of data to the same query, so
+/// a replica reporting the data as not found, or with a register far behind, can be blamed
+/// for it only when another replica proves the data to exist. Responses to the queries
+/// of client replica checks are compared this way, as well as those from any other
+/// queries for the same data received around the same time.
+///
+/// Clones share the same responses.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ReplicaChecks {
+    queries: Arc<Mutex<HashMap<DataQueryVariant, ReplicaResponses>>>,
+}
+
+impl ReplicaChecks {
+    /// Records the response of a replica to a query, returning the storage faults which can be
+    /// told from it and the responses of the other replicas. The faults may be against
+    /// replicas which responded earlier, e.g. once a replica proves the data to exist, those
+    /// which reported it as not found are blamed.
+    pub(crate) fn record(
+        &self,
+        query: &DataQueryVariant,
+        replica: XorName,
+        response: &QueryResponse,
+    ) -> Vec<(XorName, StorageFault)> {
+        self.record_at(query, replica, response, Instant::now())
+    }
+
+    fn record_at(
+        &self,
+        query: &DataQueryVariant,
+        replica: XorName,
+        response: &QueryResponse,
+        now: Instant,
+    ) -> Vec<(XorName, StorageFault)> {
+        // spentbook queries for unspent keys are expected to find nothing
+        if matches!(query, DataQueryVariant::Spentbook(_)) {
+            return vec![];
+        }
+
+        let mut faults = vec![];
+
+        // a chunk's address is derived from its content upon deserialisation
+        if let (DataQueryVariant::GetChunk(address), QueryResponse::GetChunk(Ok(chunk))) =
+            (query, response)
+        {
+            if chunk.address() != address {
+                faults.push((replica, StorageFault::CorruptData));
+                return faults;
+            }
+        }
+
+        let mut queries = match self.queries.lock() {
+            Ok(queries) => queries,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        queries
+            .retain(|_, responses| now.duration_since(responses.first_seen) < REPLICA_CHECK_WINDOW);
+        let responses = queries
+            .entry(query.clone())
+            .or_insert_with(|| ReplicaResponses::new(now));
+
+        if response.is_data_not_found() {
+            if responses.holding.is_empty() {
+                let _ = responses.missing.insert(replica);
+            } else if !responses.holding.contains(&replica) {
+                faults.push((replica, StorageFault::MissingData));
+            }
+        } else if response.is_success() {
+            let _ = responses.holding.insert(replica);
+            let missing = std::mem::take(&mut responses.missing);
+            faults.extend(
+                missing
+                    .into_iter()
+                    .filter(|name| *name != replica)
+                    .map(|name| (name, StorageFault::MissingData)),
+            );
+        }
+
+        if let QueryResponse::GetRegister(Ok(register)) = response {
+            let _ = responses.register_sizes.insert(replica, register.size());
+            let largest = responses
+                .register_sizes
+                .values()
+                .max()
+                .copied()
+                .unwrap_or_default();
+
+            let newly_stale = responses
+                .register_sizes
+                .iter()
+                .filter(|(_, size)| {
+                    largest.saturating_sub(**size) > STALE_REGISTER_REPLICA_THRESHOLD
+                })
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>();
+
+            for name in newly_stale {
+                if responses.stale.insert(name) {
+                    faults.push((name, StorageFault::StaleReplica));
+                }
+            }
+        }
+
+        faults
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sn_interface::{
+        messaging::data::{Error as DataError, RegisterQuery},
+        types::{
+            register::{Register, User},
+            Chunk, ChunkAddress, DataAddress, RegisterAddress,
+        },
+    };
+
+    use bytes::Bytes;
+    use xor_name::rand::random as random_xorname;
+
+    #[test]
+    fn replicas_missing_data_are_blamed_once_another_replica_holds_it() {
+        let checks = ReplicaChecks::default();
+        let now = Instant::now();
+        let chunk = Chunk::new(Bytes::from_static(b"some chunk content"));
+        let query = DataQueryVariant::GetChunk(*chunk.address());
+        let not_found = QueryResponse::GetChunk(Err(DataError::DataNotFound(DataAddress::Bytes(
+            *chunk.address(),
+        ))));
+        let found = QueryResponse::GetChunk(Ok(chunk));
+        let (first, second, third) = (random_xorname(), random_xorname(), random_xorname());
+
+        // the data may have never been stored, so no one can be blamed yet
+        assert!(checks.record_at(&query, first, &not_found, now).is_empty());
+        assert_eq!(
+            checks.record_at(&query, second, &found, now),
+            vec![(first, StorageFault::MissingData)]
+        );
+        assert_eq!(
+            checks.record_at(&query, third, &not_found, now),
+            vec![(third, StorageFault::MissingData)]
+        );
+    }
+
+    #[test]
+    fn replica_responses_are_only_compared_within_the_window() {
+        let checks = ReplicaChecks::default();
+        let now = Instant::now();
+        let chunk = Chunk::new(Bytes::from_static(b"some chunk content"));
+        let query = DataQueryVariant::GetChunk(*chunk.address());
+        let not_found = QueryResponse::GetChunk(Err(DataError::DataNotFound(DataAddress::Bytes(
+            *chunk.address(),
+        ))));
+        let found = QueryResponse::GetChunk(Ok(chunk));
+
+        assert!(checks
+            .record_at(&query, random_xorname(), &not_found, now)
+            .is_empty());
+        assert!(checks
+            .record_at(&query, random_xorname(), &found, now + REPLICA_CHECK_WINDOW)
+            .is_empty());
+    }
+
+    #[test]
+    fn corrupt_chunks_are_blamed_straight_away() {
+        let checks = ReplicaChecks::default();
+        let chunk = Chunk::new(Bytes::from_static(b"some chunk content"));
+        let other_address = ChunkAddress(random_xorname());
+        let query = DataQueryVariant::GetChunk(other_address);
+        let replica = random_xorname();
+
+        assert_eq!(
+            checks.record(&query, replica, &QueryResponse::GetChunk(Ok(chunk))),
+            vec![(replica, StorageFault::CorruptData)]
+        );
+    }
+
+    #[test]
+    fn registers_far_behind_other_replicas_are_stale() -> eyre::Result<()> {
+        let checks = ReplicaChecks::default();
+        let address = RegisterAddress {
+            name: random_xorname(),
+            tag: 0,
+        };
+        let query = DataQueryVariant::Register(RegisterQuery::Get(address));
+        let (behind, up_to_date) = (random_xorname(), random_xorname());
+
+        let register = Register::new_owned(User::Anyone, address.name, address.tag);
+        let mut up_to_date_register = register.clone();
+        for i in 0..=STALE_REGISTER_REPLICA_THRESHOLD {
+            let _ = up_to_date_register.write(vec![i as u8], Default::default())?;
+        }
+
+        assert!(checks
+            .record(&query, behind, &QueryResponse::GetRegister(Ok(register)))
+            .is_empty());
+        assert_eq!(
+            checks.record(
+                &query,
+                up_to_date,
+                &QueryResponse::GetRegister(Ok(up_to_date_register.clone()))
+            ),
+            vec![(behind, StorageFault::StaleReplica)]
+        );
+        // the same stale replica is not blamed twice within the window
+        assert!(checks
+            .record(
+                &query,
+                up_to_date,
+                &QueryResponse::GetRegister(Ok(up_to_date_register))
+            )
+            .is_empty());
+
+        Ok(())
+    }
+}
@@ -144,6 +144,7 @@ impl MyNode {
             SectionStateVote::JoinsAllowed(joins_allowed) => {
                 info!("Section reached agreement to set joins_allowed to: {joins_allowed:?}");
                 self.joins_allowed = joins_allowed;
+                self.persist_state();
             }
         }
         Ok(cmds)
@@ -8,24 +8,24 @@
 
 use crate::node::{core::NodeContext, Cmd, Error, MyNode, Result};
 
-use sn_comms::Error as CommsError;
-use sn_fault_detection::IssueType;
+use sn_comms::{Error as CommsError, SendStream};
+use sn_fault_detection::{IssueType, StorageFault};
 use sn_interface::{
     messaging::{
-        data::{ClientDataResponse, CmdResponse},
+        data::{ClientDataResponse, CmdResponse, QueryResponse},
         system::{NodeDataCmd, NodeDataResponse, NodeMsg},
         Dst, MsgId, MsgKind, MsgType, WireMsg,
     },
-    types::Peer,
+    types::{log_markers::LogMarker, register::User, Peer},
 };
 
-use qp2p::SendStream;
+use qp2p::UsrMsgBytes;
 use xor_name::XorName;
 
 use bytes::Bytes;
 use futures::FutureExt;
 use lazy_static::lazy_static;
-use std::{collections::BTreeSet, env::var, str::FromStr};
+use std::{collections::BTreeSet, env::var, fmt::Display, str::FromStr};
 use tokio::time::{error::Elapsed, timeout, Duration};
 
 /// Environment variable to set timeout value (in seconds) for data queries
@@ -70,7 +70,8 @@ impl MyNode {
         let (kind, payload) = MyNode::serialize_node_msg(context.name, &msg)?;
 
         match send_msg_on_stream(
-            context.network_knowledge.section_key(),
+            &context,
+            &msg,
             payload,
             kind,
             send_stream,
@@ -107,7 +108,8 @@ impl MyNode {
         trace!("Sending client response msg for {correlation_id:?}");
         let (kind, payload) = MyNode::serialize_client_msg_response(context.name, &msg)?;
         send_msg_on_stream(
-            context.network_knowledge.section_key(),
+            &context,
+            &msg,
             payload,
             kind,
             send_stream,
@@ -127,7 +129,8 @@ impl MyNode {
         trace!("Sending node response msg for {correlation_id:?}");
         let (kind, payload) = MyNode::serialize_node_data_response(context.name, &msg)?;
         send_msg_on_stream(
-            context.network_knowledge.section_key(),
+            &context,
+            &msg,
             payload,
             kind,
             send_stream,
@@ -198,40 +201,54 @@ impl MyNode {
         let mut success_count = 0;
         let mut last_success_response = None;
         let mut last_error = None;
-        results.into_iter().for_each(|(peer, result)| match result {
-            Err(_elapsed) => {
-                error!(
-                    "{msg_id:?}: No response from {peer:?} after {:?} timeout. Marking node as faulty",
-                    *NODE_RESPONSE_TIMEOUT
-                );
-                output_cmds.push(Cmd::TrackNodeIssue {
-                    name: peer.name(),
-                    issue: IssueType::Communication,
-                });
-                // TODO: report timeout error to client?
-            }
-            Ok(Ok(response)) => {
-                debug!("Response in from {peer:?} for {msg_id:?}: {response:?}");
-                success_count += 1;
-                last_success_response = Some(response);
-            }
-            Ok(Err(comms_err)) => {
-                error!("{msg_id:?} Error when sending request to holder node {peer:?}, marking node as faulty: {comms_err:?}");
-                if let CommsError::FailedSend(peer) = comms_err {
+        for (peer, result) in results {
+            match result {
+                Err(_elapsed) => {
+                    error!(
+                        "{msg_id:?}: No response from {peer:?} after {:?} timeout. Marking node as faulty",
+                        *NODE_RESPONSE_TIMEOUT
+                    );
                     output_cmds.push(Cmd::TrackNodeIssue {
                         name: peer.name(),
                         issue: IssueType::Communication,
                     });
-                } else {
-                    output_cmds.push(Cmd::TrackNodeIssue {
-                        name: peer.name(),
-                        issue: IssueType::RequestOperation,
-                    });
+                    // TODO: report timeout error to client?
+                }
+                Ok(Ok(response)) => {
+                    debug!("Response in from {peer:?} for {msg_id:?}: {response:?}");
+                    for (name, fault) in
+                        check_for_storage_faults(&context, &msg, &peer, &response).await
+                    {
+                        warn!(
+                            "{:?}: {fault:?} at {name} for {msg_id:?}",
+                            LogMarker::StorageFaultDetected
+                        );
+                        output_cmds.push(Cmd::TrackNodeIssue {
+                            name,
+                            issue: IssueType::Storage(fault),
+                        });
+                    }
+                    success_count += 1;
+                    last_success_response = Some(response);
                 }
+                Ok(Err(comms_err)) => {
+                    error!("{msg_id:?} Error when sending request to holder node {peer:?}, marking node as faulty: {comms_err:?}");
+                    if let CommsError::FailedSend(peer) = comms_err {
+                        output_cmds.push(Cmd::TrackNodeIssue {
+                            name: peer.name(),
+                            issue: IssueType::Communication,
+                        });
+                    } else {
+                        output_cmds.push(Cmd::TrackNodeIssue {
+                            name: peer.name(),
+                            issue: IssueType::RequestOperation,
+                        });
+                    }
 
-                last_error = Some(Error::Comms(comms_err));
+                    last_error = Some(Error::Comms(comms_err));
+                }
             }
-        });
+        }
 
         if success_count == targets_len {
             if let Some(response) = last_success_response {
@@ -265,6 +282,52 @@ impl MyNode {
     }
 }
 
+/// If the msg sent was a data query, check the holder's response, along with the responses
+/// of the other holders and our own replica, for storage faults
+async fn check_for_storage_faults(
+    context: &NodeContext,
+    msg_sent: &NodeMsg,
+    holder: &Peer,
+    response: &WireMsg,
+) -> Vec<(XorName, StorageFault)> {
+    let query = match msg_sent {
+        NodeMsg::NodeDataQuery(query) => query,
+        _ => return vec![],
+    };
+
+    let response = match response.into_msg() {
+        Ok(MsgType::NodeDataResponse {
+            msg: NodeDataResponse::QueryResponse { response, .. },
+            ..
+        }) => response,
+        _ => return vec![],
+    };
+
+    let mut faults = vec![];
+    // we may hold a replica ourselves, which can prove the data to exist
+    if response.is_data_not_found() || matches!(response, QueryResponse::GetRegister(Ok(_))) {
+        let our_response = context
+            .data_storage
+            .query(&query.query, User::Key(query.auth.public_key))
+            .await;
+        if our_response.is_success() {
+            faults.extend(
+                context
+                    .replica_checks
+                    .record(&query.query, context.name, &our_response),
+            );
+        }
+    }
+
+    faults.extend(
+        context
+            .replica_checks
+            .record(&query.query, holder.name(), &response),
+    );
+    faults.retain(|(name, _)| *name != context.name);
+    faults
+}
+
 /// Verify what kind of response was received, and if that's the expected type based on
 /// the type of msg sent to the nodes, then forward the corresponding response to the client
 fn build_and_send_response_to_client(
@@ -367,19 +430,51 @@ async fn send_to_target_peers_and_await_responses(
     let mut wire_msg = WireMsg::new_msg(msg_id, payload, kind, dst);
     let _bytes = wire_msg.serialize_and_cache_bytes()?;
 
+    #[cfg(feature = "chaos")]
+    let msg_variant = msg.to_string();
+
     let mut send_tasks = vec![];
     for target in targets {
         dst.name = target.name();
         let bytes_to_node = wire_msg.serialize_with_new_dst(&dst)?;
 
         let comm = context.comm.clone();
+        #[cfg(feature = "chaos")]
+        let (chaos, msg_variant) = (context.chaos.clone(), msg_variant.clone());
         info!("About to send {msg_id:?} to holder node: {target:?}");
 
         send_tasks.push(
             async move {
                 let outcome = timeout(*NODE_RESPONSE_TIMEOUT, async {
-                    comm.send_out_bytes_to_peer_and_return_response(target, msg_id, bytes_to_node)
-                        .await
+                    #[cfg(not(feature = "chaos"))]
+                    let response = comm.send_out_bytes_to_peer_and_return_response(
+                        target,
+                        msg_id,
+                        bytes_to_node,
+                    );
+
+                    // a request not sent is never responded to
+                    #[cfg(feature = "chaos")]
+                    let response = async {
+                        let send = || {
+                            comm.send_out_bytes_to_peer_and_return_response(
+                                target,
+                                msg_id,
+                                bytes_to_node.clone(),
+                            )
+                        };
+                        match chaos
+                            .send_and_await(&msg_variant, msg_id, &target, send)
+                            .await
+                            .into_iter()
+                            .next()
+                        {
+                            Some(response) => response,
+                            None => futures::future::pending().await,
+                        }
+                    };
+
+                    response.await
                 })
                 .await;
 
@@ -393,21 +488,60 @@ async fn send_to_target_peers_and_await_responses(
 }
 
 // Send a msg on a given stream
+#[cfg_attr(not(feature = "chaos"), allow(unused_variables))]
 async fn send_msg_on_stream(
-    section_key: bls::PublicKey,
+    context: &NodeContext,
+    msg: &(dyn Display + Sync),
     payload: Bytes,
     kind: MsgKind,
-    mut send_stream: SendStream,
+    send_stream: SendStream,
     target_peer: Peer,
     correlation_id: MsgId,
 ) -> Result<()> {
     let dst = Dst {
         name: target_peer.name(),
-        section_key,
+        section_key: context.network_knowledge.section_key(),
     };
     let wire_msg = WireMsg::new_msg(correlation_id, payload, kind, dst);
     let bytes = wire_msg.serialize().map_err(|_| Error::InvalidMessage)?;
 
+    #[cfg(feature = "chaos")]
+    let copies = {
+        use crate::node::chaos::MsgFate;
+        // responses are displayed along with their content, which we leave out
+        let msg = msg.to_string();
+        let msg_variant = msg.split('(').next().unwrap_or_default();
+        match context
+            .chaos
+            .msg_fate(msg_variant, correlation_id, &target_peer)
+        {
+            MsgFate::Sent => 1,
+            MsgFate::Dropped => return Ok(()),
+            MsgFate::Duplicated => 2,
+            MsgFate::Held(held) => {
+                let _handle = tokio::spawn(async move {
+                    let _turn = held.wait().await;
+                    let _result =
+                        send_on_stream(bytes, 1, send_stream, target_peer, correlation_id).await;
+                });
+                return Ok(());
+            }
+        }
+    };
+    #[cfg(not(feature = "chaos"))]
+    let copies = 1;
+
+    send_on_stream(bytes, copies, send_stream, target_peer, correlation_id).await
+}
+
+// Send the msg bytes on the stream as many times as given, and finish it
+async fn send_on_stream(
+    bytes: UsrMsgBytes,
+    copies: usize,
+    mut send_stream: SendStream,
+    target_peer: Peer,
+    correlation_id: MsgId,
+) -> Result<()> {
     let stream_id = send_stream.id();
     trace!("Sending response {correlation_id:?} to {target_peer:?} over {stream_id}");
 
@@ -415,12 +549,14 @@ async fn send_msg_on_stream(
     send_stream.set_priority(stream_prio);
     trace!("Prio set for {correlation_id:?} to {target_peer:?}, over {stream_id}");
 
-    if let Err(error) = send_stream.send_user_msg(bytes).await {
-        error!(
-            "Could not send response {correlation_id:?} to peer {target_peer:?} \
-            over response {stream_id}: {error:?}"
-        );
-        return Err(error.into());
+    for _ in 0..copies {
+        if let Err(error) = send_stream.send_user_msg(bytes.clone()).await {
+            error!(
+                "Could not send response {correlation_id:?} to peer {target_peer:?} \
+                over response {stream_id}: {error:?}"
+            );
+            return Err(error.into());
+        }
     }
 
     trace!("Msg away for {correlation_id:?} to {target_peer:?}, over {stream_id}");
@@ -7,9 +7,8 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::node::{core::NodeContext, flow_ctrl::cmds::Cmd, messaging::Peers, MyNode};
-use rand::{rngs::OsRng, seq::SliceRandom};
+use rand::seq::SliceRandom;
 use sn_interface::{
-    data_copy_count,
     messaging::system::{NodeDataCmd, NodeMsg},
     types::{log_markers::LogMarker, DataAddress, Peer},
 };
@@ -41,9 +40,10 @@ impl MyNode {
         }
         // To make each data storage node reply with different copies, so that the
         // overall queries can be reduced, the data names are scrambled.
-        data_i_have.shuffle(&mut OsRng);
+        data_i_have.shuffle(&mut sn_interface::rng::thread_rng());
 
         let members = context.network_knowledge.members();
+        let data_copy_count = context.network_knowledge.network_params().data_copy_count;
         let members_names = members.iter().map(|p2p_node| p2p_node.name());
 
         let mut data_for_sender = vec![];
@@ -55,7 +55,7 @@ impl MyNode {
             let holder_list: BTreeSet<_> = members_names
                 .clone()
                 .sorted_by(|lhs, rhs| data.name().cmp_distance(lhs, rhs))
-                .take(data_copy_count())
+                .take(data_copy_count)
                 .collect();
 
             if holder_list.contains(&sender.name()) {
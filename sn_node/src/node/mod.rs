@@ -11,6 +11,10 @@
 /// Node Configuration
 pub mod cfg;
 
+/// Fault injection
+#[cfg(feature = "chaos")]
+pub mod chaos;
+
 mod api;
 mod connectivity;
 mod dkg;
@@ -23,6 +27,7 @@ mod messaging;
 mod node_starter;
 mod node_test_api;
 mod relocation;
+mod state_store;
 
 /// Standard channel size, to allow for large swings in throughput
 pub static STANDARD_CHANNEL_SIZE: usize = 100_000;
@@ -33,11 +38,13 @@ pub use self::{
     flow_ctrl::RejoinReason,
     node_starter::{new_test_api, start_new_node},
     node_test_api::NodeTestApi,
+    relocation::{
+        builtin_relocation_policy, check as relocation_check, ChurnId, OldestFirst, Relocation,
+        RelocationDecision, RelocationPolicy,
+    },
 };
 use self::{core::MyNode, flow_ctrl::cmds::Cmd, node_starter::CmdChannel};
 pub use crate::storage::DataStorage;
-#[cfg(test)]
-pub(crate) use relocation::{check as relocation_check, ChurnId};
 
 pub use sn_interface::network_knowledge::MIN_ADULT_AGE;
 use sn_interface::{
@@ -45,7 +52,7 @@ use sn_interface::{
     types::Peer,
 };
 
-pub use qp2p::SendStream;
+pub use sn_comms::SendStream;
 pub use xor_name::{Prefix, XorName, XOR_NAME_LEN}; // TODO remove pub on API update
 
 mod core {
@@ -55,7 +62,9 @@ mod core {
             flow_ctrl::{cmds::Cmd, fault_detection::FaultsCmd},
             handover::Handover,
             membership::{elder_candidates, try_split_dkg, Membership},
-            messaging::Peers,
+            messaging::{AeBudget, Peers, ReplicaChecks},
+            relocation::{OldestFirst, RelocationPolicy},
+            state_store::NodeStateStore,
             DataStorage, Error, Result, XorName,
         },
         UsedSpace,
@@ -79,13 +88,16 @@ mod core {
 
     use ed25519_dalek::Keypair;
     use std::{
-        collections::{BTreeMap, BTreeSet, HashMap},
+        collections::{BTreeMap, BTreeSet},
         net::SocketAddr,
         path::PathBuf,
         sync::Arc,
     };
     use tokio::sync::mpsc;
 
+    #[cfg(feature = "chaos")]
+    use crate::node::chaos::Chaos;
+
     // File name where to cache this node's section tree (stored at this node's set root storage dir)
     const SECTION_TREE_FILE_NAME: &str = "section_tree";
     const GOSSIP_SECTION_COUNT: usize = 3;
@@ -107,7 +119,7 @@ mod core {
         pub(crate) network_knowledge: NetworkKnowledge,
         // DKG/Split/Churn modules
         pub(crate) dkg_start_aggregator: SignatureAggregator,
-        pub(crate) dkg_sessions_info: HashMap<Digest256, DkgSessionInfo>,
+        pub(crate) dkg_sessions_info: BTreeMap<Digest256, DkgSessionInfo>,
         pub(crate) dkg_voter: DkgVoter,
         pub(crate) elder_promotion_aggregator: SignatureAggregator,
         pub(crate) pending_split_sections:
@@ -123,6 +135,17 @@ mod core {
         // Section administration
         pub(crate) section_proposal_aggregator: SignatureAggregator,
         pub(crate) relocation_proof: Option<RelocationProof>,
+        // Where to snapshot our state to, if it's to be persisted across restarts
+        pub(crate) state_store: Option<NodeStateStore>,
+        // Per peer budget and stats of the AE msgs we send
+        pub(crate) ae_budget: AeBudget,
+        // Responses of the data replicas to recent queries, to tell their storage faults
+        pub(crate) replica_checks: ReplicaChecks,
+        // Which members to relocate on churn, when we are an elder
+        pub(crate) relocation_policy: Arc<dyn RelocationPolicy>,
+        // Faults injected into this node
+        #[cfg(feature = "chaos")]
+        pub(crate) chaos: Chaos,
     }
 
     #[derive(custom_debug::Debug, Clone)]
@@ -142,6 +165,12 @@ mod core {
         #[debug(skip)]
         pub(crate) fault_cmds_sender: mpsc::Sender<FaultsCmd>,
         pub(crate) relocation_proof: Option<RelocationProof>,
+        #[debug(skip)]
+        pub(crate) ae_budget: AeBudget,
+        #[debug(skip)]
+        pub(crate) replica_checks: ReplicaChecks,
+        #[cfg(feature = "chaos")]
+        pub(crate) chaos: Chaos,
     }
 
     impl NodeContext {
@@ -207,6 +236,10 @@ mod core {
                 data_storage: self.data_storage.clone(),
                 fault_cmds_sender: self.fault_cmds_sender.clone(),
                 relocation_proof: self.relocation_proof.clone(),
+                ae_budget: self.ae_budget.clone(),
+                replica_checks: self.replica_checks.clone(),
+                #[cfg(feature = "chaos")]
+                chaos: self.chaos.clone(),
             }
         }
 
@@ -239,6 +272,7 @@ mod core {
                     key.public_key_set,
                     n_elders,
                     bootstrap_members,
+                    network_knowledge.network_params().recommended_section_size,
                 ))
             } else {
                 None
@@ -267,7 +301,7 @@ mod core {
                 network_knowledge,
                 section_keys_provider,
                 root_storage_dir,
-                dkg_sessions_info: HashMap::default(),
+                dkg_sessions_info: BTreeMap::default(),
                 pending_split_sections: Default::default(),
                 dkg_start_aggregator: SignatureAggregator::default(),
                 dkg_voter: DkgVoter::default(),
@@ -281,6 +315,12 @@ mod core {
                 handover_request_aggregator: TotalParticipationAggregator::default(),
                 section_proposal_aggregator: SignatureAggregator::default(),
                 relocation_proof: None,
+                state_store: None,
+                ae_budget: AeBudget::default(),
+                replica_checks: ReplicaChecks::default(),
+                relocation_policy: Arc::new(OldestFirst),
+                #[cfg(feature = "chaos")]
+                chaos: Chaos::default(),
             };
 
             let context = &node.context();
@@ -291,10 +331,22 @@ mod core {
             Ok(node)
         }
 
+        /// Injects the faults of the chaos profile into this node from now on.
+        #[cfg(feature = "chaos")]
+        pub(crate) fn set_chaos(&mut self, chaos: Chaos) {
+            self.data_storage.set_chaos(chaos.clone());
+            self.chaos = chaos;
+        }
+
         pub(crate) fn info(&self) -> MyNodeInfo {
             let keypair = self.keypair.clone();
             let addr = self.addr;
-            MyNodeInfo { keypair, addr }
+            let relay = self.comm.relay();
+            MyNodeInfo {
+                keypair,
+                addr,
+                relay,
+            }
         }
 
         pub(crate) fn name(&self) -> XorName {
@@ -307,7 +359,8 @@ mod core {
 
         /// Generates a random AE probe for _anywhere_ on the network.
         pub(crate) fn generate_probe_msg(context: &NodeContext) -> Result<Cmd> {
-            use rand::{rngs::OsRng, seq::SliceRandom};
+            use rand::seq::SliceRandom;
+            let mut rng = sn_interface::rng::thread_rng();
 
             // Get prefixes of other sections.
             let our_prefix = context.network_knowledge.prefix();
@@ -318,7 +371,7 @@ mod core {
                 .collect();
 
             // take random three sections
-            other_prefixes.shuffle(&mut OsRng);
+            other_prefixes.shuffle(&mut rng);
             let target_prefixes = other_prefixes.into_iter().take(GOSSIP_SECTION_COUNT);
 
             let mut recipients = BTreeSet::new();
@@ -334,7 +387,7 @@ mod core {
                     .elders()
                     .filter(|p| p.name() != context.name)
                     .collect();
-                elders.shuffle(&mut OsRng);
+                elders.shuffle(&mut rng);
 
                 // Should always get one non-self elder to send probe to. If cannot,
                 // hopefully we'll eventually get updated on this section from somewhere else.
@@ -356,22 +409,27 @@ mod core {
             ))
         }
 
-        // /// Generates a SectionProbeMsg with our current knowledge,
-        // /// targetting our section elders.
-        // /// Even if we're up to date, we expect a response.
-        // pub(crate) fn generate_section_probe_msg(context: &NodeContext) -> Cmd {
-        //     let our_section = context.network_knowledge.section_auth();
-        //     let recipients = our_section.elders_set();
+        /// Generates a SectionProbeMsg with our current knowledge,
+        /// targetting our section elders.
+        /// Even if we're up to date, we expect a response.
+        pub(crate) fn generate_section_probe_msg(context: &NodeContext) -> Cmd {
+            let our_section = context.network_knowledge.section_auth();
+            let recipients = BTreeSet::from_iter(
+                our_section
+                    .elders()
+                    .filter(|elder| elder.name() != context.name)
+                    .copied(),
+            );
 
-        //     info!(
-        //         "ProbeMsg target our section {:?} recipients {:?}",
-        //         our_section.prefix(),
-        //         recipients,
-        //     );
+            info!(
+                "ProbeMsg target our section {:?} recipients {:?}",
+                our_section.prefix(),
+                recipients,
+            );
 
-        //     let probe = context.network_knowledge.anti_entropy_probe();
-        //     Cmd::send_msg(probe, Peers::Multiple(recipients), context.clone())
-        // }
+            let probe = context.network_knowledge.anti_entropy_probe();
+            Cmd::send_msg(probe, Peers::Multiple(recipients), context.clone())
+        }
 
         /// Generates section infos for the best elder candidate among the members at the given generation
         /// Returns a set of candidate `DkgSessionId`'s.
@@ -504,6 +562,7 @@ mod core {
                 key.public_key_set,
                 sap.elders().count(),
                 BTreeSet::from_iter(sap.members().cloned()),
+                sap.network_params().recommended_section_size,
             ));
 
             true
@@ -630,7 +689,7 @@ mod core {
             }
 
             if new.is_elder || old.is_elder {
-                cmds.extend(self.send_ae_update_to_our_section()?);
+                cmds.extend(self.send_ae_digest_to_our_section());
             }
 
             let old_elders = old
@@ -713,6 +772,8 @@ mod core {
                 self.joins_allowed_until_split = false;
             }
 
+            self.persist_state();
+
             Ok(cmds)
         }
 
@@ -9,17 +9,20 @@
 use crate::node::{
     cfg::keypair_storage::{get_reward_pk, store_network_keypair, store_new_reward_keypair},
     flow_ctrl::{
-        cmds::Cmd, dispatcher::Dispatcher, fault_detection::FaultsCmd, CmdCtrl, FlowCtrl,
-        RejoinReason,
+        cmds::Cmd,
+        dispatcher::Dispatcher,
+        fault_detection::{FaultDetectionSettings, FaultsCmd, FAULT_DETECTION_FILE_NAME},
+        CmdCtrl, FlowCtrl, RejoinReason,
     },
     logging::log_system_details,
+    state_store::{self, NodeStateStore, RestoredNode},
     Config, Error, MyNode, Result, STANDARD_CHANNEL_SIZE,
 };
 use crate::UsedSpace;
 
 use sn_comms::Comm;
 use sn_interface::{
-    network_knowledge::{NetworkKnowledge, SectionTree, MIN_ADULT_AGE},
+    network_knowledge::{Error as NetworkKnowledgeError, NetworkKnowledge, SectionTree},
     types::{keys::ed25519, log_markers::LogMarker, PublicKey as TypesPublicKey},
 };
 
@@ -92,10 +95,8 @@ async fn new_node(
         }
     };
 
-    let used_space = UsedSpace::new(config.min_capacity(), config.max_capacity());
-
     let (node, cmd_channel, rejoin_network_rx) =
-        bootstrap_node(config, used_space, root_dir, join_timeout).await?;
+        bootstrap_node(config, root_dir, join_timeout).await?;
 
     {
         debug!("[NODE WRITE]: new node...");
@@ -131,7 +132,6 @@ async fn new_node(
 // Private helper to create a new node using the given config and bootstraps it to the network.
 async fn bootstrap_node(
     config: &Config,
-    used_space: UsedSpace,
     root_storage_dir: &Path,
     join_timeout: Duration,
 ) -> Result<(
@@ -145,24 +145,26 @@ async fn bootstrap_node(
 
     let comm = Comm::new(config.local_addr(), incoming_msg_pipe).await?;
 
-    let node = if config.is_first() {
-        start_genesis_node(
-            comm,
-            used_space,
-            root_storage_dir,
-            fault_cmds_sender.clone(),
-        )
-        .await?
+    let fault_detection_settings = FaultDetectionSettings {
+        config: config.fault_detection_config().await?,
+        snapshot_path: config
+            .persist_fault_detection()
+            .then(|| root_storage_dir.join(FAULT_DETECTION_FILE_NAME)),
+    };
+
+    let mut node = if config.is_first() {
+        start_genesis_node(config, comm, root_storage_dir, fault_cmds_sender.clone()).await?
     } else {
-        start_node(
-            config,
-            comm,
-            used_space,
-            root_storage_dir,
-            fault_cmds_sender.clone(),
-        )
-        .await?
+        start_node(config, comm, root_storage_dir, fault_cmds_sender.clone()).await?
     };
+    node.relocation_policy = config.relocation_policy()?;
+    info!("Relocation policy: {}", node.relocation_policy.name());
+    #[cfg(feature = "chaos")]
+    {
+        use crate::node::chaos::{Chaos, ChaosProfile};
+        let profile = ChaosProfile::load(config.chaos_profile())?;
+        node.set_chaos(Chaos::new(profile));
+    }
 
     let node = Arc::new(RwLock::new(node));
     let (dispatcher, data_replication_receiver) = Dispatcher::new(node.clone());
@@ -172,9 +174,21 @@ async fn bootstrap_node(
         incoming_msg_receiver,
         data_replication_receiver,
         (fault_cmds_sender, fault_cmds_receiver),
+        fault_detection_settings,
     )
     .await;
 
+    if config.persist_node_state() {
+        // if we resumed as a member, catch up on anything we missed while offline
+        let context = node.read().await.context();
+        if context.network_knowledge.is_section_member(&context.name) {
+            cmd_channel
+                .send((MyNode::generate_section_probe_msg(&context), vec![]))
+                .await
+                .map_err(|_| Error::CmdChannelSendError)?;
+        }
+    }
+
     cmd_channel
         .send((Cmd::TryJoinNetwork, vec![]))
         .await
@@ -204,11 +218,15 @@ async fn await_join(node: Arc<RwLock<MyNode>>) {
 }
 
 async fn start_genesis_node(
+    config: &Config,
     comm: Comm,
-    used_space: UsedSpace,
     root_storage_dir: &Path,
     fault_cmds_sender: mpsc::Sender<FaultsCmd>,
 ) -> Result<MyNode> {
+    // The params we start the network with are fixed in the genesis SAP
+    let network_params = config.genesis_network_params().await?;
+    info!("Starting a new network with params: {network_params:?}");
+
     // Genesis node having a fix age of 255.
     let keypair = ed25519::gen_keypair(&Prefix::default().range_inclusive(), 255);
     let node_name = ed25519::name(&keypair.public);
@@ -218,6 +236,9 @@ async fn start_genesis_node(
         node_name,
         std::process::id()
     );
+    if config.relay_for_others() {
+        comm.enable_relaying(node_name);
+    }
 
     // Generate the genesis key, this will be the first key in the sections chain,
     // as well as the owner of the genesis DBC minted by this first node of the network.
@@ -225,9 +246,14 @@ async fn start_genesis_node(
     let (node, genesis_dbc) = MyNode::first_node(
         comm,
         keypair,
-        used_space.clone(),
+        UsedSpace::new(
+            config.min_capacity(),
+            config.max_capacity(),
+            network_params.recommended_section_size,
+        ),
         root_storage_dir.to_path_buf(),
         genesis_sk_set,
+        network_params,
         fault_cmds_sender,
     )?;
 
@@ -252,19 +278,77 @@ async fn start_genesis_node(
 async fn start_node(
     config: &Config,
     comm: Comm,
-    used_space: UsedSpace,
     root_storage_dir: &Path,
     fault_cmds_sender: mpsc::Sender<FaultsCmd>,
 ) -> Result<MyNode> {
-    let keypair = ed25519::gen_keypair(&Prefix::default().range_inclusive(), MIN_ADULT_AGE);
-    let node_name = ed25519::name(&keypair.public);
-    info!("{} Bootstrapping as a new node.", node_name);
     let section_tree_path = config.network_contacts_file().ok_or_else(|| {
         Error::Configuration("Could not obtain network contacts file path".to_string())
     })?;
     let section_tree = SectionTree::from_disk(&section_tree_path).await?;
-    let sap = section_tree.get_signed_by_name(&node_name)?;
-    let network_knowledge = NetworkKnowledge::new(sap.prefix(), section_tree.clone())?;
+
+    // we only join networks with the params we've been configured with, if any
+    let network_params = section_tree.network_params();
+    if let Some(expected) = config.network_params().await? {
+        if expected != network_params {
+            return Err(NetworkKnowledgeError::NetworkParamsMismatch {
+                expected: Box::new(expected),
+                found: Box::new(network_params),
+            }
+            .into());
+        }
+    }
+
+    let node_state_key = if config.persist_node_state() {
+        Some(config.node_state_key().await?)
+    } else {
+        None
+    };
+
+    let restored = if let Some(at_rest_key) = &node_state_key {
+        let addr = comm.socket_addr();
+        let genesis_key = section_tree.genesis_key();
+        match state_store::restore(root_storage_dir, addr, genesis_key, at_rest_key).await {
+            Ok(restored) => restored,
+            Err(error) => {
+                warn!("Not resuming from node state snapshot, joining as a new node: {error}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let (keypair, network_knowledge, section_key_share, snapshot) = match restored {
+        Some(RestoredNode {
+            keypair,
+            network_knowledge,
+            key_share,
+            snapshot,
+        }) => (keypair, network_knowledge, key_share, Some(snapshot)),
+        None => {
+            let keypair = ed25519::gen_keypair(
+                &Prefix::default().range_inclusive(),
+                network_params.min_adult_age,
+            );
+            let sap = section_tree.get_signed_by_name(&ed25519::name(&keypair.public))?;
+            let network_knowledge = NetworkKnowledge::new(sap.prefix(), section_tree.clone())?;
+            (keypair, network_knowledge, None, None)
+        }
+    };
+
+    let node_name = ed25519::name(&keypair.public);
+    if snapshot.is_some() {
+        info!("{} Resuming from node state snapshot.", node_name);
+    } else {
+        info!("{} Bootstrapping as a new node.", node_name);
+    }
+    let keypair = Arc::new(keypair);
+    if config.relay_for_others() {
+        comm.enable_relaying(node_name);
+    }
+    if let Some(relay) = config.relay() {
+        comm.register_with_relay(relay, keypair.clone()).await?;
+    }
 
     info!(
         "{} Starting a new node (PID: {}) with socket: {}, network's genesis key: {:?}",
@@ -274,16 +358,30 @@ async fn start_node(
         section_tree.genesis_key()
     );
 
-    let node = MyNode::new(
+    let mut node = MyNode::new(
         comm,
-        Arc::new(keypair),
+        keypair,
         network_knowledge,
-        None,
-        used_space.clone(),
+        section_key_share,
+        UsedSpace::new(
+            config.min_capacity(),
+            config.max_capacity(),
+            network_params.recommended_section_size,
+        ),
         root_storage_dir.to_path_buf(),
         fault_cmds_sender,
     )?;
 
+    if let Some(at_rest_key) = node_state_key {
+        if let Some(snapshot) = snapshot {
+            node.resume_state(snapshot);
+        }
+        node.enable_state_persistence(NodeStateStore::new(
+            root_storage_dir,
+            at_rest_key.public_key(),
+        ));
+    }
+
     info!("Node {} started.", node.info().name());
 
     Ok(node)
@@ -9,18 +9,28 @@
 //! Relocation related types and utilities.
 
 use sn_interface::{
-    elder_count,
-    network_knowledge::{recommended_section_size, NetworkKnowledge, NodeState, RelocationDst},
+    network_knowledge::{NetworkKnowledge, NodeState, RelocationDst},
+    types::log_markers::LogMarker,
 };
+
+use serde::Serialize;
 use std::{
     cmp::min,
-    collections::BTreeSet,
-    fmt::{self, Display, Formatter},
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Debug, Display, Formatter},
+    sync::Arc,
 };
 use xor_name::XorName;
 
-// Unique identifier for a churn event, which is used to select nodes to relocate.
-pub(crate) struct ChurnId(pub(crate) [u8; bls::SIG_SIZE]);
+/// Unique identifier for a churn event, which is used to select nodes to relocate.
+pub struct ChurnId(pub(crate) [u8; bls::SIG_SIZE]);
+
+impl ChurnId {
+    /// Bytes of the section signature over the churn event, unpredictable until agreed on.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
 
 impl Display for ChurnId {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
@@ -32,67 +42,261 @@ impl Display for ChurnId {
     }
 }
 
-/// Find all nodes to relocate after a churn event and generate the relocation details for them.
-pub(super) fn find_nodes_to_relocate(
-    network_knowledge: &NetworkKnowledge,
-    churn_id: &ChurnId,
-    excluded: BTreeSet<XorName>,
-) -> Vec<(NodeState, RelocationDst)> {
-    // Find the peers that pass the relocation check and take only the oldest ones to avoid
-    // relocating too many nodes at the same time.
-    // Capped by criteria that cannot relocate too many node at once.
-    let section_size = network_knowledge.section_members().len();
-    debug!(
-        "Finding relocation candidates, having {:?} members, recommended section_size {:?}",
-        section_size,
-        recommended_section_size(),
-    );
-
-    // no relocation if total section size is too small
-    if section_size < recommended_section_size() {
-        return vec![];
+/// Policy deciding which members of our section to relocate on a churn event, and where to.
+///
+/// Each node applies the policy of its [`Config`](crate::node::Config), [`OldestFirst`] unless
+/// another one is configured. Policies are only applied by elders, and all the elders of a section
+/// need to apply the same one, as the relocations are proposed to, and agreed on by, all of them.
+///
+/// A decision is only acted on for current non-elder members not `excluded`, up to
+/// `max_relocations_per_churn` of them, any other relocation is dropped.
+pub trait RelocationPolicy: Debug + Send + Sync {
+    /// Name of the policy, recorded along with each of its decisions.
+    fn name(&self) -> &str;
+
+    /// Decides which members to relocate on the churn event, if any.
+    ///
+    /// Members which have just joined the section are `excluded`, and must not be relocated.
+    fn decide(
+        &self,
+        network_knowledge: &NetworkKnowledge,
+        churn_id: &ChurnId,
+        excluded: &BTreeSet<XorName>,
+    ) -> RelocationDecision;
+}
+
+/// A member to relocate, as decided by a [`RelocationPolicy`].
+#[derive(Clone, Debug)]
+pub struct Relocation {
+    /// The member to relocate.
+    pub node: NodeState,
+    /// The destination of the member.
+    pub dst: RelocationDst,
+    /// Why the member was chosen.
+    pub reason: String,
+}
+
+/// The members to relocate on a churn event, as decided by a [`RelocationPolicy`], and why.
+#[derive(Clone, Debug, Default)]
+pub struct RelocationDecision {
+    /// Members to relocate.
+    pub relocations: Vec<Relocation>,
+    /// Why no members, or no more members, were relocated.
+    pub reasons: Vec<String>,
+}
+
+impl RelocationDecision {
+    /// A decision not to relocate any member, for the given reason.
+    pub fn none(reason: impl Into<String>) -> Self {
+        Self {
+            relocations: vec![],
+            reasons: vec![reason.into()],
+        }
     }
+}
 
-    let max_reloctions = elder_count() / 2;
-    let allowed_relocations = min(section_size - recommended_section_size(), max_reloctions);
+/// The relocation policy built into the node with the given name, if any.
+pub fn builtin_relocation_policy(name: &str) -> Option<Arc<dyn RelocationPolicy>> {
+    match name {
+        "oldest-first" => Some(Arc::new(OldestFirst)),
+        _ => None,
+    }
+}
 
-    // Find the peers that pass the relocation check
-    let mut candidates: Vec<_> = network_knowledge
+/// Drops the relocations of the decision which are not of current non-elder members of our
+/// section, of `excluded` members, or repeated, and caps them to `max_relocations_per_churn`,
+/// giving the reason of each one dropped.
+pub(crate) fn validate_decision(
+    mut decision: RelocationDecision,
+    network_knowledge: &NetworkKnowledge,
+    excluded: &BTreeSet<XorName>,
+) -> RelocationDecision {
+    let max_relocations = network_knowledge.network_params().max_relocations_per_churn;
+    let members: BTreeMap<_, _> = network_knowledge
         .section_members()
         .into_iter()
-        // only adults get relocated
-        .filter(|state| network_knowledge.is_adult(&state.name()))
-        .filter(|info| check(info.age(), churn_id))
-        // the newly joined node shall not be relocated immediately
-        .filter(|info| !excluded.contains(&info.name()))
+        .map(|state| (state.name(), state))
         .collect();
-    // To avoid a node to manipulate its name to gain priority of always being first in XorName,
-    // here we sort the nodes by its distance to the churn_id.
-    let target_name = XorName::from_content(&churn_id.0);
-    candidates.sort_by(|lhs, rhs| target_name.cmp_distance(&lhs.name(), &rhs.name()));
 
-    debug!("Finding relocation candidates {candidates:?}");
+    let mut relocations: Vec<Relocation> = vec![];
+    for relocation in decision.relocations {
+        let name = relocation.node.name();
+        let dropped_because = if members.get(&name) != Some(&relocation.node) {
+            "not a current member"
+        } else if network_knowledge.is_elder(&name) {
+            "an elder"
+        } else if excluded.contains(&name) {
+            "just joined"
+        } else if relocations.iter().any(|chosen| chosen.node.name() == name) {
+            "already being relocated"
+        } else if relocations.len() >= max_relocations {
+            "over the max of relocations per churn"
+        } else {
+            relocations.push(relocation);
+            continue;
+        };
+        warn!("Relocation policy chose {name}, which is {dropped_because}, not relocating it");
+        decision
+            .reasons
+            .push(format!("{name} not relocated, being {dropped_because}"));
+    }
+    decision.relocations = relocations;
+
+    decision
+}
+
+/// The default relocation policy.
+///
+/// The adults passing the age check against the churn signature are candidates, see
+/// [`check`], of which only the oldest are relocated, capped to leave the section with at
+/// least the recommended section size, and to `max_relocations_per_churn`.
+/// Their destination is derived from their name and the churn signature.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OldestFirst;
+
+impl RelocationPolicy for OldestFirst {
+    fn name(&self) -> &str {
+        "oldest-first"
+    }
+
+    fn decide(
+        &self,
+        network_knowledge: &NetworkKnowledge,
+        churn_id: &ChurnId,
+        excluded: &BTreeSet<XorName>,
+    ) -> RelocationDecision {
+        // Find the peers that pass the relocation check and take only the oldest ones to avoid
+        // relocating too many nodes at the same time.
+        // Capped by criteria that cannot relocate too many node at once.
+        let params = network_knowledge.network_params();
+        let section_size = network_knowledge.section_members().len();
+        debug!(
+            "Finding relocation candidates, having {:?} members, recommended section_size {:?}",
+            section_size, params.recommended_section_size,
+        );
+
+        // no relocation if total section size is too small
+        if section_size < params.recommended_section_size {
+            return RelocationDecision::none(format!(
+                "section size {section_size} is below the recommended {}",
+                params.recommended_section_size
+            ));
+        }
+
+        let allowed_relocations = min(
+            section_size - params.recommended_section_size,
+            params.max_relocations_per_churn,
+        );
+
+        // Find the peers that pass the relocation check
+        let mut candidates: Vec<_> = network_knowledge
+            .section_members()
+            .into_iter()
+            // only adults get relocated
+            .filter(|state| network_knowledge.is_adult(&state.name()))
+            .filter(|info| check(info.age(), churn_id))
+            // the newly joined node shall not be relocated immediately
+            .filter(|info| !excluded.contains(&info.name()))
+            .collect();
+        // To avoid a node to manipulate its name to gain priority of always being first in XorName,
+        // here we sort the nodes by its distance to the churn_id.
+        let target_name = XorName::from_content(&churn_id.0);
+        candidates.sort_by(|lhs, rhs| target_name.cmp_distance(&lhs.name(), &rhs.name()));
+
+        debug!("Finding relocation candidates {candidates:?}");
+
+        let max_age = if let Some(age) = candidates.iter().map(|info| info.age()).max() {
+            age
+        } else {
+            return RelocationDecision::none(format!(
+                "no adult passes the age check against {} trailing zero bits of the churn signature",
+                trailing_zeros(&churn_id.0)
+            ));
+        };
 
-    let max_age = if let Some(age) = candidates.iter().map(|info| info.age()).max() {
-        age
-    } else {
-        return vec![];
+        let oldest: Vec<_> = candidates
+            .into_iter()
+            .filter(|peer| peer.age() == max_age)
+            .collect();
+        let mut decision = RelocationDecision::default();
+        if oldest.len() > allowed_relocations {
+            decision.reasons.push(format!(
+                "{} of the oldest candidates not relocated, capped at {allowed_relocations}",
+                oldest.len() - allowed_relocations
+            ));
+        }
+        decision.relocations = oldest
+            .into_iter()
+            .take(allowed_relocations)
+            .map(|peer| {
+                let dst_section = XorName::from_content_parts(&[&peer.name().0, &churn_id.0]);
+                Relocation {
+                    node: peer,
+                    dst: RelocationDst::new(dst_section),
+                    reason: format!(
+                        "oldest adult passing the age check, of age {max_age}, closest to the churn"
+                    ),
+                }
+            })
+            .collect();
+
+        decision
+    }
+}
+
+// A relocation decision as recorded in the logs.
+#[derive(Serialize)]
+struct RelocationRecord<'a> {
+    policy: &'a str,
+    churn: String,
+    prefix: String,
+    section_size: usize,
+    relocated: Vec<RelocatedRecord>,
+    reasons: &'a [String],
+}
+
+#[derive(Serialize)]
+struct RelocatedRecord {
+    name: String,
+    age: u8,
+    dst: String,
+    reason: String,
+}
+
+/// Logs the decision as a `RelocationDecision` marker followed by its JSON record.
+pub(crate) fn log_decision(
+    policy: &str,
+    network_knowledge: &NetworkKnowledge,
+    churn_id: &ChurnId,
+    decision: &RelocationDecision,
+) {
+    let record = RelocationRecord {
+        policy,
+        churn: churn_id.to_string(),
+        prefix: format!("{:b}", network_knowledge.prefix()),
+        section_size: network_knowledge.section_members().len(),
+        relocated: decision
+            .relocations
+            .iter()
+            .map(|relocation| RelocatedRecord {
+                name: relocation.node.name().to_string(),
+                age: relocation.node.age(),
+                dst: relocation.dst.name().to_string(),
+                reason: relocation.reason.clone(),
+            })
+            .collect(),
+        reasons: &decision.reasons,
     };
 
-    candidates
-        .into_iter()
-        .filter(|peer| peer.age() == max_age)
-        .map(|peer| {
-            let dst_section = XorName::from_content_parts(&[&peer.name().0, &churn_id.0]);
-            (peer, RelocationDst::new(dst_section))
-        })
-        .take(allowed_relocations)
-        .collect()
+    match serde_json::to_string(&record) {
+        Ok(json) => info!("{} {json}", LogMarker::RelocationDecision),
+        Err(err) => warn!("Failed to serialise relocation decision {decision:?}: {err:?}"),
+    }
 }
 
-// Relocation check - returns whether a member with the given age is a candidate for relocation on
-// a churn event with the given churn id.
-pub(crate) fn check(age: u8, churn_id: &ChurnId) -> bool {
+/// Relocation check - returns whether a member with the given age is a candidate for relocation
+/// on a churn event with the given churn id.
+pub fn check(age: u8, churn_id: &ChurnId) -> bool {
     // Evaluate the formula: `signature % 2^age == 0` Which is the same as checking the signature
     // has at least `age` trailing zero bits.
     trailing_zeros(&churn_id.0) >= age as u32
@@ -119,8 +323,9 @@ mod tests {
     use super::*;
 
     use sn_interface::{
-        elder_count,
-        network_knowledge::{NodeState, SectionAuthorityProvider, SectionTree, MIN_ADULT_AGE},
+        network_knowledge::{
+            NetworkParams, NodeState, SectionAuthorityProvider, SectionTree, MIN_ADULT_AGE,
+        },
         test_utils::TestKeys,
         types::Peer,
     };
@@ -146,6 +351,15 @@ mod tests {
 
     const MAX_AGE: u8 = MIN_ADULT_AGE + 3;
 
+    // The sections of these tests are part of a network with the default params.
+    fn recommended_section_size() -> usize {
+        NetworkParams::default().recommended_section_size
+    }
+
+    fn elder_count() -> usize {
+        NetworkParams::default().elder_count
+    }
+
     proptest! {
         #[test]
         #[allow(clippy::unwrap_used)]
@@ -158,42 +372,21 @@ mod tests {
     }
 
     fn proptest_actions_impl(peers: Vec<Peer>, signature_trailing_zeros: u8) -> Result<()> {
-        let sk_set = bls::SecretKeySet::random(0, &mut thread_rng());
-        let sk = sk_set.secret_key();
-
-        // Create `Section` with `peers` as its members and set the `elder_count()` oldest peers as
-        // the elders.
-        let sap = SectionAuthorityProvider::new(
-            peers
-                .iter()
-                .sorted_by_key(|peer| peer.age())
-                .rev()
-                .take(elder_count())
-                .cloned(),
-            Prefix::default(),
-            peers.iter().map(|p| NodeState::joined(*p, None)),
-            sk_set.public_keys(),
-            0,
-        );
-        let sap = TestKeys::get_section_signed(&sk, sap);
-        let tree = SectionTree::new(sap)?;
-        let mut network_knowledge = NetworkKnowledge::new(Prefix::default(), tree)?;
-
-        for peer in &peers {
-            let info = NodeState::joined(*peer, None);
-            let info = TestKeys::get_section_signed(&sk, info);
-            assert!(network_knowledge.update_member(info));
-        }
+        let network_knowledge = network_knowledge_with(&peers)?;
 
         // Simulate a churn event whose signature has the given number of trailing zeros.
         let churn_id =
             ChurnId(signature_with_trailing_zeros(signature_trailing_zeros as u32).to_bytes());
 
-        let relocations =
-            find_nodes_to_relocate(&network_knowledge, &churn_id, BTreeSet::default());
+        let relocations = OldestFirst
+            .decide(&network_knowledge, &churn_id, &BTreeSet::default())
+            .relocations;
 
         let allowed_relocations = if peers.len() > recommended_section_size() {
-            min(elder_count() / 2, peers.len() - recommended_section_size())
+            min(
+                NetworkParams::default().max_relocations_per_churn,
+                peers.len() - recommended_section_size(),
+            )
         } else {
             0
         };
@@ -223,15 +416,124 @@ mod tests {
         assert_eq!(expected_relocated_peers.len(), relocations.len());
 
         // NOTE: `zip` works here, as both collections are sorted by the same criteria.
-        for (peer, (state, dst)) in expected_relocated_peers.into_iter().zip(relocations) {
-            assert_eq!(peer.name(), state.peer().name());
+        for (peer, relocation) in expected_relocated_peers.into_iter().zip(relocations) {
+            assert_eq!(peer.name(), relocation.node.peer().name());
             let dst_section = XorName::from_content_parts(&[&peer.name().0, &churn_id.0]);
-            assert_eq!(&dst_section, dst.name());
+            assert_eq!(&dst_section, relocation.dst.name());
         }
 
         Ok(())
     }
 
+    // Create `Section` with `peers` as its members and set the `elder_count()` oldest peers as
+    // the elders.
+    fn network_knowledge_with(peers: &[Peer]) -> Result<NetworkKnowledge> {
+        let sk_set = bls::SecretKeySet::random(0, &mut thread_rng());
+        let sk = sk_set.secret_key();
+
+        let sap = SectionAuthorityProvider::new(
+            peers
+                .iter()
+                .sorted_by_key(|peer| peer.age())
+                .rev()
+                .take(elder_count())
+                .cloned(),
+            Prefix::default(),
+            peers.iter().map(|p| NodeState::joined(*p, None)),
+            sk_set.public_keys(),
+            0,
+            NetworkParams::default(),
+        );
+        let sap = TestKeys::get_section_signed(&sk, sap);
+        let tree = SectionTree::new(sap)?;
+        let mut network_knowledge = NetworkKnowledge::new(Prefix::default(), tree)?;
+
+        for peer in peers {
+            let info = NodeState::joined(*peer, None);
+            let info = TestKeys::get_section_signed(&sk, info);
+            assert!(network_knowledge.update_member(info));
+        }
+
+        Ok(network_knowledge)
+    }
+
+    #[test]
+    fn oldest_first_gives_its_reason_not_to_relocate_in_a_small_section() -> Result<()> {
+        let mut rng = thread_rng();
+        let peers: Vec<_> = (0..recommended_section_size() - 1)
+            .map(|_| Peer::new(XorName::random(&mut rng), ([127, 0, 0, 1], 0).into()))
+            .collect();
+        let network_knowledge = network_knowledge_with(&peers)?;
+        let churn_id = ChurnId(signature_with_trailing_zeros(MAX_AGE as u32).to_bytes());
+
+        let decision = OldestFirst.decide(&network_knowledge, &churn_id, &BTreeSet::default());
+
+        assert!(decision.relocations.is_empty());
+        assert_eq!(
+            decision.reasons,
+            vec![format!(
+                "section size {} is below the recommended {}",
+                recommended_section_size() - 1,
+                recommended_section_size()
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn only_current_non_elder_members_are_relocated_up_to_the_max() -> Result<()> {
+        let mut rng = thread_rng();
+        let peers: Vec<_> = (0..recommended_section_size())
+            .map(|_| Peer::new(XorName::random(&mut rng), ([127, 0, 0, 1], 0).into()))
+            .collect();
+        let network_knowledge = network_knowledge_with(&peers)?;
+        let elder = network_knowledge
+            .elders()
+            .into_iter()
+            .next()
+            .ok_or_else(|| eyre::eyre!("no elder"))?;
+        let adults: Vec<_> = peers
+            .iter()
+            .filter(|peer| !network_knowledge.is_elder(&peer.name()))
+            .collect();
+        let stranger = Peer::new(XorName::random(&mut rng), ([127, 0, 0, 1], 0).into());
+        let excluded = BTreeSet::from([adults[0].name()]);
+
+        let relocation = |peer: &Peer| Relocation {
+            node: NodeState::joined(*peer, None),
+            dst: RelocationDst::new(XorName::random(&mut thread_rng())),
+            reason: "test".to_string(),
+        };
+        let chosen = [elder, stranger, *adults[0], *adults[1]]
+            .iter()
+            .chain(adults[1..].iter().copied())
+            .map(relocation)
+            .collect();
+        let decision = RelocationDecision {
+            relocations: chosen,
+            reasons: vec![],
+        };
+
+        let decision = validate_decision(decision, &network_knowledge, &excluded);
+
+        let max_relocations = NetworkParams::default().max_relocations_per_churn;
+        let relocated: Vec<_> = decision
+            .relocations
+            .iter()
+            .map(|relocation| relocation.node.name())
+            .collect();
+        let expected: Vec<_> = adults[1..=max_relocations]
+            .iter()
+            .map(|peer| peer.name())
+            .collect();
+        assert_eq!(relocated, expected);
+        assert_eq!(
+            decision.reasons.len(),
+            3 + 1 + adults.len() - 1 - max_relocations
+        );
+        Ok(())
+    }
+
     // Fetch a `bls::Signature` with the given number of trailing zeros. The signature is generated
     // from an unspecified random data using an unspecified random `SecretKey`. That is OK because
     // the relocation algorithm doesn't care about whether the signature is valid. It only
@@ -0,0 +1,517 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::node::{
+    cfg::keypair_storage::{get_network_keypair, store_network_keypair},
+    handover::HandoverSnapshot,
+    membership::MembershipSnapshot,
+    Error, MyNode, Result, STANDARD_CHANNEL_SIZE,
+};
+
+use sn_interface::{
+    messaging::system::SectionSigned,
+    network_knowledge::{
+        NetworkKnowledge, NodeState, RelocationProof, SectionKeyShare, SectionTree,
+    },
+    types::keys::ed25519,
+};
+
+use ed25519_dalek::Keypair;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    fs,
+    sync::mpsc::{self, Receiver, Sender},
+};
+use xor_name::{Prefix, XorName};
+
+// File name where to snapshot this node's state (stored at this node's set root storage dir)
+const NODE_STATE_FILE_NAME: &str = "node_state";
+// Version of the snapshot format, snapshots of any other version are not restored
+const NODE_STATE_VERSION: u16 = 2;
+
+/// The state a node needs to resume its duties after a restart, without having to rejoin.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct NodeStateSnapshot {
+    version: u16,
+    /// Name of the node the snapshot was taken by, it's only restored with the same keypair.
+    name: XorName,
+    prefix: Prefix,
+    section_tree: SectionTree,
+    section_members: BTreeSet<SectionSigned<NodeState>>,
+    /// Our share of the current section key, only present if we are an elder.
+    key_share: Option<EncryptedKeyShare>,
+    membership: Option<MembershipSnapshot>,
+    handover: Option<HandoverSnapshot>,
+    joins_allowed: bool,
+    joins_allowed_until_split: bool,
+    relocation_proof: Option<RelocationProof>,
+}
+
+/// A `SectionKeyShare` with its secret part encrypted to the at-rest key the node is configured
+/// with, which is kept outside the root dir.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct EncryptedKeyShare {
+    public_key_set: bls::PublicKeySet,
+    index: usize,
+    secret_key_share: bls::Ciphertext,
+}
+
+impl EncryptedKeyShare {
+    fn encrypt(share: &SectionKeyShare, at_rest_key: &bls::PublicKey) -> Self {
+        Self {
+            public_key_set: share.public_key_set.clone(),
+            index: share.index,
+            secret_key_share: at_rest_key.encrypt(share.secret_key_share.to_bytes()),
+        }
+    }
+
+    fn decrypt(&self, at_rest_key: &bls::SecretKey) -> Result<SectionKeyShare> {
+        let not_decrypted = || {
+            Error::InvalidNodeStateSnapshot(
+                "the section key share could not be decrypted with the at-rest key".to_string(),
+            )
+        };
+        let bytes = at_rest_key
+            .decrypt(&self.secret_key_share)
+            .ok_or_else(not_decrypted)?;
+        let secret_key_share = bytes
+            .try_into()
+            .ok()
+            .and_then(|bytes| bls::SecretKeyShare::from_bytes(bytes).ok())
+            .ok_or_else(not_decrypted)?;
+
+        // decrypting with another key doesn't fail by itself, it yields a different share
+        if secret_key_share.public_key_share() != self.public_key_set.public_key_share(self.index) {
+            return Err(not_decrypted());
+        }
+
+        Ok(SectionKeyShare {
+            public_key_set: self.public_key_set.clone(),
+            index: self.index,
+            secret_key_share,
+        })
+    }
+}
+
+/// Writes the node state snapshots to the node's root dir, in the order they are taken.
+#[derive(Clone, Debug)]
+pub(crate) struct NodeStateStore {
+    snapshots_sender: Sender<(Arc<Keypair>, NodeStateSnapshot)>,
+    at_rest_key: bls::PublicKey,
+}
+
+impl NodeStateStore {
+    /// Spawns the task writing the snapshots to the given root dir, with our section key share
+    /// encrypted to the given at-rest key.
+    pub(crate) fn new(root_dir: &Path, at_rest_key: bls::PublicKey) -> Self {
+        let (snapshots_sender, snapshots_receiver) = mpsc::channel(STANDARD_CHANNEL_SIZE);
+        let _handle =
+            tokio::task::spawn(write_snapshots(root_dir.to_path_buf(), snapshots_receiver));
+
+        Self {
+            snapshots_sender,
+            at_rest_key,
+        }
+    }
+
+    fn write(&self, keypair: Arc<Keypair>, snapshot: NodeStateSnapshot) {
+        if let Err(error) = self.snapshots_sender.try_send((keypair, snapshot)) {
+            warn!("Could not queue node state snapshot to be written: {error}");
+        }
+    }
+}
+
+/// The state restored from a previous run of this node.
+#[derive(Debug)]
+pub(crate) struct RestoredNode {
+    pub(crate) keypair: Keypair,
+    pub(crate) network_knowledge: NetworkKnowledge,
+    pub(crate) key_share: Option<SectionKeyShare>,
+    pub(crate) snapshot: NodeStateSnapshot,
+}
+
+/// Restores the state snapshotted at the root dir, if there is a snapshot.
+///
+/// The snapshot is checked against the network we are joining (genesis key), against the
+/// keypair stored along with it, and against our current address, since other nodes would
+/// not be able to reach us at a different address.
+/// Anything that changed while we were offline is caught up on through AE once we're started.
+pub(crate) async fn restore(
+    root_dir: &Path,
+    addr: SocketAddr,
+    genesis_key: &bls::PublicKey,
+    at_rest_key: &bls::SecretKey,
+) -> Result<Option<RestoredNode>> {
+    let snapshot = match read_snapshot(&root_dir.join(NODE_STATE_FILE_NAME)).await? {
+        Some(snapshot) => snapshot,
+        None => return Ok(None),
+    };
+    let keypair = get_network_keypair(root_dir).await?.ok_or_else(|| {
+        Error::InvalidNodeStateSnapshot("no network keypair found alongside it".to_string())
+    })?;
+
+    if snapshot.version != NODE_STATE_VERSION {
+        return Err(Error::InvalidNodeStateSnapshot(format!(
+            "unsupported version {}, expected {NODE_STATE_VERSION}",
+            snapshot.version
+        )));
+    }
+    let name = ed25519::name(&keypair.public);
+    if snapshot.name != name {
+        return Err(Error::InvalidNodeStateSnapshot(format!(
+            "it was taken by {}, but our keypair is for {name}",
+            snapshot.name
+        )));
+    }
+    if snapshot.section_tree.genesis_key() != genesis_key {
+        return Err(Error::InvalidNodeStateSnapshot(format!(
+            "it is for a network with genesis key {:?}",
+            snapshot.section_tree.genesis_key()
+        )));
+    }
+
+    let mut network_knowledge =
+        NetworkKnowledge::new(snapshot.prefix, snapshot.section_tree.clone())?;
+    let _ = network_knowledge.merge_members(snapshot.section_members.clone())?;
+
+    match network_knowledge.get_section_member(&name) {
+        Some(node_state) if node_state.addr() == addr => {}
+        Some(node_state) => {
+            return Err(Error::InvalidNodeStateSnapshot(format!(
+                "we were a member at {}, but are now at {addr}",
+                node_state.addr()
+            )))
+        }
+        None => {
+            return Err(Error::InvalidNodeStateSnapshot(
+                "we were not a member of our section yet".to_string(),
+            ))
+        }
+    }
+
+    // only restore the key share if it's for the current section key, we'll otherwise
+    // obtain the current one through DKG or be demoted
+    let key_share = match &snapshot.key_share {
+        Some(share) if network_knowledge.is_elder(&name) => {
+            let share = share.decrypt(at_rest_key)?;
+            if share.public_key_set.public_key() == network_knowledge.section_key() {
+                Some(share)
+            } else {
+                debug!("Not restoring key share, as it's not for our current section key");
+                None
+            }
+        }
+        _ => None,
+    };
+
+    Ok(Some(RestoredNode {
+        keypair,
+        network_knowledge,
+        key_share,
+        snapshot,
+    }))
+}
+
+impl MyNode {
+    /// Sets the store to snapshot our state to from now on, taking a first snapshot right away.
+    pub(crate) fn enable_state_persistence(&mut self, store: NodeStateStore) {
+        self.state_store = Some(store);
+        self.persist_state();
+    }
+
+    /// Snapshots our current state to disk, if state persistence is enabled.
+    pub(crate) fn persist_state(&self) {
+        let store = match &self.state_store {
+            Some(store) => store,
+            None => return,
+        };
+
+        let section_key = self.network_knowledge.section_key();
+        let key_share = self
+            .section_keys_provider
+            .key_share(&section_key)
+            .ok()
+            .map(|share| EncryptedKeyShare::encrypt(&share, &store.at_rest_key));
+
+        let snapshot = NodeStateSnapshot {
+            version: NODE_STATE_VERSION,
+            name: self.name(),
+            prefix: self.network_knowledge.prefix(),
+            section_tree: self.network_knowledge.section_tree().clone(),
+            section_members: self.network_knowledge.section_signed_members(),
+            key_share,
+            membership: self.membership.as_ref().map(|m| m.snapshot()),
+            handover: self.handover_voting.as_ref().map(|h| h.snapshot()),
+            joins_allowed: self.joins_allowed,
+            joins_allowed_until_split: self.joins_allowed_until_split,
+            relocation_proof: self.relocation_proof.clone(),
+        };
+
+        store.write(self.keypair.clone(), snapshot);
+    }
+
+    /// Resumes the state which `MyNode::new` doesn't set up from the restored snapshot.
+    pub(crate) fn resume_state(&mut self, snapshot: NodeStateSnapshot) {
+        if let (Some(membership), Some(membership_snapshot)) =
+            (self.membership.as_mut(), snapshot.membership)
+        {
+            membership.restore(membership_snapshot);
+        }
+        if let (Some(handover), Some(handover_snapshot)) =
+            (self.handover_voting.as_mut(), snapshot.handover)
+        {
+            handover.restore(handover_snapshot);
+        }
+        self.joins_allowed = snapshot.joins_allowed;
+        self.joins_allowed_until_split = snapshot.joins_allowed_until_split;
+        self.relocation_proof = snapshot.relocation_proof;
+
+        info!(
+            "Resumed node state at membership generation {:?}, handover generation {:?}",
+            self.membership.as_ref().map(|m| m.generation()),
+            self.handover_voting.as_ref().map(|h| h.generation())
+        );
+    }
+}
+
+async fn read_snapshot(path: &Path) -> Result<Option<NodeStateSnapshot>> {
+    match fs::read(path).await {
+        Ok(content) => Ok(Some(bincode::deserialize(&content)?)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+// Only the latest of the queued snapshots is written, as it supersedes all the others.
+async fn write_snapshots(
+    root_dir: PathBuf,
+    mut snapshots_receiver: Receiver<(Arc<Keypair>, NodeStateSnapshot)>,
+) {
+    let path = root_dir.join(NODE_STATE_FILE_NAME);
+    let mut stored_keypair = None;
+
+    while let Some(mut latest) = snapshots_receiver.recv().await {
+        while let Ok(next) = snapshots_receiver.try_recv() {
+            latest = next;
+        }
+        let (keypair, snapshot) = latest;
+
+        // our keypair changes upon relocation, and the snapshot is only restored along with it
+        if stored_keypair != Some(keypair.public) {
+            if let Err(error) = store_network_keypair(&root_dir, keypair.to_bytes()).await {
+                warn!("Could not store network keypair for node state snapshot: {error:?}");
+                continue;
+            }
+            stored_keypair = Some(keypair.public);
+        }
+
+        if let Err(error) = write_snapshot(&snapshot, &path).await {
+            warn!("Could not write node state snapshot to {path:?}: {error:?}");
+        }
+    }
+}
+
+/// Writes to a temporary file first, so a crash mid-write doesn't leave a corrupted snapshot.
+async fn write_snapshot(snapshot: &NodeStateSnapshot, path: &Path) -> Result<()> {
+    let serialized = bincode::serialize(snapshot)?;
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serialized).await?;
+    fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sn_interface::{
+        network_knowledge::{MyNodeInfo, NetworkParams, SectionAuthorityProvider},
+        test_utils::{gen_addr, gen_info, TestKeys, TestSapBuilder},
+    };
+
+    use eyre::{eyre, Result};
+    use tempfile::TempDir;
+
+    #[test]
+    fn key_share_roundtrips_through_encryption() -> Result<()> {
+        let at_rest_key = bls::SecretKey::random();
+        let sk_set = bls::SecretKeySet::random(3, &mut rand::thread_rng());
+        let share = SectionKeyShare {
+            public_key_set: sk_set.public_keys(),
+            index: 2,
+            secret_key_share: sk_set.secret_key_share(2),
+        };
+
+        let encrypted = EncryptedKeyShare::encrypt(&share, &at_rest_key.public_key());
+        let decrypted = encrypted.decrypt(&at_rest_key)?;
+
+        assert_eq!(decrypted.index, share.index);
+        assert_eq!(decrypted.public_key_set, share.public_key_set);
+        assert_eq!(decrypted.secret_key_share, share.secret_key_share);
+
+        // a different key cannot decrypt it
+        assert!(encrypted.decrypt(&bls::SecretKey::random()).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn snapshot_roundtrips_through_disk() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let path = root.path().join(NODE_STATE_FILE_NAME);
+        assert!(read_snapshot(&path).await?.is_none());
+
+        let genesis_sk_set = bls::SecretKeySet::random(0, &mut rand::thread_rng());
+        let genesis_sk = genesis_sk_set.secret_key();
+        let genesis_sap = SectionAuthorityProvider::new(
+            [],
+            Prefix::default(),
+            [],
+            genesis_sk_set.public_keys(),
+            0,
+            NetworkParams::default(),
+        );
+        let genesis_sap = TestKeys::get_section_signed(&genesis_sk, genesis_sap);
+
+        let snapshot = NodeStateSnapshot {
+            version: NODE_STATE_VERSION,
+            name: xor_name::rand::random(),
+            prefix: Prefix::default(),
+            section_tree: SectionTree::new(genesis_sap)?,
+            section_members: BTreeSet::new(),
+            key_share: None,
+            membership: None,
+            handover: None,
+            joins_allowed: true,
+            joins_allowed_until_split: false,
+            relocation_proof: None,
+        };
+        write_snapshot(&snapshot, &path).await?;
+
+        let read = read_snapshot(&path)
+            .await?
+            .expect("snapshot to have been written");
+        assert_eq!(read.name, snapshot.name);
+        assert_eq!(read.section_tree.genesis_key(), &genesis_sk.public_key());
+        assert!(read.joins_allowed);
+        assert!(!path.with_extension("tmp").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn restore_resumes_an_elder() -> Result<()> {
+        let (root, node, genesis_key, at_rest_key) = snapshotted_elder().await?;
+
+        let restored = restore(root.path(), node.addr, &genesis_key, &at_rest_key)
+            .await?
+            .ok_or_else(|| eyre!("snapshot to be restored"))?;
+
+        assert_eq!(restored.keypair.public, node.keypair.public);
+        assert!(restored.network_knowledge.is_elder(&node.name()));
+        assert_eq!(restored.network_knowledge.section_key(), genesis_key);
+        let key_share = restored
+            .key_share
+            .ok_or_else(|| eyre!("key share to be restored"))?;
+        assert_eq!(key_share.public_key_set.public_key(), genesis_key);
+        assert_eq!(key_share.index, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn restore_without_snapshot_joins_anew() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let restored = restore(
+            root.path(),
+            gen_addr(),
+            &bls::SecretKey::random().public_key(),
+            &bls::SecretKey::random(),
+        )
+        .await?;
+        assert!(restored.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn restore_rejects_mismatching_snapshot() -> Result<()> {
+        let (root, node, genesis_key, at_rest_key) = snapshotted_elder().await?;
+
+        // another network
+        let other_genesis_key = bls::SecretKey::random().public_key();
+        assert!(
+            restore(root.path(), node.addr, &other_genesis_key, &at_rest_key)
+                .await
+                .is_err()
+        );
+        // another address
+        assert!(restore(root.path(), gen_addr(), &genesis_key, &at_rest_key)
+            .await
+            .is_err());
+        // another at-rest key
+        let other_at_rest_key = bls::SecretKey::random();
+        assert!(
+            restore(root.path(), node.addr, &genesis_key, &other_at_rest_key)
+                .await
+                .is_err()
+        );
+        // another keypair
+        let other_node = gen_info(node.age(), None);
+        store_network_keypair(root.path(), other_node.keypair.to_bytes()).await?;
+        assert!(restore(root.path(), node.addr, &genesis_key, &at_rest_key)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    // Snapshots the state of the single elder of a genesis section, returning the root dir it's
+    // stored at along with the elder, the genesis key and the at-rest key used.
+    async fn snapshotted_elder() -> Result<(TempDir, MyNodeInfo, bls::PublicKey, bls::SecretKey)> {
+        let (sap, sk_set, elders, _) = TestSapBuilder::new(Prefix::default())
+            .elder_count(1)
+            .build();
+        let node = elders[0].clone();
+        let section_sk = sk_set.secret_key();
+        let section_tree = SectionTree::new(TestKeys::get_section_signed(&section_sk, sap))?;
+        let node_state =
+            TestKeys::get_section_signed(&section_sk, NodeState::joined(node.peer(), None));
+
+        let at_rest_key = bls::SecretKey::random();
+        let key_share = TestKeys::get_section_key_share(&sk_set, 0);
+        let snapshot = NodeStateSnapshot {
+            version: NODE_STATE_VERSION,
+            name: node.name(),
+            prefix: Prefix::default(),
+            section_tree,
+            section_members: BTreeSet::from([node_state]),
+            key_share: Some(EncryptedKeyShare::encrypt(
+                &key_share,
+                &at_rest_key.public_key(),
+            )),
+            membership: None,
+            handover: None,
+            joins_allowed: true,
+            joins_allowed_until_split: false,
+            relocation_proof: None,
+        };
+
+        let root = tempfile::tempdir()?;
+        write_snapshot(&snapshot, &root.path().join(NODE_STATE_FILE_NAME)).await?;
+        store_network_keypair(root.path(), node.keypair.to_bytes()).await?;
+
+        Ok((root, node, section_sk.public_key(), at_rest_key))
+    }
+}
@@ -34,6 +34,8 @@ const CHUNKS_STORE_DIR_NAME: &str = "chunks";
 pub(super) struct ChunkStorage {
     file_store_path: PathBuf,
     used_space: UsedSpace,
+    #[cfg(feature = "chaos")]
+    pub(super) chaos: crate::node::chaos::Chaos,
 }
 
 impl ChunkStorage {
@@ -46,6 +48,8 @@ impl ChunkStorage {
         Ok(Self {
             file_store_path: path.join(CHUNKS_STORE_DIR_NAME),
             used_space,
+            #[cfg(feature = "chaos")]
+            chaos: Default::default(),
         })
     }
 
@@ -145,6 +149,10 @@ impl ChunkStorage {
 
         let mut file = File::create(filepath).await?;
 
+        #[cfg(feature = "chaos")]
+        file.write_all(&self.chaos.corrupt_chunk(addr, chunk.value()))
+            .await?;
+        #[cfg(not(feature = "chaos"))]
         file.write_all(chunk.value()).await?;
         // Let's sync up OS data to disk to reduce the chances of
         // concurrent reading failing by reading an empty/incomplete file
@@ -59,6 +59,17 @@ impl DataStorage {
         })
     }
 
+    /// Injects the faults of the chaos profile into the storage of chunks.
+    #[cfg(feature = "chaos")]
+    pub(crate) fn set_chaos(&mut self, chaos: crate::node::chaos::Chaos) {
+        self.chunks.chaos = chaos;
+    }
+
+    /// Returns the tracker of the space used by the stored data.
+    pub(crate) fn used_space(&self) -> &UsedSpace {
+        &self.used_space
+    }
+
     /// Returns whether the storage min capacity has been reached or not.
     pub(crate) fn has_reached_min_capacity(&self) -> bool {
         self.used_space.has_reached_min_capacity()
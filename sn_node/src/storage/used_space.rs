@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use sn_interface::network_knowledge::recommended_section_size;
+use sn_interface::network_knowledge::NetworkParams;
 use std::sync::{
     atomic::{AtomicU8, AtomicUsize, Ordering},
     Arc,
@@ -44,6 +44,8 @@ pub struct UsedSpace {
     /// This is used by a node operator to prevent the node to fill up
     /// actual disk space beyond what the operator deems convenient.
     max_capacity: usize,
+    /// The recommended section size of the network, which sets the size of the levels.
+    recommended_section_size: usize,
     /// The level is bumped every x% increase, where x = 100 / recommended_section_size.
     ///
     /// We add a new node for every level of used space increment.
@@ -54,10 +56,11 @@ pub struct UsedSpace {
 
 impl UsedSpace {
     /// Create new `UsedSpace` tracker
-    pub fn new(min_capacity: usize, max_capacity: usize) -> Self {
+    pub fn new(min_capacity: usize, max_capacity: usize, recommended_section_size: usize) -> Self {
         Self {
             min_capacity,
             max_capacity,
+            recommended_section_size,
             used_space: Arc::new(AtomicUsize::new(0)),
             last_seen_level: Arc::new(AtomicU8::new(0)),
         }
@@ -72,7 +75,7 @@ impl UsedSpace {
         let _ = self.used_space.fetch_add(size, Ordering::Relaxed);
         let used_space = self.used_space.load(Ordering::Relaxed);
         let used_space_ratio = used_space as f64 / self.min_capacity as f64;
-        let current_level = to_storage_level(used_space_ratio);
+        let current_level = to_storage_level(used_space_ratio, self.recommended_section_size);
 
         // we do a relaxed check here, because this is not important to be exact, we will update soon enough
         if self.last_seen_level.load(Ordering::Relaxed) > current_level {
@@ -119,6 +122,19 @@ impl UsedSpace {
         current_used_space >= self.min_capacity
     }
 
+    /// Number of bytes currently stored.
+    pub(crate) fn used(&self) -> usize {
+        self.used_space.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn min_capacity(&self) -> usize {
+        self.min_capacity
+    }
+
+    pub(crate) fn max_capacity(&self) -> usize {
+        self.max_capacity
+    }
+
     pub(crate) fn ratio(&self) -> f64 {
         let used = self.used_space.load(Ordering::Relaxed);
         let min_capacity = self.min_capacity;
@@ -130,8 +146,8 @@ impl UsedSpace {
 /// where every step is a x% increase of the value.
 /// This gives an equal number of increments as the number of nodes necessary to
 /// bring a newly split section up to the size where it splits again.
-fn to_storage_level(value: f64) -> u8 {
-    ((value * 100.0) as usize / recommended_section_size()) as u8
+fn to_storage_level(value: f64, recommended_section_size: usize) -> u8 {
+    ((value * 100.0) as usize / recommended_section_size) as u8
 }
 
 impl Default for UsedSpace {
@@ -140,6 +156,7 @@ impl Default for UsedSpace {
         Self {
             min_capacity: DEFAULT_MIN_CAPACITY,
             max_capacity: DEFAULT_MAX_CAPACITY,
+            recommended_section_size: NetworkParams::default().recommended_section_size,
             used_space: Arc::new(AtomicUsize::new(0)),
             last_seen_level: Arc::new(AtomicU8::new(0)),
         }
@@ -51,6 +51,8 @@ const BASE_TRACING_DIRECTIVES: &str = "testnet=info,sn_launch_tool=debug";
 const NODES_DIR: &str = "local-test-network";
 const DEFAULT_INTERVAL: &str = "5000";
 const DEFAULT_NODE_COUNT: u32 = 30;
+// Env var the nodes read their chaos profile's path from
+const CHAOS_PROFILE_ENV: &str = "SN_CHAOS_PROFILE";
 
 #[derive(Debug, clap::StructOpt)]
 #[clap(name = "testnet", version)]
@@ -73,6 +75,11 @@ struct Cmd {
     /// have to be handled as such (ie, `sudo rm -rf ~/.safe/node/local-test-network`)
     #[clap(long)]
     flame: bool,
+
+    /// JSON file describing the faults the nodes inject, e.g. msgs dropped or crashes at given
+    /// log markers. Requires the testnet to be built with the `chaos` feature.
+    #[clap(long, value_parser)]
+    chaos_profile: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -82,6 +89,23 @@ async fn main() -> Result<()> {
 
     let cmd_args = Cmd::from_args();
 
+    if let Some(profile) = &cmd_args.chaos_profile {
+        if !cfg!(feature = "chaos") {
+            return Err(eyre!(
+                "A chaos profile requires the testnet to be built with the `chaos` feature"
+            ));
+        }
+        // the nodes are launched from another dir, and inherit our env
+        let profile = profile
+            .canonicalize()
+            .wrap_err_with(|| format!("Cannot find chaos profile {}", profile.display()))?;
+        println!(
+            "*** Nodes will inject faults as per {} ***",
+            profile.display()
+        );
+        std::env::set_var(CHAOS_PROFILE_ENV, profile);
+    }
+
     // setup dirs for flamegraph output
     if cmd_args.flame {
         let path = Path::new("nodes");